@@ -0,0 +1,29 @@
+//! Android entry point. Runs the local game full screen with touch controls;
+//! the command-line driven extras of the desktop build are left out.
+
+use bevy::{prelude::*, window::WindowMode};
+use snake_game::{
+    attract::AttractPlugin, config::ConfigPlugin, mobile::MobilePlugin, rumble::RumblePlugin,
+    SnakeGamePlugin,
+};
+
+#[bevy_main]
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Snake".to_string(),
+                mode: WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .add_plugins((
+            SnakeGamePlugin,
+            ConfigPlugin,
+            MobilePlugin,
+            RumblePlugin,
+            AttractPlugin,
+        ))
+        .run();
+}
@@ -0,0 +1,59 @@
+//! Worst cases for the plain `Vec` scans and rejection sampling in the rules.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use snake_core::{random_food_position, sim::Simulation, Arena, Direction, Position};
+
+const BOARD: Arena = Arena {
+    width: 100,
+    height: 100,
+};
+
+/// Cells of the board walked row by row, turning at each edge, so that
+/// consecutive cells are always adjacent.
+fn serpentine(arena: Arena) -> impl Iterator<Item = Position> {
+    (0..arena.height as i32).flat_map(move |y| {
+        (0..arena.width as i32).map(move |x| Position {
+            x: if y % 2 == 0 {
+                x
+            } else {
+                arena.width as i32 - 1 - x
+            },
+            y,
+        })
+    })
+}
+
+fn tick_long_snake(c: &mut Criterion) {
+    // Tail at the origin, head at the end of the fifth row heading up into
+    // open board.
+    let mut body: Vec<Position> = serpentine(BOARD).take(500).collect();
+    body.reverse();
+    c.bench_function("tick 500 segments on 100x100", |b| {
+        b.iter_batched(
+            || {
+                let mut sim = Simulation::new(0, BOARD);
+                sim.body = body.clone();
+                sim
+            },
+            |mut sim| {
+                sim.step(Direction::Up);
+                sim
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn spawn_food_crowded(c: &mut Criterion) {
+    let cells = (BOARD.width * BOARD.height) as usize;
+    let occupied: Vec<Position> = serpentine(BOARD).take(cells * 95 / 100).collect();
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    c.bench_function("spawn food at 95% occupancy", |b| {
+        b.iter(|| random_food_position(&mut rng, BOARD, &occupied))
+    });
+}
+
+criterion_group!(benches, tick_long_snake, spawn_food_crowded);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! A simple built-in player, for demos and attract mode.
+//!
+//! It heads for the nearest food but never takes a move that runs into
+//! something or leaves the head in a pocket too small to hold the snake, as
+//! long as some other move does not. Good enough to look competent, not to
+//! fill the board.
+
+use crate::{controller::BoardView, Direction, Position};
+
+/// Picks the next move, or `None` when every move is fatal.
+pub fn autopilot(board: &BoardView) -> Option<Direction> {
+    let head = board.head()?;
+    board.safe_moves().min_by_key(|&candidate| {
+        let next = head.step(candidate);
+        let cramped = free_area(board, next) < board.body.len();
+        (cramped, board.food_distance(next))
+    })
+}
+
+/// Number of cells reachable from `start` without crossing a snake.
+pub(crate) fn free_area(board: &BoardView, start: Position) -> usize {
+    let arena = board.arena;
+    let mut seen = vec![false; (arena.width * arena.height) as usize];
+    let index = |position: Position| (position.y as u32 * arena.width + position.x as u32) as usize;
+    let mut stack = vec![start];
+    seen[index(start)] = true;
+    let mut area = 0;
+    while let Some(position) = stack.pop() {
+        area += 1;
+        for direction in Direction::ALL {
+            let next = position.step(direction);
+            if !board.blocked(next) && !seen[index(next)] {
+                seen[index(next)] = true;
+                stack.push(next);
+            }
+        }
+    }
+    area
+}
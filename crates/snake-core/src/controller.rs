@@ -0,0 +1,226 @@
+//! Pluggable players: anything that looks at the board and picks a direction.
+//!
+//! Frontends run a [`SnakeController`] in place of the keyboard once per
+//! movement tick, and [`steer_match`] runs one as a versus opponent. [`bot`]
+//! looks the reference bots up by name for command-line selection, and
+//! [`Opponent`] plays versus matches at a chosen [`Difficulty`].
+
+use rand::{seq::IndexedRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    autopilot::autopilot,
+    solvers::{AStar, Greedy, Hamiltonian},
+    versus::Match,
+    Arena, Direction, Position,
+};
+
+/// What a controller gets to see each tick.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardView<'a> {
+    pub arena: Arena,
+    /// Segment positions, head first.
+    pub body: &'a [Position],
+    /// Cells taken by other snakes, and by shed segments, in versus play.
+    pub others: &'a [Position],
+    pub food: &'a [Position],
+    /// The direction the snake is moving in.
+    pub direction: Direction,
+}
+
+impl BoardView<'_> {
+    pub fn head(&self) -> Option<Position> {
+        self.body.first().copied()
+    }
+
+    /// Whether a head moving into `position` would die, ignoring head-on
+    /// collisions with other snakes.
+    pub fn blocked(&self, position: Position) -> bool {
+        !self.arena.contains(position)
+            || self.body.contains(&position)
+            || self.others.contains(&position)
+    }
+
+    /// Moves that neither reverse nor collide right away.
+    pub fn safe_moves(&self) -> impl Iterator<Item = Direction> + '_ {
+        let head = self.head();
+        Direction::ALL.into_iter().filter(move |&direction| {
+            direction != self.direction.opposite()
+                && head.is_some_and(|head| !self.blocked(head.step(direction)))
+        })
+    }
+
+    /// Whether some sequence of `moves` moves starting with `direction`
+    /// avoids every collision. The body follows the head without growing and
+    /// other snakes are assumed to stay put.
+    pub fn survives(&self, direction: Direction, moves: u32) -> bool {
+        fn search(
+            board: &BoardView,
+            body: &mut Vec<Position>,
+            direction: Direction,
+            moves: u32,
+        ) -> bool {
+            if moves == 0 {
+                return true;
+            }
+            let head = body[0].step(direction);
+            if !board.arena.contains(head) || body.contains(&head) || board.others.contains(&head) {
+                return false;
+            }
+            let tail = body.pop();
+            body.insert(0, head);
+            let survives = Direction::ALL
+                .into_iter()
+                .filter(|&next| next != direction.opposite())
+                .any(|next| search(board, body, next, moves - 1));
+            body.remove(0);
+            body.extend(tail);
+            survives
+        }
+        let mut body = self.body.to_vec();
+        !body.is_empty() && search(self, &mut body, direction, moves.max(1))
+    }
+
+    /// Manhattan distance from `position` to the nearest food, or 0 with none.
+    pub fn food_distance(&self, position: Position) -> u32 {
+        self.food
+            .iter()
+            .map(|food| food.x.abs_diff(position.x) + food.y.abs_diff(position.y))
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+pub trait SnakeController: Send + Sync + 'static {
+    /// Called once per movement tick before the snake moves. `None`, or a
+    /// reversal onto the neck, keeps the current direction.
+    fn steer(&mut self, board: &BoardView) -> Option<Direction>;
+}
+
+/// Names accepted by [`bot`], roughly weakest first.
+pub const BOTS: &[&str] = &["random", "greedy", "autopilot", "astar", "hamiltonian"];
+
+/// A reference bot by name, see [`BOTS`].
+pub fn bot(name: &str) -> Option<Box<dyn SnakeController>> {
+    match name {
+        "random" => Some(Box::new(RandomBot::new(rand::random()))),
+        "greedy" => Some(Box::new(Greedy)),
+        "autopilot" => Some(Box::new(Autopilot)),
+        "astar" => Some(Box::new(AStar)),
+        "hamiltonian" => Some(Box::new(Hamiltonian::default())),
+        _ => None,
+    }
+}
+
+/// Lets `controller` steer `player` for the coming tick of a versus match.
+pub fn steer_match(game: &mut Match, player: usize, controller: &mut dyn SnakeController) {
+    let Some(snake) = game.snakes.get(player).filter(|snake| snake.alive) else {
+        return;
+    };
+    let others: Vec<Position> = game
+        .snakes
+        .iter()
+        .enumerate()
+        .filter(|&(other, _)| other != player)
+        .flat_map(|(_, other)| other.body.iter().copied())
+        .chain(game.shed.iter().map(|shed| shed.position))
+        .collect();
+    let board = BoardView {
+        arena: game.arena(),
+        body: &snake.body,
+        others: &others,
+        food: &game.food,
+        direction: snake.direction,
+    };
+    if let Some(direction) = controller.steer(&board) {
+        game.steer(player, direction);
+    }
+}
+
+/// Heads for food while avoiding dead ends, see [`autopilot`].
+pub struct Autopilot;
+
+impl SnakeController for Autopilot {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        autopilot(board)
+    }
+}
+
+/// Wanders: picks any move that does not collide right away.
+pub struct RandomBot {
+    rng: ChaCha8Rng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl SnakeController for RandomBot {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        let safe: Vec<Direction> = board.safe_moves().collect();
+        safe.choose(&mut self.rng).copied()
+    }
+}
+
+/// How well an [`Opponent`] plays.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    Beginner,
+    #[default]
+    Normal,
+    Expert,
+}
+
+impl Difficulty {
+    /// Chance per tick of turning at random, deadly or not.
+    fn mistake_chance(self) -> f64 {
+        match self {
+            Difficulty::Beginner => 0.08,
+            Difficulty::Normal => 0.02,
+            Difficulty::Expert => 0.0,
+        }
+    }
+
+    /// Chance per tick of reacting too late and keeping straight on.
+    fn hesitation_chance(self) -> f64 {
+        match self {
+            Difficulty::Beginner => 0.3,
+            Difficulty::Normal => 0.1,
+            Difficulty::Expert => 0.0,
+        }
+    }
+}
+
+/// Versus opponent: plays like [`AStar`], except for the mistakes and slow
+/// reactions its [`Difficulty`] allows. At [`Difficulty::Expert`] it makes
+/// none.
+pub struct Opponent {
+    pub difficulty: Difficulty,
+    rng: ChaCha8Rng,
+}
+
+impl Opponent {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            difficulty,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl SnakeController for Opponent {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        if self.rng.random_bool(self.difficulty.hesitation_chance()) {
+            return None;
+        }
+        if self.rng.random_bool(self.difficulty.mistake_chance()) {
+            return Direction::ALL.choose(&mut self.rng).copied();
+        }
+        AStar.steer(board)
+    }
+}
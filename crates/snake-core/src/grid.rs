@@ -0,0 +1,87 @@
+#[cfg(feature = "bevy")]
+use bevy_ecs::{
+    component::Component,
+    reflect::{ReflectComponent, ReflectResource},
+    system::Resource,
+};
+#[cfg(feature = "bevy")]
+use bevy_reflect::Reflect;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect), reflect(Component))]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Position {
+    pub fn step(self, direction: Direction) -> Self {
+        match direction {
+            Direction::Left => Self {
+                x: self.x - 1,
+                ..self
+            },
+            Direction::Right => Self {
+                x: self.x + 1,
+                ..self
+            },
+            Direction::Up => Self {
+                y: self.y + 1,
+                ..self
+            },
+            Direction::Down => Self {
+                y: self.y - 1,
+                ..self
+            },
+        }
+    }
+}
+
+/// Size of the playing field in cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Resource, Reflect), reflect(Resource))]
+pub struct Arena {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self {
+            width: 10,
+            height: 10,
+        }
+    }
+}
+
+impl Arena {
+    pub fn contains(self, position: Position) -> bool {
+        position.x >= 0
+            && position.y >= 0
+            && (position.x as u32) < self.width
+            && (position.y as u32) < self.height
+    }
+}
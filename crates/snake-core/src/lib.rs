@@ -0,0 +1,25 @@
+//! Rules of the game, shared by every frontend.
+//!
+//! Nothing here depends on a renderer or game engine. With the `bevy` feature
+//! the grid types also derive the Bevy traits needed to use them directly as
+//! components and resources.
+
+pub mod autopilot;
+pub mod controller;
+mod grid;
+mod hash;
+mod mode;
+pub mod persist;
+pub mod replay;
+mod rules;
+pub mod sim;
+pub mod solvers;
+pub mod tournament;
+pub mod versus;
+
+pub use grid::{Arena, Direction, Position};
+pub use mode::GameMode;
+pub use rules::{
+    collision, random_food_position, Collision, FOOD_SPAWN_INTERVAL, MOVEMENT_INTERVAL,
+    SPEEDRUN_TARGET_SCORE, START_DIRECTION, START_POSITION,
+};
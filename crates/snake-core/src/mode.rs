@@ -0,0 +1,38 @@
+#[cfg(feature = "bevy")]
+use bevy_ecs::{reflect::ReflectResource, system::Resource};
+#[cfg(feature = "bevy")]
+use bevy_reflect::Reflect;
+use serde::{Deserialize, Serialize};
+
+/// Ruleset for a run. Casual modes allow assists such as rewinding; speedruns
+/// end once the score reaches [`SPEEDRUN_TARGET_SCORE`] and race a ghost of
+/// the best run.
+///
+/// [`SPEEDRUN_TARGET_SCORE`]: crate::SPEEDRUN_TARGET_SCORE
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Resource, Reflect), reflect(Resource))]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Casual,
+    Speedrun,
+}
+
+impl GameMode {
+    pub fn is_casual(self) -> bool {
+        matches!(self, Self::Casual)
+    }
+}
+
+impl std::str::FromStr for GameMode {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "classic" => Ok(Self::Classic),
+            "casual" => Ok(Self::Casual),
+            "speedrun" => Ok(Self::Speedrun),
+            _ => Err(()),
+        }
+    }
+}
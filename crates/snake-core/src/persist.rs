@@ -0,0 +1,149 @@
+//! Crash-safe saving of player files.
+//!
+//! [`write_atomic`] writes the new contents to a temporary file next to the
+//! target, syncs it, moves the previous file aside as a backup and only then
+//! renames the new file into place, so a crash at any point leaves either the
+//! old or the new file intact. [`read_with_backup`] falls back to that backup
+//! when the current file is missing or fails the caller's own validation.
+//! [`load_json`] and [`save_json`] put the two together for the player files
+//! kept as JSON.
+//!
+//! On the web there is no file system, so both go to the browser's
+//! `localStorage` instead, keyed by path, with the bytes hex-encoded.
+
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::write_atomic;
+#[cfg(target_arch = "wasm32")]
+pub use local_storage::write_atomic;
+
+#[cfg(not(target_arch = "wasm32"))]
+use file::read;
+#[cfg(target_arch = "wasm32")]
+use local_storage::read;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use std::{
+        fs::{self, File},
+        io::{self, Write},
+        path::Path,
+    };
+
+    use super::{backup_path, sibling};
+
+    pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = sibling(path, "tmp");
+        let mut file = File::create(&temp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        drop(file);
+        if path.exists() {
+            fs::rename(path, backup_path(path))?;
+        }
+        fs::rename(&temp, path)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod local_storage {
+    use std::{io, path::Path};
+
+    use super::backup_path;
+
+    fn storage() -> io::Result<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no localStorage"))
+    }
+
+    fn key(path: &Path) -> String {
+        format!("snake/{}", path.display())
+    }
+
+    fn refused(_: wasm_bindgen::JsValue) -> io::Error {
+        io::Error::other("localStorage refused the request")
+    }
+
+    /// A single `setItem` cannot be torn, so only the backup needs care.
+    pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+        let storage = storage()?;
+        if let Some(previous) = storage.get_item(&key(path)).map_err(refused)? {
+            storage
+                .set_item(&key(&backup_path(path)), &previous)
+                .map_err(refused)?;
+        }
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        storage.set_item(&key(path), &hex).map_err(refused)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+        let hex = storage()?
+            .get_item(&key(path))
+            .map_err(refused)?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        (0..hex.len())
+            .step_by(2)
+            .map(|start| {
+                hex.get(start..start + 2)
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))
+            })
+            .collect()
+    }
+}
+
+/// Reads and parses `path`, or its backup if that fails. When both fail the
+/// error for `path` itself is returned.
+pub fn read_with_backup<T, E: From<io::Error>>(
+    path: &Path,
+    parse: impl Fn(&[u8]) -> Result<T, E>,
+) -> Result<T, E> {
+    let load = |path: &Path| parse(&read(path)?);
+    load(path).or_else(|err| load(&backup_path(path)).map_err(|_| err))
+}
+
+/// Reads `path` as JSON through [`read_with_backup`], starting afresh with
+/// `T::default()` if neither it nor its backup exists yet.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match read_with_backup(path, |bytes| {
+        serde_json::from_slice::<T>(bytes).map_err(io::Error::from)
+    }) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        result => result,
+    }
+}
+
+/// Writes `value` to `path` as pretty-printed JSON through [`write_atomic`].
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    write_atomic(path, &serde_json::to_vec_pretty(value)?)
+}
+
+/// Where [`write_atomic`] keeps the previous contents of `path`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
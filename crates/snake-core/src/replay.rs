@@ -0,0 +1,250 @@
+//! Recording, sharing and playing back runs.
+//!
+//! A run is fully determined by its RNG seed, the game configuration and the
+//! direction the snake moved in on each movement tick, so a replay only stores
+//! those plus the final score and length of the run for display.
+//!
+//! On-disk layout (all integers little-endian, `varint` is unsigned LEB128):
+//!
+//! | field                  | encoding                            |
+//! |------------------------|-------------------------------------|
+//! | magic                  | `b"SNKR"`                           |
+//! | format version         | `u16`                               |
+//! | seed                   | `u64`                               |
+//! | arena width, height    | `varint`, `varint`                  |
+//! | movement interval (ms) | `varint`                            |
+//! | food interval (ms)     | `varint`                            |
+//! | mode                   | `u8`                                |
+//! | final score            | `varint`                            |
+//! | duration (ticks)       | `varint`                            |
+//! | input count            | `varint`                            |
+//! | inputs                 | `varint` tick delta, `u8` direction |
+
+use std::{io, path::Path};
+
+use crate::{hash::Fnv1a, persist, Direction, GameMode};
+
+pub const REPLAY_VERSION: u16 = 2;
+const MAGIC: &[u8; 4] = b"SNKR";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReplayConfig {
+    pub arena_width: u32,
+    pub arena_height: u32,
+    pub movement_interval_ms: u32,
+    pub food_spawn_interval_ms: u32,
+}
+
+/// Direction the snake moved in from movement tick `tick` onwards.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReplayInput {
+    pub tick: u32,
+    pub direction: Direction,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: ReplayConfig,
+    pub mode: GameMode,
+    pub inputs: Vec<ReplayInput>,
+    pub final_score: u32,
+    /// Movement ticks the run lasted.
+    pub duration_ticks: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("could not access replay file: {0}")]
+    Io(#[from] io::Error),
+    #[error("not a replay file")]
+    BadMagic,
+    #[error("replay format version {0} is not supported (expected {REPLAY_VERSION})")]
+    UnsupportedVersion(u16),
+    #[error("replay file is truncated")]
+    Truncated,
+    #[error("replay file is corrupt: {0}")]
+    Corrupt(&'static str),
+    #[error("replay was recorded with a different configuration: {0:?}")]
+    ConfigMismatch(ReplayConfig),
+}
+
+impl Replay {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + self.inputs.len() * 2);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        write_varint(&mut out, self.config.arena_width.into());
+        write_varint(&mut out, self.config.arena_height.into());
+        write_varint(&mut out, self.config.movement_interval_ms.into());
+        write_varint(&mut out, self.config.food_spawn_interval_ms.into());
+        out.push(encode_mode(self.mode));
+        write_varint(&mut out, self.final_score.into());
+        write_varint(&mut out, self.duration_ticks.into());
+        write_varint(&mut out, self.inputs.len() as u64);
+        let mut last_tick = 0;
+        for input in &self.inputs {
+            write_varint(&mut out, (input.tick - last_tick).into());
+            out.push(encode_direction(input.direction));
+            last_tick = input.tick;
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let seed = u64::from_le_bytes(reader.array()?);
+        let config = ReplayConfig {
+            arena_width: reader.varint_u32()?,
+            arena_height: reader.varint_u32()?,
+            movement_interval_ms: reader.varint_u32()?,
+            food_spawn_interval_ms: reader.varint_u32()?,
+        };
+        let mode = decode_mode(reader.byte()?)?;
+        let final_score = reader.varint_u32()?;
+        let duration_ticks = reader.varint_u32()?;
+        let count = reader.varint()?;
+        let mut inputs = Vec::new();
+        let mut tick = 0u32;
+        for _ in 0..count {
+            tick = tick
+                .checked_add(reader.varint_u32()?)
+                .ok_or(ReplayError::Corrupt("tick overflow"))?;
+            inputs.push(ReplayInput {
+                tick,
+                direction: decode_direction(reader.byte()?)?,
+            });
+        }
+        if !reader.0.is_empty() {
+            return Err(ReplayError::Corrupt("trailing bytes"));
+        }
+        Ok(Self {
+            seed,
+            config,
+            mode,
+            inputs,
+            final_score,
+            duration_ticks,
+        })
+    }
+
+    pub fn export(&self, path: &Path) -> Result<(), ReplayError> {
+        persist::write_atomic(path, &self.encode())?;
+        Ok(())
+    }
+
+    /// Loads a replay and checks that it was recorded with `expected`. A file
+    /// that fails to decode is replaced by its backup, if it has a valid one.
+    pub fn import(path: &Path, expected: &ReplayConfig) -> Result<Self, ReplayError> {
+        let replay = persist::read_with_backup(path, Self::decode)?;
+        if replay.config != *expected {
+            return Err(ReplayError::ConfigMismatch(replay.config));
+        }
+        Ok(replay)
+    }
+
+    /// Fingerprint of the encoded replay, for referring to it without
+    /// sending the whole file.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        hash.write(&self.encode());
+        hash.finish()
+    }
+
+    /// Direction in effect on movement tick `tick`, if it was recorded.
+    pub fn direction_at(&self, tick: u32) -> Option<Direction> {
+        let index = self.inputs.partition_point(|input| input.tick <= tick);
+        index
+            .checked_sub(1)
+            .map(|index| self.inputs[index].direction)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        if self.0.len() < len {
+            return Err(ReplayError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    fn byte(&mut self) -> Result<u8, ReplayError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, ReplayError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Corrupt("varint too long"))
+    }
+
+    fn varint_u32(&mut self) -> Result<u32, ReplayError> {
+        u32::try_from(self.varint()?).map_err(|_| ReplayError::Corrupt("value out of range"))
+    }
+}
+
+fn encode_direction(direction: Direction) -> u8 {
+    match direction {
+        Direction::Left => 0,
+        Direction::Right => 1,
+        Direction::Up => 2,
+        Direction::Down => 3,
+    }
+}
+
+fn decode_direction(byte: u8) -> Result<Direction, ReplayError> {
+    match byte {
+        0 => Ok(Direction::Left),
+        1 => Ok(Direction::Right),
+        2 => Ok(Direction::Up),
+        3 => Ok(Direction::Down),
+        _ => Err(ReplayError::Corrupt("unknown direction")),
+    }
+}
+
+fn encode_mode(mode: GameMode) -> u8 {
+    match mode {
+        GameMode::Classic => 0,
+        GameMode::Casual => 1,
+        GameMode::Speedrun => 2,
+    }
+}
+
+fn decode_mode(byte: u8) -> Result<GameMode, ReplayError> {
+    match byte {
+        0 => Ok(GameMode::Classic),
+        1 => Ok(GameMode::Casual),
+        2 => Ok(GameMode::Speedrun),
+        _ => Err(ReplayError::Corrupt("unknown mode")),
+    }
+}
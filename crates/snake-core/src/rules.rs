@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Arena, Direction, Position};
+
+pub const START_POSITION: Position = Position { x: 3, y: 3 };
+pub const START_DIRECTION: Direction = Direction::Up;
+
+pub const SPEEDRUN_TARGET_SCORE: u32 = 20;
+
+pub const MOVEMENT_INTERVAL: Duration = Duration::from_millis(150);
+pub const FOOD_SPAWN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a head moving into a cell would run into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    Wall,
+    Body,
+}
+
+/// Checks the cell the head is about to enter against the arena edge and the
+/// body as it is before the move, head first. The tail cell counts too, even
+/// though the tail would move out of it on the same tick.
+pub fn collision(arena: Arena, body: &[Position], next_head: Position) -> Option<Collision> {
+    if !arena.contains(next_head) {
+        Some(Collision::Wall)
+    } else if body.contains(&next_head) {
+        Some(Collision::Body)
+    } else {
+        None
+    }
+}
+
+/// Picks a random cell for new food that isn't under any of `heads`.
+pub fn random_food_position(rng: &mut ChaCha8Rng, arena: Arena, heads: &[Position]) -> Position {
+    loop {
+        let position = Position {
+            x: rng.random_range(0..arena.width as i32),
+            y: rng.random_range(0..arena.height as i32),
+        };
+        if !heads.contains(&position) {
+            return position;
+        }
+    }
+}
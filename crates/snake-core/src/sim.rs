@@ -0,0 +1,91 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    collision, hash::Fnv1a, random_food_position, Arena, Direction, Position, START_POSITION,
+};
+
+/// Entity-free copy of the game rules, stepped manually. Given the same seed
+/// and inputs it plays out exactly like the game client's ECS systems, which
+/// lets it re-run recorded games without touching the live board.
+pub struct Simulation {
+    /// Segment positions, head first.
+    pub body: Vec<Position>,
+    pub food: Vec<Position>,
+    pub score: u32,
+    pub alive: bool,
+    last_tail_position: Option<Position>,
+    arena: Arena,
+    rng: ChaCha8Rng,
+}
+
+impl Simulation {
+    pub fn new(seed: u64, arena: Arena) -> Self {
+        Self {
+            body: vec![START_POSITION, START_POSITION],
+            food: Vec::new(),
+            score: 0,
+            alive: true,
+            last_tail_position: None,
+            arena,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// One movement tick: move, then eat and grow, mirroring `snake_movement`,
+    /// `snake_eating` and `snake_growth`.
+    pub fn step(&mut self, direction: Direction) {
+        if !self.alive {
+            return;
+        }
+        let head = self.body[0].step(direction);
+        if collision(self.arena, &self.body, head).is_some() {
+            self.alive = false;
+            return;
+        }
+        self.last_tail_position = self.body.last().copied();
+        self.body.pop();
+        self.body.insert(0, head);
+
+        let food_before = self.food.len();
+        self.food.retain(|&food| food != head);
+        let eaten = food_before - self.food.len();
+        self.score += eaten as u32;
+        if eaten > 0 {
+            if let Some(tail) = self.last_tail_position {
+                self.body.push(tail);
+            }
+        }
+    }
+
+    /// Fingerprint of the board, see [`state_hash`].
+    pub fn state_hash(&self) -> u64 {
+        state_hash(&self.body, &self.food, self.score)
+    }
+
+    /// One food spawn tick, mirroring `food_spawner`.
+    pub fn spawn_food(&mut self) {
+        if !self.alive {
+            return;
+        }
+        let position = random_food_position(&mut self.rng, self.arena, &self.body[..1]);
+        self.food.push(position);
+    }
+}
+
+/// FNV-1a hash of a board, stable across platforms and builds so that
+/// independent runs can compare it. Food order does not matter.
+pub fn state_hash(body: &[Position], food: &[Position], score: u32) -> u64 {
+    let mut food = food.to_vec();
+    food.sort_by_key(|position| (position.x, position.y));
+    let mut hash = Fnv1a::new();
+    hash.write(&score.to_le_bytes());
+    for list in [body, &food[..]] {
+        hash.write(&(list.len() as u32).to_le_bytes());
+        for position in list {
+            hash.write(&position.x.to_le_bytes());
+            hash.write(&position.y.to_le_bytes());
+        }
+    }
+    hash.finish()
+}
@@ -0,0 +1,182 @@
+//! Reference AIs of increasing strength, for demos, opponents and as
+//! baselines for new bots.
+//!
+//! - [`Greedy`] steps towards the nearest food and only avoids dying on the
+//!   very next move.
+//! - [`AStar`] follows the shortest path around the snakes to the nearest
+//!   food, unless that walks into a dead end, and otherwise plays for time
+//!   like [`autopilot`].
+//! - [`Hamiltonian`] follows a fixed cycle through every cell of the arena,
+//!   so it never dies and eventually fills the whole board, slowly.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    autopilot::{autopilot, free_area},
+    controller::{BoardView, SnakeController},
+    Arena, Direction, Position,
+};
+
+pub struct Greedy;
+
+impl SnakeController for Greedy {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        let head = board.head()?;
+        board
+            .safe_moves()
+            .min_by_key(|&direction| board.food_distance(head.step(direction)))
+    }
+}
+
+pub struct AStar;
+
+impl SnakeController for AStar {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        let head = board.head()?;
+        match shortest_path_to_food(board) {
+            Some(direction) if free_area(board, head.step(direction)) >= board.body.len() => {
+                Some(direction)
+            }
+            _ => autopilot(board),
+        }
+    }
+}
+
+fn index(arena: Arena, position: Position) -> usize {
+    (position.y as u32 * arena.width + position.x as u32) as usize
+}
+
+/// First move of a shortest path from the head to any food, treating every
+/// snake as a wall for the whole path.
+fn shortest_path_to_food(board: &BoardView) -> Option<Direction> {
+    let head = board.head()?;
+    if board.food.is_empty() {
+        return None;
+    }
+    let arena = board.arena;
+    let mut cost = vec![u32::MAX; (arena.width * arena.height) as usize];
+    let mut first = vec![None; cost.len()];
+    let mut open = BinaryHeap::new();
+    for direction in board.safe_moves() {
+        let next = head.step(direction);
+        cost[index(arena, next)] = 1;
+        first[index(arena, next)] = Some(direction);
+        open.push(Reverse((1 + board.food_distance(next), 1, next.x, next.y)));
+    }
+    while let Some(Reverse((_, steps, x, y))) = open.pop() {
+        let position = Position { x, y };
+        if board.food.contains(&position) {
+            return first[index(arena, position)];
+        }
+        if steps > cost[index(arena, position)] {
+            continue;
+        }
+        for direction in Direction::ALL {
+            let next = position.step(direction);
+            if board.blocked(next) || steps + 1 >= cost[index(arena, next)] {
+                continue;
+            }
+            cost[index(arena, next)] = steps + 1;
+            first[index(arena, next)] = first[index(arena, position)];
+            open.push(Reverse((
+                steps + 1 + board.food_distance(next),
+                steps + 1,
+                next.x,
+                next.y,
+            )));
+        }
+    }
+    None
+}
+
+/// Needs an arena with an even side and both sides at least 2; on others,
+/// which have no Hamiltonian cycle, it plays like [`autopilot`]. It also
+/// assumes it has steered since the start of the run.
+#[derive(Default)]
+pub struct Hamiltonian {
+    /// Direction out of each cell along the cycle, for the arena it was built
+    /// for.
+    cycle: Option<(Arena, Vec<Direction>)>,
+}
+
+impl SnakeController for Hamiltonian {
+    fn steer(&mut self, board: &BoardView) -> Option<Direction> {
+        let head = board.head()?;
+        if self
+            .cycle
+            .as_ref()
+            .is_none_or(|(arena, _)| *arena != board.arena)
+        {
+            self.cycle = hamiltonian_cycle(board.arena).map(|cycle| (board.arena, cycle));
+        }
+        let Some((arena, cycle)) = &self.cycle else {
+            return autopilot(board);
+        };
+        // The cycle can be run either way round. Going backwards, the forward
+        // direction always points back at the neck.
+        let forward = cycle[index(*arena, head)];
+        let direction = if forward == board.direction.opposite() {
+            Direction::ALL
+                .into_iter()
+                .find(|&direction| {
+                    let previous = head.step(direction);
+                    arena.contains(previous)
+                        && cycle[index(*arena, previous)] == direction.opposite()
+                })
+                .unwrap_or(forward)
+        } else {
+            forward
+        };
+        if board.blocked(head.step(direction)) {
+            autopilot(board)
+        } else {
+            Some(direction)
+        }
+    }
+}
+
+/// Direction out of each cell along a cycle through the whole arena: along
+/// the bottom row, then back and forth over the other rows leaving the first
+/// column free for the way back down.
+fn hamiltonian_cycle(arena: Arena) -> Option<Vec<Direction>> {
+    let (width, height) = (arena.width as i32, arena.height as i32);
+    let transposed = height % 2 != 0;
+    let (width, height) = if transposed {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    if width < 2 || height < 2 || height % 2 != 0 {
+        return None;
+    }
+
+    let mut order: Vec<Position> = (0..width).map(|x| Position { x, y: 0 }).collect();
+    for y in 1..height {
+        if y % 2 == 1 {
+            order.extend((1..width).rev().map(|x| Position { x, y }));
+        } else {
+            order.extend((1..width).map(|x| Position { x, y }));
+        }
+    }
+    order.extend((1..height).rev().map(|y| Position { x: 0, y }));
+
+    let mut cycle = vec![Direction::Up; (arena.width * arena.height) as usize];
+    for (step, &from) in order.iter().enumerate() {
+        let to = order[(step + 1) % order.len()];
+        let (from, to) = if transposed {
+            (
+                Position {
+                    x: from.y,
+                    y: from.x,
+                },
+                Position { x: to.y, y: to.x },
+            )
+        } else {
+            (from, to)
+        };
+        cycle[index(arena, from)] = Direction::ALL
+            .into_iter()
+            .find(|&direction| from.step(direction) == to)?;
+    }
+    Some(cycle)
+}
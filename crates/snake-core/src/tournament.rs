@@ -0,0 +1,74 @@
+//! Bot-versus-bot matches, for measuring whether a controller change helps.
+//!
+//! [`tournament`] plays seeded two-snake survival matches between fresh
+//! controllers and tallies the results. The contestants swap starting corners
+//! every game so neither gets the better side of the board throughout.
+
+use crate::{
+    controller::{steer_match, SnakeController},
+    versus::Match,
+    Arena,
+};
+
+/// Matches still going after this many ticks count as draws.
+pub const MAX_TICKS: u32 = 10_000;
+
+/// Results for the two contestants, in the order they were given.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Standings {
+    pub games: u32,
+    pub wins: [u32; 2],
+    pub draws: u32,
+    /// Sum over all games of the longest each snake grew.
+    pub total_length: [u32; 2],
+}
+
+impl Standings {
+    pub fn win_rate(&self, contestant: usize) -> f64 {
+        f64::from(self.wins[contestant]) / f64::from(self.games.max(1))
+    }
+
+    pub fn average_length(&self, contestant: usize) -> f64 {
+        f64::from(self.total_length[contestant]) / f64::from(self.games.max(1))
+    }
+}
+
+/// Plays `games` matches on `arena`, seeded from `seed` upwards.
+/// `new_controller` builds a fresh controller for contestant 0 or 1 at the
+/// start of every game.
+pub fn tournament(
+    arena: Arena,
+    seed: u64,
+    games: u32,
+    mut new_controller: impl FnMut(usize) -> Box<dyn SnakeController>,
+) -> Standings {
+    let mut standings = Standings::default();
+    for game in 0..games {
+        // Snake index of each contestant.
+        let seats = if game % 2 == 0 { [0, 1] } else { [1, 0] };
+        let mut controllers = [new_controller(0), new_controller(1)];
+        let mut played = Match::new(seed.wrapping_add(u64::from(game)), arena, 2);
+        let mut lengths = [0; 2];
+        while !played.finished() && played.tick < MAX_TICKS {
+            for (contestant, controller) in controllers.iter_mut().enumerate() {
+                let snake = seats[contestant];
+                lengths[contestant] = lengths[contestant].max(played.snakes[snake].body.len());
+                steer_match(&mut played, snake, controller.as_mut());
+            }
+            played.tick();
+        }
+        for contestant in 0..2 {
+            let snake = &played.snakes[seats[contestant]];
+            lengths[contestant] = lengths[contestant].max(snake.body.len());
+            standings.total_length[contestant] += lengths[contestant] as u32;
+        }
+        match played.winner() {
+            Some(winner) if played.finished() => {
+                standings.wins[usize::from(winner == seats[1])] += 1;
+            }
+            _ => standings.draws += 1,
+        }
+        standings.games += 1;
+    }
+    standings
+}
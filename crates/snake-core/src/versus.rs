@@ -0,0 +1,239 @@
+//! Several snakes on one board, for networked play.
+//!
+//! [`Match`] follows the single-player rules for each snake and adds the
+//! interactions between them: all snakes move at once, a head entering any
+//! snake's body (as it was before the move) dies, and heads meeting on the
+//! same cell both die. Dead snakes leave the board. Food spawns every
+//! [`Match::food_every`] ticks, so a match is fully determined by its seed and
+//! the directions steered on each tick. The [`VersusMode`] decides when the
+//! match ends.
+//!
+//! A snake can also [`Match::shed`] its last segment, leaving it behind as a
+//! [`Shed`] wall for [`SHED_LASTS`] ticks that every snake, its own included,
+//! dies running into. It then has to wait [`SHED_COOLDOWN`] ticks to shed
+//! again.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision, random_food_position, Arena, Direction, Position, FOOD_SPAWN_INTERVAL,
+    MOVEMENT_INTERVAL,
+};
+
+pub const MAX_PLAYERS: usize = 4;
+/// Score that wins a [`VersusMode::Race`].
+pub const RACE_TARGET_SCORE: u32 = 10;
+/// Ticks a snake waits between sheds.
+pub const SHED_COOLDOWN: u32 = 40;
+/// Ticks a shed segment stays on the board.
+pub const SHED_LASTS: u32 = 30;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum VersusMode {
+    /// Last snake standing wins.
+    #[default]
+    Survival,
+    /// The first snake to reach [`RACE_TARGET_SCORE`] wins, or the last one
+    /// standing if that comes first.
+    Race,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Snake {
+    /// Segment positions, head first. Empty once the snake has died.
+    pub body: Vec<Position>,
+    pub direction: Direction,
+    pub alive: bool,
+    pub score: u32,
+    /// First tick the snake may shed on.
+    #[serde(default)]
+    pub shed_ready: u32,
+}
+
+/// A segment a snake shed, in the way until tick `until`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Shed {
+    pub position: Position,
+    pub until: u32,
+}
+
+#[derive(Clone)]
+pub struct Match {
+    pub snakes: Vec<Snake>,
+    pub food: Vec<Position>,
+    pub shed: Vec<Shed>,
+    pub tick: u32,
+    pub food_every: u32,
+    pub mode: VersusMode,
+    arena: Arena,
+    rng: ChaCha8Rng,
+    /// Snakes that asked to shed on the coming tick.
+    shedding: Vec<usize>,
+}
+
+impl Match {
+    /// Starts `players` snakes (at most [`MAX_PLAYERS`]) in the corners of
+    /// the arena, each heading along an edge.
+    pub fn new(seed: u64, arena: Arena, players: usize) -> Self {
+        assert!(players <= MAX_PLAYERS, "at most {MAX_PLAYERS} players");
+        let (right, top) = (arena.width as i32 - 2, arena.height as i32 - 2);
+        let starts = [
+            (Position { x: 1, y: 1 }, Direction::Right),
+            (Position { x: right, y: top }, Direction::Left),
+            (Position { x: right, y: 1 }, Direction::Up),
+            (Position { x: 1, y: top }, Direction::Down),
+        ];
+        Self {
+            snakes: starts[..players]
+                .iter()
+                .map(|&(start, direction)| Snake {
+                    body: vec![start, start],
+                    direction,
+                    alive: true,
+                    score: 0,
+                    shed_ready: 0,
+                })
+                .collect(),
+            food: Vec::new(),
+            shed: Vec::new(),
+            tick: 0,
+            food_every: (FOOD_SPAWN_INTERVAL.as_millis() / MOVEMENT_INTERVAL.as_millis()).max(1)
+                as u32,
+            mode: VersusMode::default(),
+            arena,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            shedding: Vec::new(),
+        }
+    }
+
+    pub fn arena(&self) -> Arena {
+        self.arena
+    }
+
+    /// Changes a snake's direction for the next tick. Reversing onto its own
+    /// neck is ignored, as in single player, and so is steering a dead snake.
+    pub fn steer(&mut self, player: usize, direction: Direction) {
+        if let Some(snake) = self.snakes.get_mut(player) {
+            if snake.alive && direction != snake.direction.opposite() {
+                snake.direction = direction;
+            }
+        }
+    }
+
+    /// Has a snake shed its last segment at the start of the coming tick.
+    /// Ignored for a dead snake, one still cooling down from its last shed,
+    /// or one with no segment to spare behind its neck.
+    pub fn shed(&mut self, player: usize) {
+        self.shedding.push(player);
+    }
+
+    /// One movement tick for every snake, then food if it is due.
+    pub fn tick(&mut self) {
+        self.tick += 1;
+        let tick = self.tick;
+        self.shed.retain(|shed| shed.until > tick);
+        for player in std::mem::take(&mut self.shedding) {
+            let Some(snake) = self
+                .snakes
+                .get_mut(player)
+                .filter(|snake| snake.alive && snake.body.len() > 2 && tick >= snake.shed_ready)
+            else {
+                continue;
+            };
+            if let Some(tail) = snake.body.pop() {
+                snake.shed_ready = tick + SHED_COOLDOWN;
+                self.shed.push(Shed {
+                    position: tail,
+                    until: tick + SHED_LASTS,
+                });
+            }
+        }
+        let heads: Vec<Option<Position>> = self
+            .snakes
+            .iter()
+            .map(|snake| snake.alive.then(|| snake.body[0].step(snake.direction)))
+            .collect();
+        let bodies: Vec<Position> = self
+            .snakes
+            .iter()
+            .flat_map(|snake| snake.body.iter().copied())
+            .chain(self.shed.iter().map(|shed| shed.position))
+            .collect();
+        for (player, head) in heads.iter().enumerate() {
+            let Some(head) = *head else {
+                continue;
+            };
+            let head_on = heads
+                .iter()
+                .enumerate()
+                .any(|(other, other_head)| other != player && *other_head == Some(head));
+            let snake = &mut self.snakes[player];
+            if head_on || collision(self.arena, &bodies, head).is_some() {
+                snake.alive = false;
+                snake.body.clear();
+                continue;
+            }
+            let tail = snake.body.pop();
+            snake.body.insert(0, head);
+            let food_before = self.food.len();
+            self.food.retain(|&food| food != head);
+            if self.food.len() < food_before {
+                snake.score += (food_before - self.food.len()) as u32;
+                snake.body.extend(tail);
+            }
+        }
+        if self.tick.is_multiple_of(self.food_every) {
+            self.spawn_food();
+        }
+    }
+
+    fn spawn_food(&mut self) {
+        let heads: Vec<Position> = self
+            .snakes
+            .iter()
+            .filter_map(|snake| snake.body.first().copied())
+            .collect();
+        let position = random_food_position(&mut self.rng, self.arena, &heads);
+        self.food.push(position);
+    }
+
+    pub fn alive(&self) -> usize {
+        self.snakes.iter().filter(|snake| snake.alive).count()
+    }
+
+    /// A match is over once at most one snake is left, or none in a solo
+    /// match, or a race has been won.
+    pub fn finished(&self) -> bool {
+        self.alive() <= usize::from(self.snakes.len() > 1) || self.race_winners().next().is_some()
+    }
+
+    /// The last snake standing, or the race winner, if there is exactly one.
+    pub fn winner(&self) -> Option<usize> {
+        let mut racers = self.race_winners();
+        if let Some(first) = racers.next() {
+            return racers.next().is_none().then_some(first);
+        }
+        let mut alive = self
+            .snakes
+            .iter()
+            .enumerate()
+            .filter(|(_, snake)| snake.alive);
+        match (alive.next(), alive.next()) {
+            (Some((player, _)), None) => Some(player),
+            _ => None,
+        }
+    }
+
+    /// Living snakes at the race target, none outside a race.
+    fn race_winners(&self) -> impl Iterator<Item = usize> + '_ {
+        self.snakes
+            .iter()
+            .enumerate()
+            .filter(move |(_, snake)| {
+                self.mode == VersusMode::Race && snake.alive && snake.score >= RACE_TARGET_SCORE
+            })
+            .map(|(player, _)| player)
+    }
+}
@@ -0,0 +1,165 @@
+use snake_core::{
+    controller::{bot, steer_match, Autopilot, BoardView, Difficulty, Opponent, SnakeController},
+    sim::Simulation,
+    solvers::{AStar, Greedy, Hamiltonian},
+    tournament::tournament,
+    versus::Match,
+    Arena, Direction, Position, START_DIRECTION,
+};
+
+/// Plays one run of at most `ticks`, spawning food every `food_every` ticks,
+/// and returns the finished simulation.
+fn play(
+    controller: &mut dyn SnakeController,
+    seed: u64,
+    arena: Arena,
+    ticks: u32,
+    food_every: u32,
+    until_full: bool,
+) -> Simulation {
+    let mut sim = Simulation::new(seed, arena);
+    let mut direction = START_DIRECTION;
+    for tick in 1..=ticks {
+        let board = BoardView {
+            arena,
+            body: &sim.body,
+            others: &[],
+            food: &sim.food,
+            direction,
+        };
+        if let Some(next) = controller.steer(&board) {
+            if next != direction.opposite() {
+                direction = next;
+            }
+        }
+        sim.step(direction);
+        if !sim.alive || (until_full && sim.body.len() == (arena.width * arena.height) as usize) {
+            break;
+        }
+        if tick.is_multiple_of(food_every) {
+            sim.spawn_food();
+        }
+    }
+    sim
+}
+
+#[test]
+fn autopilot_eats_before_it_dies() {
+    for seed in 0..20 {
+        let sim = play(&mut Autopilot, seed, Arena::default(), 300, 7, false);
+        assert!(sim.score >= 10, "seed {seed}: score {}", sim.score);
+    }
+}
+
+#[test]
+fn astar_outscores_greedy() {
+    let total = |controller: &mut dyn SnakeController| {
+        (0..20)
+            .map(|seed| play(controller, seed, Arena::default(), 500, 7, false).score)
+            .sum::<u32>()
+    };
+    assert!(total(&mut AStar) > total(&mut Greedy));
+}
+
+#[test]
+fn hamiltonian_fills_the_board() {
+    for arena in [
+        Arena {
+            width: 6,
+            height: 6,
+        },
+        Arena {
+            width: 7,
+            height: 4,
+        },
+        Arena {
+            width: 4,
+            height: 5,
+        },
+    ] {
+        let sim = play(&mut Hamiltonian::default(), 3, arena, 100_000, 1, true);
+        assert!(sim.alive, "{arena:?}");
+        assert_eq!(
+            sim.body.len(),
+            (arena.width * arena.height) as usize,
+            "{arena:?}"
+        );
+    }
+}
+
+#[test]
+fn hamiltonian_turns_back_when_the_cycle_runs_the_other_way() {
+    let arena = Arena::default();
+    let mut bot = Hamiltonian::default();
+    let body = [Position { x: 0, y: 0 }; 2];
+    let board = BoardView {
+        arena,
+        body: &body,
+        others: &[],
+        food: &[],
+        direction: Direction::Left,
+    };
+    assert_eq!(bot.steer(&board), Some(Direction::Up));
+}
+
+#[test]
+fn harder_opponents_win_more_often() {
+    let mut wins = [0; 3];
+    for seed in 0..30 {
+        let mut game = Match::new(seed, Arena::default(), 2);
+        let mut weaker = Opponent::new(Difficulty::Beginner, seed);
+        let mut stronger = Opponent::new(Difficulty::Expert, seed);
+        while !game.finished() && game.tick < 2_000 {
+            steer_match(&mut game, 0, &mut weaker);
+            steer_match(&mut game, 1, &mut stronger);
+            game.tick();
+        }
+        wins[game.winner().map_or(2, |winner| winner)] += 1;
+    }
+    assert!(wins[1] > wins[0], "{wins:?}");
+}
+
+#[test]
+fn tournaments_tally_every_game() {
+    let arena = Arena {
+        width: 20,
+        height: 20,
+    };
+    let standings = tournament(arena, 5, 10, |contestant| {
+        bot(["astar", "greedy"][contestant]).unwrap()
+    });
+    assert_eq!(standings.games, 10);
+    assert_eq!(standings.wins[0] + standings.wins[1] + standings.draws, 10);
+    assert!(standings.wins[0] > standings.wins[1], "{standings:?}");
+    assert!(standings.average_length(0) >= 2.0);
+}
+
+#[test]
+fn lookahead_spots_dead_ends() {
+    let arena = Arena {
+        width: 3,
+        height: 2,
+    };
+    // Heading right along the bottom row with the top row free.
+    let body = [Position { x: 1, y: 0 }, Position { x: 0, y: 0 }];
+    let board = BoardView {
+        arena,
+        body: &body,
+        others: &[],
+        food: &[],
+        direction: Direction::Right,
+    };
+    assert!(!board.survives(Direction::Down, 1));
+    assert!(board.survives(Direction::Right, 10));
+    assert!(board.survives(Direction::Up, 10));
+
+    // Another snake fills the top corners: going up is fine for one move and
+    // then nothing is.
+    let others = [Position { x: 0, y: 1 }, Position { x: 2, y: 1 }];
+    let board = BoardView {
+        others: &others,
+        ..board
+    };
+    assert!(board.survives(Direction::Up, 1));
+    assert!(!board.survives(Direction::Up, 2));
+}
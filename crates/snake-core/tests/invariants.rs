@@ -0,0 +1,98 @@
+use proptest::prelude::*;
+use snake_core::{collision, sim::Simulation, Arena, Collision, Direction, Position};
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Step(Direction),
+    SpawnFood,
+}
+
+fn direction() -> impl Strategy<Value = Direction> {
+    prop_oneof![
+        Just(Direction::Left),
+        Just(Direction::Right),
+        Just(Direction::Up),
+        Just(Direction::Down),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => direction().prop_map(Op::Step),
+        1 => Just(Op::SpawnFood),
+    ]
+}
+
+fn arena() -> impl Strategy<Value = Arena> {
+    (4u32..16, 4u32..16).prop_map(|(width, height)| Arena { width, height })
+}
+
+proptest! {
+    #[test]
+    fn length_only_changes_by_growth(seed: u64, arena in arena(), ops in prop::collection::vec(op(), 0..300)) {
+        let mut sim = Simulation::new(seed, arena);
+        for op in ops {
+            let (length, score) = (sim.body.len(), sim.score);
+            match op {
+                Op::Step(direction) => sim.step(direction),
+                Op::SpawnFood => sim.spawn_food(),
+            }
+            // Food can stack on a cell; eating a stack still grows one segment.
+            prop_assert_eq!(sim.body.len() - length, usize::from(sim.score > score));
+        }
+    }
+
+    #[test]
+    fn segments_never_share_a_cell(seed: u64, arena in arena(), ops in prop::collection::vec(op(), 0..300)) {
+        let mut sim = Simulation::new(seed, arena);
+        for op in ops {
+            match op {
+                Op::Step(direction) => sim.step(direction),
+                Op::SpawnFood => sim.spawn_food(),
+            }
+            // Growing appends the old tail cell, so segments may stack on the
+            // tail until it moves off; every other cell is distinct.
+            let mut cells = sim.body.clone();
+            cells.dedup();
+            for (i, cell) in cells.iter().enumerate() {
+                prop_assert!(!cells[i + 1..].contains(cell), "{:?} shares {:?}", sim.body, cell);
+            }
+        }
+    }
+
+    #[test]
+    fn head_leaves_the_arena_only_by_dying(seed: u64, arena in arena(), ops in prop::collection::vec(op(), 0..300)) {
+        let mut sim = Simulation::new(seed, arena);
+        for op in ops {
+            let was_alive = sim.alive;
+            let next_head = match op {
+                Op::Step(direction) => {
+                    let next_head = sim.body[0].step(direction);
+                    sim.step(direction);
+                    Some(next_head)
+                }
+                Op::SpawnFood => {
+                    sim.spawn_food();
+                    None
+                }
+            };
+            prop_assert!(arena.contains(sim.body[0]));
+            if next_head.is_some_and(|cell| !arena.contains(cell)) || !was_alive {
+                prop_assert!(!sim.alive);
+            }
+        }
+    }
+
+    #[test]
+    fn collision_checks_walls_before_body(arena in arena(), x in -2i32..18, y in -2i32..18) {
+        let cell = Position { x, y };
+        let expected = if !arena.contains(cell) {
+            Some(Collision::Wall)
+        } else if cell == (Position { x: 0, y: 0 }) {
+            Some(Collision::Body)
+        } else {
+            None
+        };
+        prop_assert_eq!(collision(arena, &[Position { x: 0, y: 0 }], cell), expected);
+    }
+}
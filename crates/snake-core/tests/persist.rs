@@ -0,0 +1,45 @@
+use std::{fs, path::PathBuf};
+
+use snake_core::persist::{backup_path, load_json, read_with_backup, save_json, write_atomic};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("snake-persist-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn parse(bytes: &[u8]) -> Result<String, std::io::Error> {
+    String::from_utf8(bytes.to_vec()).map_err(std::io::Error::other)
+}
+
+#[test]
+fn overwriting_keeps_the_previous_file_as_backup() {
+    let path = scratch_dir("overwrite").join("nested/scores");
+    write_atomic(&path, b"first").unwrap();
+    write_atomic(&path, b"second").unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"second");
+    assert_eq!(fs::read(backup_path(&path)).unwrap(), b"first");
+}
+
+#[test]
+fn corrupt_file_falls_back_to_backup() {
+    let path = scratch_dir("corrupt").join("scores");
+    write_atomic(&path, b"first").unwrap();
+    write_atomic(&path, b"second").unwrap();
+    fs::write(&path, [0xff, 0xfe]).unwrap();
+    assert_eq!(read_with_backup(&path, parse).unwrap(), "first");
+}
+
+#[test]
+fn missing_file_without_backup_is_an_error() {
+    let path = scratch_dir("missing").join("scores");
+    assert!(read_with_backup(&path, parse).is_err());
+}
+
+#[test]
+fn json_round_trips_and_starts_afresh_when_missing() {
+    let path = scratch_dir("json").join("scores.json");
+    assert_eq!(load_json::<Vec<u32>>(&path).unwrap(), Vec::<u32>::new());
+    save_json(&path, &vec![3, 1, 2]).unwrap();
+    assert_eq!(load_json::<Vec<u32>>(&path).unwrap(), vec![3, 1, 2]);
+}
@@ -0,0 +1,73 @@
+use snake_core::{
+    versus::{Match, Shed, SHED_COOLDOWN, SHED_LASTS},
+    Arena, Position,
+};
+
+/// A two-player match where the first snake, heading right from the top left
+/// corner, has eaten twice and is four segments long.
+fn grown_match() -> Match {
+    let mut game = Match::new(1, Arena::default(), 2);
+    game.food_every = u32::MAX;
+    game.food = vec![Position { x: 2, y: 1 }, Position { x: 3, y: 1 }];
+    game.tick();
+    game.tick();
+    assert_eq!(game.snakes[0].body.len(), 4);
+    game
+}
+
+#[test]
+fn shedding_leaves_the_tail_behind_as_a_wall() {
+    let mut game = grown_match();
+    let tail = *game.snakes[0].body.last().unwrap();
+    game.shed(0);
+    game.tick();
+    assert_eq!(game.snakes[0].body.len(), 3);
+    assert_eq!(game.shed.len(), 1);
+    assert_eq!(game.shed[0].position, tail);
+}
+
+#[test]
+fn shedding_waits_for_the_cooldown() {
+    let mut game = grown_match();
+    game.shed(0);
+    game.tick();
+    game.shed(0);
+    game.tick();
+    assert_eq!(game.shed.len(), 1, "shed again while cooling down");
+    assert_eq!(game.snakes[0].body.len(), 3);
+    assert_eq!(game.snakes[0].shed_ready, game.tick - 1 + SHED_COOLDOWN);
+}
+
+#[test]
+fn short_snakes_cannot_shed() {
+    let mut game = Match::new(1, Arena::default(), 2);
+    game.food_every = u32::MAX;
+    game.shed(0);
+    game.tick();
+    assert!(game.shed.is_empty());
+    assert_eq!(game.snakes[0].body.len(), 2);
+}
+
+#[test]
+fn shed_segments_crumble_away() {
+    let mut game = grown_match();
+    game.shed(0);
+    game.tick();
+    for _ in 0..SHED_LASTS {
+        assert_eq!(game.shed.len(), 1);
+        game.tick();
+    }
+    assert!(game.shed.is_empty());
+}
+
+#[test]
+fn running_into_a_shed_segment_is_fatal() {
+    let mut game = grown_match();
+    let ahead = game.snakes[1].body[0].step(game.snakes[1].direction);
+    game.shed.push(Shed {
+        position: ahead,
+        until: u32::MAX,
+    });
+    game.tick();
+    assert!(!game.snakes[1].alive);
+}
@@ -0,0 +1,42 @@
+//! Lists every file under `assets/`, for the `embedded-assets` feature to
+//! bake into the executable.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+fn main() -> io::Result<()> {
+    println!("cargo:rerun-if-changed=assets");
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    let assets = manifest_dir.join("assets");
+    let mut files = Vec::new();
+    collect(&assets, &mut files)?;
+    files.sort();
+    let mut list = String::from("&[\n");
+    for file in files {
+        let path = file
+            .strip_prefix(&assets)
+            .expect("collected under assets/")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        list += &format!("    ({path:?}, include_bytes!({file:?}).as_slice()),\n");
+    }
+    list += "]\n";
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("set by cargo"));
+    fs::write(out_dir.join("embedded_assets.rs"), list)
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
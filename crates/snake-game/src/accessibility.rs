@@ -0,0 +1,73 @@
+//! High-contrast and reduced-motion modes, switched on with `high_contrast`
+//! and `reduced_motion` in `assets/config.ron`.
+//!
+//! High contrast replaces the configured theme with [`HIGH_CONTRAST`] and
+//! draws a thick outline around every snake segment. Reduced motion is for
+//! effects that shake the view, flash or spawn particles: each checks
+//! [`Accessibility::reduced_motion`] and leaves itself out when it is set.
+
+use bevy::prelude::*;
+
+use crate::{SnakeSegment, Theme};
+
+/// Black board, white head, yellow body, green food and blue obstacles, with
+/// Bevy's built-in font, which reads more easily than the pixel one.
+pub(crate) const HIGH_CONTRAST: Theme = Theme {
+    background: Color::linear_rgb(0.0, 0.0, 0.0),
+    snake_head: Color::linear_rgb(1.0, 1.0, 1.0),
+    snake_segment: Color::linear_rgb(1.0, 1.0, 0.0),
+    food: Color::linear_rgb(0.0, 1.0, 0.0),
+    obstacle: Color::linear_rgb(0.0, 0.6, 1.0),
+    font: None,
+};
+const OUTLINE_COLOR: Color = Color::linear_rgb(1.0, 1.0, 1.0);
+/// Outline size relative to the segment it surrounds.
+const OUTLINE_SCALE: f32 = 1.3;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Accessibility {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+}
+
+/// A segment's outline: a larger sprite drawn just behind it.
+#[derive(Component)]
+pub(crate) struct Outline;
+
+/// Marks segments that already have an [`Outline`] child.
+#[derive(Component)]
+pub(crate) struct Outlined;
+
+/// Gives segments outlines while high contrast is on and removes them when
+/// it is switched off. Pooled segments keep theirs, hidden along with them.
+pub(crate) fn sync_outlines(
+    mut commands: Commands,
+    accessibility: Res<Accessibility>,
+    bare: Query<Entity, (With<SnakeSegment>, Without<Outlined>)>,
+    outlined: Query<Entity, With<Outlined>>,
+    outlines: Query<Entity, With<Outline>>,
+) {
+    if accessibility.high_contrast {
+        for segment in &bare {
+            commands.entity(segment).insert(Outlined).with_child((
+                Outline,
+                Sprite {
+                    color: OUTLINE_COLOR,
+                    ..Default::default()
+                },
+                Transform::from_xyz(0.0, 0.0, -0.5).with_scale(Vec3::new(
+                    OUTLINE_SCALE,
+                    OUTLINE_SCALE,
+                    1.0,
+                )),
+            ));
+        }
+    } else if accessibility.is_changed() {
+        for outline in &outlines {
+            commands.entity(outline).despawn_recursive();
+        }
+        for segment in &outlined {
+            commands.entity(segment).remove::<Outlined>();
+        }
+    }
+}
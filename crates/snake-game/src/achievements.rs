@@ -0,0 +1,374 @@
+//! Achievements kept in the player's [`Profile`], and the screen listing them.
+//!
+//! Each achievement has a target to reach, either in a single run (a score of
+//! 25) or added up over every run (1000 food eaten in total). Progress towards
+//! each one and the time it was unlocked are kept in `achievements.json`, and
+//! updated from every run [`StatsPlugin`](crate::stats::StatsPlugin) records,
+//! so runs the bot plays never count. Each one unlocked is announced in a
+//! [toast](crate::toast) and sent as an [`AchievementUnlocked`] event, and F7
+//! shows the list.
+
+use std::{collections::BTreeMap, fmt::Write as _, io, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use snake_core::{persist, GameMode, SPEEDRUN_TARGET_SCORE};
+
+use crate::{
+    profile::Profile,
+    stats::{RunRecord, RunRecorded},
+    toast::{Toast, ToastPlugin},
+};
+
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+/// Format version written to new progress files.
+pub const PROGRESS_VERSION: u32 = 1;
+
+/// How a run counts towards an [`Achievement`].
+pub enum Goal {
+    /// The best any single run has done.
+    Best(fn(&RunRecord) -> u64),
+    /// Added up over every run.
+    Total(fn(&RunRecord) -> u64),
+}
+
+pub struct Achievement {
+    /// Key in the progress file; never change one that has shipped.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub goal: Goal,
+    /// Unlocked once progress reaches this.
+    pub target: u64,
+}
+
+fn food_eaten(run: &RunRecord) -> u64 {
+    run.foods.values().map(|&count| u64::from(count)).sum()
+}
+
+/// Whether the run ended by reaching its goal rather than by crashing.
+fn won(run: &RunRecord) -> bool {
+    matches!(run.cause.as_str(), "won" | "finished")
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_bite",
+        name: "First bite",
+        description: "Eat a piece of food",
+        goal: Goal::Best(food_eaten),
+        target: 1,
+    },
+    Achievement {
+        id: "score_25",
+        name: "Getting long",
+        description: "Score 25 in one run",
+        goal: Goal::Best(|run| run.score.into()),
+        target: 25,
+    },
+    Achievement {
+        id: "classic_50",
+        name: "Classic",
+        description: "Score 50 in one classic run",
+        goal: Goal::Best(|run| match run.mode {
+            GameMode::Classic => run.score.into(),
+            _ => 0,
+        }),
+        target: 50,
+    },
+    Achievement {
+        id: "length_10",
+        name: "Growing up",
+        description: "Reach a length of 10",
+        goal: Goal::Best(|run| run.max_length as u64),
+        target: 10,
+    },
+    Achievement {
+        id: "length_25",
+        name: "Long boi",
+        description: "Reach a length of 25",
+        goal: Goal::Best(|run| run.max_length as u64),
+        target: 25,
+    },
+    Achievement {
+        id: "length_50",
+        name: "Anaconda",
+        description: "Reach a length of 50",
+        goal: Goal::Best(|run| run.max_length as u64),
+        target: 50,
+    },
+    Achievement {
+        id: "survive_5_minutes",
+        name: "Survivor",
+        description: "Stay alive for 5 minutes in one run",
+        goal: Goal::Best(|run| run.duration_ms / 1000),
+        target: 5 * 60,
+    },
+    Achievement {
+        id: "speedrun_finished",
+        name: "Against the clock",
+        description: "Finish a speedrun",
+        goal: Goal::Best(|run| {
+            (run.mode == GameMode::Speedrun
+                && run.cause == "finished"
+                && run.score >= SPEEDRUN_TARGET_SCORE)
+                .into()
+        }),
+        target: 1,
+    },
+    Achievement {
+        id: "food_100",
+        name: "Snacking",
+        description: "Eat 100 food in total",
+        goal: Goal::Total(food_eaten),
+        target: 100,
+    },
+    Achievement {
+        id: "food_1000",
+        name: "Glutton",
+        description: "Eat 1000 food in total",
+        goal: Goal::Total(food_eaten),
+        target: 1000,
+    },
+    Achievement {
+        id: "runs_50",
+        name: "Regular",
+        description: "Play 50 runs",
+        goal: Goal::Total(|_| 1),
+        target: 50,
+    },
+    Achievement {
+        id: "hour_played",
+        name: "Marathon",
+        description: "Play for an hour in total",
+        goal: Goal::Total(|run| run.duration_ms / 1000),
+        target: 60 * 60,
+    },
+    Achievement {
+        id: "close_calls_100",
+        name: "Daredevil",
+        description: "Turn away from 100 crashes in total",
+        goal: Goal::Total(|run| run.close_calls.into()),
+        target: 100,
+    },
+    Achievement {
+        id: "perfect_game",
+        name: "Perfect game",
+        description: "Fill the whole board with the snake",
+        goal: Goal::Best(|run| (run.cause == "won").into()),
+        target: 1,
+    },
+    Achievement {
+        id: "no_left_turns",
+        name: "Right-minded",
+        description: "Win a run without turning left",
+        goal: Goal::Best(|run| (won(run) && run.left_turns == 0).into()),
+        target: 1,
+    },
+];
+
+/// Sent as a run unlocks an achievement.
+#[derive(Event, Clone, Copy)]
+pub struct AchievementUnlocked(pub &'static Achievement);
+
+/// What a player has done towards each achievement, by id.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    pub version: u32,
+    pub progress: BTreeMap<String, u64>,
+    /// Seconds since the Unix epoch when each was unlocked.
+    pub unlocked: BTreeMap<String, u64>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            version: PROGRESS_VERSION,
+            progress: BTreeMap::new(),
+            unlocked: BTreeMap::new(),
+        }
+    }
+}
+
+impl Progress {
+    /// Reads `path`, starting afresh if it does not exist yet.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        persist::load_json(path)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        persist::save_json(path, self)
+    }
+
+    /// Counts `run` towards every achievement and returns the ones it
+    /// unlocked.
+    pub fn record(&mut self, run: &RunRecord) -> Vec<&'static Achievement> {
+        let mut unlocked = Vec::new();
+        for achievement in ACHIEVEMENTS {
+            let progress = self.progress.entry(achievement.id.to_string()).or_default();
+            *progress = match achievement.goal {
+                Goal::Best(amount) => (*progress).max(amount(run)),
+                Goal::Total(amount) => progress.saturating_add(amount(run)),
+            }
+            .min(achievement.target);
+            if *progress >= achievement.target && !self.unlocked.contains_key(achievement.id) {
+                self.unlocked
+                    .insert(achievement.id.to_string(), run.ended_at);
+                unlocked.push(achievement);
+            }
+        }
+        unlocked
+    }
+
+    /// Takes in progress made elsewhere: the further of the two towards each
+    /// achievement, and every unlock at the earlier of its times.
+    pub fn merge(&mut self, other: &Self) {
+        self.version = self.version.max(other.version);
+        for (id, &theirs) in &other.progress {
+            let progress = self.progress.entry(id.clone()).or_default();
+            *progress = (*progress).max(theirs);
+        }
+        for (id, &theirs) in &other.unlocked {
+            self.unlocked
+                .entry(id.clone())
+                .and_modify(|unlocked| *unlocked = (*unlocked).min(theirs))
+                .or_insert(theirs);
+        }
+    }
+}
+
+/// Keeps the profile's achievements up to date and adds the screen.
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToastPlugin>() {
+            app.add_plugins(ToastPlugin);
+        }
+        app.init_resource::<Profile>()
+            .add_event::<RunRecorded>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(Startup, spawn_screen)
+            .add_systems(
+                Update,
+                (
+                    record_progress.run_if(on_event::<RunRecorded>),
+                    toggle_screen,
+                ),
+            );
+    }
+}
+
+fn record_progress(
+    mut reader: EventReader<RunRecorded>,
+    profile: Res<Profile>,
+    mut toasts: EventWriter<Toast>,
+    mut unlocks: EventWriter<AchievementUnlocked>,
+) {
+    let path = profile.path(ACHIEVEMENTS_FILE);
+    let mut progress = match Progress::read(&path) {
+        Ok(progress) => progress,
+        Err(err) => {
+            // Leaves the file alone rather than wiping what it held.
+            warn!("could not read {}: {err}", path.display());
+            return;
+        }
+    };
+    for RunRecorded(run) in reader.read() {
+        for achievement in progress.record(run) {
+            info!("achievement unlocked: {}", achievement.name);
+            toasts.send(Toast(format!("Achievement unlocked: {}", achievement.name)));
+            unlocks.send(AchievementUnlocked(achievement));
+        }
+    }
+    if let Err(err) = progress.write(&path) {
+        warn!("could not save {}: {err}", path.display());
+    }
+}
+
+#[derive(Component)]
+struct AchievementsScreen;
+
+fn spawn_screen(mut commands: Commands) {
+    commands.spawn((
+        AchievementsScreen,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_screen(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<Profile>,
+    mut screens: Query<(&mut Visibility, &mut Text), With<AchievementsScreen>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F7) {
+        return;
+    }
+    let Ok((mut visibility, mut text)) = screens.get_single_mut() else {
+        return;
+    };
+    visibility.toggle_visible_hidden();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let progress = Progress::read(&profile.path(ACHIEVEMENTS_FILE)).unwrap_or_default();
+    text.0 = describe(&progress);
+}
+
+fn describe(progress: &Progress) -> String {
+    let mut text = format!(
+        "Achievements ({}/{})",
+        progress.unlocked.len(),
+        ACHIEVEMENTS.len()
+    );
+    for achievement in ACHIEVEMENTS {
+        let _ = write!(
+            text,
+            "\n{} - {}: ",
+            achievement.name, achievement.description
+        );
+        match progress.unlocked.get(achievement.id) {
+            Some(&at) => {
+                let _ = write!(text, "unlocked {}", date(at));
+            }
+            None => {
+                let done = progress.progress.get(achievement.id).copied().unwrap_or(0);
+                let _ = write!(text, "{done}/{}", achievement.target);
+            }
+        }
+    }
+    text
+}
+
+/// `YYYY-MM-DD` for a time in seconds since the Unix epoch, in UTC.
+pub(crate) fn date(seconds: u64) -> String {
+    // Howard Hinnant's days-to-civil algorithm.
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
@@ -0,0 +1,195 @@
+//! Armor: pickups that turn up in the harder modes, where a single crash ends
+//! the run, and plate the snake's tail against the next one.
+//!
+//! Each pickup eaten adds a hit point of [`Armor`], up to [`MAX_ARMOR`], drawn
+//! as that many tail segments in steel. Crashing into a wall or the body with
+//! armor left takes a hit point instead of the run: the snake holds still and
+//! is invulnerable for a moment, as after losing a life, so it can turn away.
+//! One pickup at a time is placed every [`ARMOR_INTERVAL`] while the snake has
+//! room for more. Pickups are placed with randomness of their own, and a run
+//! that eats one is no longer kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, Collision, GameMode, Position};
+
+use crate::{
+    game_over,
+    lives::{Invulnerable, INVULNERABLE_FOR},
+    paint_sprites,
+    power_ups::Occupancy,
+    replay::ReplayRecorder,
+    snake_growth, timer_finished,
+    world_clock::WorldClock,
+    Food, GameOverEvent, GameSet, MovementTick, Obstacle, Segments, Size, SnakeHead, SnakeSegment,
+    Theme, ThemeColor,
+};
+
+pub const ARMOR_INTERVAL: Duration = Duration::from_secs(20);
+/// Most hit points the snake can carry.
+pub const MAX_ARMOR: u32 = 3;
+const ARMOR_COLOR: Color = Color::linear_rgb(0.55, 0.6, 0.68);
+
+/// Hit points of armor the snake carries.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Armor {
+    pub hit_points: u32,
+}
+
+/// Sent when a crash takes a hit point of armor instead of the run.
+#[derive(Event)]
+pub(crate) struct ArmorHit(pub Collision);
+
+/// An armor pickup waiting on the board to be eaten.
+#[derive(Component)]
+pub struct ArmorPickup;
+
+/// Puts an armor pickup down at `position`.
+pub fn spawn_armor(commands: &mut Commands, position: Position) -> Entity {
+    commands
+        .spawn((
+            ArmorPickup,
+            Sprite::from_color(ARMOR_COLOR, Vec2::ONE),
+            position,
+            Size::square(0.7),
+        ))
+        .id()
+}
+
+/// Places armor in the harder modes and plates the tail with it.
+pub struct ArmorPlugin;
+
+impl Plugin for ArmorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Armor>()
+            .insert_resource(ArmorTimer(Timer::new(ARMOR_INTERVAL, TimerMode::Repeating)))
+            .add_systems(
+                FixedUpdate,
+                (
+                    place_armor.in_set(GameSet::Spawning),
+                    put_on_armor
+                        .after(snake_growth)
+                        .before(game_over)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    clear_armor
+                        .after(game_over)
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                paint_armor
+                    .after(paint_sprites)
+                    .in_set(GameSet::Presentation),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct ArmorTimer(Timer);
+
+#[allow(clippy::too_many_arguments)]
+fn place_armor(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    armor: Res<Armor>,
+    mut timer: ResMut<ArmorTimer>,
+    pickups: Query<(), With<ArmorPickup>>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    if !timer.0.tick(clock.delta()).just_finished()
+        || mode.is_casual()
+        || armor.hit_points >= MAX_ARMOR
+        || !pickups.is_empty()
+    {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "armor placed");
+    spawn_armor(&mut commands, position);
+}
+
+/// Eats any armor pickup the head reaches.
+fn put_on_armor(
+    mut commands: Commands,
+    mut armor: ResMut<Armor>,
+    mut recorder: ResMut<ReplayRecorder>,
+    heads: Query<&Position, With<SnakeHead>>,
+    pickups: Query<(Entity, &Position), With<ArmorPickup>>,
+) {
+    for head in &heads {
+        for (entity, position) in &pickups {
+            if position == head {
+                armor.hit_points = (armor.hit_points + 1).min(MAX_ARMOR);
+                info!(hit_points = armor.hit_points, "armor put on");
+                recorder.tainted = true;
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Takes a hit point for a crash and holds the snake still for a moment.
+pub(crate) fn take_hit(
+    mut commands: Commands,
+    mut reader: EventReader<ArmorHit>,
+    mut armor: ResMut<Armor>,
+) {
+    let Some(&ArmorHit(cause)) = reader.read().last() else {
+        return;
+    };
+    armor.hit_points = armor.hit_points.saturating_sub(1);
+    info!(?cause, hit_points = armor.hit_points, "armor took the hit");
+    commands.insert_resource(Invulnerable(Timer::new(INVULNERABLE_FOR, TimerMode::Once)));
+}
+
+fn clear_armor(
+    mut commands: Commands,
+    mut armor: ResMut<Armor>,
+    mut timer: ResMut<ArmorTimer>,
+    pickups: Query<Entity, With<ArmorPickup>>,
+) {
+    *armor = Armor::default();
+    timer.0.reset();
+    for pickup in &pickups {
+        commands.entity(pickup).despawn();
+    }
+}
+
+/// Draws the last segments in steel, one for each hit point of armor.
+fn paint_armor(
+    armor: Res<Armor>,
+    theme: Res<Theme>,
+    snakes: Query<&Segments>,
+    mut sprites: Query<&mut Sprite, (With<SnakeSegment>, Without<SnakeHead>)>,
+) {
+    let Some(segments) = snakes.iter().next() else {
+        return;
+    };
+    let plated = segments.0.len().saturating_sub(armor.hit_points as usize);
+    for (index, &segment) in segments.0.iter().enumerate() {
+        let Ok(mut sprite) = sprites.get_mut(segment) else {
+            continue;
+        };
+        let color = if index >= plated {
+            ARMOR_COLOR
+        } else {
+            theme.color(ThemeColor::SnakeSegment)
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Optional help for newer players.
+//!
+//! Safe-path hints, toggled with F4, mark the cell next to the head in each
+//! direction the snake can turn: green when some way of playing on survives
+//! the next [`SafePathHints::lookahead`] moves, red when every one of them
+//! dies.
+//!
+//! The collision warning flashes the head red while keeping straight on
+//! would crash on the next tick, and can play a short blip when it starts.
+//! With reduced motion the head turns red without flashing. Both are set with
+//! `collision_warning` and `warning_blip` in `assets/config.ron`.
+
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+use snake_core::{collision, controller::BoardView, Arena, Direction, Position};
+
+use crate::{accessibility::Accessibility, Food, GameSet, Segments, Size, SnakeHead, Theme};
+
+const SAFE_COLOR: Color = Color::linear_rgb(0.1, 0.9, 0.2);
+const DOOMED_COLOR: Color = Color::linear_rgb(0.9, 0.1, 0.1);
+/// Marker size along and across the direction it points in, in cells.
+const HINT_LENGTH: f32 = 0.5;
+const HINT_WIDTH: f32 = 0.15;
+const WARNING_COLOR: Color = Color::linear_rgb(1.0, 0.1, 0.1);
+/// Head color changes per second while flashing.
+const FLASH_RATE: f32 = 8.0;
+const BLIP_FREQUENCY: f32 = 880.0;
+const BLIP_LENGTH: Duration = Duration::from_millis(60);
+
+pub struct AssistPlugin;
+
+impl Plugin for AssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SafePathHints>()
+            .init_resource::<CollisionWarning>()
+            .add_systems(Startup, spawn_hints)
+            .add_systems(
+                Update,
+                ((toggle_hints, update_hints).chain(), warn_of_collision),
+            )
+            .add_systems(
+                PostUpdate,
+                point_hints
+                    .after(GameSet::Presentation)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SafePathHints {
+    pub enabled: bool,
+    /// Moves looked ahead before calling a turn safe.
+    pub lookahead: u32,
+}
+
+impl Default for SafePathHints {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookahead: 4,
+        }
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollisionWarning {
+    pub enabled: bool,
+    pub blip: bool,
+}
+
+impl Default for CollisionWarning {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            blip: false,
+        }
+    }
+}
+
+/// Marker for the turn towards its direction.
+#[derive(Component)]
+struct Hint(Direction);
+
+fn spawn_hints(mut commands: Commands) {
+    for direction in Direction::ALL {
+        let (width, height) = match direction {
+            Direction::Left | Direction::Right => (HINT_LENGTH, HINT_WIDTH),
+            Direction::Up | Direction::Down => (HINT_WIDTH, HINT_LENGTH),
+        };
+        commands.spawn((
+            Hint(direction),
+            Sprite::default(),
+            Position { x: 0, y: 0 },
+            Size { width, height },
+            Visibility::Hidden,
+        ));
+    }
+}
+
+fn toggle_hints(keyboard_input: Res<ButtonInput<KeyCode>>, mut hints: ResMut<SafePathHints>) {
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        hints.enabled = !hints.enabled;
+        info!(enabled = hints.enabled, "safe-path hints");
+    }
+}
+
+fn update_hints(
+    settings: Res<SafePathHints>,
+    arena: Res<Arena>,
+    heads: Query<(&SnakeHead, &Segments)>,
+    positions: Query<&Position, Without<Hint>>,
+    food: Query<&Position, (With<Food>, Without<Hint>)>,
+    mut hints: Query<(&Hint, &mut Position, &mut Sprite, &mut Visibility)>,
+) {
+    let snake = heads.get_single().ok();
+    let head = snake.map(|(head, _)| head);
+    let body: Vec<Position> = snake.map_or_else(Vec::new, |(_, segments)| {
+        positions.iter_many(&segments.0).copied().collect()
+    });
+    let food: Vec<Position> = food.iter().copied().collect();
+    for (hint, mut position, mut sprite, mut visibility) in &mut hints {
+        let Some(head) = head.filter(|head| {
+            settings.enabled && !body.is_empty() && hint.0 != head.direction.opposite()
+        }) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let board = BoardView {
+            arena: *arena,
+            body: &body,
+            others: &[],
+            food: &food,
+            direction: head.direction,
+        };
+        position.set_if_neq(body[0].step(hint.0));
+        sprite.color = if board.survives(hint.0, settings.lookahead) {
+            SAFE_COLOR
+        } else {
+            DOOMED_COLOR
+        };
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+/// Draws the markers above the board so food or segments never hide them.
+fn point_hints(mut hints: Query<&mut Transform, Added<Hint>>) {
+    for mut transform in &mut hints {
+        transform.translation.z = 1.0;
+    }
+}
+
+fn warn_of_collision(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<CollisionWarning>,
+    accessibility: Res<Accessibility>,
+    theme: Res<Theme>,
+    arena: Res<Arena>,
+    positions: Query<&Position>,
+    mut heads: Query<(&SnakeHead, &Segments, &mut Sprite)>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut warning: Local<bool>,
+) {
+    let Ok((head, segments, mut sprite)) = heads.get_single_mut() else {
+        return;
+    };
+    let body: Vec<Position> = positions.iter_many(&segments.0).copied().collect();
+    let danger = settings.enabled
+        && body.first().is_some_and(|position| {
+            collision(*arena, &body, position.step(head.direction)).is_some()
+        });
+    if danger && !*warning && settings.blip {
+        commands.spawn((
+            AudioPlayer(pitches.add(Pitch::new(BLIP_FREQUENCY, BLIP_LENGTH))),
+            PlaybackSettings::DESPAWN,
+        ));
+    }
+    if danger {
+        let lit = accessibility.reduced_motion
+            || ((time.elapsed_secs() * FLASH_RATE) as u32).is_multiple_of(2);
+        sprite.color = if lit { WARNING_COLOR } else { theme.snake_head };
+    } else if *warning {
+        sprite.color = theme.snake_head;
+    }
+    *warning = danger;
+}
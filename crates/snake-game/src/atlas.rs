@@ -0,0 +1,190 @@
+//! One texture for all the game's art, so the renderer can draw the snake,
+//! food and obstacles in a handful of batches instead of one per image.
+//!
+//! The art is drawn in white into a single [`SpriteAtlas`] image, one
+//! [`TILE`]-pixel cell per [`Art`], and each sprite is tinted with its theme
+//! color as before. Sprites with a [`ThemeColor`] are given their cell when
+//! they appear, and the last segment of each snake is drawn as a tail.
+
+use bevy::{
+    image::ImageSampler,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::{Segments, SnakeHead, SnakeSegment, ThemeColor};
+
+/// Side of each cell of the atlas in pixels.
+pub const TILE: u32 = 32;
+
+/// Packs the art into one atlas. Needs images, so it is left out of
+/// [`crate::SnakeGamePlugin`].
+pub struct AtlasPlugin;
+
+impl Plugin for AtlasPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            load_atlas.run_if(not(resource_exists::<SpriteAtlas>)),
+        )
+        .add_systems(
+            Update,
+            (dress_sprites, draw_tails)
+                .chain()
+                .run_if(resource_exists::<SpriteAtlas>),
+        );
+    }
+}
+
+/// A cell of the atlas.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Art {
+    Head,
+    Body,
+    Tail,
+    Food,
+    Obstacle,
+}
+
+impl Art {
+    pub const ALL: [Art; 5] = [Art::Head, Art::Body, Art::Tail, Art::Food, Art::Obstacle];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    fn of(role: ThemeColor) -> Self {
+        match role {
+            ThemeColor::SnakeHead => Art::Head,
+            ThemeColor::SnakeSegment => Art::Body,
+            ThemeColor::Food => Art::Food,
+            ThemeColor::Obstacle => Art::Obstacle,
+        }
+    }
+
+    /// Signed distance from the shape's edge, for `point` within -1..=1 of
+    /// the cell's center. Negative inside.
+    fn distance(self, point: Vec2) -> f32 {
+        let rounded = |half: f32, radius: f32| {
+            let corner = point.abs() - Vec2::splat(half - radius);
+            corner.max(Vec2::ZERO).length() + corner.max_element().min(0.0) - radius
+        };
+        match self {
+            Art::Head => rounded(0.95, 0.45),
+            Art::Body => rounded(0.9, 0.25),
+            Art::Tail => point.length() - 0.75,
+            Art::Food => point.length() - 0.9,
+            Art::Obstacle => rounded(1.0, 0.1),
+        }
+    }
+}
+
+/// Handles to the atlas every themed sprite is drawn from.
+#[derive(Resource, Clone)]
+pub struct SpriteAtlas {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+impl SpriteAtlas {
+    /// A sprite of one cell of the atlas, sized by its [`Transform`] like the
+    /// flat sprites it replaces.
+    pub fn sprite(&self, art: Art) -> Sprite {
+        Sprite {
+            image: self.image.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: self.layout.clone(),
+                index: art.index(),
+            }),
+            custom_size: Some(Vec2::ONE),
+            ..default()
+        }
+    }
+}
+
+/// Whether a sprite has been given its cell of the atlas.
+#[derive(Component)]
+struct Dressed;
+
+fn draw(art: Art) -> impl Fn(u32, u32) -> u8 {
+    move |x, y| {
+        let point = (Vec2::new(x as f32, y as f32) + 0.5) / TILE as f32 * 2.0 - 1.0;
+        let coverage = 0.5 - art.distance(point) * TILE as f32 / 2.0;
+        (coverage.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+}
+
+fn load_atlas(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let columns = Art::ALL.len() as u32;
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: TILE * columns,
+            height: TILE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::linear();
+    let width = (TILE * columns) as usize;
+    for art in Art::ALL {
+        let alpha = draw(art);
+        for y in 0..TILE {
+            for x in 0..TILE {
+                let pixel = y as usize * width + art.index() * TILE as usize + x as usize;
+                image.data[pixel * 4 + 3] = alpha(x, y);
+            }
+        }
+    }
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(TILE), columns, 1, None, None);
+    commands.insert_resource(SpriteAtlas {
+        image: images.add(image),
+        layout: layouts.add(layout),
+    });
+}
+
+/// Swaps the flat sprite of anything themed for its art, keeping its color.
+fn dress_sprites(
+    mut commands: Commands,
+    atlas: Res<SpriteAtlas>,
+    mut sprites: Query<(Entity, &ThemeColor, &mut Sprite), Without<Dressed>>,
+) {
+    for (entity, &role, mut sprite) in &mut sprites {
+        *sprite = Sprite {
+            color: sprite.color,
+            ..atlas.sprite(Art::of(role))
+        };
+        commands.entity(entity).insert(Dressed);
+    }
+}
+
+/// Draws the last segment of each snake that grew or was cut as its tail.
+fn draw_tails(
+    snakes: Query<Ref<Segments>>,
+    mut sprites: Query<&mut Sprite, (With<SnakeSegment>, Without<SnakeHead>)>,
+) {
+    for segments in snakes.iter().filter(|segments| segments.is_changed()) {
+        let last = segments.0.len() - 1;
+        for (i, &segment) in segments.0.iter().enumerate().skip(1) {
+            let art = if i == last { Art::Tail } else { Art::Body };
+            let Ok(mut sprite) = sprites.get_mut(segment) else {
+                continue;
+            };
+            let drawn = sprite.texture_atlas.as_ref().map(|cell| cell.index);
+            if drawn.is_some_and(|index| index != art.index()) {
+                if let Some(cell) = &mut sprite.texture_atlas {
+                    cell.index = art.index();
+                }
+            }
+        }
+    }
+}
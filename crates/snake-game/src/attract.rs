@@ -0,0 +1,152 @@
+//! Arcade-style attract mode.
+//!
+//! After [`IDLE_TIMEOUT`] without any key, gamepad button or touch, the run is
+//! abandoned and the [`Autopilot`] bot plays demo games under a "press any
+//! key" banner. Any input ends the demo and starts a fresh run for the player.
+//! Like any bot run, demo runs are not submitted, saved as personal bests or
+//! counted in stats. A bot chosen with `--bot` is never interrupted.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use snake_core::controller::Autopilot;
+
+use crate::{
+    bot::Pilot, game_over, snake_movement, timer_finished, GameOverCause, GameOverEvent, GameSet,
+    MovementTick,
+};
+
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AttractMode {
+            idle: Timer::new(IDLE_TIMEOUT, TimerMode::Once),
+            demo: false,
+            switch: None,
+        })
+        .add_systems(Startup, spawn_banner)
+        .add_systems(Update, (watch_input, show_banner).chain())
+        .add_systems(
+            FixedUpdate,
+            (
+                switch_run
+                    .run_if(timer_finished::<MovementTick>)
+                    .before(snake_movement)
+                    .in_set(GameSet::Logic),
+                finish_switch
+                    .run_if(on_event::<GameOverEvent>)
+                    .after(game_over)
+                    .in_set(GameSet::Logic),
+            ),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Switch {
+    StartDemo,
+    StopDemo,
+}
+
+#[derive(Resource)]
+pub struct AttractMode {
+    idle: Timer,
+    /// Set from the moment the idle run is abandoned until the player's fresh
+    /// run begins.
+    pub(crate) demo: bool,
+    /// Ends the current run on the next movement tick.
+    switch: Option<Switch>,
+}
+
+fn watch_input(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    touches: Res<Touches>,
+    pilot: Option<Res<Pilot>>,
+    mut attract: ResMut<AttractMode>,
+) {
+    if pilot.is_some() && !attract.demo {
+        return;
+    }
+    let input = keyboard_input.get_just_pressed().len() > 0
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+        || touches.any_just_pressed();
+    if input {
+        attract.idle.reset();
+        if attract.demo && attract.switch.is_none() {
+            info!("player is back, ending demo");
+            attract.switch = Some(Switch::StopDemo);
+        }
+    } else if !attract.demo && attract.idle.tick(time.delta()).just_finished() {
+        info!("idle, starting demo");
+        attract.switch = Some(Switch::StartDemo);
+    }
+}
+
+/// Abandons the current run so the demo, or the player, starts on a fresh
+/// board. Demo mode starts here so the abandoned idle run is not counted.
+fn switch_run(
+    mut commands: Commands,
+    mut attract: ResMut<AttractMode>,
+    mut game_over_writer: EventWriter<GameOverEvent>,
+) {
+    let Some(switch) = attract.switch else {
+        return;
+    };
+    if switch == Switch::StartDemo && !attract.demo {
+        attract.demo = true;
+        commands.insert_resource(Pilot(Box::new(Autopilot)));
+    }
+    game_over_writer.send(GameOverEvent {
+        cause: GameOverCause::Interrupted,
+        cell: None,
+    });
+}
+
+/// Ends demo mode once the last demo run has been reset.
+fn finish_switch(mut commands: Commands, mut attract: ResMut<AttractMode>) {
+    if attract.switch.take() == Some(Switch::StopDemo) {
+        attract.demo = false;
+        attract.idle.reset();
+        commands.remove_resource::<Pilot>();
+    }
+}
+
+#[derive(Component)]
+struct Banner;
+
+fn spawn_banner(mut commands: Commands) {
+    commands.spawn((
+        Banner,
+        Text::new("DEMO - press any key"),
+        TextFont {
+            font_size: 24.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+    ));
+}
+
+fn show_banner(attract: Res<AttractMode>, mut banners: Query<&mut Visibility, With<Banner>>) {
+    for mut visibility in &mut banners {
+        visibility.set_if_neq(if attract.demo {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
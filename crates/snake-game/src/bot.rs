@@ -0,0 +1,63 @@
+//! Computer players in place of the keyboard.
+//!
+//! `--bot <name>` picks one of [`snake_core::controller::BOTS`] to play every
+//! run; attract mode drives its demos the same way. While a [`Pilot`] is set
+//! the arrow keys are ignored and it chooses the direction on each movement
+//! tick.
+
+use bevy::prelude::*;
+use snake_core::{
+    controller::{bot, BoardView, SnakeController, BOTS},
+    Arena, Position,
+};
+
+use crate::{attract::AttractMode, Food, Segments, SnakeHead};
+
+#[derive(Resource)]
+pub struct Pilot(pub Box<dyn SnakeController>);
+
+pub fn select_from_args(mut commands: Commands) {
+    let Some(name) = std::env::args().skip_while(|arg| arg != "--bot").nth(1) else {
+        return;
+    };
+    match bot(&name) {
+        Some(controller) => {
+            info!("{name} bot is playing");
+            commands.insert_resource(Pilot(controller));
+        }
+        None => warn!("unknown bot {name:?}, expected one of {BOTS:?}"),
+    }
+}
+
+/// Run condition for systems that should only count runs a person played:
+/// leaderboards, stats and personal bests. Daily challenge practice is kept
+/// out the same way, with [`practicing`](crate::daily::practicing).
+pub fn bot_playing(pilot: Option<Res<Pilot>>, attract: Option<Res<AttractMode>>) -> bool {
+    pilot.is_some() || attract.is_some_and(|attract| attract.demo)
+}
+
+pub fn steer_with_pilot(
+    mut pilot: ResMut<Pilot>,
+    arena: Res<Arena>,
+    positions: Query<&Position>,
+    food: Query<&Position, With<Food>>,
+    mut heads: Query<(&mut SnakeHead, &Segments)>,
+) {
+    let Ok((mut head, segments)) = heads.get_single_mut() else {
+        return;
+    };
+    let body: Vec<Position> = positions.iter_many(&segments.0).copied().collect();
+    let food: Vec<Position> = food.iter().copied().collect();
+    let board = BoardView {
+        arena: *arena,
+        body: &body,
+        others: &[],
+        food: &food,
+        direction: head.direction,
+    };
+    if let Some(direction) = pilot.0.steer(&board) {
+        if direction != head.direction.opposite() {
+            head.direction = direction;
+        }
+    }
+}
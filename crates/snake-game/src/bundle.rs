@@ -0,0 +1,272 @@
+//! A profile's saved files packed into one JSON document, for moving them
+//! between machines.
+//!
+//! Each file is kept with its path inside the profile directory and the time
+//! it was last written. [`Bundle::merge`] combines two bundles file by file.
+//! Files that gather runs over time, the high scores, run history, daily
+//! challenges and achievements, are merged by content, so runs played on
+//! either machine all survive. Any other file, like the settings, is taken
+//! from whichever copy was written last. Backups and half-written temporary
+//! files left by [`persist`] are not bundled.
+//!
+//! The same format is used to copy a profile by hand: see
+//! [`ProfilePlugin`](crate::profile::ProfilePlugin) for `--export-profile` and
+//! `--import-profile`.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use snake_core::persist;
+
+use crate::{
+    achievements::{Progress, ACHIEVEMENTS_FILE},
+    daily::{DailyHistory, DAILIES_FILE},
+    high_scores::{HighScores, HIGH_SCORES_FILE},
+    stats::{merge_history, HISTORY_FILE},
+};
+
+/// Format version written to new bundles.
+pub const BUNDLE_VERSION: u32 = 1;
+/// Where a replacing import writes the bundle's files inside the profile
+/// before anything of the profile's own is touched.
+const STAGING_DIR: &str = "import.tmp";
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    /// Name of the profile the files came from.
+    pub profile: String,
+    /// Keyed by path relative to the profile directory, `/`-separated.
+    pub files: BTreeMap<String, BundledFile>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BundledFile {
+    /// Seconds since the Unix epoch.
+    pub modified: u64,
+    #[serde(with = "hex")]
+    pub contents: Vec<u8>,
+}
+
+/// What to do with a profile's own files when importing a bundle into it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportMode {
+    /// Combine the two copies of each file, as a sync does.
+    Merge,
+    /// Delete the files the bundle does not have and overwrite the rest.
+    Replace,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("could not access profile files: {0}")]
+    Io(#[from] io::Error),
+    #[error("bundle is malformed: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("bundle was made by a newer version (format {0})")]
+    TooNew(u32),
+    #[error("bundle holds an unsafe path {0:?}")]
+    UnsafePath(String),
+}
+
+impl Bundle {
+    /// Packs every file under `dir`. A missing directory gives an empty
+    /// bundle.
+    pub fn collect(dir: &Path, profile: &str) -> Result<Self, BundleError> {
+        let mut bundle = Self {
+            version: BUNDLE_VERSION,
+            profile: profile.to_string(),
+            files: BTreeMap::new(),
+        };
+        if dir.is_dir() {
+            bundle.collect_dir(dir, "")?;
+        }
+        Ok(bundle)
+    }
+
+    fn collect_dir(&mut self, dir: &Path, prefix: &str) -> Result<(), BundleError> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            let relative = format!("{prefix}{name}");
+            if name.ends_with(".tmp") || name.ends_with(".bak") {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                self.collect_dir(&entry.path(), &format!("{relative}/"))?;
+            } else {
+                let modified = entry
+                    .metadata()?
+                    .modified()?
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                self.files.insert(
+                    relative,
+                    BundledFile {
+                        modified,
+                        contents: fs::read(entry.path())?,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the named files into `dir`, keeping their modification times.
+    pub fn unpack<'a>(
+        &self,
+        dir: &Path,
+        names: impl IntoIterator<Item = &'a String>,
+    ) -> Result<(), BundleError> {
+        for name in names {
+            let Some(file) = self.files.get(name) else {
+                continue;
+            };
+            if name
+                .split('/')
+                .any(|part| matches!(part, "" | "." | "..") || part.contains(['\\', ':']))
+            {
+                return Err(BundleError::UnsafePath(name.clone()));
+            }
+            let path = dir.join(name);
+            persist::write_atomic(&path, &file.contents)?;
+            fs::File::options()
+                .write(true)
+                .open(&path)?
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(file.modified))?;
+        }
+        Ok(())
+    }
+
+    /// Takes each file from `other` that this bundle lacks, merges in those
+    /// kept by content and takes the rest where `other` holds a newer copy.
+    /// Returns the names of the files that changed.
+    pub fn merge(&mut self, other: &Bundle) -> Vec<String> {
+        let mut taken = Vec::new();
+        for (name, theirs) in &other.files {
+            let merged = match self.files.get(name) {
+                None => theirs.clone(),
+                Some(ours) => match merge_contents(name, &ours.contents, &theirs.contents) {
+                    Some(contents) => BundledFile {
+                        modified: ours.modified.max(theirs.modified),
+                        contents,
+                    },
+                    None if theirs.modified > ours.modified => theirs.clone(),
+                    None => continue,
+                },
+            };
+            if self.files.get(name) != Some(&merged) {
+                self.files.insert(name.clone(), merged);
+                taken.push(name.clone());
+            }
+        }
+        taken
+    }
+
+    /// Writes this bundle's files into the profile directory `dir`, and
+    /// returns the names of those written.
+    pub fn import(&self, dir: &Path, mode: ImportMode) -> Result<Vec<String>, BundleError> {
+        let mut local = Self::collect(dir, &self.profile)?;
+        let names = match mode {
+            ImportMode::Merge => local.merge(self),
+            ImportMode::Replace => return self.replace(dir, &local),
+        };
+        self.unpack(dir, &names)?;
+        Ok(names)
+    }
+
+    /// Makes `dir`, holding `local`, an exact copy of this bundle. Every file
+    /// is written to a staging directory first, so a bad path or a failed
+    /// write leaves the profile as it was.
+    fn replace(&self, dir: &Path, local: &Bundle) -> Result<Vec<String>, BundleError> {
+        let names: Vec<String> = self.files.keys().cloned().collect();
+        let staging = dir.join(STAGING_DIR);
+        let _ = fs::remove_dir_all(&staging);
+        if let Err(err) = self.unpack(&staging, &names) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+        for name in local.files.keys() {
+            if !self.files.contains_key(name) {
+                fs::remove_file(dir.join(name))?;
+            }
+        }
+        for name in &names {
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(staging.join(name), path)?;
+        }
+        fs::remove_dir_all(&staging)?;
+        Ok(names)
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, BundleError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, BundleError> {
+        // The version comes first, as a newer format may not parse as this one.
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+        let Versioned { version } = serde_json::from_slice(bytes)?;
+        if version > BUNDLE_VERSION {
+            return Err(BundleError::TooNew(version));
+        }
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Both copies of `name` combined, for the files that gather runs over time.
+/// `None` for any other file, or if either copy does not parse.
+fn merge_contents(name: &str, ours: &[u8], theirs: &[u8]) -> Option<Vec<u8>> {
+    fn json<T: Serialize + DeserializeOwned>(
+        ours: &[u8],
+        theirs: &[u8],
+        merge: fn(&mut T, &T),
+    ) -> Option<Vec<u8>> {
+        let mut merged: T = serde_json::from_slice(ours).ok()?;
+        merge(&mut merged, &serde_json::from_slice(theirs).ok()?);
+        serde_json::to_vec_pretty(&merged).ok()
+    }
+    match name {
+        HIGH_SCORES_FILE => json(ours, theirs, HighScores::merge),
+        ACHIEVEMENTS_FILE => json(ours, theirs, Progress::merge),
+        DAILIES_FILE => json(ours, theirs, DailyHistory::merge),
+        HISTORY_FILE => Some(merge_history(ours, theirs)),
+        _ => None,
+    }
+}
+
+/// File contents as a lowercase hex string.
+mod hex {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        (0..hex.len())
+            .step_by(2)
+            .map(|start| {
+                hex.get(start..start + 2)
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| D::Error::custom("invalid hex"))
+            })
+            .collect()
+    }
+}
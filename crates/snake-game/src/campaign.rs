@@ -0,0 +1,641 @@
+//! The campaign, played with `--campaign`: a run of levels to clear in order,
+//! each with walls of its own and an amount of food to eat.
+//!
+//! Clearing a level earns one star, and one more for each of its two time
+//! limits it beats; it also unlocks the next level. Progress is kept in one of
+//! [`SLOTS`] independent save slots under `campaign/` in the [`Profile`], and
+//! the game starts paused on a list of the slots with how much of the
+//! campaign each has done, once a profile has been picked.
+//!
+//! Levels are laid out to fit whatever arena size the player chose, and keep
+//! the snake's starting column clear. Some have [`Terrain`] to cross too,
+//! gates that only open once their [`Lock`]'s key is found, [`Tunnels`]
+//! through the edge of the board, or [`Weather`].
+//! Their walls are not part of the core rules, so campaign runs are not
+//! replayable and do not count towards high scores or leaderboards.
+
+use std::{fmt::Write as _, io, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use snake_core::{persist, Arena, GameMode, Position, START_POSITION};
+
+use crate::{
+    game_over,
+    gates::{spawn_lock, Lock},
+    ghost::speedrun_goal,
+    profile::{picking_profile, Profile},
+    replay::{self, ReplayRecorder},
+    snake_growth, spawn_obstacle,
+    terrain::{Terrain, Tile},
+    timer_finished,
+    tunnels::{Tunnel, Tunnels},
+    victory::free_playing,
+    weather::Weather,
+    Food, GameOverCause, GameOverEvent, GameSet, MovementTick, Obstacle, Run, Score,
+};
+
+pub const SLOTS: usize = 3;
+/// Format version written to new save slots.
+pub const SLOT_VERSION: u32 = 1;
+pub const MAX_STARS: u8 = 3;
+
+pub struct Level {
+    pub name: &'static str,
+    /// Food to eat to clear the level.
+    pub goal: u32,
+    /// Movement ticks to clear it within for a second and a third star.
+    pub star_ticks: [u32; 2],
+    /// Walls for an arena of the given size.
+    pub layout: fn(Arena) -> Vec<Position>,
+    /// Speed strips and mud for an arena of the given size.
+    pub terrain: fn(Arena) -> Vec<(Position, Tile)>,
+    /// Gates and their keys for an arena of the given size.
+    pub locks: fn(Arena) -> Vec<Lock>,
+    /// Tunnels through the edge of an arena of the given size.
+    pub tunnels: fn(Arena) -> Vec<Tunnel>,
+    pub weather: Weather,
+}
+
+impl Level {
+    /// The level's walls in `arena`, leaving the snake room to start.
+    pub fn obstacles(&self, arena: Arena) -> Vec<Position> {
+        (self.layout)(arena)
+            .into_iter()
+            .filter(|&position| clear_of_start(arena, position))
+            .collect()
+    }
+
+    /// The level's locks in `arena`, with their gates leaving the snake room
+    /// to start like its walls.
+    pub fn locks(&self, arena: Arena) -> Vec<Lock> {
+        (self.locks)(arena)
+            .into_iter()
+            .filter(|lock| arena.contains(lock.key))
+            .map(|mut lock| {
+                lock.gate
+                    .retain(|&position| clear_of_start(arena, position));
+                lock
+            })
+            .collect()
+    }
+
+    /// Puts up the level's walls, lays its terrain in `arena`, digs its
+    /// tunnels and sets its weather.
+    pub(crate) fn build(&self, commands: &mut Commands, arena: Arena) {
+        for position in self.obstacles(arena) {
+            spawn_obstacle(commands, position);
+        }
+        for (index, lock) in self.locks(arena).iter().enumerate() {
+            spawn_lock(commands, index, lock);
+        }
+        commands.insert_resource(Terrain::new(arena, (self.terrain)(arena)));
+        commands.insert_resource(Tunnels::new(arena, (self.tunnels)(arena)));
+        commands.insert_resource(self.weather);
+    }
+
+    /// Stars for clearing the level in `ticks`.
+    pub fn stars(&self, ticks: u32) -> u8 {
+        1 + self
+            .star_ticks
+            .iter()
+            .filter(|&&limit| ticks <= limit)
+            .count() as u8
+    }
+}
+
+fn clear_of_start(arena: Arena, position: Position) -> bool {
+    arena.contains(position)
+        && !(position.x == START_POSITION.x && position.y >= START_POSITION.y - 1)
+}
+
+fn cells(arena: Arena, wall: impl Fn(i32, i32) -> bool) -> Vec<Position> {
+    let (width, height) = (arena.width as i32, arena.height as i32);
+    (0..width)
+        .flat_map(|x| (0..height).map(move |y| Position { x, y }))
+        .filter(|position| wall(position.x, position.y))
+        .collect()
+}
+
+pub const LEVELS: &[Level] = &[
+    Level {
+        name: "Open field",
+        goal: 5,
+        star_ticks: [200, 120],
+        layout: |_| Vec::new(),
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Pillars",
+        goal: 8,
+        star_ticks: [320, 200],
+        layout: |arena| cells(arena, |x, y| x % 3 == 1 && y % 3 == 1),
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Divide",
+        goal: 10,
+        star_ticks: [400, 260],
+        layout: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| x == width / 2 && y >= 2 && y < height - 2)
+        },
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Frame",
+        goal: 12,
+        star_ticks: [480, 320],
+        layout: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| {
+                let ring = (x == 1 || x == width - 2) && (1..height - 1).contains(&y)
+                    || (y == 1 || y == height - 2) && (1..width - 1).contains(&x);
+                ring && x != width / 2 && y != height / 2
+            })
+        },
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Crossroads",
+        goal: 15,
+        star_ticks: [600, 400],
+        layout: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| {
+                (x == width / 2 || y == height / 2)
+                    && (x - width / 2).abs() + (y - height / 2).abs() > 1
+            })
+        },
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Fast lane",
+        goal: 15,
+        star_ticks: [500, 330],
+        layout: |_| Vec::new(),
+        terrain: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            let lane = cells(arena, |_, y| y == height - 2)
+                .into_iter()
+                .map(|position| (position, Tile::Speed));
+            let mud = cells(arena, |x, y| x >= width - 3 && y < 3)
+                .into_iter()
+                .map(|position| (position, Tile::Mud));
+            lane.chain(mud).collect()
+        },
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Rain,
+    },
+    Level {
+        name: "Thin ice",
+        goal: 15,
+        star_ticks: [520, 350],
+        layout: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| (x == 0 || x == width - 1) && y == height / 2)
+        },
+        terrain: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| {
+                (1..width - 1).contains(&x) && (height / 2 - 1..=height / 2 + 1).contains(&y)
+            })
+            .into_iter()
+            .map(|position| (position, Tile::Ice))
+            .collect()
+        },
+        locks: |_| Vec::new(),
+        tunnels: |_| Vec::new(),
+        weather: Weather::Snow,
+    },
+    Level {
+        name: "Locked room",
+        goal: 15,
+        star_ticks: [560, 380],
+        layout: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            cells(arena, |x, y| {
+                x == width * 2 / 3 && !(height / 2 - 1..=height / 2 + 1).contains(&y)
+            })
+        },
+        terrain: |_| Vec::new(),
+        locks: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            vec![Lock {
+                key: Position { x: 1, y: 1 },
+                gate: cells(arena, |x, y| {
+                    x == width * 2 / 3 && (height / 2 - 1..=height / 2 + 1).contains(&y)
+                }),
+            }]
+        },
+        tunnels: |_| Vec::new(),
+        weather: Weather::Clear,
+    },
+    Level {
+        name: "Underpass",
+        goal: 15,
+        star_ticks: [540, 360],
+        layout: |arena| cells(arena, |x, _| x == arena.width as i32 / 2),
+        terrain: |_| Vec::new(),
+        locks: |_| Vec::new(),
+        tunnels: |arena| {
+            let (width, height) = (arena.width as i32, arena.height as i32);
+            vec![
+                Tunnel(
+                    Position {
+                        x: 0,
+                        y: height / 2,
+                    },
+                    Position {
+                        x: width - 1,
+                        y: height / 2,
+                    },
+                ),
+                Tunnel(
+                    Position {
+                        x: width / 4,
+                        y: height - 1,
+                    },
+                    Position {
+                        x: width * 3 / 4,
+                        y: 0,
+                    },
+                ),
+            ]
+        },
+        weather: Weather::Clear,
+    },
+];
+
+/// One save slot's progress through the campaign.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CampaignSlot {
+    pub version: u32,
+    /// Index of the level being played.
+    pub level: usize,
+    /// How many levels can be played, counting from the first.
+    pub unlocked: usize,
+    /// Best stars earned on each level, zero where not cleared yet.
+    pub stars: Vec<u8>,
+}
+
+impl Default for CampaignSlot {
+    fn default() -> Self {
+        Self {
+            version: SLOT_VERSION,
+            level: 0,
+            unlocked: 1,
+            stars: Vec::new(),
+        }
+    }
+}
+
+impl CampaignSlot {
+    /// Where slot `index`, counting from zero, is kept.
+    pub fn file(index: usize) -> String {
+        format!("campaign/slot-{}.json", index + 1)
+    }
+
+    /// Reads `path`, or `None` if the slot has not been used.
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        match persist::read_with_backup(path, |bytes| {
+            serde_json::from_slice::<Self>(bytes).map_err(io::Error::from)
+        }) {
+            Ok(slot) => Ok(Some(slot)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        persist::save_json(path, self)
+    }
+
+    /// Share of all the stars there are to earn, in percent.
+    pub fn completion(&self) -> u32 {
+        let earned: u32 = self.stars.iter().map(|&stars| u32::from(stars)).sum();
+        earned * 100 / (LEVELS.len() as u32 * u32::from(MAX_STARS))
+    }
+
+    /// Records clearing the current level in `ticks`, unlocks the next and
+    /// moves on to it. Returns the stars earned.
+    pub fn clear(&mut self, ticks: u32) -> u8 {
+        let level = self.level.min(LEVELS.len() - 1);
+        let stars = LEVELS[level].stars(ticks);
+        if self.stars.len() <= level {
+            self.stars.resize(level + 1, 0);
+        }
+        self.stars[level] = self.stars[level].max(stars);
+        self.unlocked = self.unlocked.max(level + 2).min(LEVELS.len());
+        self.level = (level + 1).min(LEVELS.len() - 1);
+        stars
+    }
+}
+
+/// The campaign being played, once a slot is picked.
+#[derive(Resource, Clone, Debug)]
+pub struct Campaign {
+    pub slot: usize,
+    pub progress: CampaignSlot,
+}
+
+impl Campaign {
+    pub fn level(&self) -> &'static Level {
+        &LEVELS[self.progress.level.min(LEVELS.len() - 1)]
+    }
+}
+
+/// Run condition for systems that only count runs of the plain game.
+pub fn in_campaign(campaign: Option<Res<Campaign>>) -> bool {
+    campaign.is_some()
+}
+
+/// Adds the campaign and its slot picker if the player passed `--campaign`.
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        if !std::env::args().any(|arg| arg == "--campaign") {
+            return;
+        }
+        app.init_resource::<Profile>()
+            .insert_resource(SlotPicker::default())
+            .add_systems(Startup, spawn_label)
+            .add_systems(
+                Update,
+                (open_picker.run_if(not(picking_profile)), pick_slot)
+                    .chain()
+                    .run_if(resource_exists::<SlotPicker>),
+            )
+            .add_systems(
+                Update,
+                update_label.run_if(resource_exists_and_changed::<Campaign>),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    // Ahead of the speedrun goal, which everything that
+                    // looks at how runs end is ordered after.
+                    level_goal
+                        .after(snake_growth)
+                        .before(speedrun_goal)
+                        .run_if(not(free_playing)),
+                    clear_level
+                        .run_if(on_event::<GameOverEvent>)
+                        .before(game_over),
+                    start_level
+                        .run_if(on_event::<GameOverEvent>)
+                        .after(replay::reset_recorder),
+                )
+                    .run_if(resource_exists::<Campaign>)
+                    .run_if(timer_finished::<MovementTick>)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(
+                FixedUpdate,
+                clear_walled_food
+                    .run_if(resource_exists::<Campaign>)
+                    .in_set(GameSet::Spawning),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+struct SlotPicker {
+    slots: Vec<Option<CampaignSlot>>,
+    selected: usize,
+    open: bool,
+}
+
+impl SlotPicker {
+    fn text(&self) -> String {
+        let mut text = String::from("Campaign: choose a save slot");
+        for (index, slot) in self.slots.iter().enumerate() {
+            let marker = if index == self.selected { '>' } else { ' ' };
+            let _ = write!(text, "\n{marker} Slot {}  ", index + 1);
+            match slot {
+                Some(slot) => {
+                    let _ = write!(
+                        text,
+                        "{}%  level {}/{}",
+                        slot.completion(),
+                        slot.level + 1,
+                        LEVELS.len()
+                    );
+                }
+                None => text.push_str("empty"),
+            }
+        }
+        text.push_str("\n\nUp/Down to choose, Enter to play");
+        text
+    }
+}
+
+#[derive(Component)]
+struct SlotScreen;
+
+#[derive(Component)]
+struct LevelLabel;
+
+/// Shows the slots once the profile is settled, and holds the game until one
+/// is picked.
+fn open_picker(
+    mut commands: Commands,
+    mut picker: ResMut<SlotPicker>,
+    profile: Res<Profile>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if picker.open {
+        return;
+    }
+    picker.open = true;
+    picker.slots = (0..SLOTS)
+        .map(|index| {
+            let path = profile.path(&CampaignSlot::file(index));
+            CampaignSlot::read(&path).unwrap_or_else(|err| {
+                warn!("could not read {}: {err}", path.display());
+                None
+            })
+        })
+        .collect();
+    time.pause();
+    commands.spawn((
+        SlotScreen,
+        Text(picker.text()),
+        TextFont {
+            font_size: 20.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pick_slot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut picker: ResMut<SlotPicker>,
+    arena: Res<Arena>,
+    mut mode: ResMut<GameMode>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut time: ResMut<Time<Virtual>>,
+    mut screens: Query<(Entity, &mut Text), With<SlotScreen>>,
+) {
+    if !picker.open {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        picker.selected = (picker.selected + 1) % SLOTS;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        picker.selected = (picker.selected + SLOTS - 1) % SLOTS;
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        let campaign = Campaign {
+            slot: picker.selected,
+            progress: picker.slots[picker.selected].clone().unwrap_or_default(),
+        };
+        info!(
+            "playing campaign slot {}, level {}",
+            campaign.slot + 1,
+            campaign.level().name
+        );
+        *mode = GameMode::Classic;
+        recorder.tainted = true;
+        campaign.level().build(&mut commands, *arena);
+        commands.insert_resource(campaign);
+        for (screen, _) in &screens {
+            commands.entity(screen).despawn_recursive();
+        }
+        commands.remove_resource::<SlotPicker>();
+        time.unpause();
+    } else if picker.is_changed() {
+        for (_, mut text) in &mut screens {
+            text.0 = picker.text();
+        }
+    }
+}
+
+/// Ends the run once the level's food has been eaten.
+fn level_goal(
+    campaign: Res<Campaign>,
+    score: Res<Score>,
+    mut game_over_writer: EventWriter<GameOverEvent>,
+) {
+    if score.0 >= campaign.level().goal {
+        game_over_writer.send(GameOverEvent {
+            cause: GameOverCause::Finished,
+            cell: None,
+        });
+    }
+}
+
+/// Awards the stars for a cleared level and saves the slot.
+pub(crate) fn clear_level(
+    mut reader: EventReader<GameOverEvent>,
+    mut campaign: ResMut<Campaign>,
+    run: Res<Run>,
+    profile: Res<Profile>,
+) {
+    let Some(&GameOverEvent {
+        cause: GameOverCause::Finished,
+        ..
+    }) = reader.read().last()
+    else {
+        return;
+    };
+    let name = campaign.level().name;
+    let last = campaign.progress.level + 1 == LEVELS.len();
+    let stars = campaign.progress.clear(run.tick);
+    info!("cleared {name} with {stars} of {MAX_STARS} stars");
+    if last {
+        info!("campaign complete: {}%", campaign.progress.completion());
+    }
+    let path = profile.path(&CampaignSlot::file(campaign.slot));
+    if let Err(err) = campaign.progress.write(&path) {
+        warn!(
+            "could not save campaign progress to {}: {err}",
+            path.display()
+        );
+    }
+}
+
+/// Builds the level again for the next run, which is never replayable.
+fn start_level(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    arena: Res<Arena>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    recorder.tainted = true;
+    campaign.level().build(&mut commands, *arena);
+}
+
+/// Food that lands inside a wall could never be eaten.
+fn clear_walled_food(
+    mut commands: Commands,
+    food: Query<(Entity, &Position), Added<Food>>,
+    obstacles: Query<&Position, With<Obstacle>>,
+) {
+    for (entity, position) in &food {
+        if obstacles.iter().any(|obstacle| obstacle == position) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn spawn_label(mut commands: Commands) {
+    commands.spawn((
+        LevelLabel,
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+    ));
+}
+
+fn update_label(campaign: Res<Campaign>, mut labels: Query<&mut Text, With<LevelLabel>>) {
+    let level = campaign.level();
+    let index = campaign.progress.level;
+    let best = campaign.progress.stars.get(index).copied().unwrap_or(0);
+    for mut text in &mut labels {
+        text.0 = format!(
+            "Slot {}  Level {}: {}  eat {}  best {best}/{MAX_STARS} stars",
+            campaign.slot + 1,
+            index + 1,
+            level.name,
+            level.goal
+        );
+    }
+}
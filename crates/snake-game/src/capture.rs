@@ -0,0 +1,128 @@
+//! Run recording to GIF, enabled with `--capture`.
+//!
+//! A frame of the window is grabbed on every movement tick, downscaled and
+//! kept in memory, up to the last [`MAX_FRAMES`]. When the run ends, or when
+//! F8 is pressed mid-run, the frames are encoded in the background into
+//! `captures/<seed>-<tick>.gif`, ready to share.
+
+use std::{collections::VecDeque, fs, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    tasks::IoTaskPool,
+};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    imageops::FilterType,
+    Delay, Frame, RgbaImage,
+};
+use snake_core::MOVEMENT_INTERVAL;
+
+use crate::{
+    bot::bot_playing, game_over, timer_finished, GameOverEvent, GameSet, MovementTick, Run,
+};
+
+const CAPTURE_DIR: &str = "captures";
+/// About 30 seconds at the default speed.
+const MAX_FRAMES: usize = 200;
+/// Frames are scaled down to at most this many pixels on their longer side.
+const MAX_SIDE: u32 = 320;
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        if !std::env::args().any(|arg| arg == "--capture") {
+            return;
+        }
+        info!("capturing runs to {CAPTURE_DIR}/, F8 saves the run so far");
+        app.init_resource::<Capture>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    save_on_game_over
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(not(bot_playing))
+                        .before(game_over)
+                        .in_set(GameSet::Logic),
+                    grab_frame
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Spawning),
+                ),
+            )
+            .add_systems(Update, save_on_demand);
+    }
+}
+
+#[derive(Resource, Default)]
+struct Capture {
+    frames: VecDeque<RgbaImage>,
+}
+
+fn grab_frame(mut commands: Commands) {
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(store_frame);
+}
+
+fn store_frame(trigger: Trigger<ScreenshotCaptured>, mut capture: ResMut<Capture>) {
+    let image = match trigger.event().0.clone().try_into_dynamic() {
+        Ok(image) => image,
+        Err(err) => {
+            warn!("could not read captured frame: {err}");
+            return;
+        }
+    };
+    let frame = image
+        .resize(MAX_SIDE, MAX_SIDE, FilterType::Nearest)
+        .to_rgba8();
+    if capture.frames.len() == MAX_FRAMES {
+        capture.frames.pop_front();
+    }
+    capture.frames.push_back(frame);
+}
+
+fn save_on_game_over(mut capture: ResMut<Capture>, run: Res<Run>) {
+    save(&mut capture, &run);
+}
+
+fn save_on_demand(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut capture: ResMut<Capture>,
+    run: Res<Run>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F8) {
+        save(&mut capture, &run);
+    }
+}
+
+/// Hands the frames so far to a background task that writes the GIF.
+fn save(capture: &mut Capture, run: &Run) {
+    if capture.frames.is_empty() {
+        warn!("no frames captured yet");
+        return;
+    }
+    let frames = std::mem::take(&mut capture.frames);
+    let path = PathBuf::from(CAPTURE_DIR).join(format!("{:016x}-{}.gif", run.seed, run.tick));
+    IoTaskPool::get()
+        .spawn(async move {
+            match encode(&path, frames) {
+                Ok(()) => info!("saved capture to {}", path.display()),
+                Err(err) => error!("could not save capture: {err}"),
+            }
+        })
+        .detach();
+}
+
+fn encode(path: &PathBuf, frames: VecDeque<RgbaImage>) -> image::ImageResult<()> {
+    fs::create_dir_all(CAPTURE_DIR)?;
+    let mut encoder = GifEncoder::new_with_speed(fs::File::create(path)?, 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    let delay = Delay::from_saturating_duration(MOVEMENT_INTERVAL);
+    encoder.encode_frames(
+        frames
+            .into_iter()
+            .map(|frame| Frame::from_parts(frame, 0, 0, delay)),
+    )
+}
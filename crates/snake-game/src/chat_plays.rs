@@ -0,0 +1,182 @@
+//! "Chat plays": Twitch viewers steer the snake by voting.
+//!
+//! Only compiled with the `chat-plays` feature, and only active when the
+//! player passes `--chat-plays <channel>`. Viewers vote by chatting `u`, `d`,
+//! `l` or `r` (or the full words). Each viewer's latest vote counts once, and
+//! on every movement tick the direction with the most votes is taken, unless
+//! it is a reversal or tied for first, before the votes are cleared. A bar
+//! at the bottom of the screen shows the running tally. The keyboard keeps
+//! working alongside.
+
+use std::{
+    collections::HashMap,
+    sync::{mpsc::Receiver, Mutex},
+};
+
+use bevy::prelude::*;
+use snake_core::Direction;
+
+use crate::{
+    irc::{self, ChatMessage},
+    snake_movement_input, tick_timer, timer_finished, GameSet, MovementTick, SnakeHead,
+};
+
+const DIRECTIONS: [(Direction, &str); 4] = [
+    (Direction::Up, "U"),
+    (Direction::Down, "D"),
+    (Direction::Left, "L"),
+    (Direction::Right, "R"),
+];
+/// Width of a bar holding every vote.
+const BAR_WIDTH: f32 = 120.0;
+const BAR_COLOR: Color = Color::linear_rgba(0.6, 0.3, 0.9, 0.8);
+
+/// Connects to chat if the player asked for it on the command line. Needs a
+/// window and UI, so it is left out of [`crate::SnakeGamePlugin`].
+pub struct ChatPlaysPlugin;
+
+impl Plugin for ChatPlaysPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(channel) = std::env::args()
+            .skip_while(|arg| arg != "--chat-plays")
+            .nth(1)
+        else {
+            return;
+        };
+        info!("chat plays: reading votes from #{channel}");
+        app.insert_resource(Chat(Mutex::new(irc::join(&channel))))
+            .init_resource::<VoteTally>()
+            .add_systems(Startup, spawn_vote_bar)
+            .add_systems(Update, (collect_votes, show_votes).chain())
+            .add_systems(
+                FixedUpdate,
+                apply_votes
+                    .after(tick_timer::<MovementTick>)
+                    .after(snake_movement_input)
+                    .run_if(timer_finished::<MovementTick>)
+                    .in_set(GameSet::Input),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct Chat(Mutex<Receiver<ChatMessage>>);
+
+/// Each viewer's latest vote since the last movement tick.
+#[derive(Default, Resource)]
+struct VoteTally {
+    votes: HashMap<String, Direction>,
+}
+
+impl VoteTally {
+    fn count(&self, direction: Direction) -> usize {
+        self.votes
+            .values()
+            .filter(|&&vote| vote == direction)
+            .count()
+    }
+
+    /// The direction with the most votes, if exactly one leads.
+    fn winner(&self) -> Option<Direction> {
+        let counts = DIRECTIONS.map(|(direction, _)| (direction, self.count(direction)));
+        let best = counts.iter().map(|&(_, count)| count).max()?;
+        let mut leaders = counts.iter().filter(|&&(_, count)| count == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(&(direction, count)), None) if count > 0 => Some(direction),
+            _ => None,
+        }
+    }
+}
+
+fn parse_vote(text: &str) -> Option<Direction> {
+    match text.trim().to_lowercase().as_str() {
+        "u" | "up" => Some(Direction::Up),
+        "d" | "down" => Some(Direction::Down),
+        "l" | "left" => Some(Direction::Left),
+        "r" | "right" => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+fn collect_votes(chat: Res<Chat>, mut tally: ResMut<VoteTally>) {
+    let chat = chat.0.lock().unwrap();
+    for message in chat.try_iter() {
+        if let Some(direction) = parse_vote(&message.text) {
+            tally.votes.insert(message.user, direction);
+        }
+    }
+}
+
+fn apply_votes(mut tally: ResMut<VoteTally>, mut heads: Query<&mut SnakeHead>) {
+    if let Some(direction) = tally.winner() {
+        for mut head in &mut heads {
+            if direction != head.direction.opposite() {
+                debug!(?direction, votes = tally.count(direction), "chat steered");
+                head.direction = direction;
+            }
+        }
+    }
+    tally.votes.clear();
+}
+
+#[derive(Component)]
+struct VoteBar(Direction);
+
+#[derive(Component)]
+struct VoteCount(Direction);
+
+fn spawn_vote_bar(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(4.0),
+            left: Val::Px(4.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (direction, label) in DIRECTIONS {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(4.0),
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((Text::new(label), TextFont::from_font_size(12.0)));
+                        row.spawn((
+                            VoteBar(direction),
+                            Node {
+                                width: Val::Px(0.0),
+                                height: Val::Px(10.0),
+                                ..Default::default()
+                            },
+                            BackgroundColor(BAR_COLOR),
+                        ));
+                        row.spawn((
+                            VoteCount(direction),
+                            Text::default(),
+                            TextFont::from_font_size(12.0),
+                        ));
+                    });
+            }
+        });
+}
+
+fn show_votes(
+    tally: Res<VoteTally>,
+    mut bars: Query<(&VoteBar, &mut Node)>,
+    mut counts: Query<(&VoteCount, &mut Text)>,
+) {
+    if !tally.is_changed() {
+        return;
+    }
+    let total = tally.votes.len().max(1) as f32;
+    for (bar, mut node) in &mut bars {
+        node.width = Val::Px(BAR_WIDTH * tally.count(bar.0) as f32 / total);
+    }
+    for (count, mut text) in &mut counts {
+        text.0 = tally.count(count.0).to_string();
+    }
+}
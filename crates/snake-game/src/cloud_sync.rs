@@ -0,0 +1,172 @@
+//! Opt-in sync of the player's profile, enabled with `--sync <url>`.
+//!
+//! The profile's files are packed into a [`Bundle`] kept at
+//! `<url>/<profile>.json`, on any server that stores the body of a PUT and
+//! returns it on GET: a WebDAV share, or an S3-compatible bucket whose policy
+//! allows the requests, as they are not signed. `--sync-user <name>` adds
+//! basic auth, with the password read from `SNAKE_SYNC_PASSWORD` so it stays
+//! out of the process list.
+//!
+//! A sync runs in the background when a profile is chosen and after every
+//! run. It fetches the remote bundle, merges it with the local files
+//! ([`Bundle::merge`]), writes the files that changed locally and uploads
+//! the merged bundle if the remote was missing anything. Settings pulled this
+//! way apply straight away. The [`Default`](Profile::default) profile has no
+//! directory of its own and is never synced.
+
+use std::{io::Read, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+
+use crate::{
+    bundle::{Bundle, BundleError},
+    profile::Profile,
+    settings::SETTINGS_FILE,
+    GameOverEvent,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Adds profile sync if the player passed `--sync`.
+pub struct CloudSyncPlugin;
+
+impl Plugin for CloudSyncPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(url) = std::env::args().skip_while(|arg| arg != "--sync").nth(1) else {
+            return;
+        };
+        let authorization = std::env::args()
+            .skip_while(|arg| arg != "--sync-user")
+            .nth(1)
+            .map(|user| {
+                let password = std::env::var("SNAKE_SYNC_PASSWORD").unwrap_or_default();
+                format!("Basic {}", STANDARD.encode(format!("{user}:{password}")))
+            });
+        info!("syncing profiles with {url}");
+        app.insert_resource(CloudSync {
+            remote: Remote {
+                url: url.trim_end_matches('/').to_string(),
+                authorization,
+            },
+            syncing: None,
+            again: false,
+        })
+        .add_systems(
+            Update,
+            (
+                start_sync.run_if(resource_changed::<Profile>.or(on_event::<GameOverEvent>)),
+                finish_sync,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct CloudSync {
+    remote: Remote,
+    syncing: Option<Task<Result<Vec<String>, SyncError>>>,
+    /// Whether another sync was asked for while one was running.
+    again: bool,
+}
+
+impl CloudSync {
+    fn start(&mut self, profile: &Profile) {
+        let remote = self.remote.clone();
+        let profile = profile.clone();
+        self.syncing = Some(IoTaskPool::get().spawn(async move { remote.sync(&profile) }));
+    }
+}
+
+#[derive(Clone)]
+struct Remote {
+    url: String,
+    authorization: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SyncError {
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
+    #[error("request failed: {0}")]
+    Http(#[from] Box<ureq::Error>),
+    #[error("could not read response: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Remote {
+    fn request(&self, method: &str, profile: &str) -> ureq::Request {
+        let url = format!("{}/{}.json", self.url, profile.replace(' ', "%20"));
+        let request = ureq::AgentBuilder::new()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .request(method, &url);
+        match &self.authorization {
+            Some(authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    /// Merges the remote copy of `profile` into its directory and the local
+    /// files into the remote copy. Returns the files pulled.
+    fn sync(&self, profile: &Profile) -> Result<Vec<String>, SyncError> {
+        let mut local = Bundle::collect(&profile.dir, &profile.name)?;
+        let remote = match self.request("GET", &profile.name).call() {
+            Ok(response) => {
+                let mut bytes = Vec::new();
+                response.into_reader().read_to_end(&mut bytes)?;
+                Bundle::decode(&bytes)?
+            }
+            Err(ureq::Error::Status(404, _)) => Bundle::default(),
+            Err(err) => return Err(Box::new(err).into()),
+        };
+        let pulled = local.merge(&remote);
+        local.unpack(&profile.dir, &pulled)?;
+        if local.files != remote.files {
+            self.request("PUT", &profile.name)
+                .set("Content-Type", "application/json")
+                .send_bytes(&local.encode()?)
+                .map_err(Box::new)?;
+        }
+        Ok(pulled)
+    }
+}
+
+fn start_sync(mut sync: ResMut<CloudSync>, profile: Res<Profile>) {
+    if profile.dir.as_os_str().is_empty() {
+        return;
+    }
+    if sync.syncing.is_some() {
+        sync.again = true;
+        return;
+    }
+    sync.start(&profile);
+}
+
+fn finish_sync(mut sync: ResMut<CloudSync>, mut profile: ResMut<Profile>) {
+    let Some(task) = &mut sync.syncing else {
+        return;
+    };
+    let Some(result) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    sync.syncing = None;
+    match result {
+        Ok(pulled) if pulled.is_empty() => debug!("profile {} is in sync", profile.name),
+        Ok(pulled) => {
+            info!("pulled {} from the cloud", pulled.join(", "));
+            if pulled.iter().any(|name| name == SETTINGS_FILE) {
+                // Reloads the settings.
+                profile.set_changed();
+            }
+        }
+        Err(err) => warn!("could not sync profile {}: {err}", profile.name),
+    }
+    if std::mem::take(&mut sync.again) {
+        sync.start(&profile);
+    }
+}
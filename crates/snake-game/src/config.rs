@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use snake_core::{Arena, Position};
+
+#[cfg(feature = "embedded-assets")]
+use crate::embedded::EmbeddedAssetsPlugin;
+use crate::{
+    accessibility::{Accessibility, HIGH_CONTRAST},
+    assist::CollisionWarning,
+    embedded::asset_path,
+    loading::LoadingAssets,
+    mobile::TouchDpad,
+    predator::Predators,
+    rumble::Rumble,
+    tail_cutting::TailCutting,
+    Food, FoodSpawnTick, MovementTick, Theme, TickTimer, TICK_HZ,
+};
+
+const CONFIG_PATH: &str = "config.ron";
+
+/// Colors, speeds, arena size, rumble strength, accessibility modes and
+/// assists loaded from `assets/config.ron`. Built with the `hot-reload`
+/// feature, changes to the file apply to the running game. Once the player
+/// has changed a setting, [`crate::settings`] puts the saved ones back on top.
+#[derive(Asset, TypePath, Deserialize)]
+pub struct GameConfig {
+    theme: ThemeConfig,
+    arena: Arena,
+    movement_interval_ms: u64,
+    food_spawn_interval_ms: u64,
+    /// Simulation steps a second, see [`crate::TICK_HZ`].
+    #[serde(default = "default_tick_hz")]
+    tick_hz: f64,
+    /// Scales gamepad rumble, from 0 (off) to 1.
+    #[serde(default = "full_rumble")]
+    rumble_intensity: f32,
+    /// See [`crate::accessibility`].
+    #[serde(default)]
+    high_contrast: bool,
+    #[serde(default)]
+    reduced_motion: bool,
+    /// See [`crate::assist`].
+    #[serde(default = "enabled")]
+    collision_warning: bool,
+    #[serde(default)]
+    warning_blip: bool,
+    /// See [`crate::tail_cutting`].
+    #[serde(default)]
+    tail_cutting: bool,
+    /// See [`crate::predator`].
+    #[serde(default)]
+    predator: bool,
+    /// See [`crate::mobile`].
+    #[serde(default = "enabled")]
+    touch_dpad: bool,
+}
+
+fn default_tick_hz() -> f64 {
+    TICK_HZ
+}
+
+fn full_rumble() -> f32 {
+    1.0
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Linear RGB triples for each [`Theme`] color, and its font.
+#[derive(Deserialize)]
+struct ThemeConfig {
+    background: (f32, f32, f32),
+    snake_head: (f32, f32, f32),
+    snake_segment: (f32, f32, f32),
+    food: (f32, f32, f32),
+    #[serde(default = "default_obstacle")]
+    obstacle: (f32, f32, f32),
+    #[serde(default)]
+    font: Option<String>,
+}
+
+fn default_obstacle() -> (f32, f32, f32) {
+    (0.3, 0.4, 0.6)
+}
+
+impl From<&ThemeConfig> for Theme {
+    fn from(config: &ThemeConfig) -> Self {
+        let color = |(r, g, b)| Color::linear_rgb(r, g, b);
+        Self {
+            background: color(config.background),
+            snake_head: color(config.snake_head),
+            snake_segment: color(config.snake_segment),
+            food: color(config.food),
+            obstacle: color(config.obstacle),
+            font: config.font.clone(),
+        }
+    }
+}
+
+impl GameConfig {
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.arena.width == 0 || self.arena.height == 0 {
+            return Err("arena must be at least one cell wide and high");
+        }
+        if self.movement_interval_ms == 0 || self.food_spawn_interval_ms == 0 {
+            return Err("intervals must be longer than zero");
+        }
+        if !(1.0..=1000.0).contains(&self.tick_hz) {
+            return Err("tick rate must be between 1 and 1000 Hz");
+        }
+        if !(0.0..=1.0).contains(&self.rumble_intensity) {
+            return Err("rumble intensity must be between 0 and 1");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameConfigError {
+    #[error("could not read config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("config is malformed: {0}")]
+    Format(#[from] ron::de::SpannedError),
+}
+
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = GameConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GameConfig, GameConfigError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+/// Loads `assets/config.ron` and applies it to the running game. Needs the
+/// asset plugin, so it is kept apart from the headless gameplay systems.
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "embedded-assets")]
+        if !app.is_plugin_added::<EmbeddedAssetsPlugin>() {
+            app.add_plugins(EmbeddedAssetsPlugin);
+        }
+        app.init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .add_event::<ConfigApplied>()
+            .add_systems(Startup, load_config)
+            .add_systems(Update, apply_config);
+    }
+}
+
+/// Sent when a loaded or edited config has been applied, so saved settings
+/// can be put back on top of it.
+#[derive(Event)]
+pub struct ConfigApplied;
+
+#[derive(Resource)]
+pub(crate) struct ConfigHandle(Handle<GameConfig>);
+
+fn load_config(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    loading: Option<ResMut<LoadingAssets>>,
+) {
+    let handle = asset_server.load(asset_path(CONFIG_PATH));
+    if let Some(mut loading) = loading {
+        loading.track(handle.clone());
+    }
+    commands.insert_resource(ConfigHandle(handle));
+}
+
+/// Applies the config whenever it finishes loading or is edited on disk.
+/// Food left outside a shrunken arena is removed; a snake left outside dies on
+/// its next move.
+pub(crate) fn apply_config(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<GameConfig>>,
+    mut applied: EventWriter<ConfigApplied>,
+    configs: Res<Assets<GameConfig>>,
+    handle: Res<ConfigHandle>,
+    mut theme: ResMut<Theme>,
+    mut accessibility: ResMut<Accessibility>,
+    mut arena: ResMut<Arena>,
+    (mut fixed_time, mut movement_timer, mut food_spawn_timer): (
+        ResMut<Time<Fixed>>,
+        ResMut<TickTimer<MovementTick>>,
+        ResMut<TickTimer<FoodSpawnTick>>,
+    ),
+    mut rumble: Option<ResMut<Rumble>>,
+    mut dpad: Option<ResMut<TouchDpad>>,
+    mut warning: Option<ResMut<CollisionWarning>>,
+    mut tail_cutting: ResMut<TailCutting>,
+    mut predators: ResMut<Predators>,
+    food: Query<(Entity, &Position), With<Food>>,
+) {
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        if let Err(err) = config.validate() {
+            warn!("ignoring invalid {CONFIG_PATH}: {err}");
+            continue;
+        }
+        accessibility.set_if_neq(Accessibility {
+            high_contrast: config.high_contrast,
+            reduced_motion: config.reduced_motion,
+        });
+        theme.set_if_neq(if config.high_contrast {
+            HIGH_CONTRAST
+        } else {
+            (&config.theme).into()
+        });
+        if arena.set_if_neq(config.arena) {
+            for (entity, position) in food.iter() {
+                if !arena.contains(*position) {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+        fixed_time.set_timestep_hz(config.tick_hz);
+        movement_timer.set_interval(Duration::from_millis(config.movement_interval_ms));
+        food_spawn_timer.set_interval(Duration::from_millis(config.food_spawn_interval_ms));
+        if let Some(rumble) = rumble.as_mut() {
+            rumble.intensity = config.rumble_intensity;
+        }
+        if let Some(dpad) = dpad.as_mut() {
+            dpad.set_if_neq(TouchDpad {
+                enabled: config.touch_dpad,
+            });
+        }
+        if let Some(warning) = warning.as_mut() {
+            warning.set_if_neq(CollisionWarning {
+                enabled: config.collision_warning,
+                blip: config.warning_blip,
+            });
+        }
+        tail_cutting.set_if_neq(TailCutting(config.tail_cutting));
+        predators.set_if_neq(Predators(config.predator));
+        applied.send(ConfigApplied);
+        info!("applied {CONFIG_PATH}");
+    }
+}
@@ -0,0 +1,238 @@
+//! Developer console, toggled with the backtick key.
+//!
+//! Typed lines are parsed into [`CheatCommand`] events and applied by
+//! [`apply_cheats`]:
+//! - `spawn food <x> <y>` places food on a cell.
+//! - `spawn obstacle <x> <y>` blocks a cell.
+//! - `grow <n>` adds `n` segments at the tail.
+//! - `speed <ms>` sets the movement interval.
+//! - `teleport <x> <y>` moves the head to a cell.
+//!
+//! Cheating taints the run, so it is not kept as a replay and does not count
+//! towards high scores, stats, dailies or Steam. The console only exists in
+//! debug builds, or with `--dev` in release builds, see [`enabled`].
+
+use std::time::Duration;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_core::{Arena, Position};
+
+use crate::{
+    replay::ReplayRecorder, spawn_food, spawn_obstacle, spawn_segment, MovementTick, SegmentPool,
+    Segments, SnakeSegment, TickTimer,
+};
+
+/// Whether the console is available: always in debug builds, and with `--dev`
+/// in release builds.
+pub fn enabled() -> bool {
+    cfg!(debug_assertions) || std::env::args().any(|arg| arg == "--dev")
+}
+
+/// Adds the console UI and cheat handling. Needs a window and UI, so it is
+/// left out of [`crate::SnakeGamePlugin`].
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CheatCommand>()
+            .add_systems(Startup, spawn_console)
+            .add_systems(
+                Update,
+                (toggle_console, edit_console, apply_cheats, show_console).chain(),
+            );
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheatCommand {
+    SpawnFood(Position),
+    SpawnObstacle(Position),
+    Grow(u32),
+    Speed(Duration),
+    Teleport(Position),
+}
+
+impl std::str::FromStr for CheatCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse::<i64>()
+                .map_err(|_| format!("expected a number, got {word:?}"))
+        };
+        let position = |x: &str, y: &str| -> Result<Position, String> {
+            Ok(Position {
+                x: number(x)? as i32,
+                y: number(y)? as i32,
+            })
+        };
+        match words.as_slice() {
+            ["spawn", "food", x, y] => Ok(Self::SpawnFood(position(x, y)?)),
+            ["spawn", "obstacle", x, y] => Ok(Self::SpawnObstacle(position(x, y)?)),
+            ["grow", n] => Ok(Self::Grow(number(n)?.max(0) as u32)),
+            ["speed", ms] => Ok(Self::Speed(
+                Duration::from_millis(number(ms)?.max(1) as u64),
+            )),
+            ["teleport", x, y] => Ok(Self::Teleport(position(x, y)?)),
+            _ => Err(format!("unknown command {line:?}")),
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub(crate) struct Console {
+    pub(crate) open: bool,
+    line: String,
+    /// Result of the last command, shown under the input line.
+    feedback: String,
+}
+
+/// Whether the console is closed, so letter keys typed into it are not also
+/// taken as hotkeys.
+pub(crate) fn console_closed(consoles: Query<&Console>) -> bool {
+    !consoles.iter().any(|console| console.open)
+}
+
+fn spawn_console(mut commands: Commands) {
+    commands.spawn((
+        Console::default(),
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(4.0),
+            left: Val::Px(4.0),
+            ..Default::default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_console(keyboard_input: Res<ButtonInput<KeyCode>>, mut consoles: Query<&mut Console>) {
+    if !keyboard_input.just_pressed(KeyCode::Backquote) {
+        return;
+    }
+    for mut console in &mut consoles {
+        console.open = !console.open;
+        console.line.clear();
+    }
+}
+
+fn edit_console(
+    mut key_events: EventReader<KeyboardInput>,
+    mut consoles: Query<&mut Console>,
+    mut cheat_writer: EventWriter<CheatCommand>,
+) {
+    let Ok(mut console) = consoles.get_single_mut() else {
+        return;
+    };
+    if !console.open {
+        key_events.clear();
+        return;
+    }
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.line);
+                console.feedback = match line.parse::<CheatCommand>() {
+                    Ok(command) => {
+                        cheat_writer.send(command);
+                        format!("> {line}")
+                    }
+                    Err(err) => err,
+                };
+            }
+            Key::Backspace => {
+                console.line.pop();
+            }
+            Key::Escape => console.open = false,
+            Key::Character(text) if text.as_str() != "`" => console.line.push_str(text),
+            Key::Space => console.line.push(' '),
+            _ => {}
+        }
+    }
+}
+
+fn apply_cheats(
+    mut commands: Commands,
+    mut cheat_reader: EventReader<CheatCommand>,
+    arena: Res<Arena>,
+    mut snakes: Query<&mut Segments>,
+    mut pool: ResMut<SegmentPool>,
+    mut movement_timer: ResMut<TickTimer<MovementTick>>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut positions: Query<&mut Position, With<SnakeSegment>>,
+) {
+    for &command in cheat_reader.read() {
+        recorder.tainted = true;
+        recorder.cheated = true;
+        match command {
+            CheatCommand::SpawnFood(position) if arena.contains(position) => {
+                spawn_food(&mut commands, position);
+            }
+            CheatCommand::SpawnObstacle(position) if arena.contains(position) => {
+                spawn_obstacle(&mut commands, position);
+            }
+            CheatCommand::Teleport(position) if arena.contains(position) => {
+                let head = snakes
+                    .iter()
+                    .next()
+                    .and_then(|segments| segments.0.first().copied());
+                if let Some(mut head) = head.and_then(|head| positions.get_mut(head).ok()) {
+                    *head = position;
+                }
+            }
+            CheatCommand::SpawnFood(position)
+            | CheatCommand::SpawnObstacle(position)
+            | CheatCommand::Teleport(position) => {
+                warn!("cheat ignored, {position:?} is outside the arena");
+            }
+            CheatCommand::Grow(count) => {
+                let Some(mut segments) = snakes.iter_mut().next() else {
+                    warn!("cheat ignored, there is no snake to grow");
+                    continue;
+                };
+                let tail = segments
+                    .0
+                    .last()
+                    .and_then(|&tail| positions.get(tail).ok())
+                    .copied();
+                let Some(tail) = tail else {
+                    warn!("cheat ignored, there is no snake to grow");
+                    continue;
+                };
+                for _ in 0..count {
+                    segments
+                        .0
+                        .push(spawn_segment(&mut commands, &mut pool, tail));
+                }
+            }
+            CheatCommand::Speed(interval) => movement_timer.set_interval(interval),
+        }
+        info!("cheat applied: {command:?}");
+    }
+}
+
+fn show_console(mut consoles: Query<(&Console, &mut Text, &mut Visibility), Changed<Console>>) {
+    for (console, mut text, mut visibility) in &mut consoles {
+        *visibility = if console.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        text.0 = format!("{}\n] {}_", console.feedback, console.line);
+    }
+}
@@ -0,0 +1,193 @@
+//! Critters: small pests that wander casual boards and steal food.
+//!
+//! A critter turns up on a free cell every [`CRITTER_INTERVAL`], up to
+//! [`MAX_CRITTERS`] at once, and steps one cell towards the nearest food every
+//! [`CRITTER_STEP`], wandering at random when there is none or the way is
+//! blocked. Food a critter reaches is gone, with a [toast](crate::toast) to say
+//! so, and the snake has to be quick. Critters do the snake no harm: it passes
+//! over them, and they over it. They go around walls and each other, and stand
+//! still with the rest of the board while the [`WorldClock`] is frozen.
+//!
+//! Like pickups, critters have randomness of their own, but a run that loses
+//! food to one is no longer kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, Direction, GameMode, Position};
+
+use crate::{
+    game_over,
+    power_ups::Occupancy,
+    replay::ReplayRecorder,
+    timer_finished,
+    toast::{Toast, ToastPlugin},
+    world_clock::WorldClock,
+    Food, GameOverEvent, GameSet, MovementTick, Obstacle, Size, SnakeSegment,
+};
+
+pub const CRITTER_INTERVAL: Duration = Duration::from_secs(12);
+pub const MAX_CRITTERS: usize = 2;
+pub const CRITTER_STEP: Duration = Duration::from_millis(300);
+const CRITTER_COLOR: Color = Color::linear_rgb(0.6, 0.4, 0.3);
+
+/// A critter on the board.
+#[derive(Component)]
+pub struct Critter;
+
+/// Sends critters out in casual runs and moves them about.
+pub struct CrittersPlugin;
+
+impl Plugin for CrittersPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToastPlugin>() {
+            app.add_plugins(ToastPlugin);
+        }
+        app.insert_resource(CritterTimers {
+            spawn: Timer::new(CRITTER_INTERVAL, TimerMode::Repeating),
+            step: Timer::new(CRITTER_STEP, TimerMode::Repeating),
+        })
+        .add_systems(
+            FixedUpdate,
+            (
+                (spawn_critter, move_critters, steal_food)
+                    .chain()
+                    .in_set(GameSet::Spawning),
+                clear_critters
+                    .after(game_over)
+                    .run_if(on_event::<GameOverEvent>)
+                    .run_if(timer_finished::<MovementTick>)
+                    .in_set(GameSet::Logic),
+            ),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct CritterTimers {
+    spawn: Timer,
+    step: Timer,
+}
+
+fn spawn_critter(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    mut timers: ResMut<CritterTimers>,
+    critters: Query<(), With<Critter>>,
+    taken: Query<
+        &Position,
+        Or<(
+            With<SnakeSegment>,
+            With<Food>,
+            With<Obstacle>,
+            With<Critter>,
+        )>,
+    >,
+) {
+    if !timers.spawn.tick(clock.delta()).just_finished()
+        || !mode.is_casual()
+        || critters.iter().count() >= MAX_CRITTERS
+    {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "critter placed");
+    spawn_critter_at(&mut commands, position);
+}
+
+/// Puts a critter down at `position`.
+pub(crate) fn spawn_critter_at(commands: &mut Commands, position: Position) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                color: CRITTER_COLOR,
+                ..Default::default()
+            },
+            Critter,
+            position,
+            Size::square(0.5),
+        ))
+        .id()
+}
+
+fn distance(from: Position, to: Position) -> u32 {
+    from.x.abs_diff(to.x) + from.y.abs_diff(to.y)
+}
+
+/// Steps each critter towards the food nearest to it.
+fn move_critters(
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mut timers: ResMut<CritterTimers>,
+    food: Query<&Position, (With<Food>, Without<Critter>)>,
+    obstacles: Query<&Position, (With<Obstacle>, Without<Critter>)>,
+    mut critters: Query<&mut Position, With<Critter>>,
+) {
+    if !timers.step.tick(clock.delta()).just_finished() {
+        return;
+    }
+    let mut blocked = Occupancy::new(*arena, obstacles.iter().chain(&critters));
+    let mut rng = rand::rng();
+    for mut position in &mut critters {
+        let steps: Vec<Position> = Direction::ALL
+            .into_iter()
+            .map(|direction| position.step(direction))
+            .filter(|&step| blocked.free(step))
+            .collect();
+        let towards_food = food
+            .iter()
+            .min_by_key(|&&food| distance(*position, food))
+            .and_then(|&target| {
+                steps
+                    .iter()
+                    .copied()
+                    .filter(|&step| distance(step, target) < distance(*position, target))
+                    .min_by_key(|&step| distance(step, target))
+            });
+        // Wanders off when there is no food, or the way to it is blocked.
+        let next = towards_food
+            .or_else(|| (!steps.is_empty()).then(|| steps[rng.random_range(0..steps.len())]));
+        if let Some(next) = next {
+            blocked.set(*position, false);
+            blocked.set(next, true);
+            *position = next;
+        }
+    }
+}
+
+fn steal_food(
+    mut commands: Commands,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut toasts: EventWriter<Toast>,
+    critters: Query<&Position, With<Critter>>,
+    food: Query<(Entity, &Position), With<Food>>,
+) {
+    for critter in &critters {
+        for (entity, position) in &food {
+            if position == critter {
+                info!(food = ?*position, "food stolen by a critter");
+                toasts.send(Toast("A critter stole some food!".to_string()));
+                commands.entity(entity).despawn();
+                recorder.tainted = true;
+            }
+        }
+    }
+}
+
+fn clear_critters(
+    mut commands: Commands,
+    mut timers: ResMut<CritterTimers>,
+    critters: Query<Entity, With<Critter>>,
+) {
+    timers.spawn.reset();
+    for critter in &critters {
+        commands.entity(critter).despawn();
+    }
+}
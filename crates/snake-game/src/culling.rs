@@ -0,0 +1,101 @@
+//! Culling for arenas far bigger than the window, such as 500x500 boards.
+//!
+//! The board is split into square chunks of [`CHUNK`] cells. Food, obstacles,
+//! terrain and tunnel openings in chunks out of the camera's view, with a
+//! chunk to spare all round, have their [`Sprite`] put away in [`Culled`] and
+//! are left as data the game still plays by, so nothing is drawn or sent to
+//! the GPU for them.
+//! They are given their sprites back as the view reaches them. Only what moved
+//! is looked at again, unless the view crossed into other chunks.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use snake_core::{Arena, Position};
+
+use crate::{
+    follow_head, terrain::TerrainTile, tile_size, tunnels::TunnelMarker, Food, Obstacle, Theme,
+    ThemeColor,
+};
+
+/// Side of a chunk in cells.
+pub const CHUNK: i32 = 16;
+
+/// Culls what lies out of view. Needs a window, so it is left out of
+/// [`crate::SnakeGamePlugin`].
+pub struct CullingPlugin;
+
+impl Plugin for CullingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            cull.after(follow_head)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The sprite of something out of view, kept for when it comes back.
+#[derive(Component)]
+pub struct Culled(pub Sprite);
+
+/// Chunks drawn, corner to corner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct View {
+    min: IVec2,
+    max: IVec2,
+}
+
+impl View {
+    fn contains(&self, position: Position) -> bool {
+        let chunk = IVec2::new(position.x.div_euclid(CHUNK), position.y.div_euclid(CHUNK));
+        chunk.cmpge(self.min).all() && chunk.cmple(self.max).all()
+    }
+}
+
+type Cullable = Or<(
+    With<Food>,
+    With<Obstacle>,
+    With<TerrainTile>,
+    With<TunnelMarker>,
+)>;
+
+fn cull(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    theme: Res<Theme>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<&Transform, With<Camera2d>>,
+    mut last_view: Local<Option<View>>,
+    shown: Query<(Entity, Ref<Position>, &Sprite), (Cullable, Without<Culled>)>,
+    culled: Query<(Entity, Ref<Position>, &Culled, Option<&ThemeColor>)>,
+) {
+    let (Ok(window), Ok(camera)) = (windows.get_single(), cameras.get_single()) else {
+        return;
+    };
+    let tile = tile_size(&arena, window);
+    let board = Vec2::new(arena.width as f32, arena.height as f32);
+    let center = camera.translation.truncate() / tile + (board - 1.0) / 2.0;
+    let half = Vec2::new(window.width(), window.height()) / tile / 2.0;
+    let chunk = |cell: Vec2| (cell / CHUNK as f32).floor().as_ivec2();
+    let view = View {
+        min: chunk(center - half) - 1,
+        max: chunk(center + half) + 1,
+    };
+    let moved = last_view.replace(view) != Some(view);
+    for (entity, position, sprite) in &shown {
+        if (moved || position.is_changed()) && !view.contains(*position) {
+            commands
+                .entity(entity)
+                .remove::<Sprite>()
+                .insert(Culled(sprite.clone()));
+        }
+    }
+    for (entity, position, culled, role) in &culled {
+        if (moved || position.is_changed()) && view.contains(*position) {
+            let mut sprite = culled.0.clone();
+            if let Some(&role) = role {
+                sprite.color = theme.color(role);
+            }
+            commands.entity(entity).remove::<Culled>().insert(sprite);
+        }
+    }
+}
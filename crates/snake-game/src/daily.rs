@@ -0,0 +1,317 @@
+//! The daily challenge, played with `--daily`, and its history.
+//!
+//! Every run of a daily challenge starts from the same seed, taken from the
+//! date in UTC, so everyone playing that day gets the same food. The best
+//! score of each day is kept in `dailies.json` in the [`Profile`] along with
+//! the seed and how many tries it took, and the run itself is saved as
+//! `replays/daily-<date>.snkr` to be watched again with `--replay`. F1 lists
+//! past days.
+//!
+//! `--daily <YYYY-MM-DD>` retries an earlier day as practice: the same seed,
+//! but flagged on screen and not counted towards the history, high scores,
+//! stats, personal bests or leaderboards.
+
+use std::{collections::BTreeMap, fmt::Write as _, io, path::Path};
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
+use snake_core::persist;
+
+use crate::{
+    achievements::date,
+    bot::bot_playing,
+    game_over,
+    profile::Profile,
+    replay::{run_cheated, LastReplay},
+    GameOverEvent, GameSet, Score,
+};
+
+pub const DAILIES_FILE: &str = "dailies.json";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const SHOWN_DAYS: usize = 14;
+
+/// The day being played, by its number of days since the Unix epoch.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Daily {
+    pub day: u64,
+    /// Whether this is an earlier day played again, which does not count.
+    pub practice: bool,
+}
+
+impl Daily {
+    /// Seed every run of the day starts from.
+    pub fn seed(self) -> u64 {
+        // Spreads consecutive days far apart.
+        (self.day + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    }
+
+    /// `YYYY-MM-DD`.
+    pub fn date(self) -> String {
+        date(self.day * SECONDS_PER_DAY)
+    }
+}
+
+/// Run condition for systems that only count runs that are played for real,
+/// next to [`bot_playing`].
+pub fn practicing(daily: Option<Res<Daily>>) -> bool {
+    daily.is_some_and(|daily| daily.practice)
+}
+
+/// Today's number of days since the Unix epoch, in UTC.
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_PER_DAY
+}
+
+/// Days since the Unix epoch for a `YYYY-MM-DD` date.
+pub fn parse_date(text: &str) -> Option<u64> {
+    let mut parts = text.splitn(3, '-').map(str::parse::<i64>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Howard Hinnant's days-from-civil algorithm.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    // Rejects days the month does not have, like the 31st of April.
+    let days = u64::try_from(days).ok()?;
+    (date(days * SECONDS_PER_DAY) == text).then_some(days)
+}
+
+/// One day's challenge as the player left it.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub seed: u64,
+    pub best_score: u32,
+    pub attempts: u32,
+}
+
+/// Every daily challenge played, by date.
+#[derive(Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct DailyHistory(pub BTreeMap<String, DailyEntry>);
+
+impl DailyHistory {
+    /// Reads `path`, starting afresh if it does not exist yet.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        persist::load_json(path)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        persist::save_json(path, self)
+    }
+
+    /// Counts a try at `daily` that scored `score`, and returns whether it
+    /// was the day's best so far.
+    pub fn record(&mut self, daily: Daily, score: u32) -> bool {
+        let entry = self.0.entry(daily.date()).or_insert(DailyEntry {
+            seed: daily.seed(),
+            best_score: 0,
+            attempts: 0,
+        });
+        entry.attempts += 1;
+        let best = entry.attempts == 1 || score > entry.best_score;
+        if best {
+            entry.best_score = score;
+        }
+        best
+    }
+
+    /// Adds the days played in `other`, keeping the better score and the
+    /// larger count of tries for days both played.
+    pub fn merge(&mut self, other: &Self) {
+        for (date, theirs) in &other.0 {
+            let entry = self.0.entry(date.clone()).or_insert_with(|| theirs.clone());
+            entry.best_score = entry.best_score.max(theirs.best_score);
+            entry.attempts = entry.attempts.max(theirs.attempts);
+        }
+    }
+}
+
+/// Where the best run of the day on `date` is kept.
+pub fn replay_path(date: &str) -> String {
+    format!("replays/daily-{date}.snkr")
+}
+
+/// Sets up the daily challenge if the player passed `--daily`, and adds the
+/// history screen either way.
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .add_systems(Startup, spawn_screens)
+            .add_systems(
+                FixedUpdate,
+                record_daily
+                    .run_if(resource_exists::<Daily>)
+                    .run_if(on_event::<GameOverEvent>)
+                    .run_if(not(bot_playing))
+                    .run_if(not(run_cheated))
+                    .run_if(not(practicing))
+                    .after(crate::replay::finish_recording)
+                    .before(game_over)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(Update, toggle_history);
+        if let Some(daily) = daily_from_args() {
+            app.insert_resource(daily);
+        }
+    }
+}
+
+/// The challenge asked for with `--daily`, if there is one.
+fn daily_from_args() -> Option<Daily> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--daily");
+    args.next()?;
+    let today = today();
+    let daily = match args.next().filter(|arg| !arg.starts_with('-')) {
+        None => Daily {
+            day: today,
+            practice: false,
+        },
+        Some(text) => match parse_date(&text) {
+            Some(day) if day <= today => Daily {
+                day,
+                practice: day < today,
+            },
+            Some(_) => {
+                warn!("the daily challenge for {text} is not out yet");
+                return None;
+            }
+            None => {
+                warn!("ignoring daily challenge date {text:?}: use YYYY-MM-DD");
+                return None;
+            }
+        },
+    };
+    if daily.practice {
+        info!("practising the daily challenge for {}", daily.date());
+    } else {
+        info!("playing the daily challenge for {}", daily.date());
+    }
+    Some(daily)
+}
+
+fn record_daily(
+    daily: Res<Daily>,
+    score: Res<Score>,
+    last_replay: Res<LastReplay>,
+    profile: Res<Profile>,
+) {
+    let path = profile.path(DAILIES_FILE);
+    let mut history = match DailyHistory::read(&path) {
+        Ok(history) => history,
+        Err(err) => {
+            warn!("could not read {}: {err}", path.display());
+            return;
+        }
+    };
+    let best = history.record(*daily, score.0);
+    if let Err(err) = history.write(&path) {
+        warn!("could not save {}: {err}", path.display());
+    }
+    if !best {
+        return;
+    }
+    info!("best daily score for {}: {}", daily.date(), score.0);
+    if let Some(replay) = &last_replay.0 {
+        if let Err(err) = replay.export(&profile.path(&replay_path(&daily.date()))) {
+            warn!("could not save the daily replay: {err}");
+        }
+    }
+}
+
+#[derive(Component)]
+struct DailyLabel;
+
+#[derive(Component)]
+struct HistoryScreen;
+
+fn spawn_screens(mut commands: Commands, daily: Option<Res<Daily>>) {
+    if let Some(daily) = daily {
+        let label = if daily.practice {
+            format!("Practice: daily {} (not scored)", daily.date())
+        } else {
+            format!("Daily {}", daily.date())
+        };
+        commands.spawn((
+            DailyLabel,
+            Text(label),
+            TextFont {
+                font_size: 14.0,
+                ..Default::default()
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(8.0),
+                left: Val::Px(8.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        ));
+    }
+    commands.spawn((
+        HistoryScreen,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_history(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<Profile>,
+    mut screens: Query<(&mut Visibility, &mut Text), With<HistoryScreen>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F1) {
+        return;
+    }
+    let Ok((mut visibility, mut text)) = screens.get_single_mut() else {
+        return;
+    };
+    visibility.toggle_visible_hidden();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let history = DailyHistory::read(&profile.path(DAILIES_FILE)).unwrap_or_default();
+    text.0 = describe(&history);
+}
+
+fn describe(history: &DailyHistory) -> String {
+    let mut text = String::from("Daily challenges");
+    if history.0.is_empty() {
+        text.push_str("\nNone played yet: start one with --daily");
+        return text;
+    }
+    for (date, entry) in history.0.iter().rev().take(SHOWN_DAYS) {
+        let _ = write!(
+            text,
+            "\n{date}  best {:>4}  ({} tries)",
+            entry.best_score, entry.attempts
+        );
+    }
+    text.push_str("\n\nRetry a day with --daily <date>, watch it with --replay");
+    text
+}
@@ -0,0 +1,210 @@
+//! The day and night cycle of casual runs.
+//!
+//! Every run starts in the morning and goes round a [`DAY_LENGTH`] cycle of
+//! [`Phase`]s, tinting the board as it goes. Special food only turns up at
+//! some times of day:
+//!
+//! - golden food, worth [`GOLDEN_WORTH`] points, at night;
+//! - bonus food, worth [`BONUS_WORTH`] but gone again after [`BONUS_FOR`], at
+//!   dawn.
+//!
+//! The cycle follows the [`WorldClock`], so it stops whenever the game is
+//! paused or the world is frozen. Special food is placed with randomness of
+//! its own, and a run that gets any is no longer kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, GameMode, Position};
+
+use crate::{
+    game_over, power_ups::Occupancy, replay::ReplayRecorder, spawn_food, timer_finished,
+    world_clock::WorldClock, Food, GameOverEvent, GameSet, MovementTick, Obstacle, Size,
+    SnakeSegment, ThemeColor, Worth,
+};
+
+pub const DAY_LENGTH: Duration = Duration::from_secs(240);
+pub const GOLDEN_WORTH: u32 = 5;
+pub const BONUS_WORTH: u32 = 3;
+pub const BONUS_FOR: Duration = Duration::from_secs(6);
+/// How often special food turns up while its time of day lasts.
+pub const SPECIAL_FOOD_INTERVAL: Duration = Duration::from_secs(10);
+const GOLDEN_COLOR: Color = Color::linear_rgb(1.0, 0.75, 0.1);
+const BONUS_COLOR: Color = Color::linear_rgb(0.3, 0.9, 1.0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase {
+    Day,
+    Dusk,
+    Night,
+    Dawn,
+}
+
+impl Phase {
+    /// Color laid over the board.
+    fn tint(self) -> Color {
+        match self {
+            Self::Day => Color::NONE,
+            Self::Dusk => Color::linear_rgba(0.35, 0.1, 0.3, 0.2),
+            Self::Night => Color::linear_rgba(0.0, 0.02, 0.12, 0.4),
+            Self::Dawn => Color::linear_rgba(0.9, 0.45, 0.15, 0.15),
+        }
+    }
+}
+
+/// How far the run has got through the day.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DayNight {
+    pub elapsed: Duration,
+}
+
+impl DayNight {
+    pub fn phase(self) -> Phase {
+        let day = (self.elapsed.as_secs_f32() / DAY_LENGTH.as_secs_f32()).fract();
+        match day {
+            day if day < 0.4 => Phase::Day,
+            day if day < 0.5 => Phase::Dusk,
+            day if day < 0.85 => Phase::Night,
+            _ => Phase::Dawn,
+        }
+    }
+}
+
+/// Special food that goes away by itself.
+#[derive(Component)]
+pub(crate) struct Expires(pub(crate) Timer);
+
+/// Puts golden food down at `position`.
+pub fn spawn_golden(commands: &mut Commands, position: Position) -> Entity {
+    spawn_special(commands, position, GOLDEN_WORTH, GOLDEN_COLOR)
+}
+
+/// Puts bonus food down at `position`, `elapsed` into its time on the board.
+pub(crate) fn spawn_bonus(
+    commands: &mut Commands,
+    position: Position,
+    elapsed: Duration,
+) -> Entity {
+    let food = spawn_special(commands, position, BONUS_WORTH, BONUS_COLOR);
+    let mut expires = Timer::new(BONUS_FOR, TimerMode::Once);
+    expires.set_elapsed(elapsed);
+    commands.entity(food).insert(Expires(expires));
+    food
+}
+
+fn spawn_special(commands: &mut Commands, position: Position, worth: u32, color: Color) -> Entity {
+    let food = spawn_food(commands, position);
+    commands.entity(food).remove::<ThemeColor>().insert((
+        Worth(worth),
+        Sprite::from_color(color, Vec2::ONE),
+        Size::square(0.7),
+    ));
+    food
+}
+
+/// Turns day into night in casual runs, and places the special food.
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DayNight>()
+            .insert_resource(SpecialFoodTimer(Timer::new(
+                SPECIAL_FOOD_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .add_systems(Startup, spawn_tint)
+            .add_systems(
+                FixedUpdate,
+                (
+                    (pass_time, expire, spawn_special_food)
+                        .chain()
+                        .in_set(GameSet::Spawning),
+                    start_day
+                        .after(game_over)
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                ),
+            )
+            .add_systems(Update, tint);
+    }
+}
+
+#[derive(Resource)]
+struct SpecialFoodTimer(Timer);
+
+#[derive(Component)]
+struct Tint;
+
+fn pass_time(mode: Res<GameMode>, clock: Res<WorldClock>, mut day_night: ResMut<DayNight>) {
+    if mode.is_casual() {
+        day_night.elapsed += clock.delta();
+    }
+}
+
+fn start_day(mut day_night: ResMut<DayNight>, mut timer: ResMut<SpecialFoodTimer>) {
+    *day_night = DayNight::default();
+    timer.0.reset();
+}
+
+fn expire(mut commands: Commands, clock: Res<WorldClock>, mut food: Query<(Entity, &mut Expires)>) {
+    for (entity, mut expires) in &mut food {
+        if expires.0.tick(clock.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_special_food(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    day_night: Res<DayNight>,
+    mut timer: ResMut<SpecialFoodTimer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    if !timer.0.tick(clock.delta()).just_finished() || !mode.is_casual() {
+        return;
+    }
+    let phase = day_night.phase();
+    if !matches!(phase, Phase::Night | Phase::Dawn) {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    recorder.tainted = true;
+    if phase == Phase::Night {
+        debug!(?position, "golden food placed");
+        spawn_golden(&mut commands, position);
+    } else {
+        debug!(?position, "bonus food placed");
+        spawn_bonus(&mut commands, position, Duration::ZERO);
+    }
+}
+
+fn spawn_tint(mut commands: Commands) {
+    commands.spawn((
+        Tint,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::NONE),
+    ));
+}
+
+fn tint(day_night: Res<DayNight>, mut tints: Query<&mut BackgroundColor, With<Tint>>) {
+    let color = day_night.phase().tint();
+    for mut background in &mut tints {
+        background.set_if_neq(BackgroundColor(color));
+    }
+}
@@ -0,0 +1,96 @@
+//! Why the last run ended: a line for the game over screen, like "Hit the wall
+//! at (9,4)" or "Bit your own tail at length 23", and the cell it ended on lit
+//! up for a moment.
+//!
+//! The board clears on the tick the run ends, so the highlight marks where the
+//! snake died over the fresh board, fading out over [`FATAL_CELL_FOR`]. With
+//! reduced motion it stays solid until it goes.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use snake_core::{Collision, Position};
+
+use crate::{accessibility::Accessibility, GameOverCause, GameOverEvent, Segments, Size};
+
+pub const FATAL_CELL_FOR: Duration = Duration::from_secs(1);
+const FATAL_CELL_COLOR: Color = Color::linear_rgb(1.0, 0.1, 0.1);
+
+/// How the last run ended, kept until the next one does.
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub struct LastDeath {
+    pub reason: String,
+    pub cell: Option<Position>,
+}
+
+/// The highlight on the cell a run ended on.
+#[derive(Component)]
+pub struct FatalCell(Timer);
+
+/// What to tell the player about a run that ended by `cause` on `cell`, the
+/// snake being `length` long.
+fn reason(cause: GameOverCause, cell: Option<Position>, length: usize) -> String {
+    let at = cell.map_or_else(String::new, |cell| format!(" at ({},{})", cell.x, cell.y));
+    match cause {
+        GameOverCause::Collision(Collision::Wall) => format!("Hit the wall{at}"),
+        GameOverCause::Collision(Collision::Body) => {
+            format!("Bit your own tail at length {length}")
+        }
+        GameOverCause::Caught => format!("Caught by the predator{at}"),
+        GameOverCause::Finished => "Reached the target score".to_string(),
+        GameOverCause::Interrupted => "Run interrupted".to_string(),
+        GameOverCause::Won => "Filled the board".to_string(),
+    }
+}
+
+/// Notes why the run ended, while the snake is still there to measure, and
+/// lights up where it did.
+pub(crate) fn record_death(
+    mut commands: Commands,
+    mut reader: EventReader<GameOverEvent>,
+    snakes: Query<&Segments>,
+    mut last_death: ResMut<LastDeath>,
+    marks: Query<Entity, With<FatalCell>>,
+) {
+    let Some(&GameOverEvent { cause, cell }) = reader.read().last() else {
+        return;
+    };
+    *last_death = LastDeath {
+        reason: reason(
+            cause,
+            cell,
+            snakes.iter().next().map_or(0, |segments| segments.0.len()),
+        ),
+        cell,
+    };
+    info!(reason = last_death.reason, "run ended");
+    for mark in &marks {
+        commands.entity(mark).despawn();
+    }
+    if let Some(cell) = cell {
+        commands.spawn((
+            Sprite {
+                color: FATAL_CELL_COLOR,
+                ..Default::default()
+            },
+            FatalCell(Timer::new(FATAL_CELL_FOR, TimerMode::Once)),
+            cell,
+            Size::square(1.0),
+        ));
+    }
+}
+
+pub(crate) fn fade_fatal_cell(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+    mut marks: Query<(Entity, &mut FatalCell, &mut Sprite)>,
+) {
+    for (entity, mut mark, mut sprite) in &mut marks {
+        if mark.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else if !accessibility.reduced_motion {
+            sprite.color = FATAL_CELL_COLOR.with_alpha(mark.0.fraction_remaining());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Timing and state readout toggled with F3.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::entity::Entities,
+    prelude::*,
+};
+
+use snake_core::Position;
+
+use crate::{Food, MovementTick, Run, Segments, SnakeHead, TickTimer};
+
+const ARROW_KEYS: [(KeyCode, &str); 4] = [
+    (KeyCode::ArrowLeft, "Left"),
+    (KeyCode::ArrowRight, "Right"),
+    (KeyCode::ArrowUp, "Up"),
+    (KeyCode::ArrowDown, "Down"),
+];
+
+/// Adds the F3 overlay. Needs a window and UI, so it is left out of
+/// [`crate::SnakeGamePlugin`].
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.add_systems(Startup, spawn_overlay)
+            .add_systems(Update, (toggle_overlay, update_overlay).chain());
+    }
+}
+
+#[derive(Component)]
+struct DebugOverlay;
+
+fn spawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        DebugOverlay,
+        Text::default(),
+        TextFont {
+            font_size: 12.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.0),
+            left: Val::Px(4.0),
+            ..Default::default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlays: Query<&mut Visibility, With<DebugOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+    for mut visibility in &mut overlays {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    fixed_time: Res<Time<Fixed>>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+    run: Res<Run>,
+    entities: &Entities,
+    food: Query<(), With<Food>>,
+    heads: Query<(&SnakeHead, &Position, &Segments)>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlays: Query<(&mut Text, &Visibility), With<DebugOverlay>>,
+) {
+    let Ok((mut text, visibility)) = overlays.get_single_mut() else {
+        return;
+    };
+    if visibility == Visibility::Hidden {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+    let movement_interval = movement_timer.current_interval().as_secs_f64();
+    let (direction, head, length) = match heads.iter().next() {
+        Some((head, position, segments)) => (
+            format!("{:?}", head.direction),
+            format!("({}, {})", position.x, position.y),
+            segments.0.len(),
+        ),
+        None => ("-".to_string(), "-".to_string(), 0),
+    };
+    let held: Vec<&str> = ARROW_KEYS
+        .iter()
+        .filter(|(key, _)| keyboard_input.pressed(*key))
+        .map(|&(_, name)| name)
+        .collect();
+
+    text.0 = format!(
+        "fps {fps:.0}\n\
+         tick {} ({:.1}/s, fixed {:.0} Hz)\n\
+         entities {} (food {})\n\
+         length {}\n\
+         direction {direction}\n\
+         head {head}\n\
+         held [{}]",
+        run.tick,
+        1.0 / movement_interval,
+        1.0 / fixed_time.timestep().as_secs_f64(),
+        entities.len(),
+        food.iter().count(),
+        length,
+        held.join(", "),
+    );
+}
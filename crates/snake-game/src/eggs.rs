@@ -0,0 +1,121 @@
+//! Eggs: food that turns up now and then in casual runs and hatches if left
+//! alone.
+//!
+//! An egg is eaten like any food, for a point, but left for [`HATCH_AFTER`] it
+//! hatches into [`HATCHLINGS`] ordinary food on free cells within
+//! [`HATCH_RADIUS`] of it, announced in a [toast](crate::toast), so guarding it
+//! pays better than eating it. One egg at a time is laid every
+//! [`EGG_INTERVAL`]. Hatching follows the [`WorldClock`], and eggs are laid
+//! with randomness of their own; a run with an egg in it is no longer kept as a
+//! replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use snake_core::{Arena, GameMode, Position};
+
+use crate::{
+    power_ups::Occupancy,
+    replay::ReplayRecorder,
+    spawn_food,
+    toast::{Toast, ToastPlugin},
+    world_clock::WorldClock,
+    Food, GameSet, Obstacle, Size, SnakeSegment, ThemeColor,
+};
+
+pub const EGG_INTERVAL: Duration = Duration::from_secs(15);
+pub const HATCH_AFTER: Duration = Duration::from_secs(10);
+pub const HATCHLINGS: usize = 3;
+/// Cells from the egg its food can land, diagonals included.
+pub const HATCH_RADIUS: i32 = 2;
+const EGG_COLOR: Color = Color::linear_rgb(0.95, 0.9, 0.75);
+
+/// Food that hatches once its timer finishes.
+#[derive(Component)]
+pub struct Egg(pub Timer);
+
+/// Puts an egg down at `position`.
+pub fn spawn_egg(commands: &mut Commands, position: Position) -> Entity {
+    let egg = spawn_food(commands, position);
+    commands.entity(egg).remove::<ThemeColor>().insert((
+        Egg(Timer::new(HATCH_AFTER, TimerMode::Once)),
+        Sprite::from_color(EGG_COLOR, Vec2::ONE),
+        Size::square(0.6),
+    ));
+    egg
+}
+
+/// Lays eggs in casual runs and hatches them.
+pub struct EggsPlugin;
+
+impl Plugin for EggsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToastPlugin>() {
+            app.add_plugins(ToastPlugin);
+        }
+        app.insert_resource(EggLayer(Timer::new(EGG_INTERVAL, TimerMode::Repeating)))
+            .add_systems(
+                FixedUpdate,
+                (lay_egg, hatch_eggs).chain().in_set(GameSet::Spawning),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct EggLayer(Timer);
+
+fn lay_egg(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    mut layer: ResMut<EggLayer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    eggs: Query<(), With<Egg>>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    if !layer.0.tick(clock.delta()).just_finished() || !mode.is_casual() || !eggs.is_empty() {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "egg laid");
+    recorder.tainted = true;
+    spawn_egg(&mut commands, position);
+}
+
+fn hatch_eggs(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mut toasts: EventWriter<Toast>,
+    mut eggs: Query<(Entity, &mut Egg, &Position)>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    let mut occupancy = Occupancy::new(*arena, &taken);
+    for (entity, mut egg, &position) in &mut eggs {
+        if !egg.0.tick(clock.delta()).finished() {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let mut nearby: Vec<Position> = (-HATCH_RADIUS..=HATCH_RADIUS)
+            .flat_map(|dx| (-HATCH_RADIUS..=HATCH_RADIUS).map(move |dy| (dx, dy)))
+            .map(|(dx, dy)| Position {
+                x: position.x + dx,
+                y: position.y + dy,
+            })
+            .filter(|&cell| occupancy.free(cell))
+            .collect();
+        nearby.shuffle(&mut rand::rng());
+        info!(?position, "egg hatched");
+        toasts.send(Toast("An egg hatched!".to_string()));
+        for &cell in nearby.iter().take(HATCHLINGS) {
+            occupancy.set(cell, true);
+            spawn_food(&mut commands, cell);
+        }
+    }
+}
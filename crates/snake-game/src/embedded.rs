@@ -0,0 +1,42 @@
+//! Assets baked into the executable with the `embedded-assets` feature, so a
+//! release build is a single file that runs from any directory.
+//!
+//! Every file under `assets/` is embedded, as listed by the build script, and
+//! loads from the `embedded://` source at the same path it has there.
+//! [`asset_path`] picks that source or `assets/` on disk, whichever the build
+//! has.
+
+use bevy::asset::AssetPath;
+#[cfg(feature = "embedded-assets")]
+use bevy::prelude::*;
+
+/// Every file under `assets/`, by its path there.
+#[cfg(feature = "embedded-assets")]
+const EMBEDDED: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// Where to load the asset at `path` under `assets/` from: the copy baked
+/// into the executable with the `embedded-assets` feature, or else the file.
+pub fn asset_path(path: &str) -> AssetPath<'static> {
+    let path = AssetPath::from(path).into_owned();
+    #[cfg(feature = "embedded-assets")]
+    let path = path.with_source("embedded");
+    path
+}
+
+/// Registers every embedded asset. Plugins that load from `assets/` add it
+/// themselves.
+#[cfg(feature = "embedded-assets")]
+pub struct EmbeddedAssetsPlugin;
+
+#[cfg(feature = "embedded-assets")]
+impl Plugin for EmbeddedAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let registry = app
+            .world()
+            .resource::<bevy::asset::io::embedded::EmbeddedAssetRegistry>();
+        for &(path, bytes) in EMBEDDED {
+            registry.insert_asset(assets.join(path), std::path::Path::new(path), bytes);
+        }
+    }
+}
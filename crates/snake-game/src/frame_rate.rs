@@ -0,0 +1,158 @@
+//! Vsync and a frame rate cap, so a game that moves a few times a second does
+//! not keep the GPU busy drawing hundreds of identical frames.
+//!
+//! Ctrl+V turns [`Vsync`] on and off and Ctrl+L steps through the
+//! [`FpsCap`]s; both are saved with the other [`crate::settings`]. The cap
+//! holds each frame back until its share of the second is up. Browsers pace
+//! frames themselves, so on the web only vsync applies.
+//!
+//! While a menu or dialog has the game paused, or it is frame-stepping, the
+//! game drops to [`LOW_POWER_FRAME`] and only updates sooner for input, so an
+//! idle window costs next to no battery. Full rate is back as soon as play
+//! resumes.
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+    winit::{UpdateMode, WinitSettings},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{console::console_closed, frame_step::FrameStep};
+
+/// Longest wait between updates while the game is paused.
+pub const LOW_POWER_FRAME: Duration = Duration::from_millis(100);
+
+/// Whether frames wait for the display's refresh.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Vsync(pub bool);
+
+impl Default for Vsync {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Most frames drawn per second.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum FpsCap {
+    Fps30,
+    Fps60,
+    Fps120,
+    #[default]
+    Unlimited,
+}
+
+impl FpsCap {
+    /// Shortest time a frame may take, if there is one.
+    pub fn frame_time(self) -> Option<Duration> {
+        let fps = match self {
+            FpsCap::Fps30 => 30,
+            FpsCap::Fps60 => 60,
+            FpsCap::Fps120 => 120,
+            FpsCap::Unlimited => return None,
+        };
+        Some(Duration::from_secs(1) / fps)
+    }
+
+    fn next(self) -> Self {
+        match self {
+            FpsCap::Fps30 => FpsCap::Fps60,
+            FpsCap::Fps60 => FpsCap::Fps120,
+            FpsCap::Fps120 => FpsCap::Unlimited,
+            FpsCap::Unlimited => FpsCap::Fps30,
+        }
+    }
+}
+
+/// Applies vsync to the window, caps the frame rate and slows down while
+/// paused. Needs a window, so it is left out of [`crate::SnakeGamePlugin`].
+pub struct FrameRatePlugin;
+
+impl Plugin for FrameRatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Vsync>()
+            .init_resource::<FpsCap>()
+            .init_resource::<WinitSettings>()
+            .add_systems(
+                Update,
+                (
+                    frame_rate_input.run_if(console_closed),
+                    apply_vsync,
+                    low_power_when_paused,
+                )
+                    .chain(),
+            );
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Last, limit_frame_rate);
+    }
+}
+
+fn frame_rate_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut vsync: ResMut<Vsync>,
+    mut cap: ResMut<FpsCap>,
+) {
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+        vsync.0 = !vsync.0;
+        info!(vsync = vsync.0, "vsync");
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        *cap = cap.next();
+        info!(cap = ?*cap, "frame rate cap");
+    }
+}
+
+fn apply_vsync(vsync: Res<Vsync>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    let present_mode = if vsync.0 {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    for mut window in &mut windows {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+}
+
+/// Updates only every [`LOW_POWER_FRAME`] or on input while nothing moves,
+/// and continuously again once something does.
+fn low_power_when_paused(
+    time: Res<Time<Virtual>>,
+    frame_step: Res<FrameStep>,
+    mut winit: ResMut<WinitSettings>,
+    mut low_power: Local<bool>,
+) {
+    let paused = time.is_paused() || frame_step.paused;
+    if paused == *low_power {
+        return;
+    }
+    *low_power = paused;
+    *winit = if paused {
+        WinitSettings {
+            focused_mode: UpdateMode::reactive(LOW_POWER_FRAME),
+            unfocused_mode: UpdateMode::reactive_low_power(LOW_POWER_FRAME),
+        }
+    } else {
+        WinitSettings::game()
+    };
+    debug!(low_power = paused, "update rate");
+}
+
+/// Sleeps out the rest of the frame's time at the end of each update. Frames
+/// that ran long are not made up for.
+#[cfg(not(target_arch = "wasm32"))]
+fn limit_frame_rate(cap: Res<FpsCap>, mut last: Local<Option<std::time::Instant>>) {
+    if let (Some(frame_time), Some(last)) = (cap.frame_time(), *last) {
+        if let Some(rest) = frame_time.checked_sub(last.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+    *last = Some(std::time::Instant::now());
+}
@@ -0,0 +1,52 @@
+//! Debug pause that advances the game one movement tick at a time.
+//!
+//! F10 freezes the tick timers; while frozen, `.` runs exactly one movement
+//! tick, with food spawning advanced by the same amount of game time.
+
+use bevy::prelude::*;
+
+use crate::{FoodSpawnTick, MovementTick, TickTimer};
+
+#[derive(Default, Resource)]
+pub struct FrameStep {
+    pub paused: bool,
+    /// Set by the step key, consumed by the next fixed update.
+    pub step_requested: bool,
+}
+
+pub fn frame_step_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut frame_step: ResMut<FrameStep>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F10) {
+        frame_step.paused = !frame_step.paused;
+        info!(
+            "frame step {}",
+            if frame_step.paused {
+                "paused"
+            } else {
+                "resumed"
+            }
+        );
+    }
+    if frame_step.paused && keyboard_input.just_pressed(KeyCode::Period) {
+        frame_step.step_requested = true;
+    }
+}
+
+/// Runs the timers up to the next movement tick. Goes after the regular
+/// timer ticks, which see zero elapsed time while paused.
+pub fn step_timers(
+    fixed_time: Res<Time<Fixed>>,
+    mut frame_step: ResMut<FrameStep>,
+    mut movement_timer: ResMut<TickTimer<MovementTick>>,
+    mut food_spawn_timer: ResMut<TickTimer<FoodSpawnTick>>,
+) {
+    if !std::mem::take(&mut frame_step.step_requested) {
+        return;
+    }
+    let timestep = fixed_time.timestep();
+    let remaining = movement_timer.remaining(timestep);
+    movement_timer.tick(remaining, timestep);
+    food_spawn_timer.tick(remaining, timestep);
+}
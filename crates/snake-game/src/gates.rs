@@ -0,0 +1,88 @@
+//! Locked gates and the keys that open them.
+//!
+//! A [`Lock`] is a key lying on the board and the gate cells it opens. Gates
+//! are walls like any other [`Obstacle`](crate::Obstacle) until the snake's
+//! head reaches the key, when every gate of that lock opens at once. Each lock
+//! on a board has its own color, shared by its key and its gates, so levels
+//! can hide one part of the board behind another.
+
+use bevy::prelude::*;
+use snake_core::Position;
+
+use crate::{spawn_obstacle, Size, SnakeHead, ThemeColor};
+
+const LOCK_COLORS: [Color; 3] = [
+    Color::linear_rgb(0.9, 0.7, 0.1),
+    Color::linear_rgb(0.2, 0.5, 0.95),
+    Color::linear_rgb(0.8, 0.25, 0.8),
+];
+
+/// A key and the gate cells it opens.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Lock {
+    pub key: Position,
+    pub gate: Vec<Position>,
+}
+
+/// A gate cell, opened by the key of the same lock.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Gate(pub usize);
+
+/// A key waiting to be picked up.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key(pub usize);
+
+fn color(lock: usize) -> Color {
+    LOCK_COLORS[lock % LOCK_COLORS.len()]
+}
+
+/// Puts down lock number `index`: its key, and its gate as walls.
+pub fn spawn_lock(commands: &mut Commands, index: usize, lock: &Lock) {
+    for &position in &lock.gate {
+        spawn_gate(commands, index, position);
+    }
+    spawn_key(commands, index, lock.key);
+}
+
+/// Puts down one gate cell of lock number `index`.
+pub(crate) fn spawn_gate(commands: &mut Commands, index: usize, position: Position) -> Entity {
+    let gate = spawn_obstacle(commands, position);
+    commands
+        .entity(gate)
+        .remove::<ThemeColor>()
+        .insert((Gate(index), Sprite::from_color(color(index), Vec2::ONE)));
+    gate
+}
+
+/// Puts down the key of lock number `index`.
+pub(crate) fn spawn_key(commands: &mut Commands, index: usize, position: Position) -> Entity {
+    commands
+        .spawn((
+            Key(index),
+            Sprite::from_color(color(index), Vec2::ONE),
+            position,
+            Size::square(0.5),
+        ))
+        .id()
+}
+
+/// Opens the gates of any key the head has reached.
+pub(crate) fn unlock(
+    mut commands: Commands,
+    heads: Query<&Position, With<SnakeHead>>,
+    keys: Query<(Entity, &Key, &Position)>,
+    gates: Query<(Entity, &Gate)>,
+) {
+    for head in &heads {
+        for (entity, &Key(lock), position) in &keys {
+            if position != head {
+                continue;
+            }
+            info!(lock, "gates unlocked");
+            commands.entity(entity).despawn();
+            for (gate, _) in gates.iter().filter(|(_, gate)| gate.0 == lock) {
+                commands.entity(gate).despawn();
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Racing the personal-best speedrun.
+//!
+//! Each level, meaning each arena size and pair of tick intervals, keeps its
+//! own best run under `replays/` in the [`Profile`], replaced whenever a run
+//! reaches the target faster. The best for the level being played runs
+//! alongside as a translucent ghost. G turns racing it on or off, and Delete
+//! throws the level's best away so the next finished run sets a new one.
+
+use std::{fs, io};
+
+use bevy::prelude::*;
+
+use snake_core::{
+    persist,
+    replay::{Replay, ReplayConfig},
+    sim::Simulation,
+    Arena, GameMode, Position, SPEEDRUN_TARGET_SCORE,
+};
+
+use crate::{
+    profile::Profile,
+    replay::{CurrentConfig, LastReplay},
+    GameOverCause, GameOverEvent, Run, Score, Size,
+};
+
+/// Where the single personal best was kept before there was one per level.
+const LEGACY_PERSONAL_BEST_PATH: &str = "replays/speedrun-pb.snkr";
+const GHOST_HEAD_COLOR: Color = Color::linear_rgba(0.7, 0.7, 0.7, 0.35);
+const GHOST_SEGMENT_COLOR: Color = Color::linear_rgba(0.3, 0.3, 0.3, 0.35);
+
+#[derive(Component)]
+pub struct GhostSegment;
+
+/// The personal-best speedrun, re-simulated tick for tick alongside the live
+/// run.
+#[derive(Resource)]
+pub struct Ghost {
+    replay: Replay,
+    sim: Simulation,
+    segments: Vec<Entity>,
+}
+
+/// Whether the player wants the ghost shown, toggled with G.
+#[derive(Resource)]
+pub struct RaceGhost(pub bool);
+
+impl Default for RaceGhost {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Where the personal best for the level `config` describes is kept.
+fn personal_best_path(config: &ReplayConfig) -> String {
+    format!(
+        "replays/speedrun-{}x{}-{}ms-{}ms-pb.snkr",
+        config.arena_width,
+        config.arena_height,
+        config.movement_interval_ms,
+        config.food_spawn_interval_ms
+    )
+}
+
+/// The stored personal best for the level `config` describes.
+fn load_personal_best(profile: &Profile, config: &ReplayConfig) -> Option<Replay> {
+    Replay::import(&profile.path(&personal_best_path(config)), config)
+        .or_else(|_| Replay::import(&profile.path(LEGACY_PERSONAL_BEST_PATH), config))
+        .ok()
+}
+
+pub fn in_speedrun(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Speedrun
+}
+
+/// Ends the run once the speedrun target is reached.
+pub fn speedrun_goal(score: Res<Score>, mut game_over_writer: EventWriter<GameOverEvent>) {
+    if score.0 >= SPEEDRUN_TARGET_SCORE {
+        game_over_writer.send(GameOverEvent {
+            cause: GameOverCause::Finished,
+            cell: None,
+        });
+    }
+}
+
+/// Keeps the just-finished run as the new personal best if it reached the
+/// target faster than the stored one.
+pub fn save_personal_best(
+    score: Res<Score>,
+    config: CurrentConfig,
+    last_replay: Res<LastReplay>,
+    profile: Res<Profile>,
+) {
+    let Some(replay) = &last_replay.0 else {
+        return;
+    };
+    if score.0 < SPEEDRUN_TARGET_SCORE {
+        return;
+    }
+    let config = config.get();
+    if load_personal_best(&profile, &config)
+        .is_some_and(|best| best.duration_ticks <= replay.duration_ticks)
+    {
+        return;
+    }
+    let path = profile.path(&personal_best_path(&config));
+    match replay.export(&path) {
+        Ok(()) => info!("new personal best: {} ticks", replay.duration_ticks),
+        Err(err) => error!("could not save personal best: {err}"),
+    }
+}
+
+fn remove_ghost(commands: &mut Commands, ghost: Option<ResMut<Ghost>>) {
+    if let Some(mut ghost) = ghost {
+        for segment in ghost.segments.drain(..) {
+            commands.entity(segment).despawn();
+        }
+        commands.remove_resource::<Ghost>();
+    }
+}
+
+/// Restarts the ghost from the stored personal best at the start of a run.
+pub fn reset_ghost(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    arena: Res<Arena>,
+    config: CurrentConfig,
+    profile: Res<Profile>,
+    race: Res<RaceGhost>,
+    ghost: Option<ResMut<Ghost>>,
+) {
+    remove_ghost(&mut commands, ghost);
+    if *mode != GameMode::Speedrun || !race.0 {
+        return;
+    }
+    let Some(replay) = load_personal_best(&profile, &config.get()) else {
+        info!("no personal best for this level yet");
+        return;
+    };
+    info!(
+        "racing your best of {} ticks (G to hide it, Delete to remove it)",
+        replay.duration_ticks
+    );
+    commands.insert_resource(Ghost {
+        sim: Simulation::new(replay.seed, *arena),
+        replay,
+        segments: Vec::new(),
+    });
+}
+
+/// G shows or hides the ghost, from the next run on; Delete forgets the
+/// level's personal best.
+pub fn ghost_input(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mode: Res<GameMode>,
+    config: CurrentConfig,
+    profile: Res<Profile>,
+    mut race: ResMut<RaceGhost>,
+    ghost: Option<ResMut<Ghost>>,
+) {
+    if *mode != GameMode::Speedrun {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        race.0 = !race.0;
+        if race.0 {
+            info!("racing your personal best from the next run");
+        } else {
+            info!("racing without your personal best");
+            remove_ghost(&mut commands, ghost);
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Delete) {
+        let config = config.get();
+        let mut deleted = false;
+        for path in [
+            profile.path(&personal_best_path(&config)),
+            profile.path(LEGACY_PERSONAL_BEST_PATH),
+        ] {
+            if Replay::import(&path, &config).is_err() {
+                continue;
+            }
+            for file in [persist::backup_path(&path), path] {
+                match fs::remove_file(&file) {
+                    Ok(()) => deleted = true,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => warn!("could not delete {}: {err}", file.display()),
+                }
+            }
+        }
+        if deleted {
+            info!("deleted the personal best for this level");
+            remove_ghost(&mut commands, ghost);
+        }
+    }
+}
+
+pub fn step_ghost(run: Res<Run>, mut ghost: ResMut<Ghost>) {
+    if run.tick > ghost.replay.duration_ticks {
+        return;
+    }
+    if let Some(direction) = ghost.replay.direction_at(run.tick) {
+        ghost.sim.step(direction);
+    }
+}
+
+pub fn spawn_ghost_food(mut ghost: ResMut<Ghost>) {
+    ghost.sim.spawn_food();
+}
+
+/// Matches the ghost's sprites to its simulated body.
+pub fn sync_ghost(
+    mut commands: Commands,
+    mut ghost: ResMut<Ghost>,
+    mut positions: Query<&mut Position, With<GhostSegment>>,
+) {
+    let Ghost { sim, segments, .. } = &mut *ghost;
+    let body = if sim.alive { &sim.body[..] } else { &[] };
+    for (&segment, &position) in segments.iter().zip(body) {
+        if let Ok(mut segment_position) = positions.get_mut(segment) {
+            *segment_position = position;
+        }
+    }
+    while segments.len() > body.len() {
+        if let Some(segment) = segments.pop() {
+            commands.entity(segment).despawn();
+        }
+    }
+    while segments.len() < body.len() {
+        let (color, size) = if segments.is_empty() {
+            (GHOST_HEAD_COLOR, 0.8)
+        } else {
+            (GHOST_SEGMENT_COLOR, 0.65)
+        };
+        let segment = commands
+            .spawn(Sprite {
+                color,
+                ..Default::default()
+            })
+            .insert(GhostSegment)
+            .insert(Size::square(size))
+            .insert(body[segments.len()])
+            .id();
+        segments.push(segment);
+    }
+}
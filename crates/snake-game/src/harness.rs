@@ -0,0 +1,235 @@
+//! Headless driver for gameplay tests.
+//!
+//! [`TestGame`] runs [`SnakeGamePlugin`] under [`MinimalPlugins`] with a
+//! manual clock that advances exactly one fixed timestep per update, so tests
+//! can script arrow-key input, step a given number of movement ticks and
+//! inspect the result without a window.
+
+use bevy::{ecs::event::EventCursor, input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+use snake_core::{controller::SnakeController, Direction, Position};
+
+use crate::{
+    bot::Pilot,
+    gates::{spawn_lock, Lock},
+    spawn_food, spawn_obstacle, spawn_snake_at,
+    verify::{reset_verification, VerifyDeterminism},
+    Food, FoodSpawnTick, GameOverEvent, MovementTick, Score, SegmentPool, Segments,
+    SnakeGamePlugin, SnakeHead, TickTimer, TICK_HZ,
+};
+
+/// Upper bound on app updates per movement tick before [`TestGame::advance`]
+/// gives up, so a stalled game fails the test instead of hanging it.
+const MAX_UPDATES_PER_TICK: usize = 1_000;
+
+pub struct TestGame {
+    app: App,
+    game_over_cursor: EventCursor<GameOverEvent>,
+    game_overs: usize,
+    exit_cursor: EventCursor<AppExit>,
+    exit: Option<AppExit>,
+}
+
+impl Default for TestGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestGame {
+    /// Starts a game with random food spawning paused, so the board only
+    /// holds food placed with [`TestGame::place_food`].
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, SnakeGamePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                Time::<Fixed>::from_hz(TICK_HZ).timestep(),
+            ));
+        app.update();
+        app.world_mut()
+            .resource_mut::<TickTimer<FoodSpawnTick>>()
+            .pause();
+        Self {
+            app,
+            game_over_cursor: EventCursor::default(),
+            game_overs: 0,
+            exit_cursor: EventCursor::default(),
+            exit: None,
+        }
+    }
+
+    pub fn enable_food_spawning(&mut self) {
+        self.app
+            .world_mut()
+            .resource_mut::<TickTimer<FoodSpawnTick>>()
+            .unpause();
+    }
+
+    /// Shadows every run from now on with the core simulation, as `--verify`
+    /// does. A mismatch shows up in [`TestGame::exited`].
+    pub fn verify_determinism(&mut self) {
+        let world = self.app.world_mut();
+        world.insert_resource(VerifyDeterminism);
+        world.run_system_cached(reset_verification).unwrap();
+    }
+
+    /// Whether the game asked to exit, and with what status.
+    pub fn exited(&self) -> Option<&AppExit> {
+        self.exit.as_ref()
+    }
+
+    /// Holds down the arrow key for `direction`, releasing any other.
+    pub fn steer(&mut self, direction: Direction) {
+        let key = match direction {
+            Direction::Left => KeyCode::ArrowLeft,
+            Direction::Right => KeyCode::ArrowRight,
+            Direction::Up => KeyCode::ArrowUp,
+            Direction::Down => KeyCode::ArrowDown,
+        };
+        let mut keyboard_input = self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard_input.release_all();
+        keyboard_input.press(key);
+    }
+
+    /// Hands the snake to `controller`, as `--bot` does.
+    pub fn pilot(&mut self, controller: Box<dyn SnakeController>) {
+        self.app.world_mut().insert_resource(Pilot(controller));
+    }
+
+    pub fn place_food(&mut self, position: Position) -> Entity {
+        let world = self.app.world_mut();
+        let food = spawn_food(&mut world.commands(), position);
+        world.flush();
+        food
+    }
+
+    pub fn place_obstacle(&mut self, position: Position) {
+        let world = self.app.world_mut();
+        spawn_obstacle(&mut world.commands(), position);
+        world.flush();
+    }
+
+    /// Adds another snake heading `direction` from `position`, returning its
+    /// head. [`TestGame::head`] and [`TestGame::direction`] expect one snake.
+    pub fn add_snake(&mut self, position: Position, direction: Direction) -> Entity {
+        let world = self.app.world_mut();
+        let head = world.resource_scope(|world, mut pool: Mut<SegmentPool>| {
+            spawn_snake_at(&mut world.commands(), &mut pool, position, direction)
+        });
+        world.flush();
+        head
+    }
+
+    pub fn place_lock(&mut self, index: usize, lock: &Lock) {
+        let world = self.app.world_mut();
+        spawn_lock(&mut world.commands(), index, lock);
+        world.flush();
+    }
+
+    /// Runs the app until `ticks` more movement ticks have happened. A tick
+    /// that ends the run resets the timer, so it is detected by the game over
+    /// event instead.
+    pub fn advance(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            let mut updates = 0;
+            loop {
+                self.app.update();
+                let game_over = self.collect_events();
+                let moved = self
+                    .app
+                    .world()
+                    .resource::<TickTimer<MovementTick>>()
+                    .just_finished();
+                if moved || game_over {
+                    break;
+                }
+                updates += 1;
+                assert!(
+                    updates < MAX_UPDATES_PER_TICK,
+                    "no movement tick after {MAX_UPDATES_PER_TICK} updates"
+                );
+            }
+        }
+    }
+
+    fn collect_events(&mut self) -> bool {
+        let events = self.app.world().resource::<Events<GameOverEvent>>();
+        let count = self.game_over_cursor.read(events).count();
+        self.game_overs += count;
+        let exits = self.app.world().resource::<Events<AppExit>>();
+        if let Some(exit) = self.exit_cursor.read(exits).next() {
+            self.exit.get_or_insert_with(|| exit.clone());
+        }
+        count > 0
+    }
+
+    /// Where food lies on the board, eggs included.
+    pub fn food(&mut self) -> Vec<Position> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&Position, With<Food>>()
+            .iter(world)
+            .copied()
+            .collect()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.app.world().resource::<Score>().0
+    }
+
+    /// Number of segments, including the head.
+    pub fn length(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&Segments, With<SnakeHead>>()
+            .iter(world)
+            .next()
+            .map_or(0, |segments| segments.0.len())
+    }
+
+    /// Heads of every snake on the board.
+    pub fn snakes(&mut self) -> Vec<Entity> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<Entity, With<SnakeHead>>()
+            .iter(world)
+            .collect()
+    }
+
+    /// Where the snake headed by `head` is.
+    pub fn head_of(&mut self, head: Entity) -> Position {
+        *self.app.world().get::<Position>(head).unwrap()
+    }
+
+    /// Number of segments of the snake headed by `head`.
+    pub fn length_of(&mut self, head: Entity) -> usize {
+        self.app
+            .world()
+            .get::<Segments>(head)
+            .map_or(0, |segments| segments.0.len())
+    }
+
+    pub fn head(&mut self) -> Position {
+        *self
+            .app
+            .world_mut()
+            .query_filtered::<&Position, With<SnakeHead>>()
+            .single(self.app.world())
+    }
+
+    pub fn direction(&mut self) -> Direction {
+        self.app
+            .world_mut()
+            .query::<&SnakeHead>()
+            .single(self.app.world())
+            .direction
+    }
+
+    /// Game over events seen since the game started.
+    pub fn game_overs(&self) -> usize {
+        self.game_overs
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
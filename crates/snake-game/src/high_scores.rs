@@ -0,0 +1,233 @@
+//! The player's own high score tables, and the game over screen showing them.
+//!
+//! A run only competes with runs of the same mode on the same arena size, as
+//! a classic game on 10x10 and one on 20x20 are hardly the same game. Each
+//! such [`Table`] keeps its best [`TABLE_SIZE`] scores in `high-scores.json`
+//! in the [`Profile`]. When a run ends its table is shown for a few seconds,
+//! under the reason it ended, with the run marked if it made it in. Runs the
+//! bot plays are not counted.
+
+use std::{collections::BTreeMap, fmt, io, path::Path, time::Duration};
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
+use snake_core::{persist, Arena, GameMode};
+
+use crate::{
+    achievements::date, bot::bot_playing, campaign::in_campaign, daily::practicing,
+    death::LastDeath, game_over, profile::Profile, replay, replay::run_cheated, GameOverEvent,
+    GameSet, Score,
+};
+
+pub const HIGH_SCORES_FILE: &str = "high-scores.json";
+pub const TABLE_SIZE: usize = 10;
+const SCREEN_DURATION: Duration = Duration::from_secs(4);
+
+/// Which runs compete with each other.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Table {
+    pub mode: GameMode,
+    pub arena: Arena,
+}
+
+impl Table {
+    /// Name of the table in files and leaderboard requests, like
+    /// `classic-10x10`.
+    pub fn key(self) -> String {
+        format!(
+            "{}-{}x{}",
+            format!("{:?}", self.mode).to_lowercase(),
+            self.arena.width,
+            self.arena.height
+        )
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {}x{}",
+            self.mode, self.arena.width, self.arena.height
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    /// Seconds since the Unix epoch.
+    pub ended_at: u64,
+}
+
+/// Every table the player has a score in, best first, by [`Table::key`].
+#[derive(Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct HighScores(pub BTreeMap<String, Vec<HighScore>>);
+
+impl HighScores {
+    /// Reads `path`, starting afresh if it does not exist yet.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        persist::load_json(path)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        persist::save_json(path, self)
+    }
+
+    pub fn table(&self, table: Table) -> &[HighScore] {
+        self.0.get(&table.key()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds `entry` to `table` if it is good enough, and returns where it
+    /// ranked. Ties go below the scores already there.
+    pub fn insert(&mut self, table: Table, entry: HighScore) -> Option<usize> {
+        let scores = self.0.entry(table.key()).or_default();
+        let rank = scores.partition_point(|high| high.score >= entry.score);
+        if rank >= TABLE_SIZE {
+            return None;
+        }
+        scores.insert(rank, entry);
+        scores.truncate(TABLE_SIZE);
+        Some(rank)
+    }
+
+    /// Adds the scores from `other` this does not have yet, keeping the best
+    /// [`TABLE_SIZE`] of each table and the earlier of any tie first.
+    pub fn merge(&mut self, other: &Self) {
+        for (key, theirs) in &other.0 {
+            let scores = self.0.entry(key.clone()).or_default();
+            for entry in theirs {
+                if !scores.contains(entry) {
+                    scores.push(entry.clone());
+                }
+            }
+            scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.ended_at.cmp(&b.ended_at)));
+            scores.truncate(TABLE_SIZE);
+        }
+    }
+}
+
+/// Keeps the high score tables and shows them when a run ends.
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .add_systems(Startup, spawn_screen)
+            .add_systems(
+                FixedUpdate,
+                record_score
+                    .run_if(on_event::<GameOverEvent>)
+                    .run_if(not(bot_playing))
+                    .run_if(not(run_cheated))
+                    .run_if(not(practicing))
+                    .run_if(not(in_campaign))
+                    .after(replay::finish_recording)
+                    .before(game_over)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(Update, hide_screen);
+    }
+}
+
+#[derive(Component)]
+struct GameOverScreen {
+    hide: Timer,
+}
+
+fn record_score(
+    mode: Res<GameMode>,
+    arena: Res<Arena>,
+    score: Res<Score>,
+    profile: Res<Profile>,
+    last_death: Res<LastDeath>,
+    mut screens: Query<(&mut GameOverScreen, &mut Visibility, &mut Text)>,
+) {
+    let table = Table {
+        mode: *mode,
+        arena: *arena,
+    };
+    let path = profile.path(HIGH_SCORES_FILE);
+    let mut high_scores = match HighScores::read(&path) {
+        Ok(high_scores) => high_scores,
+        Err(err) => {
+            warn!("could not read {}: {err}", path.display());
+            return;
+        }
+    };
+    let rank = high_scores.insert(
+        table,
+        HighScore {
+            score: score.0,
+            ended_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        },
+    );
+    if rank.is_some() {
+        if let Err(err) = high_scores.write(&path) {
+            warn!("could not save {}: {err}", path.display());
+        }
+    }
+    if let Ok((mut screen, mut visibility, mut text)) = screens.get_single_mut() {
+        text.0 = describe(
+            table,
+            high_scores.table(table),
+            score.0,
+            &last_death.reason,
+            rank,
+        );
+        *visibility = Visibility::Visible;
+        screen.hide.reset();
+    }
+}
+
+fn spawn_screen(mut commands: Commands) {
+    commands.spawn((
+        GameOverScreen {
+            hide: Timer::new(SCREEN_DURATION, TimerMode::Once),
+        },
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn hide_screen(time: Res<Time>, mut screens: Query<(&mut GameOverScreen, &mut Visibility)>) {
+    for (mut screen, mut visibility) in &mut screens {
+        if *visibility != Visibility::Hidden && screen.hide.tick(time.delta()).just_finished() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+fn describe(
+    table: Table,
+    scores: &[HighScore],
+    score: u32,
+    reason: &str,
+    rank: Option<usize>,
+) -> String {
+    let mut lines = vec![format!("Game over: {score}\n{reason}\n{table} high scores")];
+    lines.extend(scores.iter().enumerate().map(|(index, high)| {
+        let marker = if Some(index) == rank { " <" } else { "" };
+        format!(
+            "{:>2}. {:>4}  {}{marker}",
+            index + 1,
+            high.score,
+            date(high.ended_at)
+        )
+    }));
+    lines.join("\n")
+}
@@ -0,0 +1,131 @@
+//! Minimal read-only Twitch chat client.
+//!
+//! Joins a channel anonymously over plain IRC, answers the server's pings and
+//! hands each chat line over through a channel. It never sends chat. The
+//! connection is made and kept on a thread of its own: a connection that
+//! fails, drops or goes quiet for longer than the server's pings allow is
+//! made again, waiting a little longer after each failure.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use bevy::log::{info, warn};
+
+const TWITCH_IRC: &str = "irc.chat.twitch.tv:6667";
+/// Twitch accepts any `justinfan` nick without a password, read-only.
+const ANONYMOUS_NICK: &str = "justinfan";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Twitch pings about every five minutes, so a connection silent for longer
+/// is gone.
+const READ_TIMEOUT: Duration = Duration::from_secs(6 * 60);
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const LAST_RETRY: Duration = Duration::from_secs(60);
+
+pub struct ChatMessage {
+    pub user: String,
+    pub text: String,
+}
+
+/// Why a connection to chat ended.
+enum Ended {
+    /// The connection failed or dropped, and is worth making again.
+    Dropped(io::Error),
+    /// Nobody is reading the chat any more.
+    Closed,
+}
+
+impl From<io::Error> for Ended {
+    fn from(err: io::Error) -> Self {
+        Ended::Dropped(err)
+    }
+}
+
+/// Joins `channel` in the background and returns chat as it arrives,
+/// reconnecting whenever the connection is lost, for as long as the receiver
+/// is kept.
+pub fn join(channel: &str) -> Receiver<ChatMessage> {
+    let channel = channel.trim_start_matches('#').to_lowercase();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut retry = FIRST_RETRY;
+        loop {
+            match read_chat(&channel, &sender, &mut retry) {
+                Ended::Closed => return,
+                Ended::Dropped(err) => {
+                    warn!("lost the chat of #{channel}, retrying in {retry:?}: {err}");
+                }
+            }
+            thread::sleep(retry);
+            retry = (retry * 2).min(LAST_RETRY);
+        }
+    });
+    receiver
+}
+
+/// Connects to `channel` and passes its chat to `sender` until the connection
+/// ends. Resets `retry` once joined.
+fn read_chat(channel: &str, sender: &Sender<ChatMessage>, retry: &mut Duration) -> Ended {
+    let mut stream = match connect() {
+        Ok(stream) => stream,
+        Err(err) => return Ended::Dropped(err),
+    };
+    let nick = format!("{ANONYMOUS_NICK}{}", rand::random::<u32>() % 100_000);
+    if let Err(err) = write!(stream, "NICK {nick}\r\nJOIN #{channel}\r\n") {
+        return Ended::Dropped(err);
+    }
+    let reader = match stream.try_clone() {
+        Ok(reader) => BufReader::new(reader),
+        Err(err) => return Ended::Dropped(err),
+    };
+    info!("joined the chat of #{channel}");
+    *retry = FIRST_RETRY;
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => return Ended::Dropped(err),
+        };
+        if let Some(token) = line.strip_prefix("PING ") {
+            if let Err(err) = write!(stream, "PONG {token}\r\n") {
+                return Ended::Dropped(err);
+            }
+        } else if let Some(message) = parse_privmsg(&line) {
+            if sender.send(message).is_err() {
+                return Ended::Closed;
+            }
+        }
+    }
+    Ended::Dropped(io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Connects to the first of Twitch's addresses that answers in time.
+fn connect() -> io::Result<TcpStream> {
+    let mut last_err = io::Error::from(io::ErrorKind::AddrNotAvailable);
+    for address in TWITCH_IRC.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                return Ok(stream);
+            }
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+/// Reads `:user!user@host PRIVMSG #channel :text`.
+fn parse_privmsg(line: &str) -> Option<ChatMessage> {
+    let line = line.strip_prefix(':')?;
+    let (prefix, rest) = line.split_once(' ')?;
+    let rest = rest.strip_prefix("PRIVMSG ")?;
+    let (_channel, text) = rest.split_once(" :")?;
+    let user = prefix.split('!').next()?;
+    Some(ChatMessage {
+        user: user.to_string(),
+        text: text.to_string(),
+    })
+}
@@ -0,0 +1,344 @@
+//! Optional online leaderboard, enabled with `--leaderboard <url>`.
+//!
+//! Every finished replayable run is posted to `<url>/scores` with the seed,
+//! mode and a hash of its replay, so the server can ask for the replay to
+//! check a suspicious score. Submissions that fail are kept in
+//! `leaderboard-pending.json` and retried with the next one. Tab shows the
+//! global top scores for the current mode and arena size, a [`Table`] of
+//! their own, fetched from `<url>/scores?table=` and cached in
+//! `leaderboard-cache.json` for when the server is unreachable. Both files
+//! live in the player's [`Profile`], so they travel with its bundle and
+//! cloud sync. N on that screen changes the name scores go under for the
+//! rest of the session, with the [name entry](crate::name_entry).
+
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+use snake_core::{persist, Arena, GameMode};
+
+use crate::{
+    bot::bot_playing,
+    daily::practicing,
+    game_over,
+    high_scores::Table,
+    name_entry::{edit_name, entering_name, spawn_name_entry, NameEntered, NameEntryPlugin},
+    profile::{Profile, MAX_NAME_LENGTH},
+    replay::LastReplay,
+    GameOverEvent, GameSet,
+};
+
+const PENDING_PATH: &str = "leaderboard-pending.json";
+const CACHE_PATH: &str = "leaderboard-cache.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const SHOWN_SCORES: usize = 10;
+
+/// Adds leaderboard submission and the Tab screen if the player passed
+/// `--leaderboard`.
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(url) = std::env::args()
+            .skip_while(|arg| arg != "--leaderboard")
+            .nth(1)
+        else {
+            return;
+        };
+        let name = std::env::args().skip_while(|arg| arg != "--name").nth(1);
+        app.insert_resource(Leaderboard {
+            url: url.trim_end_matches('/').to_string(),
+            name,
+            submitting: None,
+            fetching: None,
+            naming: None,
+        })
+        .add_systems(Startup, spawn_screen)
+        .add_systems(
+            FixedUpdate,
+            submit_run
+                .run_if(on_event::<GameOverEvent>)
+                .run_if(not(bot_playing))
+                .run_if(not(crate::replay::run_cheated))
+                .run_if(not(practicing))
+                .after(crate::replay::finish_recording)
+                .before(game_over)
+                .in_set(GameSet::Logic),
+        )
+        .add_systems(
+            Update,
+            (
+                finish_submission,
+                toggle_screen.run_if(not(entering_name)),
+                rename.after(edit_name),
+                finish_fetch,
+            )
+                .chain(),
+        );
+        if !app.is_plugin_added::<NameEntryPlugin>() {
+            app.add_plugins(NameEntryPlugin);
+        }
+    }
+}
+
+/// One finished run as posted to `<url>/scores`.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Submission {
+    pub name: String,
+    pub mode: GameMode,
+    /// [`Table::key`] of the mode and arena size.
+    pub table: String,
+    pub score: u32,
+    pub duration_ticks: u32,
+    pub seed: u64,
+    /// Hex [`snake_core::replay::Replay::content_hash`].
+    pub replay_hash: String,
+}
+
+/// Runs the server has not accepted yet, as kept in
+/// `leaderboard-pending.json`.
+#[derive(Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Pending(pub Vec<Submission>);
+
+impl Pending {
+    /// Drops the submissions the server accepted, keeping any that failed or
+    /// were queued while they were being sent.
+    pub fn confirm(&mut self, sent: &[Submission]) {
+        for submission in sent {
+            if let Some(index) = self.0.iter().position(|queued| queued == submission) {
+                self.0.remove(index);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry {
+    name: String,
+    score: u32,
+}
+
+/// Top scores by [`Table::key`].
+type Cache = BTreeMap<String, Vec<Entry>>;
+
+#[derive(Resource)]
+struct Leaderboard {
+    url: String,
+    /// Given with `--name`, otherwise scores go under the profile's name.
+    name: Option<String>,
+    /// Submissions being sent, handing back the ones the server accepted.
+    submitting: Option<Task<Vec<Submission>>>,
+    fetching: Option<Task<(Table, Result<Vec<Entry>, String>)>>,
+    /// The name entry opened from the screen, while it is open.
+    naming: Option<Entity>,
+}
+
+fn read_json<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> T {
+    persist::read_with_backup(path, |bytes| {
+        serde_json::from_slice(bytes).map_err(std::io::Error::other)
+    })
+    .unwrap_or_default()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) {
+    let result = serde_json::to_vec(value)
+        .map_err(std::io::Error::other)
+        .and_then(|bytes| persist::write_atomic(path, &bytes));
+    if let Err(err) = result {
+        warn!("could not write {}: {err}", path.display());
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build()
+}
+
+/// Queues the run that just ended and sends the queue in the background.
+fn submit_run(
+    mut leaderboard: ResMut<Leaderboard>,
+    last_replay: Res<LastReplay>,
+    profile: Res<Profile>,
+    arena: Res<Arena>,
+) {
+    let Some(replay) = &last_replay.0 else {
+        return;
+    };
+    let pending_path = profile.path(PENDING_PATH);
+    let mut pending: Pending = read_json(&pending_path);
+    pending.0.push(Submission {
+        name: leaderboard
+            .name
+            .clone()
+            .unwrap_or_else(|| profile.name.clone()),
+        mode: replay.mode,
+        table: Table {
+            mode: replay.mode,
+            arena: *arena,
+        }
+        .key(),
+        score: replay.final_score,
+        duration_ticks: replay.duration_ticks,
+        seed: replay.seed,
+        replay_hash: format!("{:016x}", replay.content_hash()),
+    });
+    write_json(&pending_path, &pending);
+    if leaderboard.submitting.is_some() {
+        return;
+    }
+    let url = format!("{}/scores", leaderboard.url);
+    leaderboard.submitting = Some(IoTaskPool::get().spawn(async move {
+        let agent = agent();
+        let mut sent = Vec::new();
+        for submission in pending.0 {
+            match agent.post(&url).send_json(&submission) {
+                Ok(_) => sent.push(submission),
+                Err(err) => warn!("score submission failed, keeping it for later: {err}"),
+            }
+        }
+        sent
+    }));
+}
+
+fn finish_submission(mut leaderboard: ResMut<Leaderboard>, profile: Res<Profile>) {
+    let Some(task) = &mut leaderboard.submitting else {
+        return;
+    };
+    let Some(sent) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    leaderboard.submitting = None;
+    // Runs may have been queued while these were on their way.
+    let path = profile.path(PENDING_PATH);
+    let mut pending: Pending = read_json(&path);
+    pending.confirm(&sent);
+    write_json(&path, &pending);
+}
+
+#[derive(Component)]
+struct LeaderboardScreen;
+
+fn spawn_screen(mut commands: Commands) {
+    commands.spawn((
+        LeaderboardScreen,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_screen(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mode: Res<GameMode>,
+    arena: Res<Arena>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut screens: Query<(&mut Visibility, &mut Text), With<LeaderboardScreen>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let Ok((mut visibility, mut text)) = screens.get_single_mut() else {
+        return;
+    };
+    visibility.toggle_visible_hidden();
+    if *visibility == Visibility::Hidden || leaderboard.fetching.is_some() {
+        return;
+    }
+    let table = Table {
+        mode: *mode,
+        arena: *arena,
+    };
+    text.0 = format!("{table} top scores\nloading...");
+    let url = format!("{}/scores", leaderboard.url);
+    leaderboard.fetching = Some(IoTaskPool::get().spawn(async move {
+        let result = agent()
+            .get(&url)
+            .query("table", &table.key())
+            .call()
+            .map_err(|err| err.to_string())
+            .and_then(|response| response.into_json().map_err(|err| err.to_string()));
+        (table, result)
+    }));
+}
+
+/// N on the screen asks for the name to submit scores under.
+fn rename(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut entered: EventReader<NameEntered>,
+    screens: Query<&Visibility, With<LeaderboardScreen>>,
+) {
+    if let Some(naming) = leaderboard.naming {
+        if let Some(event) = entered.read().find(|event| event.entry == naming) {
+            leaderboard.naming = None;
+            if let Some(name) = &event.name {
+                info!("submitting scores as {name}");
+                leaderboard.name = Some(name.clone());
+            }
+        }
+        return;
+    }
+    let shown = screens
+        .get_single()
+        .is_ok_and(|visibility| *visibility != Visibility::Hidden);
+    if shown && keyboard_input.just_pressed(KeyCode::KeyN) {
+        leaderboard.naming = Some(spawn_name_entry(
+            &mut commands,
+            &mut time,
+            "Name for the leaderboard",
+            MAX_NAME_LENGTH,
+        ));
+    }
+}
+
+fn finish_fetch(
+    mut leaderboard: ResMut<Leaderboard>,
+    profile: Res<Profile>,
+    mut screens: Query<&mut Text, With<LeaderboardScreen>>,
+) {
+    let Some(task) = &mut leaderboard.fetching else {
+        return;
+    };
+    let Some((table, result)) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    leaderboard.fetching = None;
+    let path = profile.path(CACHE_PATH);
+    let mut cache: Cache = read_json(&path);
+    let heading = match result {
+        Ok(mut entries) => {
+            entries.truncate(SHOWN_SCORES);
+            cache.insert(table.key(), entries);
+            write_json(&path, &cache);
+            format!("{table} top scores")
+        }
+        Err(err) => {
+            warn!("could not fetch leaderboard: {err}");
+            format!("{table} top scores (offline, cached)")
+        }
+    };
+    let lines: Vec<String> = cache
+        .get(&table.key())
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(rank, entry)| format!("{:>2}. {:<16} {}", rank + 1, entry.name, entry.score))
+        .collect();
+    for mut text in &mut screens {
+        text.0 = format!("{heading}\n{}\n\nN: change name", lines.join("\n"));
+    }
+}
@@ -0,0 +1,1176 @@
+use std::{marker::PhantomData, time::Duration};
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use snake_core::{
+    collision, random_food_position, Arena, Collision, Direction, GameMode, Position,
+    FOOD_SPAWN_INTERVAL, MOVEMENT_INTERVAL, START_DIRECTION, START_POSITION,
+};
+
+pub mod accessibility;
+pub mod achievements;
+pub mod armor;
+pub mod assist;
+pub mod atlas;
+pub mod attract;
+mod bot;
+pub mod bundle;
+pub mod campaign;
+#[cfg(feature = "capture")]
+pub mod capture;
+#[cfg(feature = "chat-plays")]
+pub mod chat_plays;
+#[cfg(feature = "cloud-sync")]
+pub mod cloud_sync;
+pub mod config;
+pub mod console;
+pub mod critters;
+pub mod culling;
+pub mod daily;
+pub mod day_night;
+pub mod death;
+pub mod debug_overlay;
+pub mod eggs;
+pub mod embedded;
+pub mod frame_rate;
+mod frame_step;
+pub mod gates;
+mod ghost;
+pub mod harness;
+pub mod high_scores;
+#[cfg(feature = "chat-plays")]
+mod irc;
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+pub mod lives;
+pub mod loading;
+#[cfg(feature = "scripting")]
+pub mod lua;
+pub mod minimap;
+pub mod mobile;
+pub mod name_entry;
+pub mod online;
+pub mod plants;
+pub mod power_ups;
+pub mod predator;
+pub mod profile;
+pub mod quit;
+#[cfg(feature = "remote-control")]
+pub mod remote_control;
+mod replay;
+mod rewind;
+pub mod rumble;
+pub mod screen_reader;
+#[cfg(any(feature = "scripting", feature = "wasm-mods"))]
+pub mod scripting;
+pub mod settings;
+pub mod snake_mesh;
+mod snapshot;
+pub mod stats;
+#[cfg(feature = "steam")]
+pub mod steam;
+pub mod tail_cutting;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+pub mod terrain;
+pub mod toast;
+pub mod tunnels;
+pub mod typography;
+pub mod ui_scale;
+pub mod venom;
+mod verify;
+pub mod victory;
+#[cfg(feature = "wasm-mods")]
+pub mod wasm_mods;
+pub mod weather;
+pub mod world_clock;
+
+const SEGMENT_POOL_PREWARM: usize = 64;
+
+/// Colors the board is painted with, and the typeface of its text. Sprites
+/// pick their color through a [`ThemeColor`].
+#[derive(Resource, Clone, PartialEq, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+struct Theme {
+    background: Color,
+    snake_head: Color,
+    snake_segment: Color,
+    food: Color,
+    obstacle: Color,
+    /// Font file under `assets/`, or `None` for the built-in font. See
+    /// [`typography`].
+    #[serde(default)]
+    font: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Color::linear_rgb(0.0, 0.0, 0.0),
+            snake_head: Color::linear_rgb(0.7, 0.7, 0.7),
+            snake_segment: Color::linear_rgb(0.3, 0.3, 0.3),
+            food: Color::linear_rgb(1.0, 0.0, 1.0),
+            obstacle: Color::linear_rgb(0.3, 0.4, 0.6),
+            font: Some(typography::PIXEL_FONT.to_string()),
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Reflect)]
+#[reflect(Component)]
+enum ThemeColor {
+    SnakeHead,
+    SnakeSegment,
+    Food,
+    Obstacle,
+}
+
+impl Theme {
+    fn color(&self, role: ThemeColor) -> Color {
+        match role {
+            ThemeColor::SnakeHead => self.snake_head,
+            ThemeColor::SnakeSegment => self.snake_segment,
+            ThemeColor::Food => self.food,
+            ThemeColor::Obstacle => self.obstacle,
+        }
+    }
+}
+
+/// Keys that steer the snake, arrows unless changed in the settings.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct Controls {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            up: KeyCode::ArrowUp,
+            down: KeyCode::ArrowDown,
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SnakeHead {
+    direction: Direction,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SnakeSegment;
+
+/// A snake's segments, head first, kept on its head so any number of snakes
+/// can share the board.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+struct Segments(Vec<Entity>);
+/// Where a snake's tail was before its last move, for it to grow into. Kept on
+/// its head, next to its [`Segments`].
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+struct LastTailPosition(Option<Position>);
+/// Hidden segment entities waiting to be reused, so restarts and growth
+/// recycle sprites instead of despawning and respawning them.
+#[derive(Default, Resource, Reflect)]
+#[reflect(Resource)]
+struct SegmentPool(Vec<Entity>);
+#[derive(Default, Resource, Reflect)]
+#[reflect(Resource)]
+struct Score(u32);
+/// Identifies the run in progress and counts its movement ticks. A run is
+/// reproducible from its seed and the inputs given on each tick.
+#[derive(Default, Resource, Reflect)]
+#[reflect(Resource)]
+struct Run {
+    seed: u64,
+    tick: u32,
+}
+/// Source of all gameplay randomness, kept as a resource so its state can be
+/// saved and restored.
+#[derive(Resource)]
+struct GameRng(ChaCha8Rng);
+
+/// Stages of a frame, in the order they run. New features should add their
+/// systems to one of these sets rather than ordering against individual
+/// systems.
+///
+/// `Input`, `Logic` and `Spawning` run in `FixedUpdate`, chained in that order:
+/// - `Input` advances tick timers and reads player input.
+/// - `Logic` moves the snake, resolves eating and growth, then game over.
+/// - `Spawning` places new entities such as food on the board.
+///
+/// `Presentation` runs in `PostUpdate`, before transform propagation, and
+/// syncs grid state to sprites.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum GameSet {
+    Input,
+    Logic,
+    Spawning,
+    Presentation,
+}
+
+struct MovementTick;
+struct FoodSpawnTick;
+
+/// Repeating gate for a periodic system, counted in whole `FixedUpdate` steps
+/// so the gated systems run on a steady beat. An interval that is not a
+/// multiple of the step rounds to the nearest number of steps. Change the
+/// interval or pause the timer at runtime to change how often they run, or
+/// scale it for a while without touching the interval players chose.
+#[derive(Resource)]
+struct TickTimer<T> {
+    interval: Duration,
+    /// Share of `interval` the timer actually runs for, 1 unless sped up.
+    scale: f32,
+    /// Steps counted since the gated systems last ran.
+    steps: u32,
+    paused: bool,
+    finished: bool,
+    /// Whether the gated systems run on the next step whatever the count.
+    hurried: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TickTimer<T> {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            scale: 1.0,
+            steps: 0,
+            paused: false,
+            finished: false,
+            hurried: false,
+            _marker: PhantomData,
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// The interval in effect, with the scale applied.
+    fn current_interval(&self) -> Duration {
+        self.interval.mul_f32(self.scale)
+    }
+
+    fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Steps of `timestep` between runs, at least one.
+    fn period(&self, timestep: Duration) -> u32 {
+        (self.current_interval().as_secs_f64() / timestep.as_secs_f64())
+            .round()
+            .max(1.0) as u32
+    }
+
+    /// Steps of `timestep` until the gated systems next run.
+    fn remaining(&self, timestep: Duration) -> u32 {
+        if self.hurried {
+            return 1;
+        }
+        self.period(timestep).saturating_sub(self.steps).max(1)
+    }
+
+    /// Counts `steps` more steps of `timestep`, finishing if that reaches the
+    /// period.
+    fn tick(&mut self, steps: u32, timestep: Duration) {
+        self.finished = false;
+        if self.paused || steps == 0 {
+            return;
+        }
+        self.steps += steps;
+        if self.hurried || self.steps >= self.period(timestep) {
+            self.finished = true;
+            self.hurried = false;
+            self.steps = 0;
+        }
+    }
+
+    /// Makes the gated systems run on the next step.
+    fn hurry(&mut self) {
+        self.hurried = true;
+    }
+
+    fn reset(&mut self) {
+        self.steps = 0;
+        self.finished = false;
+        self.hurried = false;
+    }
+
+    fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Whether the gated systems run on this step.
+    fn just_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Rate the simulation steps at, in `FixedUpdate`, whatever the frame rate.
+/// Movement and food spawning are counted in whole steps, so the default
+/// intervals are multiples of one; `tick_hz` in `assets/config.ron` changes
+/// it.
+pub const TICK_HZ: f64 = 100.0;
+
+/// How far the frame being drawn is between the last movement tick and the
+/// next, from 0 to 1, so drawing can smooth between ticks at any frame rate.
+/// The camera uses it to glide after the head.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Debug)]
+pub struct TickAlpha(pub f32);
+
+/// Sent when the snake with this head ate, to grow it on the same tick.
+#[derive(Event)]
+struct GrowthEvent(Entity);
+/// Why a run ended.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum GameOverCause {
+    Collision(Collision),
+    /// The speedrun target score was reached.
+    Finished,
+    /// Attract mode started or ended, see [`attract`].
+    Interrupted,
+    /// The predator caught the snake, see [`predator`].
+    Caught,
+    /// The snake filled the board, see [`victory`].
+    Won,
+}
+
+/// A run ending, and the cell it ended on for causes that happen somewhere.
+#[derive(Event)]
+struct GameOverEvent {
+    cause: GameOverCause,
+    cell: Option<Position>,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Food;
+
+/// Points a piece of food is worth, for food worth more than the usual one.
+#[derive(Component)]
+struct Worth(u32);
+
+/// A blocked cell: the snake dies running into it, as into a wall.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Obstacle;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Size {
+    width: f32,
+    height: f32,
+}
+
+impl Size {
+    pub fn square(x: f32) -> Self {
+        Self {
+            width: x,
+            height: x,
+        }
+    }
+}
+
+/// Draws whatever is on the grid: a camera, the theme, and the systems that
+/// turn [`Position`] and [`Size`] into sprite transforms. Shared by local and
+/// online play.
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_camera)
+            .add_systems(Update, accessibility::sync_outlines)
+            .configure_sets(
+                PostUpdate,
+                GameSet::Presentation.before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                PostUpdate,
+                (position_translation, size_scaling, paint_sprites).in_set(GameSet::Presentation),
+            )
+            .insert_resource(ClearColor(Theme::default().background))
+            .insert_resource(Theme::default())
+            .insert_resource(Arena::default())
+            .init_resource::<accessibility::Accessibility>()
+            .register_type::<Position>()
+            .register_type::<Size>()
+            .register_type::<ThemeColor>()
+            .register_type::<Arena>()
+            .register_type::<Theme>();
+    }
+}
+
+/// All gameplay and presentation systems, minus windowing and asset
+/// configuration, so the game can also run headless under
+/// [`MinimalPlugins`].
+pub struct SnakeGamePlugin;
+
+impl Plugin for SnakeGamePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<BoardPlugin>() {
+            app.add_plugins(BoardPlugin);
+        }
+        app.add_systems(
+            Startup,
+            (
+                select_mode,
+                bot::select_from_args,
+                verify::enable_from_args,
+                replay::load_from_args,
+                prewarm_segment_pool,
+                spawn_snake,
+                begin_run,
+                lives::reset_lives,
+                ghost::reset_ghost,
+                verify::reset_verification.run_if(resource_exists::<verify::VerifyDeterminism>),
+            )
+                .chain(),
+        )
+        .configure_sets(
+            FixedUpdate,
+            (GameSet::Input, GameSet::Logic, GameSet::Spawning).chain(),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                (
+                    world_clock::advance,
+                    tick_timer::<MovementTick>,
+                    tick_world_timer::<FoodSpawnTick>,
+                    frame_step::step_timers,
+                )
+                    .chain(),
+                snake_movement_input
+                    .run_if(not(resource_exists::<replay::ReplayPlayback>))
+                    .run_if(not(resource_exists::<bot::Pilot>)),
+                bot::steer_with_pilot
+                    .run_if(resource_exists::<bot::Pilot>)
+                    .run_if(timer_finished::<MovementTick>)
+                    .after(frame_step::step_timers),
+            )
+                .in_set(GameSet::Input),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                rewind::rewind.run_if(rewind::rewinding),
+                (
+                    rewind::record_history,
+                    advance_run_tick,
+                    replay::playback_input.run_if(resource_exists::<replay::ReplayPlayback>),
+                    replay::record_input,
+                    terrain::wade,
+                    snake_movement,
+                    terrain::speed_strips.run_if(resource_exists::<terrain::Terrain>),
+                    (
+                        snake_eating,
+                        snake_growth,
+                        gates::unlock,
+                        victory::board_filled.run_if(not(victory::free_playing)),
+                    )
+                        .chain()
+                        .run_if(not(on_event::<GameOverEvent>)),
+                    predator::hunt
+                        .run_if(predator::predators_on)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(not(on_event::<lives::LifeLost>)),
+                    lives::lose_life.run_if(on_event::<lives::LifeLost>),
+                    tail_cutting::cut_tail.run_if(on_event::<tail_cutting::TailCut>),
+                    armor::take_hit.run_if(on_event::<armor::ArmorHit>),
+                    verify::verify_tick.run_if(resource_exists::<verify::Verification>),
+                    ghost::speedrun_goal.run_if(ghost::in_speedrun),
+                    (ghost::step_ghost, ghost::sync_ghost)
+                        .chain()
+                        .run_if(resource_exists::<ghost::Ghost>),
+                    (
+                        death::record_death,
+                        replay::finish_recording,
+                        ghost::save_personal_best
+                            .run_if(ghost::in_speedrun)
+                            .run_if(not(bot::bot_playing))
+                            .run_if(not(replay::run_cheated))
+                            .run_if(not(daily::practicing)),
+                        replay::finish_playback.run_if(resource_exists::<replay::ReplayPlayback>),
+                        game_over,
+                        begin_run,
+                        lives::reset_lives,
+                        replay::reset_recorder,
+                        ghost::reset_ghost,
+                        verify::reset_verification
+                            .run_if(resource_exists::<verify::VerifyDeterminism>),
+                    )
+                        .chain()
+                        .run_if(on_event::<GameOverEvent>),
+                )
+                    .chain()
+                    .run_if(not(rewind::rewinding)),
+            )
+                .chain()
+                .run_if(timer_finished::<MovementTick>)
+                .in_set(GameSet::Logic),
+        )
+        .add_systems(
+            FixedUpdate,
+            lives::wear_off
+                .run_if(resource_exists::<lives::Invulnerable>)
+                .in_set(GameSet::Logic),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                food_spawner.run_if(not(rewind::rewinding)),
+                ghost::spawn_ghost_food.run_if(resource_exists::<ghost::Ghost>),
+                verify::spawn_verification_food.run_if(resource_exists::<verify::Verification>),
+            )
+                .run_if(timer_finished::<FoodSpawnTick>)
+                .in_set(GameSet::Spawning),
+        )
+        .add_systems(
+            FixedUpdate,
+            predator::place_predator
+                .run_if(resource_changed::<predator::Predators>.or(predator::predators_on))
+                .in_set(GameSet::Spawning),
+        )
+        .insert_resource(SegmentPool::default())
+        .insert_resource(Score::default())
+        .insert_resource(GameRng(ChaCha8Rng::from_os_rng()))
+        .insert_resource(Run::default())
+        .insert_resource(GameMode::default())
+        .insert_resource(Controls::default())
+        .init_resource::<profile::Profile>()
+        .insert_resource(rewind::RewindHistory::default())
+        .insert_resource(replay::ReplayRecorder::default())
+        .insert_resource(replay::LastReplay::default())
+        .init_resource::<ghost::RaceGhost>()
+        .init_resource::<lives::Lives>()
+        .init_resource::<death::LastDeath>()
+        .init_resource::<tail_cutting::TailCutting>()
+        .init_resource::<predator::Predators>()
+        .insert_resource(frame_step::FrameStep::default())
+        .init_resource::<world_clock::WorldClock>()
+        .insert_resource(Time::<Fixed>::from_hz(TICK_HZ))
+        .init_resource::<TickAlpha>()
+        .insert_resource(TickTimer::<MovementTick>::new(MOVEMENT_INTERVAL))
+        .insert_resource(TickTimer::<FoodSpawnTick>::new(FOOD_SPAWN_INTERVAL))
+        .add_systems(
+            Update,
+            (
+                snapshot::quicksave,
+                snapshot::quickload,
+                replay::export_last_replay,
+                frame_step::frame_step_input,
+                death::fade_fatal_cell,
+                ghost::ghost_input
+                    .run_if(not(name_entry::entering_name))
+                    .run_if(console::console_closed),
+                lives::blink.run_if(resource_exists::<lives::Invulnerable>),
+                lives::stop_blinking.run_if(resource_removed::<lives::Invulnerable>),
+                terrain::paint_terrain.run_if(resource_changed_or_removed::<terrain::Terrain>),
+                tunnels::mark_tunnels.run_if(resource_changed_or_removed::<tunnels::Tunnels>),
+                window_title.run_if(resource_changed::<Score>.or(resource_changed::<GameMode>)),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                tick_alpha.before(GameSet::Presentation),
+                terrain::sink_terrain.after(GameSet::Presentation),
+                tunnels::sink_markers.after(GameSet::Presentation),
+                follow_head.after(position_translation),
+            )
+                .before(TransformSystem::TransformPropagate),
+        )
+        .add_event::<GrowthEvent>()
+        .add_event::<GameOverEvent>()
+        .add_event::<lives::LifeLost>()
+        .add_event::<tail_cutting::TailCut>()
+        .add_event::<armor::ArmorHit>()
+        .register_type::<SnakeHead>()
+        .register_type::<SnakeSegment>()
+        .register_type::<Food>()
+        .register_type::<Obstacle>()
+        .register_type::<Segments>()
+        .register_type::<LastTailPosition>()
+        .register_type::<SegmentPool>()
+        .register_type::<Score>()
+        .register_type::<Run>()
+        .register_type::<GameMode>()
+        .register_type::<Controls>();
+    }
+}
+
+/// Counts no steps while frame-stepping is paused, which still clears the
+/// timer's finished flag so gated systems don't run again.
+fn tick_timer<T: Send + Sync + 'static>(
+    fixed_time: Res<Time<Fixed>>,
+    frame_step: Res<frame_step::FrameStep>,
+    mut timer: ResMut<TickTimer<T>>,
+) {
+    let steps = u32::from(!frame_step.paused);
+    timer.tick(steps, fixed_time.timestep());
+}
+
+/// Counts steps by the [`WorldClock`](world_clock::WorldClock), for timers of
+/// things that stand still while the world is frozen.
+fn tick_world_timer<T: Send + Sync + 'static>(
+    fixed_time: Res<Time<Fixed>>,
+    clock: Res<world_clock::WorldClock>,
+    mut timer: ResMut<TickTimer<T>>,
+) {
+    let steps = u32::from(!clock.delta().is_zero());
+    timer.tick(steps, fixed_time.timestep());
+}
+
+/// Adds the time the fixed-step accumulator holds but has not stepped yet to
+/// the steps the movement timer has counted.
+fn tick_alpha(
+    fixed_time: Res<Time<Fixed>>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+    mut alpha: ResMut<TickAlpha>,
+) {
+    let timestep = fixed_time.timestep();
+    let counted = timestep * movement_timer.steps + fixed_time.overstep();
+    let period = timestep * movement_timer.period(timestep);
+    let progress = counted.as_secs_f32() / period.as_secs_f32();
+    alpha.set_if_neq(TickAlpha(progress.clamp(0.0, 1.0)));
+}
+
+fn timer_finished<T: Send + Sync + 'static>(timer: Res<TickTimer<T>>) -> bool {
+    timer.just_finished()
+}
+
+fn select_mode(mut mode: ResMut<GameMode>) {
+    let Some(name) = std::env::args().skip_while(|arg| arg != "--mode").nth(1) else {
+        return;
+    };
+    match name.parse() {
+        Ok(selected) => *mode = selected,
+        Err(()) => warn!("unknown game mode {name:?}, falling back to {:?}", *mode),
+    }
+}
+
+/// Starts a fresh run: picks a seed (or takes the one being replayed, or the
+/// daily challenge's) and restarts the tick timers so the run plays out the
+/// same way every time.
+fn begin_run(
+    mut run: ResMut<Run>,
+    mut rng: ResMut<GameRng>,
+    playback: Option<Res<replay::ReplayPlayback>>,
+    daily: Option<Res<daily::Daily>>,
+    mut movement_timer: ResMut<TickTimer<MovementTick>>,
+    mut food_spawn_timer: ResMut<TickTimer<FoodSpawnTick>>,
+) {
+    let seed = match (playback, daily) {
+        (Some(playback), _) => playback.0.seed,
+        (None, Some(daily)) => daily.seed(),
+        (None, None) => rand::random(),
+    };
+    *run = Run { seed, tick: 0 };
+    info!(seed, "run started");
+    rng.0 = ChaCha8Rng::seed_from_u64(seed);
+    movement_timer.reset();
+    food_spawn_timer.reset();
+}
+
+fn advance_run_tick(mut run: ResMut<Run>) {
+    run.tick += 1;
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2d);
+}
+
+fn spawn_snake(mut commands: Commands, mut pool: ResMut<SegmentPool>) {
+    spawn_snake_at(&mut commands, &mut pool, START_POSITION, START_DIRECTION);
+}
+
+/// Spawns a two-segment snake with its head at `position`, returning the head.
+pub(crate) fn spawn_snake_at(
+    commands: &mut Commands,
+    pool: &mut SegmentPool,
+    position: Position,
+    direction: Direction,
+) -> Entity {
+    let head = spawn_head(commands, position, direction);
+    let segment = spawn_segment(commands, pool, position);
+    commands.entity(head).insert(Segments(vec![head, segment]));
+    head
+}
+
+fn spawn_head(commands: &mut Commands, position: Position, direction: Direction) -> Entity {
+    commands
+        .spawn(Sprite {
+            ..Default::default()
+        })
+        .insert(ThemeColor::SnakeHead)
+        .insert(SnakeHead { direction })
+        .insert(SnakeSegment)
+        .insert(LastTailPosition::default())
+        .insert(position)
+        .insert(Size::square(0.8))
+        .id()
+}
+
+fn paint_sprites(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut sprites: Query<(Ref<ThemeColor>, &mut Sprite)>,
+) {
+    if theme.is_changed() {
+        clear_color.0 = theme.background;
+    }
+    for (role, mut sprite) in sprites.iter_mut() {
+        if theme.is_changed() || role.is_added() {
+            sprite.color = theme.color(*role);
+        }
+    }
+}
+
+/// Smallest side of a cell in pixels. An arena that would need smaller cells
+/// to fit the window is drawn at this size instead, with the camera following
+/// the head around it.
+pub const MIN_TILE: f32 = 8.0;
+
+/// Side of one square cell in pixels: the largest that fits the whole arena
+/// in the window, whatever its aspect ratio, but no smaller than [`MIN_TILE`].
+fn tile_size(arena: &Arena, window: &Window) -> f32 {
+    fitting_tile(arena, window).max(MIN_TILE)
+}
+
+fn fitting_tile(arena: &Arena, window: &Window) -> f32 {
+    (window.width() / arena.width as f32).min(window.height() / arena.height as f32)
+}
+
+/// Whether the whole arena fits in the window, so the camera stays put.
+pub fn arena_fits(arena: &Arena, window: &Window) -> bool {
+    fitting_tile(arena, window) >= MIN_TILE
+}
+
+/// Center of the cell at `position`, in pixels from the middle of the arena.
+fn cell_translation(arena: &Arena, tile: f32, position: Position) -> Vec2 {
+    fn convert(pos: f32, tile: f32, bound_game: f32) -> f32 {
+        (pos - (bound_game - 1.) / 2.) * tile
+    }
+    Vec2::new(
+        convert(position.x as f32, tile, arena.width as f32),
+        convert(position.y as f32, tile, arena.height as f32),
+    )
+}
+
+/// Sizes what changed size, or everything when the cell size changed with the
+/// window or the arena.
+fn size_scaling(
+    arena: Res<Arena>,
+    windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut laid_out: Local<Option<f32>>,
+    mut query: Query<(Ref<Size>, &mut Transform)>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let tile = tile_size(&arena, window);
+    let resized = laid_out.replace(tile) != Some(tile);
+    for (sprite_size, mut transform) in query.iter_mut() {
+        if resized || sprite_size.is_changed() {
+            transform.scale = Vec3::new(sprite_size.width * tile, sprite_size.height * tile, 1.0);
+        }
+    }
+}
+
+/// Keeps the mode and score in the window title, so the score can be read
+/// with the window small or in the background.
+fn window_title(
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let mode = match *mode {
+        GameMode::Classic => "Classic",
+        GameMode::Casual => "Casual",
+        GameMode::Speedrun => "Speedrun",
+    };
+    let title = format!("Snake — {mode} — {}", score.0);
+    for mut window in &mut windows {
+        if window.title != title {
+            window.title.clone_from(&title);
+        }
+    }
+}
+
+/// Places cells on the window with the arena centered, leaving bars on the
+/// sides that do not fit the arena's aspect ratio. Only what moved is placed
+/// again, unless the cell size or the arena changed, and depths are left as
+/// they were set.
+fn position_translation(
+    arena: Res<Arena>,
+    windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut laid_out: Local<Option<(Arena, f32)>>,
+    mut query: Query<(Ref<Position>, &mut Transform)>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let tile = tile_size(&arena, window);
+    let resized = laid_out.replace((*arena, tile)) != Some((*arena, tile));
+    for (pos, mut transform) in query.iter_mut() {
+        if resized || pos.is_changed() {
+            let z = transform.translation.z;
+            transform.translation = cell_translation(&arena, tile, *pos).extend(z);
+        }
+    }
+}
+
+/// Keeps the head in view on an arena too big for the window, without
+/// showing past its edges, and the arena centered otherwise. The view glides
+/// from the cell the head left to the one it is in by the [`TickAlpha`],
+/// rather than jumping a cell each tick.
+fn follow_head(
+    arena: Res<Arena>,
+    alpha: Res<TickAlpha>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    heads: Query<(&Position, &Segments), With<SnakeHead>>,
+    positions: Query<&Position>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let tile = tile_size(&arena, window);
+    let head = heads.iter().next().map_or(Vec2::ZERO, |(&head, segments)| {
+        let to = cell_translation(&arena, tile, head);
+        // The neck is where the head was, unless it wrapped or tunnelled.
+        match segments.0.get(1).and_then(|&neck| positions.get(neck).ok()) {
+            Some(&neck) if neck.x.abs_diff(head.x) + neck.y.abs_diff(head.y) == 1 => {
+                cell_translation(&arena, tile, neck).lerp(to, alpha.0)
+            }
+            _ => to,
+        }
+    });
+    let board = Vec2::new(arena.width as f32, arena.height as f32) * tile;
+    let limit = ((board - window.size()) / 2.0).max(Vec2::ZERO);
+    let center = head.clamp(-limit, limit);
+    for mut transform in &mut cameras {
+        if transform.translation.truncate() != center {
+            transform.translation = center.extend(transform.translation.z);
+        }
+    }
+}
+
+fn snake_movement_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    controls: Res<Controls>,
+    effects: Option<Res<power_ups::TimedEffects>>,
+    terrain: Option<Res<terrain::Terrain>>,
+    mut heads: Query<(&mut SnakeHead, &Position)>,
+) {
+    if let Some((mut head, &position)) = heads.iter_mut().next() {
+        if terrain.is_some_and(|terrain| terrain.at(position) == terrain::Tile::Ice) {
+            return;
+        }
+        let pressed = if keyboard_input.pressed(controls.left) {
+            Some(Direction::Left)
+        } else if keyboard_input.pressed(controls.down) {
+            Some(Direction::Down)
+        } else if keyboard_input.pressed(controls.up) {
+            Some(Direction::Up)
+        } else if keyboard_input.pressed(controls.right) {
+            Some(Direction::Right)
+        } else {
+            None
+        };
+        let reversed =
+            effects.is_some_and(|effects| effects.active(power_ups::Effect::ReverseControls));
+        let dir = match pressed {
+            Some(dir) if reversed => dir.opposite(),
+            Some(dir) => dir,
+            None => head.direction,
+        };
+        if dir != head.direction && dir != head.direction.opposite() {
+            debug!(from = ?head.direction, to = ?dir, "direction changed");
+            head.direction = dir;
+        }
+    }
+}
+
+fn snake_movement(
+    arena: Res<Arena>,
+    run: Res<Run>,
+    mode: Res<GameMode>,
+    tail_cutting: Res<tail_cutting::TailCutting>,
+    tunnels: Option<Res<tunnels::Tunnels>>,
+    mut heads: Query<
+        (Entity, &mut SnakeHead, &Segments, &mut LastTailPosition),
+        Without<terrain::Held>,
+    >,
+    mut positions: Query<&mut Position, Without<Obstacle>>,
+    obstacles: Query<&Position, With<Obstacle>>,
+    lives: Res<lives::Lives>,
+    invulnerable: Option<Res<lives::Invulnerable>>,
+    armor: Option<Res<armor::Armor>>,
+    mut game_over_writer: EventWriter<GameOverEvent>,
+    mut life_lost_writer: EventWriter<lives::LifeLost>,
+    mut tail_cut_writer: EventWriter<tail_cutting::TailCut>,
+    mut armor_hit_writer: EventWriter<armor::ArmorHit>,
+) {
+    let _span = debug_span!("tick", tick = run.tick).entered();
+    'snakes: for (head_entity, mut head, segments, mut last_tail_position) in &mut heads {
+        let mut segment_positions = Vec::with_capacity(segments.0.len());
+        for &segment in &segments.0 {
+            match positions.get(segment) {
+                Ok(pos) => segment_positions.push(*pos),
+                Err(err) => {
+                    warn!("skipping snake movement, segment {segment} has no position: {err}");
+                    continue 'snakes;
+                }
+            }
+        }
+        let Ok(mut head_pos) = positions.get_mut(head_entity) else {
+            warn!("skipping snake movement, head {head_entity} has no position");
+            continue;
+        };
+        match tunnels
+            .as_ref()
+            .and_then(|tunnels| tunnels.exit(*head_pos, head.direction))
+        {
+            Some((exit, heading)) => {
+                trace!(from = ?*head_pos, to = ?exit, "through a tunnel");
+                *head_pos = exit;
+                head.direction = heading;
+            }
+            None => *head_pos = head_pos.step(head.direction),
+        }
+
+        let mut cause = collision(*arena, &segment_positions, *head_pos).or_else(|| {
+            obstacles
+                .iter()
+                .any(|obstacle| obstacle == &*head_pos)
+                .then_some(Collision::Wall)
+        });
+        let mut length = segment_positions.len();
+        if cause == Some(Collision::Body) && tail_cutting.applies(*mode) {
+            if let Some(bitten) = segment_positions.iter().position(|pos| pos == &*head_pos) {
+                tail_cut_writer.send(tail_cutting::TailCut {
+                    head: head_entity,
+                    at: bitten,
+                });
+                length = bitten;
+                cause = None;
+            }
+        }
+        if let Some(cause) = cause {
+            if invulnerable.is_some() {
+                trace!(?cause, "held still while invulnerable");
+                *head_pos = segment_positions[0];
+                continue;
+            }
+            if armor.as_ref().is_some_and(|armor| armor.hit_points > 0) {
+                armor_hit_writer.send(armor::ArmorHit(cause));
+                *head_pos = segment_positions[0];
+                continue;
+            }
+            info!(?cause, head = ?*head_pos, length = segment_positions.len(), "collision");
+            if lives.spare > 0 {
+                life_lost_writer.send(lives::LifeLost(GameOverCause::Collision(cause)));
+            } else {
+                // Off the board the head hit the wall from the cell it was on.
+                let cell = if arena.contains(*head_pos) {
+                    *head_pos
+                } else {
+                    segment_positions[0]
+                };
+                game_over_writer.send(GameOverEvent {
+                    cause: GameOverCause::Collision(cause),
+                    cell: Some(cell),
+                });
+            }
+            continue;
+        }
+        trace!(head = ?*head_pos, direction = ?head.direction, "moved");
+
+        for (pos, &segment) in segment_positions.iter().zip(segments.0.iter().skip(1)) {
+            if let Ok(mut segment_pos) = positions.get_mut(segment) {
+                *segment_pos = *pos;
+            }
+        }
+        last_tail_position.0 = segment_positions.get(length - 1).copied();
+    }
+}
+
+fn prewarm_segment_pool(mut commands: Commands, mut pool: ResMut<SegmentPool>) {
+    for _ in 0..SEGMENT_POOL_PREWARM {
+        let segment = commands
+            .spawn(Sprite {
+                ..Default::default()
+            })
+            .insert(ThemeColor::SnakeSegment)
+            .insert(Size::square(0.65))
+            .insert(Visibility::Hidden)
+            .id();
+        pool.0.push(segment);
+    }
+}
+
+fn spawn_segment(commands: &mut Commands, pool: &mut SegmentPool, position: Position) -> Entity {
+    if let Some(segment) = pool.0.pop() {
+        return commands
+            .entity(segment)
+            .insert(SnakeSegment)
+            .insert(position)
+            .insert(Visibility::Inherited)
+            .id();
+    }
+    commands
+        .spawn(Sprite {
+            ..Default::default()
+        })
+        .insert(ThemeColor::SnakeSegment)
+        .insert(SnakeSegment)
+        .insert(position)
+        .insert(Size::square(0.65))
+        .id()
+}
+
+fn release_segment(commands: &mut Commands, pool: &mut SegmentPool, segment: Entity) {
+    commands
+        .entity(segment)
+        .remove::<(SnakeSegment, Position)>()
+        .insert(Visibility::Hidden);
+    pool.0.push(segment);
+}
+
+fn food_spawner(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    mut rng: ResMut<GameRng>,
+    head_positions: Query<&Position, With<SnakeHead>>,
+) {
+    let heads = head_positions.iter().copied().collect::<Vec<_>>();
+    let position = random_food_position(&mut rng.0, *arena, &heads);
+    debug!(?position, "food spawned");
+    spawn_food(&mut commands, position);
+}
+
+fn spawn_food(commands: &mut Commands, position: Position) -> Entity {
+    commands
+        .spawn(Sprite {
+            ..Default::default()
+        })
+        .insert(ThemeColor::Food)
+        .insert(Food)
+        .insert(position)
+        .insert(Size::square(0.8))
+        .id()
+}
+
+fn spawn_obstacle(commands: &mut Commands, position: Position) -> Entity {
+    commands
+        .spawn(Sprite {
+            ..Default::default()
+        })
+        .insert(ThemeColor::Obstacle)
+        .insert(Obstacle)
+        .insert(position)
+        .insert(Size::square(1.0))
+        .id()
+}
+
+fn snake_eating(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut growth_writer: EventWriter<GrowthEvent>,
+    food_positions: Query<
+        (Entity, &Position, Option<&Worth>),
+        (With<Food>, Without<weather::Drifted>),
+    >,
+    head_positions: Query<(Entity, &Position), With<SnakeHead>>,
+) {
+    for (head, head_pos) in head_positions.iter() {
+        for (ent, food_pos, worth) in food_positions.iter() {
+            if food_pos == head_pos {
+                commands.entity(ent).despawn();
+                score.0 += worth.map_or(1, |worth| worth.0);
+                debug!(food = ?*food_pos, score = score.0, "food eaten");
+                growth_writer.send(GrowthEvent(head));
+            }
+        }
+    }
+}
+
+/// Grows each snake that ate by one segment, however much it ate.
+fn snake_growth(
+    mut commands: Commands,
+    mut snakes: Query<(&LastTailPosition, &mut Segments)>,
+    mut pool: ResMut<SegmentPool>,
+    mut growth_reader: EventReader<GrowthEvent>,
+) {
+    let mut grown = Vec::new();
+    for &GrowthEvent(head) in growth_reader.read() {
+        if grown.contains(&head) {
+            continue;
+        }
+        grown.push(head);
+        let Ok((last_tail_position, mut segments)) = snakes.get_mut(head) else {
+            continue;
+        };
+        match last_tail_position.0 {
+            Some(position) => {
+                segments
+                    .0
+                    .push(spawn_segment(&mut commands, &mut pool, position));
+                trace!(length = segments.0.len(), "grew");
+            }
+            None => warn!("skipping snake growth, no tail position recorded yet"),
+        }
+    }
+}
+
+fn game_over(
+    mut commands: Commands,
+    mut reader: EventReader<GameOverEvent>,
+    mut score: ResMut<Score>,
+    mut pool: ResMut<SegmentPool>,
+    food: Query<
+        Entity,
+        Or<(
+            With<Food>,
+            With<Obstacle>,
+            With<gates::Key>,
+            With<predator::Predator>,
+        )>,
+    >,
+    heads: Query<Entity, With<SnakeHead>>,
+    segments: Query<Entity, (With<SnakeSegment>, Without<SnakeHead>)>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = reader.read().last() else {
+        return;
+    };
+    info!(score = score.0, ?cause, "game over");
+    for ent in food.iter().chain(heads.iter()) {
+        commands.entity(ent).despawn();
+    }
+    for segment in segments.iter() {
+        release_segment(&mut commands, &mut pool, segment);
+    }
+    score.0 = 0;
+    spawn_snake(commands, pool);
+}
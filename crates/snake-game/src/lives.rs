@@ -0,0 +1,107 @@
+//! Spare lives, which casual runs start with.
+//!
+//! Crashing with a spare left costs the spare instead of the run: the snake
+//! comes back at the start, keeping its score but not its length. For
+//! [`INVULNERABLE_FOR`] afterwards it blinks and cannot crash, holding still
+//! instead, so whatever it came back next to does not kill it straight away.
+//! The core simulation knows nothing of lives, so a run that loses one is not
+//! kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use snake_core::GameMode;
+
+use crate::{
+    accessibility::Accessibility, release_segment, replay::ReplayRecorder, spawn_snake,
+    GameOverCause, SegmentPool, SnakeHead, SnakeSegment,
+};
+
+/// Spares a casual run starts with.
+pub const SPARE_LIVES: u32 = 2;
+pub const INVULNERABLE_FOR: Duration = Duration::from_secs(2);
+/// Times per second the snake disappears while invulnerable.
+const BLINK_RATE: f32 = 5.0;
+
+/// Lives left in the run besides the one being played.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Lives {
+    pub spare: u32,
+}
+
+/// Present while the snake cannot crash, after coming back.
+#[derive(Resource)]
+pub struct Invulnerable(pub Timer);
+
+/// Sent instead of a game over when a crash, or being caught, costs a spare
+/// life.
+#[derive(Event)]
+pub struct LifeLost(pub(crate) GameOverCause);
+
+/// Hands out the spares for the run that is beginning.
+pub(crate) fn reset_lives(mut commands: Commands, mode: Res<GameMode>, mut lives: ResMut<Lives>) {
+    lives.spare = if mode.is_casual() { SPARE_LIVES } else { 0 };
+    commands.remove_resource::<Invulnerable>();
+}
+
+/// Spends a spare and brings the snake back at the start.
+pub(crate) fn lose_life(
+    mut commands: Commands,
+    mut reader: EventReader<LifeLost>,
+    mut lives: ResMut<Lives>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut pool: ResMut<SegmentPool>,
+    heads: Query<Entity, With<SnakeHead>>,
+    segments: Query<Entity, (With<SnakeSegment>, Without<SnakeHead>)>,
+) {
+    let Some(LifeLost(cause)) = reader.read().last() else {
+        return;
+    };
+    lives.spare = lives.spare.saturating_sub(1);
+    info!(?cause, spare = lives.spare, "life lost");
+    recorder.tainted = true;
+    for head in &heads {
+        commands.entity(head).despawn();
+    }
+    for segment in &segments {
+        release_segment(&mut commands, &mut pool, segment);
+    }
+    commands.insert_resource(Invulnerable(Timer::new(INVULNERABLE_FOR, TimerMode::Once)));
+    spawn_snake(commands, pool);
+}
+
+pub(crate) fn wear_off(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerable: ResMut<Invulnerable>,
+) {
+    if invulnerable.0.tick(time.delta()).finished() {
+        commands.remove_resource::<Invulnerable>();
+    }
+}
+
+/// Blinks the snake while it is invulnerable, or leaves it be with reduced
+/// motion.
+pub(crate) fn blink(
+    invulnerable: Res<Invulnerable>,
+    accessibility: Res<Accessibility>,
+    mut visibilities: Query<&mut Visibility, With<SnakeSegment>>,
+) {
+    let shown = accessibility.reduced_motion
+        || ((invulnerable.0.elapsed_secs() * BLINK_RATE * 2.0) as u32).is_multiple_of(2);
+    let visibility = if shown {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut current in &mut visibilities {
+        current.set_if_neq(visibility);
+    }
+}
+
+/// Shows the snake again once it can crash.
+pub(crate) fn stop_blinking(mut visibilities: Query<&mut Visibility, With<SnakeSegment>>) {
+    for mut visibility in &mut visibilities {
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
@@ -0,0 +1,165 @@
+//! Loading screen shown at startup until every asset the game needs is in.
+//!
+//! Plugins that load assets the game should not start without hand their
+//! handles to [`LoadingAssets`]. While any of them is still loading, the game
+//! is paused behind a screen with a progress bar; once all have loaded, or
+//! failed to so the game can fall back on its defaults, the screen goes and
+//! play starts. Assets loaded later, such as the font of a theme picked in
+//! game, come in without it.
+
+use bevy::{asset::UntypedAssetId, prelude::*};
+
+/// Assets the game waits for before it starts.
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<UntypedHandle>,
+    finished: bool,
+}
+
+impl LoadingAssets {
+    /// Waits for `handle` before starting, unless the game already has.
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        if !self.finished {
+            self.handles.push(handle.into());
+        }
+    }
+
+    /// Whether the game has started.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// How many of the tracked assets are done with, out of how many.
+    pub fn progress(&self, asset_server: &AssetServer) -> (usize, usize) {
+        let done = self
+            .handles
+            .iter()
+            .filter(|handle| settled(asset_server, handle.id()))
+            .count();
+        (done, self.handles.len())
+    }
+}
+
+/// Whether an asset has loaded, with its dependencies, or failed for good.
+fn settled(asset_server: &AssetServer, id: UntypedAssetId) -> bool {
+    asset_server.is_loaded_with_dependencies(id)
+        || asset_server
+            .get_load_state(id)
+            .is_some_and(|state| state.is_failed())
+}
+
+/// Present while the loading screen is up.
+#[derive(Resource)]
+pub struct LoadingScreen {
+    /// Whether the game was paused already, and so stays paused after.
+    was_paused: bool,
+}
+
+#[derive(Component)]
+struct LoadingNode;
+
+#[derive(Component)]
+struct ProgressBar;
+
+#[derive(Component)]
+struct ProgressText;
+
+/// Holds the game behind a loading screen until its assets are in. Needs the
+/// asset server, so it is left out of [`crate::SnakeGamePlugin`].
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingAssets>()
+            .add_systems(PostUpdate, loading_screen);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn loading_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+    screen: Option<Res<LoadingScreen>>,
+    mut time: ResMut<Time<Virtual>>,
+    nodes: Query<Entity, With<LoadingNode>>,
+    mut bars: Query<&mut Node, With<ProgressBar>>,
+    mut texts: Query<&mut Text, With<ProgressText>>,
+) {
+    if loading.finished {
+        return;
+    }
+    let (done, total) = loading.progress(&asset_server);
+    if done == total {
+        loading.finished = true;
+        loading.handles.clear();
+        if let Some(screen) = screen {
+            info!("loaded {total} assets");
+            if !screen.was_paused {
+                time.unpause();
+            }
+            commands.remove_resource::<LoadingScreen>();
+            for node in &nodes {
+                commands.entity(node).despawn_recursive();
+            }
+        }
+        return;
+    }
+    let percent = done as f32 / total as f32 * 100.0;
+    if screen.is_some() {
+        for mut bar in &mut bars {
+            bar.width = Val::Percent(percent);
+        }
+        for mut text in &mut texts {
+            text.0 = format!("Loading... {percent:.0}%");
+        }
+        return;
+    }
+    commands.insert_resource(LoadingScreen {
+        was_paused: time.is_paused(),
+    });
+    time.pause();
+    commands
+        .spawn((
+            LoadingNode,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::BLACK),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ProgressText,
+                Text::new(format!("Loading... {percent:.0}%")),
+                TextFont {
+                    font_size: 20.0,
+                    ..Default::default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(50.0),
+                        height: Val::Px(12.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                ))
+                .with_child((
+                    ProgressBar,
+                    Node {
+                        width: Val::Percent(percent),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::WHITE),
+                ));
+        });
+}
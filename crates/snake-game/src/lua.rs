@@ -0,0 +1,163 @@
+//! Lua scripts for [`crate::scripting`], loaded with `--script <file.lua>`.
+//!
+//! A script defines the hooks as global functions and changes the game
+//! through the `snake` table:
+//! - `snake.spawn_food(x, y)` and `snake.spawn_obstacle(x, y)`
+//! - `snake.set_speed(ms)` sets the time between moves
+//! - `snake.score()`, `snake.head()` returning `x, y`, and `snake.arena()`
+//!   returning `width, height`
+//!
+//! Only the `table`, `string`, `math` and `utf8` libraries are loaded, so
+//! scripts cannot reach files, the OS or other modules.
+
+use std::{path::Path, time::Duration};
+
+use bevy::prelude::*;
+use mlua::{Function, HookTriggers, IntoLuaMulti, Lua, LuaOptions, StdLib, Table, VmState};
+use snake_core::Position;
+
+use crate::scripting::{Hook, Mod, Mods, ScriptCommand, View};
+
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+/// Instructions between budget checks, and checks allowed per call.
+const INSTRUCTIONS_PER_CHECK: u32 = 1_000;
+const CHECKS_PER_CALL: u32 = 1_000;
+
+/// Budget checks left for the current call.
+struct Budget(u32);
+
+pub struct LuaScript {
+    lua: Lua,
+}
+
+impl LuaScript {
+    /// Runs `source` in a fresh sandbox, which defines the hooks.
+    pub fn new(source: &str) -> mlua::Result<Self> {
+        let lua = Lua::new_with(
+            StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8,
+            LuaOptions::default(),
+        )?;
+        lua.set_memory_limit(MEMORY_LIMIT)?;
+        lua.set_app_data(Vec::<ScriptCommand>::new());
+        lua.set_app_data(View::default());
+        lua.set_app_data(Budget(CHECKS_PER_CALL));
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(INSTRUCTIONS_PER_CHECK),
+            |lua, _| {
+                let mut budget = lua
+                    .app_data_mut::<Budget>()
+                    .ok_or_else(|| mlua::Error::runtime("script budget missing"))?;
+                budget.0 = budget.0.saturating_sub(1);
+                if budget.0 == 0 {
+                    Err(mlua::Error::runtime("script ran too long"))
+                } else {
+                    Ok(VmState::Continue)
+                }
+            },
+        )?;
+        lua.globals().set("snake", api(&lua)?)?;
+        lua.load(source).exec()?;
+        Ok(Self { lua })
+    }
+
+    fn call_global(&self, hook: &str, args: impl IntoLuaMulti) {
+        let Ok(Some(function)) = self.lua.globals().get::<Option<Function>>(hook) else {
+            return;
+        };
+        if let Err(err) = function.call::<()>(args) {
+            warn!("script {hook} failed: {err}");
+        }
+    }
+}
+
+impl Mod for LuaScript {
+    fn call(&mut self, hook: Hook, view: View) -> Vec<ScriptCommand> {
+        self.lua.set_app_data(view);
+        self.lua.set_app_data(Budget(CHECKS_PER_CALL));
+        let name = hook.name();
+        match hook {
+            Hook::Tick(tick) => self.call_global(name, tick),
+            Hook::Eat { food, score } => self.call_global(name, (food.x, food.y, score)),
+            Hook::Spawn(food) => self.call_global(name, (food.x, food.y)),
+            Hook::Death { cause, score } => self.call_global(name, (cause, score)),
+        }
+        self.lua
+            .app_data_mut::<Vec<ScriptCommand>>()
+            .map(|mut commands| std::mem::take(&mut *commands))
+            .unwrap_or_default()
+    }
+}
+
+fn api(lua: &Lua) -> mlua::Result<Table> {
+    fn queue(lua: &Lua, command: ScriptCommand) -> mlua::Result<()> {
+        if let Some(mut commands) = lua.app_data_mut::<Vec<ScriptCommand>>() {
+            commands.push(command);
+        }
+        Ok(())
+    }
+    fn view(lua: &Lua) -> View {
+        lua.app_data_ref::<View>()
+            .map(|view| *view)
+            .unwrap_or_default()
+    }
+
+    let api = lua.create_table()?;
+    api.set(
+        "spawn_food",
+        lua.create_function(|lua, (x, y): (i32, i32)| {
+            queue(lua, ScriptCommand::SpawnFood(Position { x, y }))
+        })?,
+    )?;
+    api.set(
+        "spawn_obstacle",
+        lua.create_function(|lua, (x, y): (i32, i32)| {
+            queue(lua, ScriptCommand::SpawnObstacle(Position { x, y }))
+        })?,
+    )?;
+    api.set(
+        "set_speed",
+        lua.create_function(|lua, ms: u64| {
+            queue(
+                lua,
+                ScriptCommand::SetSpeed(Duration::from_millis(ms.max(1))),
+            )
+        })?,
+    )?;
+    api.set("score", lua.create_function(|lua, ()| Ok(view(lua).score))?)?;
+    api.set(
+        "head",
+        lua.create_function(|lua, ()| {
+            Ok(view(lua)
+                .head
+                .map_or((None, None), |head| (Some(head.x), Some(head.y))))
+        })?,
+    )?;
+    api.set(
+        "arena",
+        lua.create_function(|lua, ()| {
+            let arena = view(lua).arena;
+            Ok((arena.width, arena.height))
+        })?,
+    )?;
+    Ok(api)
+}
+
+pub(crate) fn load_from_args(mut mods: ResMut<Mods>) {
+    let Some(path) = std::env::args().skip_while(|arg| arg != "--script").nth(1) else {
+        return;
+    };
+    let source = match std::fs::read_to_string(Path::new(&path)) {
+        Ok(source) => source,
+        Err(err) => {
+            warn!("could not read script {path}: {err}");
+            return;
+        }
+    };
+    match LuaScript::new(&source) {
+        Ok(script) => {
+            info!("loaded script {path}");
+            mods.0.push(Box::new(script));
+        }
+        Err(err) => warn!("script {path} failed to load: {err}"),
+    }
+}
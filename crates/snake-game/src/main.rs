@@ -0,0 +1,142 @@
+use bevy::{
+    log::LogPlugin,
+    prelude::*,
+    window::{close_when_requested, WindowResolution},
+};
+use snake_game::{
+    achievements::AchievementsPlugin, armor::ArmorPlugin, assist::AssistPlugin, atlas::AtlasPlugin,
+    attract::AttractPlugin, campaign::CampaignPlugin, config::ConfigPlugin, console::ConsolePlugin,
+    critters::CrittersPlugin, culling::CullingPlugin, daily::DailyPlugin,
+    day_night::DayNightPlugin, debug_overlay::DebugOverlayPlugin, eggs::EggsPlugin,
+    frame_rate::FrameRatePlugin, high_scores::HighScoresPlugin, loading::LoadingPlugin,
+    minimap::MinimapPlugin, mobile::MobilePlugin, online::OnlinePlugin, plants::PlantsPlugin,
+    power_ups::PowerUpsPlugin, profile::ProfilePlugin, quit::QuitPlugin, rumble::RumblePlugin,
+    screen_reader::ScreenReaderPlugin, settings::SettingsPlugin, snake_mesh::SnakeMeshPlugin,
+    stats::StatsPlugin, typography::TypographyPlugin, ui_scale::UiScalePlugin, venom::VenomPlugin,
+    victory::VictoryPlugin, weather::WeatherPlugin, BoardPlugin, SnakeGamePlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Snake".to_string(),
+                    resizable: false,
+                    resolution: WindowResolution::new(500.0, 500.0),
+                    // The web shell provides the canvas; see `web/index.html`.
+                    #[cfg(target_arch = "wasm32")]
+                    canvas: Some("#snake".to_string()),
+                    ..Default::default()
+                }),
+                // Asked about first, see `QuitPlugin`.
+                close_when_requested: false,
+                ..Default::default()
+            })
+            .set(LogPlugin {
+                filter: log_filter(),
+                ..Default::default()
+            }),
+    );
+
+    if let Some(online) = OnlinePlugin::from_args() {
+        app.add_plugins((BoardPlugin, online))
+            .add_systems(Update, close_when_requested);
+    } else {
+        app.add_plugins((
+            SnakeGamePlugin,
+            ConfigPlugin,
+            DebugOverlayPlugin,
+            MobilePlugin,
+            RumblePlugin,
+            ScreenReaderPlugin,
+            AttractPlugin,
+            AssistPlugin,
+        ))
+        .add_plugins((
+            ProfilePlugin,
+            SettingsPlugin,
+            StatsPlugin,
+            AchievementsPlugin,
+            HighScoresPlugin,
+            DailyPlugin,
+            CampaignPlugin,
+            PowerUpsPlugin,
+            CrittersPlugin,
+            EggsPlugin,
+            PlantsPlugin,
+            WeatherPlugin,
+            DayNightPlugin,
+            ArmorPlugin,
+            VenomPlugin,
+        ))
+        .add_plugins((
+            VictoryPlugin,
+            QuitPlugin,
+            MinimapPlugin,
+            UiScalePlugin,
+            TypographyPlugin,
+            SnakeMeshPlugin,
+            CullingPlugin,
+            AtlasPlugin,
+            FrameRatePlugin,
+            LoadingPlugin,
+        ));
+
+        if snake_game::console::enabled() {
+            app.add_plugins(ConsolePlugin);
+        }
+
+        #[cfg(feature = "telemetry")]
+        app.add_plugins(snake_game::telemetry::TelemetryPlugin);
+
+        #[cfg(feature = "leaderboard")]
+        app.add_plugins(snake_game::leaderboard::LeaderboardPlugin);
+
+        #[cfg(feature = "steam")]
+        app.add_plugins(snake_game::steam::SteamPlugin);
+
+        #[cfg(feature = "cloud-sync")]
+        app.add_plugins(snake_game::cloud_sync::CloudSyncPlugin);
+
+        #[cfg(feature = "capture")]
+        app.add_plugins(snake_game::capture::CapturePlugin);
+
+        #[cfg(any(feature = "scripting", feature = "wasm-mods"))]
+        app.add_plugins(snake_game::scripting::ScriptingPlugin);
+
+        #[cfg(feature = "chat-plays")]
+        app.add_plugins(snake_game::chat_plays::ChatPlaysPlugin);
+
+        #[cfg(feature = "remote-control")]
+        app.add_plugins(snake_game::remote_control::RemoteControlPlugin);
+    }
+
+    #[cfg(feature = "inspector")]
+    app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
+
+    app.run();
+}
+
+/// Log filter for the game's own crates: `info` by default, `debug` with `-v`
+/// (direction changes, food, per-tick spans) and `trace` with `-vv` (every
+/// move).
+fn log_filter() -> String {
+    let verbosity: usize = std::env::args()
+        .map(|arg| match arg.as_str() {
+            "-v" | "--verbose" => 1,
+            "-vv" => 2,
+            _ => 0,
+        })
+        .sum();
+    let level = match verbosity {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    format!(
+        "{},snake_game={level},snake_core={level},snake_net={level}",
+        LogPlugin::default().filter
+    )
+}
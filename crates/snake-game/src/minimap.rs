@@ -0,0 +1,150 @@
+//! Minimap for arenas too big for the window, where the camera follows the
+//! head: the whole board in a corner at one pixel per cell.
+//!
+//! The snake, food and obstacles are drawn in the theme's colors and redrawn
+//! every movement tick. While the arena fits the window the minimap is hidden.
+
+use bevy::{
+    image::ImageSampler,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    window::PrimaryWindow,
+};
+use snake_core::{Arena, Position};
+
+use crate::{arena_fits, timer_finished, Food, GameSet, MovementTick, Theme, ThemeColor};
+
+const BACKGROUND: [u8; 4] = [0, 0, 0, 160];
+const MARGIN: f32 = 8.0;
+
+/// Adds the minimap. Needs a window and images, so it is left out of
+/// [`crate::SnakeGamePlugin`].
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, show_minimap).add_systems(
+            FixedUpdate,
+            draw_minimap
+                .after(GameSet::Spawning)
+                .run_if(timer_finished::<MovementTick>),
+        );
+    }
+}
+
+/// The minimap's picture of the board.
+#[derive(Resource)]
+pub struct Minimap(pub Handle<Image>);
+
+#[derive(Component)]
+struct MinimapNode;
+
+fn blank(arena: &Arena) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: arena.width,
+            height: arena.height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &BACKGROUND,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Shows the minimap while the camera follows the head, making it the first
+/// time it is needed.
+fn show_minimap(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    minimap: Option<Res<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut nodes: Query<&mut Visibility, With<MinimapNode>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let fits = arena_fits(&arena, window);
+    if minimap.is_none() && !fits {
+        let image = images.add(blank(&arena));
+        commands.spawn((
+            MinimapNode,
+            ImageNode::new(image.clone()),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(MARGIN),
+                right: Val::Px(MARGIN),
+                width: Val::Px(arena.width as f32),
+                height: Val::Px(arena.height as f32),
+                ..Default::default()
+            },
+        ));
+        commands.insert_resource(Minimap(image));
+    }
+    let shown = if fits {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut visibility in &mut nodes {
+        visibility.set_if_neq(shown);
+    }
+}
+
+/// Redraws the board, fitting the picture to the arena if it was resized.
+fn draw_minimap(
+    arena: Res<Arena>,
+    theme: Res<Theme>,
+    minimap: Option<Res<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    mut nodes: Query<(&mut Node, &Visibility), With<MinimapNode>>,
+    cells: Query<(&Position, Option<&ThemeColor>), Or<(With<ThemeColor>, With<Food>)>>,
+) {
+    let Some(minimap) = minimap else {
+        return;
+    };
+    let Ok((mut node, visibility)) = nodes.get_single_mut() else {
+        return;
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.0) else {
+        return;
+    };
+    if image.width() != arena.width || image.height() != arena.height {
+        *image = blank(&arena);
+        node.width = Val::Px(arena.width as f32);
+        node.height = Val::Px(arena.height as f32);
+    } else {
+        for pixel in image.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&BACKGROUND);
+        }
+    }
+    let mut cells: Vec<(Position, ThemeColor)> = cells
+        .iter()
+        .filter(|(position, _)| arena.contains(**position))
+        .map(|(&position, role)| (position, role.copied().unwrap_or(ThemeColor::Food)))
+        .collect();
+    // The snake on top, its head over the segment it starts on.
+    cells.sort_by_key(|(_, role)| match role {
+        ThemeColor::Obstacle => 0,
+        ThemeColor::Food => 1,
+        ThemeColor::SnakeSegment => 2,
+        ThemeColor::SnakeHead => 3,
+    });
+    for (position, role) in cells {
+        let color = theme.color(role);
+        // The picture's rows run top down, the arena's bottom up.
+        let row = arena.height - 1 - position.y as u32;
+        let at = ((row * arena.width + position.x as u32) * 4) as usize;
+        image.data[at..at + 4].copy_from_slice(&color.to_srgba().to_u8_array());
+    }
+}
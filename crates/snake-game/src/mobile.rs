@@ -0,0 +1,194 @@
+//! Touch controls and app lifecycle handling for phones and tablets.
+//!
+//! Swiping steers the snake in the swipe's main direction, and so does the
+//! d-pad drawn in the bottom left corner once the screen is touched. The
+//! d-pad hides again as soon as a key or gamepad button is pressed, and can
+//! be turned off with `touch_dpad` in `assets/config.ron` or the settings.
+//! When the OS suspends the app the game pauses, and a tap resumes it. Works
+//! anywhere touch input does, so desktop and web builds add it too.
+
+use bevy::{prelude::*, window::AppLifecycle};
+use snake_core::Direction;
+
+use crate::{frame_step::FrameStep, SnakeHead};
+
+/// Shortest swipe that steers, in logical pixels. Anything shorter is a tap.
+const MIN_SWIPE: f32 = 30.0;
+/// Side of each d-pad button, in logical pixels.
+const DPAD_BUTTON: f32 = 56.0;
+const DPAD_MARGIN: f32 = 16.0;
+const DPAD_COLOR: Color = Color::linear_rgba(1.0, 1.0, 1.0, 0.2);
+const DPAD_PRESSED_COLOR: Color = Color::linear_rgba(1.0, 1.0, 1.0, 0.45);
+
+pub struct MobilePlugin;
+
+impl Plugin for MobilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchDpad>().add_systems(
+            Update,
+            (pause_on_suspend, touch_input, show_dpad, dpad_input).chain(),
+        );
+    }
+}
+
+/// Whether the on-screen d-pad may be shown.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TouchDpad {
+    pub enabled: bool,
+}
+
+impl Default for TouchDpad {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// The d-pad, holding its four buttons.
+#[derive(Component)]
+pub struct Dpad;
+
+/// A d-pad button, steering towards its direction.
+#[derive(Component)]
+pub struct DpadButton(pub Direction);
+
+fn pause_on_suspend(mut lifecycle: EventReader<AppLifecycle>, mut frame_step: ResMut<FrameStep>) {
+    for event in lifecycle.read() {
+        if matches!(event, AppLifecycle::WillSuspend | AppLifecycle::Suspended) {
+            info!("suspended, pausing until tapped");
+            frame_step.paused = true;
+        }
+    }
+}
+
+fn touch_input(
+    touches: Res<Touches>,
+    mut frame_step: ResMut<FrameStep>,
+    mut heads: Query<&mut SnakeHead>,
+) {
+    for touch in touches.iter_just_released() {
+        let swipe = touch.distance();
+        if swipe.length() < MIN_SWIPE {
+            frame_step.paused = false;
+            continue;
+        }
+        // Screen y grows downwards.
+        let direction = if swipe.x.abs() > swipe.y.abs() {
+            if swipe.x > 0.0 {
+                Direction::Right
+            } else {
+                Direction::Left
+            }
+        } else if swipe.y > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+        steer(&mut heads, direction);
+    }
+}
+
+/// Turns the snake towards `direction`, unless it is heading that way or the
+/// opposite one already.
+fn steer(heads: &mut Query<&mut SnakeHead>, direction: Direction) {
+    for mut head in heads {
+        if direction != head.direction && direction != head.direction.opposite() {
+            debug!(from = ?head.direction, to = ?direction, "direction changed");
+            head.direction = direction;
+        }
+    }
+}
+
+fn spawn_dpad(commands: &mut Commands) {
+    let buttons = [
+        (Direction::Up, "^", 1.0, 0.0),
+        (Direction::Left, "<", 0.0, 1.0),
+        (Direction::Right, ">", 2.0, 1.0),
+        (Direction::Down, "v", 1.0, 2.0),
+    ];
+    commands
+        .spawn((
+            Dpad,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(DPAD_MARGIN),
+                bottom: Val::Px(DPAD_MARGIN),
+                width: Val::Px(DPAD_BUTTON * 3.0),
+                height: Val::Px(DPAD_BUTTON * 3.0),
+                ..Default::default()
+            },
+        ))
+        .with_children(|dpad| {
+            for (direction, label, column, row) in buttons {
+                dpad.spawn((
+                    DpadButton(direction),
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(DPAD_BUTTON * column),
+                        top: Val::Px(DPAD_BUTTON * row),
+                        width: Val::Px(DPAD_BUTTON),
+                        height: Val::Px(DPAD_BUTTON),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    BackgroundColor(DPAD_COLOR),
+                ))
+                .with_child((
+                    Text::new(label),
+                    TextFont {
+                        font_size: 24.0,
+                        ..Default::default()
+                    },
+                    TextColor(Color::linear_rgba(1.0, 1.0, 1.0, 0.6)),
+                ));
+            }
+        });
+}
+
+/// Shows the d-pad from the first touch, and hides it from the first key or
+/// gamepad button pressed after. It is only made once it is first needed.
+fn show_dpad(
+    mut commands: Commands,
+    touches: Res<Touches>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    dpad: Res<TouchDpad>,
+    mut touched: Local<bool>,
+    mut shown: Query<&mut Visibility, With<Dpad>>,
+) {
+    if touches.any_just_pressed() {
+        *touched = true;
+    } else if keyboard_input.get_just_pressed().next().is_some()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        *touched = false;
+    }
+    let visibility = if dpad.enabled && *touched {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if shown.is_empty() && visibility != Visibility::Hidden {
+        spawn_dpad(&mut commands);
+    }
+    for mut shown in &mut shown {
+        shown.set_if_neq(visibility);
+    }
+}
+
+fn dpad_input(
+    mut buttons: Query<(&DpadButton, &Interaction, &mut BackgroundColor), Changed<Interaction>>,
+    mut heads: Query<&mut SnakeHead>,
+) {
+    for (button, interaction, mut color) in &mut buttons {
+        if *interaction == Interaction::Pressed {
+            color.0 = DPAD_PRESSED_COLOR;
+            steer(&mut heads, button.0);
+        } else {
+            color.0 = DPAD_COLOR;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Arcade-style name entry: a row of letter wheels, for wherever the game asks
+//! for a name.
+//!
+//! Up and Down spin the wheel under the cursor through [`WHEEL`], Left and
+//! Right move between wheels, Backspace clears one and Enter confirms; typing
+//! a letter sets it straight away. The gamepad's d-pad does the same, with
+//! South to confirm and East to cancel, as Esc does. The wheels start on one
+//! of the [`DEFAULT_NAMES`], cleared by the first key typed, and a name
+//! confirmed blank or with anything on the [`BLOCKED`] list comes back as that
+//! default instead.
+//!
+//! [`spawn_name_entry`] opens one, pausing the game behind it, and a
+//! [`NameEntered`] event tells whoever opened it how it went.
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use rand::seq::IndexedRandom;
+
+/// Characters each wheel goes through, in order.
+pub const WHEEL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-_ ";
+pub const DEFAULT_NAMES: [&str; 6] = ["ADDER", "COBRA", "MAMBA", "VIPER", "KRAIT", "TAIPAN"];
+/// Words a name may not contain, whatever else is around them.
+pub const BLOCKED: [&str; 9] = [
+    "FUCK", "SHIT", "CUNT", "COCK", "DICK", "PISS", "TWAT", "NIGG", "FAG",
+];
+
+/// Whether `name` is free of [`BLOCKED`] words, ignoring case and anything
+/// between their letters.
+pub fn is_clean(name: &str) -> bool {
+    let letters: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    !BLOCKED.iter().any(|word| letters.contains(word))
+}
+
+/// A name entry on screen.
+#[derive(Component)]
+pub struct NameEntry {
+    prompt: String,
+    wheels: Vec<char>,
+    cursor: usize,
+    default: String,
+    /// Whether anything has been typed, so the default is gone.
+    typed: bool,
+    /// Whether the game was paused already, and so stays paused after.
+    was_paused: bool,
+}
+
+impl NameEntry {
+    /// The name as it stands, without trailing blanks.
+    pub fn name(&self) -> String {
+        self.wheels.iter().collect::<String>().trim().to_string()
+    }
+
+    fn spin(&mut self, by: isize) {
+        let wheel: Vec<char> = WHEEL.chars().collect();
+        let at = wheel
+            .iter()
+            .position(|&c| c == self.wheels[self.cursor])
+            .unwrap_or(0);
+        let next = (at as isize + by).rem_euclid(wheel.len() as isize) as usize;
+        self.wheels[self.cursor] = wheel[next];
+    }
+
+    fn type_over(&mut self) {
+        if !std::mem::replace(&mut self.typed, true) {
+            self.wheels.fill(' ');
+        }
+    }
+
+    fn set(&mut self, c: char) {
+        let c = c.to_ascii_uppercase();
+        if WHEEL.contains(c) {
+            self.type_over();
+            self.wheels[self.cursor] = c;
+            self.cursor = (self.cursor + 1).min(self.wheels.len() - 1);
+        }
+    }
+
+    /// The name to hand over: the one entered if it will do, otherwise the
+    /// default.
+    fn confirmed(&self) -> String {
+        let name = self.name();
+        if name.is_empty() || !is_clean(&name) {
+            self.default.clone()
+        } else {
+            name
+        }
+    }
+
+    fn text(&self) -> String {
+        let wheels: String = self
+            .wheels
+            .iter()
+            .enumerate()
+            .map(|(index, &c)| {
+                let c = if c == ' ' { '_' } else { c };
+                if index == self.cursor {
+                    format!("[{c}]")
+                } else {
+                    format!(" {c} ")
+                }
+            })
+            .collect();
+        format!(
+            "{}\n\n{wheels}\n\nUp/Down: letter  Left/Right: move  Enter: done",
+            self.prompt
+        )
+    }
+}
+
+/// Sent when a name entry closes: with the name when confirmed, or `None`
+/// when cancelled.
+#[derive(Event)]
+pub struct NameEntered {
+    pub entry: Entity,
+    pub name: Option<String>,
+}
+
+/// Opens a name entry of `length` wheels under `prompt`, and returns it so the
+/// [`NameEntered`] event for it can be told apart.
+pub fn spawn_name_entry(
+    commands: &mut Commands,
+    time: &mut Time<Virtual>,
+    prompt: &str,
+    length: usize,
+) -> Entity {
+    let default = DEFAULT_NAMES
+        .choose(&mut rand::rng())
+        .copied()
+        .unwrap_or(DEFAULT_NAMES[0]);
+    let mut wheels: Vec<char> = default.chars().take(length).collect();
+    wheels.resize(length, ' ');
+    let entry = NameEntry {
+        prompt: prompt.to_string(),
+        wheels,
+        cursor: 0,
+        default: default.chars().take(length).collect(),
+        typed: false,
+        was_paused: time.is_paused(),
+    };
+    time.pause();
+    let text = entry.text();
+    commands
+        .spawn((
+            entry,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        ))
+        .with_child((
+            Text::new(text),
+            TextFont {
+                font_size: 20.0,
+                ..Default::default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+        ))
+        .id()
+}
+
+/// Runs any name entries opened with [`spawn_name_entry`].
+pub struct NameEntryPlugin;
+
+impl Plugin for NameEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NameEntered>()
+            .add_systems(Update, (edit_name, show_name).chain());
+    }
+}
+
+/// Whether a name entry is open, so other keys can be left alone.
+pub(crate) fn entering_name(entries: Query<(), With<NameEntry>>) -> bool {
+    !entries.is_empty()
+}
+
+pub(crate) fn edit_name(
+    mut commands: Commands,
+    mut key_events: EventReader<KeyboardInput>,
+    gamepads: Query<&Gamepad>,
+    mut time: ResMut<Time<Virtual>>,
+    mut entries: Query<(Entity, &mut NameEntry)>,
+    mut entered: EventWriter<NameEntered>,
+) {
+    let Some((entity, mut entry)) = entries.iter_mut().next() else {
+        key_events.clear();
+        return;
+    };
+    let pressed = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    let mut done = None;
+    if pressed(GamepadButton::DPadUp) {
+        entry.spin(-1);
+    }
+    if pressed(GamepadButton::DPadDown) {
+        entry.spin(1);
+    }
+    if pressed(GamepadButton::DPadLeft) {
+        entry.cursor = entry.cursor.saturating_sub(1);
+    }
+    if pressed(GamepadButton::DPadRight) {
+        entry.cursor = (entry.cursor + 1).min(entry.wheels.len() - 1);
+    }
+    if pressed(GamepadButton::South) {
+        done = Some(Some(entry.confirmed()));
+    } else if pressed(GamepadButton::East) {
+        done = Some(None);
+    }
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed || done.is_some() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::ArrowUp => entry.spin(-1),
+            Key::ArrowDown => entry.spin(1),
+            Key::ArrowLeft => entry.cursor = entry.cursor.saturating_sub(1),
+            Key::ArrowRight => entry.cursor = (entry.cursor + 1).min(entry.wheels.len() - 1),
+            Key::Backspace => {
+                entry.type_over();
+                let cursor = entry.cursor;
+                if entry.wheels[cursor] == ' ' {
+                    entry.cursor = cursor.saturating_sub(1);
+                }
+                let cursor = entry.cursor;
+                entry.wheels[cursor] = ' ';
+            }
+            Key::Enter => done = Some(Some(entry.confirmed())),
+            Key::Escape => done = Some(None),
+            Key::Space => entry.set(' '),
+            Key::Character(text) => {
+                for c in text.chars() {
+                    entry.set(c);
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(name) = done else {
+        return;
+    };
+    if !entry.was_paused {
+        time.unpause();
+    }
+    commands.entity(entity).despawn_recursive();
+    entered.send(NameEntered {
+        entry: entity,
+        name,
+    });
+}
+
+fn show_name(
+    entries: Query<(&NameEntry, &Children), Changed<NameEntry>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (entry, children) in &entries {
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = entry.text();
+            }
+        }
+    }
+}
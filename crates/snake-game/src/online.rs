@@ -0,0 +1,955 @@
+//! Versus play against other players over the network.
+//!
+//! `--host <port>` runs a [`snake_net::server`] room on a background thread
+//! and joins it, announcing it on the local network; `--join <address>` joins
+//! someone else's, such as a `snake-server`, and `--lan` lists the games
+//! announced nearby to pick one with the number keys. Adding `--spectate`
+//! watches the room instead of playing, with a scoreboard and a free camera:
+//! arrow keys pan, `=` and `-` zoom and Home resets the view. `--quick-match`
+//! joins any open room on the server given with `--join`, or on the public
+//! lobby server named by `SNAKE_PUBLIC_SERVER`.
+//!
+//! In the lobby, R toggles ready, C cycles the snake color and N edits the
+//! name. The owner also cycles the mode with M and the arena size with A, and
+//! starts with Enter once everyone else is ready. `--room <name>` picks the
+//! room to join (default `main`) and `--name <name>` sets the name shown in
+//! the lobby. The server owns the board, so this side only sends steering,
+//! mirrors the board it is sent and draws it. Space sheds the snake's last
+//! segment as a wall in the way of the others, see
+//! [`snake_core::versus::Match::shed`].
+//!
+//! `--peer-host <port>` and `--peer-join <address>` instead start a direct
+//! two-player match using [`snake_net::rollback`], with `--input-delay
+//! <ticks>` (default 1) trading responsiveness for fewer rollbacks. These
+//! matches have no shedding.
+
+use std::{
+    net::{TcpListener, ToSocketAddrs},
+    thread,
+};
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_core::{
+    controller::Difficulty,
+    versus::{VersusMode, MAX_PLAYERS},
+    Arena, Direction, Position,
+};
+use snake_net::{
+    client::Client,
+    discovery::{announce, broadcast_address, Announcement, Browser, DISCOVERY_PORT},
+    protocol::{
+        Board, ClientMessage, LobbySeat, ProtocolError, RoomSettings, ServerMessage, COLORS,
+        QUICK_MATCH,
+    },
+    rollback::{Peer, PeerMessage, RollbackSession},
+    server::{serve, ServerConfig},
+};
+
+use crate::{Size, ThemeColor};
+
+const DEFAULT_INPUT_DELAY: u32 = 1;
+/// Environment variable naming the public lobby server for quick matches.
+const PUBLIC_SERVER_VAR: &str = "SNAKE_PUBLIC_SERVER";
+/// Arena sizes the owner cycles through in the lobby.
+const ARENA_SIDES: [u32; 3] = [10, 16, 24];
+
+const SNAKE_COLORS: [Color; 4] = [
+    Color::linear_rgb(0.2, 0.8, 0.2),
+    Color::linear_rgb(0.2, 0.4, 1.0),
+    Color::linear_rgb(1.0, 0.6, 0.1),
+    Color::linear_rgb(0.9, 0.2, 0.2),
+];
+const SNAKE_COLOR_NAMES: [&str; 4] = ["green", "blue", "orange", "red"];
+
+/// Free camera speed while spectating, in pixels per second at normal zoom.
+const PAN_SPEED: f32 = 300.0;
+const ZOOM_STEP: f32 = 1.25;
+
+#[derive(Clone)]
+pub enum OnlineTarget {
+    Host(u16),
+    Join(String),
+    Lan,
+    PeerHost { port: u16, input_delay: u32 },
+    PeerJoin { address: String, input_delay: u32 },
+}
+
+/// Replaces local gameplay with a connection to a versus room. Add it with
+/// [`crate::BoardPlugin`] instead of [`crate::SnakeGamePlugin`].
+pub struct OnlinePlugin {
+    pub target: OnlineTarget,
+    pub name: String,
+    pub room: String,
+    /// Watch the room instead of taking a seat. Only for server targets.
+    pub spectate: bool,
+}
+
+impl OnlinePlugin {
+    /// Reads the flags described in the [module docs](self), or returns
+    /// `None` for a local game.
+    pub fn from_args() -> Option<Self> {
+        let arg = |flag: &str| std::env::args().skip_while(|arg| arg != flag).nth(1);
+        let port = |flag: &str| {
+            let port = arg(flag)?;
+            port.parse()
+                .inspect_err(|_| error!("{flag} expects a port number, got {port:?}"))
+                .ok()
+        };
+        let input_delay = arg("--input-delay")
+            .and_then(|delay| delay.parse().ok())
+            .unwrap_or(DEFAULT_INPUT_DELAY);
+        let quick_match = std::env::args().any(|arg| arg == "--quick-match");
+        let public_server = || {
+            let server = std::env::var(PUBLIC_SERVER_VAR).ok();
+            if server.is_none() {
+                error!("--quick-match needs --join <address> or {PUBLIC_SERVER_VAR}");
+            }
+            server
+        };
+        let target = if let Some(port) = port("--host") {
+            OnlineTarget::Host(port)
+        } else if let Some(address) = arg("--join") {
+            OnlineTarget::Join(address)
+        } else if quick_match {
+            OnlineTarget::Join(public_server()?)
+        } else if std::env::args().any(|arg| arg == "--lan") {
+            OnlineTarget::Lan
+        } else if let Some(port) = port("--peer-host") {
+            OnlineTarget::PeerHost { port, input_delay }
+        } else if let Some(address) = arg("--peer-join") {
+            OnlineTarget::PeerJoin {
+                address,
+                input_delay,
+            }
+        } else {
+            return None;
+        };
+        Some(Self {
+            target,
+            name: arg("--name").unwrap_or_else(|| "player".to_string()),
+            room: if quick_match {
+                QUICK_MATCH.to_string()
+            } else {
+                arg("--room").unwrap_or_else(|| "main".to_string())
+            },
+            spectate: std::env::args().any(|arg| arg == "--spectate"),
+        })
+    }
+}
+
+impl Plugin for OnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OnlineSprites>()
+            .add_systems(Startup, spawn_status)
+            .add_systems(Update, (sync_board, show_status).chain());
+        let (peer, hosting, input_delay) = match &self.target {
+            OnlineTarget::PeerHost { port, input_delay } => (Peer::host(*port), true, *input_delay),
+            OnlineTarget::PeerJoin {
+                address,
+                input_delay,
+            } => (Peer::join(address.as_str()), false, *input_delay),
+            OnlineTarget::Host(_) | OnlineTarget::Join(_) | OnlineTarget::Lan => {
+                self.build_client(app);
+                return;
+            }
+        };
+        let status = match peer {
+            Ok(peer) => {
+                app.insert_resource(PeerGame {
+                    peer,
+                    hosting,
+                    seed: rand::random(),
+                    input_delay,
+                    session: None,
+                    timer: Timer::new(snake_core::MOVEMENT_INTERVAL, TimerMode::Repeating),
+                    turn: None,
+                })
+                .add_systems(
+                    Update,
+                    (peer_input, peer_network, peer_tick)
+                        .chain()
+                        .before(sync_board),
+                );
+                "waiting for the other player...".to_string()
+            }
+            Err(err) => format!("could not open connection: {err}"),
+        };
+        app.insert_resource(OnlineSession {
+            status,
+            ..Default::default()
+        });
+    }
+}
+
+impl OnlinePlugin {
+    fn build_client(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                browse_lan.run_if(resource_exists::<LanBrowser>),
+                receive_messages,
+                edit_name,
+                send_input,
+            )
+                .chain()
+                .before(sync_board),
+        );
+        if self.spectate {
+            app.insert_resource(Spectating)
+                .add_systems(Startup, spawn_scoreboard)
+                .add_systems(
+                    Update,
+                    (free_camera, show_scoreboard).run_if(resource_exists::<Spectating>),
+                );
+        }
+        if let OnlineTarget::Lan = self.target {
+            let status = match Browser::bind(DISCOVERY_PORT) {
+                Ok(browser) => {
+                    app.insert_resource(LanBrowser {
+                        browser,
+                        name: self.name.clone(),
+                        room: self.room.clone(),
+                        spectate: self.spectate,
+                    });
+                    "looking for LAN games...".to_string()
+                }
+                Err(err) => format!("cannot listen for LAN games: {err}"),
+            };
+            app.insert_resource(OnlineSession {
+                status,
+                ..Default::default()
+            });
+            return;
+        }
+        let session = match connect(&self.target, &self.name, &self.room, self.spectate) {
+            Ok(client) => OnlineSession {
+                client: Some(client),
+                status: "connecting...".to_string(),
+                ..Default::default()
+            },
+            Err(err) => OnlineSession {
+                status: format!("could not connect: {err}"),
+                ..Default::default()
+            },
+        };
+        app.insert_resource(session);
+    }
+}
+
+fn connect(
+    target: &OnlineTarget,
+    name: &str,
+    room: &str,
+    spectate: bool,
+) -> Result<Client, String> {
+    let address = match target {
+        OnlineTarget::Host(port) => {
+            let listener = TcpListener::bind(("0.0.0.0", *port))
+                .map_err(|err| format!("cannot host on port {port}: {err}"))?;
+            let address = format!(
+                "127.0.0.1:{}",
+                listener.local_addr().map_err(|err| err.to_string())?.port()
+            );
+            thread::spawn(move || {
+                if let Err(err) = serve(listener, ServerConfig::default()) {
+                    error!("server stopped: {err}");
+                }
+            });
+            let announcement = Announcement {
+                name: format!("{name}'s game"),
+                port: *port,
+            };
+            if let Err(err) = announce(announcement, broadcast_address()) {
+                warn!("cannot announce the game on the local network: {err}");
+            }
+            info!("hosting on port {port}");
+            address
+        }
+        OnlineTarget::Join(address) => address.clone(),
+        OnlineTarget::Lan => unreachable!("LAN games are joined from the browser"),
+        OnlineTarget::PeerHost { .. } | OnlineTarget::PeerJoin { .. } => {
+            unreachable!("peer matches do not use a server")
+        }
+    };
+    join(address.as_str(), name, room, spectate).map_err(|err| err.to_string())
+}
+
+fn join(
+    address: impl ToSocketAddrs,
+    name: &str,
+    room: &str,
+    spectate: bool,
+) -> Result<Client, ProtocolError> {
+    if spectate {
+        Client::spectate(address, name, room)
+    } else {
+        Client::connect(address, name, room)
+    }
+}
+
+#[derive(Default, Resource)]
+struct OnlineSession {
+    client: Option<Client>,
+    seat: Option<u8>,
+    players: Vec<Option<LobbySeat>>,
+    owner: u8,
+    settings: RoomSettings,
+    /// The name being typed in the lobby, while editing it.
+    name_entry: Option<String>,
+    /// Accepted as a spectator rather than seated.
+    watching: bool,
+    spectators: u8,
+    board: Option<Board>,
+    /// Player names and colors in snake order for the current match.
+    names: Vec<String>,
+    colors: Vec<u8>,
+    /// Our snake in the current match, if we play in it.
+    you: Option<u8>,
+    status: String,
+}
+
+impl OnlineSession {
+    fn me(&self) -> Option<&LobbySeat> {
+        self.players.get(usize::from(self.seat?))?.as_ref()
+    }
+
+    fn in_lobby(&self) -> bool {
+        self.board
+            .as_ref()
+            .is_none_or(|board| board.snakes.iter().filter(|snake| snake.alive).count() <= 1)
+    }
+
+    /// Palette index of snake `snake` in the current match.
+    fn color_of(&self, snake: usize) -> usize {
+        let color = self
+            .colors
+            .get(snake)
+            .map_or(snake, |&color| usize::from(color));
+        color % SNAKE_COLORS.len()
+    }
+
+    fn send(&mut self, message: ClientMessage) {
+        let Some(client) = &self.client else {
+            return;
+        };
+        if let Err(err) = client.send(&message) {
+            self.status = format!("connection lost: {err}");
+            self.client = None;
+        }
+    }
+}
+
+fn receive_messages(mut session: ResMut<OnlineSession>, mut arena: ResMut<Arena>) {
+    let Some(client) = &session.client else {
+        return;
+    };
+    let messages = match client.poll() {
+        Ok(messages) => messages,
+        Err(err) => {
+            session.status = err.to_string();
+            session.client = None;
+            return;
+        }
+    };
+    for message in messages {
+        match message {
+            ServerMessage::Welcome { seat } => session.seat = Some(seat),
+            ServerMessage::Watching => session.watching = true,
+            ServerMessage::Rejected { reason } => {
+                session.status = format!("rejected: {reason}");
+                session.client = None;
+            }
+            ServerMessage::Lobby {
+                players,
+                owner,
+                spectators,
+                settings,
+            } => {
+                session.players = players;
+                session.owner = owner;
+                session.spectators = spectators;
+                session.settings = settings;
+            }
+            ServerMessage::MatchStarted {
+                board,
+                you,
+                names,
+                colors,
+            } => {
+                arena.set_if_neq(board.arena);
+                session.board = Some(board);
+                session.you = you;
+                session.names = names;
+                session.colors = colors;
+                session.status.clear();
+            }
+            ServerMessage::Update(diff) => {
+                if let Some(board) = &mut session.board {
+                    board.apply(&diff);
+                }
+            }
+            ServerMessage::MatchOver { winner } => {
+                let name = |snake: u8| format!("snake {}", snake + 1);
+                session.status = match winner {
+                    Some(winner) if Some(winner) == session.you => "you win!".to_string(),
+                    Some(winner) => format!("{} wins", name(winner)),
+                    None => "draw".to_string(),
+                };
+            }
+        }
+    }
+}
+
+/// The "Join LAN game" list, shown until a game is picked.
+#[derive(Resource)]
+struct LanBrowser {
+    browser: Browser,
+    name: String,
+    room: String,
+    spectate: bool,
+}
+
+const GAME_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+fn browse_lan(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut lan: ResMut<LanBrowser>,
+    mut session: ResMut<OnlineSession>,
+) {
+    let games = match lan.browser.poll() {
+        Ok(games) => games.to_vec(),
+        Err(err) => {
+            session.status = format!("cannot listen for LAN games: {err}");
+            commands.remove_resource::<LanBrowser>();
+            return;
+        }
+    };
+    let picked = GAME_KEYS
+        .iter()
+        .zip(&games)
+        .find(|(&key, _)| keyboard_input.just_pressed(key));
+    if let Some((_, game)) = picked {
+        match join(game.address, &lan.name, &lan.room, lan.spectate) {
+            Ok(client) => {
+                session.client = Some(client);
+                session.status = "connecting...".to_string();
+                commands.remove_resource::<LanBrowser>();
+                return;
+            }
+            Err(err) => warn!("could not connect to {}: {err}", game.address),
+        }
+    }
+    let mut status = "Join LAN game:".to_string();
+    for (index, game) in games.iter().take(GAME_KEYS.len()).enumerate() {
+        status.push_str(&format!(
+            "\n{}. {} ({})",
+            index + 1,
+            game.name,
+            game.address
+        ));
+    }
+    if games.is_empty() {
+        status.push_str("\nlooking...");
+    }
+    if session.status != status {
+        session.status = status;
+    }
+}
+
+/// Types into the name field opened with N; Enter sends it, Escape drops it.
+fn edit_name(
+    mut key_events: EventReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut session: ResMut<OnlineSession>,
+) {
+    if session.name_entry.is_none() {
+        key_events.clear();
+        return;
+    }
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some(entry) = &mut session.name_entry else {
+            return;
+        };
+        match &event.logical_key {
+            Key::Enter => {
+                let name = std::mem::take(entry);
+                session.name_entry = None;
+                // Confirming the name must not also start the match.
+                keyboard_input.clear_just_pressed(KeyCode::Enter);
+                if let Some(color) = session.me().map(|me| me.color) {
+                    session.send(ClientMessage::SetProfile { name, color });
+                }
+            }
+            Key::Backspace => {
+                entry.pop();
+            }
+            Key::Escape => session.name_entry = None,
+            Key::Character(text) => entry.push_str(text),
+            Key::Space => entry.push(' '),
+            _ => {}
+        }
+    }
+}
+
+fn send_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    spectating: Option<Res<Spectating>>,
+    mut session: ResMut<OnlineSession>,
+) {
+    if spectating.is_some() || session.name_entry.is_some() {
+        return;
+    }
+    if session.in_lobby() {
+        lobby_input(&keyboard_input, &mut session);
+    }
+    let steering = [
+        (KeyCode::ArrowLeft, Direction::Left),
+        (KeyCode::ArrowRight, Direction::Right),
+        (KeyCode::ArrowUp, Direction::Up),
+        (KeyCode::ArrowDown, Direction::Down),
+    ];
+    for (key, direction) in steering {
+        if keyboard_input.just_pressed(key) {
+            session.send(ClientMessage::Steer(direction));
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        session.send(ClientMessage::Shed);
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        session.send(ClientMessage::Start);
+    }
+}
+
+fn lobby_input(keyboard_input: &ButtonInput<KeyCode>, session: &mut OnlineSession) {
+    let Some(me) = session.me().cloned() else {
+        return;
+    };
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        session.send(ClientMessage::Ready(!me.ready));
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        session.send(ClientMessage::SetProfile {
+            name: me.name.clone(),
+            color: (me.color + 1) % COLORS,
+        });
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        // The N itself is not typed: edit_name already skipped this frame.
+        session.name_entry = Some(String::new());
+    }
+    if session.seat != Some(session.owner) {
+        return;
+    }
+    let mut settings = session.settings;
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        settings.mode = match settings.mode {
+            VersusMode::Survival => VersusMode::Race,
+            VersusMode::Race => VersusMode::Survival,
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyA) {
+        let next = ARENA_SIDES
+            .iter()
+            .position(|&side| side == settings.arena.width)
+            .map_or(0, |index| (index + 1) % ARENA_SIDES.len());
+        settings.arena = Arena {
+            width: ARENA_SIDES[next],
+            height: ARENA_SIDES[next],
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        settings.bots = (settings.bots + 1) % MAX_PLAYERS as u8;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyD) {
+        settings.difficulty = match settings.difficulty {
+            Difficulty::Beginner => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Expert,
+            Difficulty::Expert => Difficulty::Beginner,
+        };
+    }
+    if settings != session.settings {
+        session.send(ClientMessage::Configure(settings));
+    }
+}
+
+/// A direct match against one other player.
+#[derive(Resource)]
+struct PeerGame {
+    peer: Peer,
+    hosting: bool,
+    /// Match seed, picked by the host.
+    seed: u64,
+    input_delay: u32,
+    /// Starts once both peers agreed on a seed.
+    session: Option<RollbackSession>,
+    timer: Timer,
+    /// Latest turn pressed since the last tick.
+    turn: Option<Direction>,
+}
+
+fn peer_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut game: ResMut<PeerGame>) {
+    let steering = [
+        (KeyCode::ArrowLeft, Direction::Left),
+        (KeyCode::ArrowRight, Direction::Right),
+        (KeyCode::ArrowUp, Direction::Up),
+        (KeyCode::ArrowDown, Direction::Down),
+    ];
+    for (key, direction) in steering {
+        if keyboard_input.just_pressed(key) {
+            game.turn = Some(direction);
+        }
+    }
+}
+
+/// Runs the join handshake, then feeds the other player's inputs into the
+/// session. The joiner keeps sending [`PeerMessage::Join`] until the host's
+/// seed arrives, and the host answers every join, in case a reply was lost.
+fn peer_network(mut game: ResMut<PeerGame>, mut session: ResMut<OnlineSession>) {
+    let PeerGame {
+        peer,
+        hosting,
+        seed,
+        input_delay,
+        session: rollback,
+        ..
+    } = &mut *game;
+    let messages = match peer.poll() {
+        Ok(messages) => messages,
+        Err(err) => {
+            session.status = format!("connection lost: {err}");
+            return;
+        }
+    };
+    for message in messages {
+        match message {
+            PeerMessage::Join if *hosting => {
+                let _ = peer.send(&PeerMessage::Start { seed: *seed });
+                if rollback.is_none() {
+                    *rollback = Some(RollbackSession::new(
+                        *seed,
+                        Arena::default(),
+                        0,
+                        *input_delay,
+                    ));
+                    session.status.clear();
+                }
+            }
+            PeerMessage::Start { seed } if !*hosting && rollback.is_none() => {
+                *rollback = Some(RollbackSession::new(
+                    seed,
+                    Arena::default(),
+                    1,
+                    *input_delay,
+                ));
+                session.status.clear();
+            }
+            PeerMessage::Inputs(packet) => {
+                if let Some(rollback) = rollback {
+                    rollback.receive(&packet);
+                }
+            }
+            _ => {}
+        }
+    }
+    if !*hosting && rollback.is_none() {
+        let _ = peer.send(&PeerMessage::Join);
+    }
+}
+
+fn peer_tick(
+    time: Res<Time>,
+    mut game: ResMut<PeerGame>,
+    mut session: ResMut<OnlineSession>,
+    mut arena: ResMut<Arena>,
+) {
+    game.timer.tick(time.delta());
+    let ticks = game.timer.times_finished_this_tick();
+    let PeerGame {
+        peer,
+        session: rollback,
+        turn,
+        ..
+    } = &mut *game;
+    let Some(rollback) = rollback else {
+        return;
+    };
+    for _ in 0..ticks {
+        if rollback.game().finished() || !rollback.can_advance() {
+            break;
+        }
+        let packet = rollback.advance(turn.take());
+        let _ = peer.send(&PeerMessage::Inputs(packet));
+    }
+    let board = Board::from(rollback.game());
+    arena.set_if_neq(board.arena);
+    session.you = Some(rollback.local() as u8);
+    if rollback.game().finished() && rollback.confirmed() == rollback.game().tick {
+        session.status = match rollback.game().winner() {
+            Some(winner) if winner == rollback.local() => "you win!".to_string(),
+            Some(_) => "you lose".to_string(),
+            None => "draw".to_string(),
+        };
+    }
+    session.board = Some(board);
+}
+
+/// Sprites for each snake's segments and for the food, matched to the
+/// mirrored board every frame.
+#[derive(Default, Resource)]
+struct OnlineSprites {
+    snakes: Vec<Vec<Entity>>,
+    food: Vec<Entity>,
+    shed: Vec<Entity>,
+}
+
+fn sync_board(
+    mut commands: Commands,
+    session: Res<OnlineSession>,
+    mut sprites: ResMut<OnlineSprites>,
+    mut positions: Query<&mut Position>,
+) {
+    if !session.is_changed() {
+        return;
+    }
+    let Some(board) = &session.board else {
+        return;
+    };
+    let OnlineSprites { snakes, food, shed } = &mut *sprites;
+    snakes.resize_with(board.snakes.len(), Vec::new);
+    for (index, (snake, entities)) in board.snakes.iter().zip(snakes).enumerate() {
+        let color = SNAKE_COLORS[session.color_of(index)];
+        sync_cells(
+            &mut commands,
+            entities,
+            &snake.body,
+            &mut positions,
+            |segment| {
+                let size = if segment == 0 { 0.8 } else { 0.65 };
+                (
+                    Sprite {
+                        color: color.with_alpha(if segment == 0 { 1.0 } else { 0.7 }),
+                        ..Default::default()
+                    },
+                    Size::square(size),
+                )
+            },
+        );
+    }
+    sync_cells(&mut commands, food, &board.food, &mut positions, |_| {
+        (Sprite::default(), ThemeColor::Food, Size::square(0.8))
+    });
+    sync_cells(&mut commands, shed, &board.shed, &mut positions, |_| {
+        (Sprite::default(), ThemeColor::Obstacle, Size::square(1.0))
+    });
+}
+
+/// Moves `entities` onto `cells`, spawning or despawning to match the count.
+fn sync_cells<B: Bundle>(
+    commands: &mut Commands,
+    entities: &mut Vec<Entity>,
+    cells: &[Position],
+    positions: &mut Query<&mut Position>,
+    bundle: impl Fn(usize) -> B,
+) {
+    for (&entity, &cell) in entities.iter().zip(cells) {
+        if let Ok(mut position) = positions.get_mut(entity) {
+            *position = cell;
+        }
+    }
+    while entities.len() > cells.len() {
+        if let Some(entity) = entities.pop() {
+            commands.entity(entity).despawn();
+        }
+    }
+    while entities.len() < cells.len() {
+        let index = entities.len();
+        entities.push(commands.spawn((bundle(index), cells[index])).id());
+    }
+}
+
+#[derive(Component)]
+struct StatusText;
+
+fn spawn_status(mut commands: Commands) {
+    commands.spawn((
+        StatusText,
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.0),
+            left: Val::Px(4.0),
+            ..Default::default()
+        },
+    ));
+}
+
+fn show_status(session: Res<OnlineSession>, mut texts: Query<&mut Text, With<StatusText>>) {
+    if !session.is_changed() {
+        return;
+    }
+    let mut lines = Vec::new();
+    if !session.status.is_empty() {
+        lines.push(session.status.clone());
+    }
+    if session.watching {
+        lines.push("spectating".to_string());
+    }
+    if session.in_lobby() || !session.status.is_empty() {
+        let RoomSettings {
+            mode,
+            arena,
+            bots,
+            difficulty,
+        } = session.settings;
+        if !session.players.is_empty() {
+            lines.push(format!("{mode:?}, {}x{} arena", arena.width, arena.height));
+            if bots > 0 {
+                lines.push(format!("{bots} {difficulty:?} bots fill free seats"));
+            }
+        }
+        for (seat, player) in session.players.iter().enumerate() {
+            if let Some(player) = player {
+                let color = SNAKE_COLOR_NAMES[usize::from(player.color) % SNAKE_COLOR_NAMES.len()];
+                let mut line = format!("{}. {} ({color})", seat + 1, player.name);
+                if seat as u8 == session.owner {
+                    line.push_str(" (owner)");
+                } else if player.ready {
+                    line.push_str(" ready");
+                }
+                if Some(seat as u8) == session.seat {
+                    line.push_str(" <- you");
+                }
+                lines.push(line);
+            }
+        }
+        if session.spectators > 0 {
+            lines.push(format!("{} watching", session.spectators));
+        }
+        if let Some(entry) = &session.name_entry {
+            lines.push(format!("name: {entry}_"));
+        } else if session.seat.is_some() {
+            lines.push("R ready, C color, N name".to_string());
+        }
+        if session.seat == Some(session.owner) {
+            lines.push("M mode, A arena, B bots, D difficulty, Enter to start".to_string());
+        }
+    }
+    for mut text in &mut texts {
+        text.0 = lines.join("\n");
+    }
+}
+
+/// Watching a room: input moves the camera instead of steering.
+#[derive(Resource)]
+struct Spectating;
+
+fn free_camera(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let panning = [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+    ];
+    let pan: Vec2 = panning
+        .iter()
+        .filter(|(key, _)| keyboard_input.pressed(*key))
+        .map(|(_, direction)| *direction)
+        .sum();
+    for (mut transform, mut projection) in &mut cameras {
+        if keyboard_input.just_pressed(KeyCode::Home) {
+            transform.translation = Vec3::ZERO;
+            projection.scale = 1.0;
+            continue;
+        }
+        if keyboard_input.just_pressed(KeyCode::Equal) {
+            projection.scale /= ZOOM_STEP;
+        }
+        if keyboard_input.just_pressed(KeyCode::Minus) {
+            projection.scale *= ZOOM_STEP;
+        }
+        let step = pan * PAN_SPEED * projection.scale * time.delta_secs();
+        transform.translation += step.extend(0.0);
+    }
+}
+
+#[derive(Component)]
+struct ScoreboardText;
+
+fn spawn_scoreboard(mut commands: Commands) {
+    commands.spawn((
+        ScoreboardText,
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        TextLayout::new_with_justify(JustifyText::Right),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.0),
+            right: Val::Px(4.0),
+            ..Default::default()
+        },
+    ));
+}
+
+/// Lists the snakes of the watched match by score, best first.
+fn show_scoreboard(session: Res<OnlineSession>, mut texts: Query<&mut Text, With<ScoreboardText>>) {
+    if !session.is_changed() {
+        return;
+    }
+    let mut lines = Vec::new();
+    if let Some(board) = &session.board {
+        let mut snakes: Vec<usize> = (0..board.snakes.len()).collect();
+        snakes.sort_by_key(|&snake| std::cmp::Reverse(board.snakes[snake].score));
+        for snake in snakes {
+            let name = session
+                .names
+                .get(snake)
+                .cloned()
+                .unwrap_or_else(|| format!("snake {}", snake + 1));
+            let state = &board.snakes[snake];
+            let mut line = format!(
+                "{name} ({}): {}",
+                SNAKE_COLOR_NAMES[session.color_of(snake)],
+                state.score
+            );
+            if !state.alive {
+                line.push_str(" - out");
+            }
+            lines.push(line);
+        }
+    }
+    for mut text in &mut texts {
+        text.0 = lines.join("\n");
+    }
+}
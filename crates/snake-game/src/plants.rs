@@ -0,0 +1,208 @@
+//! Plants: sprouts that turn up now and then in casual runs and grow into
+//! walls if left alone.
+//!
+//! A plant goes through the [`Stage`]s in [`GROW_FOR`], drawn bigger at each.
+//! Until it is grown the snake can eat it, for nothing but the space it frees;
+//! once grown it is an [`Obstacle`] like any other. A plant with the snake's
+//! body over it waits for the snake to move on before it finishes growing. Up
+//! to [`MAX_PLANTS`] sprouts are planted, one every [`PLANT_INTERVAL`].
+//! Growing follows the [`WorldClock`], and plants are placed with randomness
+//! of their own; a run with one in it is no longer kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use snake_core::{Arena, GameMode, Position};
+
+use crate::{
+    game_over, power_ups::Occupancy, replay::ReplayRecorder, snake_growth, timer_finished,
+    world_clock::WorldClock, Food, GameOverEvent, GameSet, MovementTick, Obstacle, Size, SnakeHead,
+    SnakeSegment,
+};
+
+pub const PLANT_INTERVAL: Duration = Duration::from_secs(10);
+pub const GROW_FOR: Duration = Duration::from_secs(15);
+/// Plants still growing at once.
+pub const MAX_PLANTS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Stage {
+    Sprout,
+    Budding,
+    /// A wall.
+    Grown,
+}
+
+impl Stage {
+    /// The stage a plant is at once `grown` of [`GROW_FOR`] has passed.
+    fn after(grown: f32) -> Self {
+        if grown >= 1.0 {
+            Self::Grown
+        } else if grown >= 0.5 {
+            Self::Budding
+        } else {
+            Self::Sprout
+        }
+    }
+
+    fn sprite(self) -> (Color, f32) {
+        match self {
+            Self::Sprout => (Color::linear_rgb(0.4, 0.9, 0.3), 0.35),
+            Self::Budding => (Color::linear_rgb(0.25, 0.7, 0.2), 0.65),
+            Self::Grown => (Color::linear_rgb(0.1, 0.4, 0.1), 1.0),
+        }
+    }
+}
+
+/// A plant and how far it has grown.
+#[derive(Component)]
+pub struct Plant {
+    pub stage: Stage,
+    pub growth: Timer,
+}
+
+/// Plants a sprout at `position`.
+pub fn spawn_plant(commands: &mut Commands, position: Position) -> Entity {
+    spawn_grown_plant(commands, position, Stage::Sprout, Duration::ZERO)
+}
+
+/// Plants a plant at `position` that is already at `stage`, `grown` into
+/// [`GROW_FOR`]. A grown one is a wall straight away.
+pub fn spawn_grown_plant(
+    commands: &mut Commands,
+    position: Position,
+    stage: Stage,
+    grown: Duration,
+) -> Entity {
+    let (color, size) = stage.sprite();
+    let mut growth = Timer::new(GROW_FOR, TimerMode::Once);
+    growth.set_elapsed(grown);
+    let plant = commands
+        .spawn((
+            Plant { stage, growth },
+            Sprite::from_color(color, Vec2::ONE),
+            position,
+            Size::square(size),
+        ))
+        .id();
+    if stage == Stage::Grown {
+        commands.entity(plant).insert(Obstacle);
+    }
+    plant
+}
+
+/// Plants sprouts in casual runs and grows them.
+pub struct PlantsPlugin;
+
+impl Plugin for PlantsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Planter(Timer::new(PLANT_INTERVAL, TimerMode::Repeating)))
+            .add_systems(
+                FixedUpdate,
+                (
+                    (plant, grow).chain().in_set(GameSet::Spawning),
+                    eat_sprouts
+                        .after(snake_growth)
+                        .before(game_over)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    clear_plants
+                        .after(game_over)
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                ),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct Planter(Timer);
+
+fn plant(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    mut planter: ResMut<Planter>,
+    mut recorder: ResMut<ReplayRecorder>,
+    plants: Query<&Plant>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>, With<Plant>)>>,
+) {
+    let growing = plants
+        .iter()
+        .filter(|plant| plant.stage != Stage::Grown)
+        .count();
+    if !planter.0.tick(clock.delta()).just_finished() || !mode.is_casual() || growing >= MAX_PLANTS
+    {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "sprout planted");
+    recorder.tainted = true;
+    spawn_plant(&mut commands, position);
+}
+
+fn grow(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    segments: Query<&Position, (With<SnakeSegment>, Without<Plant>)>,
+    mut plants: Query<(Entity, &mut Plant, &Position, &mut Sprite, &mut Size)>,
+) {
+    for (entity, mut plant, position, mut sprite, mut size) in &mut plants {
+        if plant.stage == Stage::Grown {
+            continue;
+        }
+        let grown = plant.growth.tick(clock.delta()).fraction();
+        let mut stage = Stage::after(grown);
+        if stage == Stage::Grown && segments.iter().any(|segment| segment == position) {
+            stage = Stage::Budding;
+        }
+        if stage == plant.stage {
+            continue;
+        }
+        plant.stage = stage;
+        let (color, scale) = stage.sprite();
+        sprite.color = color;
+        *size = Size::square(scale);
+        if stage == Stage::Grown {
+            debug!(position = ?*position, "plant grown");
+            commands.entity(entity).insert(Obstacle);
+        }
+    }
+}
+
+/// Eats any plant the head reaches before it has grown.
+fn eat_sprouts(
+    mut commands: Commands,
+    heads: Query<&Position, With<SnakeHead>>,
+    plants: Query<(Entity, &Plant, &Position)>,
+) {
+    for head in &heads {
+        for (entity, plant, position) in &plants {
+            if position == head && plant.stage != Stage::Grown {
+                debug!(position = ?*position, "plant eaten");
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn clear_plants(
+    mut commands: Commands,
+    mut planter: ResMut<Planter>,
+    // Grown plants go with the other walls.
+    plants: Query<Entity, (With<Plant>, Without<Obstacle>)>,
+) {
+    planter.0.reset();
+    for plant in &plants {
+        commands.entity(plant).despawn();
+    }
+}
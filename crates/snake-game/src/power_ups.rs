@@ -0,0 +1,529 @@
+//! Power-ups: pickups that turn up now and then in casual runs and give the
+//! snake a timed effect when it eats one. Boards with obstacles on them, like
+//! the campaign's, get bombs whatever the mode.
+//!
+//! One pickup at a time is placed on a free cell every [`PICKUP_INTERVAL`].
+//! Effects last a set time, kept in [`TimedEffects`], and the systems they
+//! change check for them each tick. Pickups are placed with randomness of
+//! their own so the food still comes as the run's seed says, but eating one
+//! changes the game in ways the core simulation does not know of, so the run
+//! is no longer kept as a replay.
+//!
+//! - The magnet pulls food within [`MAGNET_RANGE`] one cell towards the head
+//!   each tick, never onto a cell something else is on.
+//! - Time freeze stops the [`WorldClock`] for [`TIME_FREEZE_FOR`]: no food or
+//!   pickups turn up and nothing on the board changes while the snake moves
+//!   on.
+//! - A bomb blows up every obstacle within [`BOMB_RADIUS`] cells of where it
+//!   was eaten.
+//! - The mystery pickup does one [`Mystery`] thing picked at random: grows
+//!   the snake by three, shrinks it by two, speeds it up or swaps the
+//!   controls around for a while.
+//!
+//! What a pickup did is shown in a [toast](crate::toast).
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use snake_core::{Arena, GameMode, Position};
+
+use crate::{
+    accessibility::Accessibility,
+    game_over, release_segment,
+    replay::ReplayRecorder,
+    snake_growth, spawn_segment, timer_finished,
+    toast::{Toast, ToastPlugin},
+    world_clock::WorldClock,
+    Food, GameOverEvent, GameSet, MovementTick, Obstacle, SegmentPool, Segments, Size, SnakeHead,
+    SnakeSegment, TickTimer,
+};
+
+pub const PICKUP_INTERVAL: Duration = Duration::from_secs(8);
+pub const MAGNET_FOR: Duration = Duration::from_secs(5);
+/// Farthest food the magnet pulls, in steps from the head.
+pub const MAGNET_RANGE: u32 = 5;
+pub const TIME_FREEZE_FOR: Duration = Duration::from_secs(5);
+pub const SPEED_UP_FOR: Duration = Duration::from_secs(5);
+/// Movement interval while sped up, as a share of the usual one.
+const SPEED_UP_FACTOR: f32 = 2.0 / 3.0;
+pub const REVERSE_CONTROLS_FOR: Duration = Duration::from_secs(5);
+const GROW_BY: usize = 3;
+const SHRINK_BY: usize = 2;
+/// Segments a mystery pickup never shrinks the snake below, head included.
+const MIN_LENGTH: usize = 2;
+/// Cells a bomb reaches in each direction, diagonals included.
+pub const BOMB_RADIUS: i32 = 2;
+const EXPLOSION_COLOR: Color = Color::linear_rgb(1.0, 0.5, 0.1);
+const EXPLOSION_DURATION: Duration = Duration::from_millis(400);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PowerUp {
+    Magnet,
+    TimeFreeze,
+    Bomb,
+    Mystery,
+}
+
+impl PowerUp {
+    pub const ALL: [Self; 4] = [Self::Magnet, Self::TimeFreeze, Self::Bomb, Self::Mystery];
+
+    fn color(self) -> Color {
+        match self {
+            Self::Magnet => Color::linear_rgb(0.9, 0.2, 0.2),
+            Self::TimeFreeze => Color::linear_rgb(0.5, 0.8, 1.0),
+            Self::Bomb => Color::linear_rgb(0.15, 0.15, 0.15),
+            Self::Mystery => Color::linear_rgb(1.0, 0.8, 0.1),
+        }
+    }
+}
+
+/// What a mystery pickup can do.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mystery {
+    Grow,
+    Shrink,
+    SpeedUp,
+    ReverseControls,
+}
+
+impl Mystery {
+    pub const ALL: [Self; 4] = [
+        Self::Grow,
+        Self::Shrink,
+        Self::SpeedUp,
+        Self::ReverseControls,
+    ];
+
+    fn message(self) -> &'static str {
+        match self {
+            Self::Grow => "Mystery: grow!",
+            Self::Shrink => "Mystery: shrink!",
+            Self::SpeedUp => "Mystery: speed up!",
+            Self::ReverseControls => "Mystery: controls reversed!",
+        }
+    }
+}
+
+/// A power-up waiting on the board to be eaten.
+#[derive(Component)]
+pub struct Pickup(pub PowerUp);
+
+/// An effect that wears off after a while.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Effect {
+    Magnet,
+    /// The [`WorldClock`] stands still.
+    TimeFreeze,
+    SpeedUp,
+    /// Each arrow key steers the opposite way.
+    ReverseControls,
+}
+
+/// The effects running, with how long each has left.
+#[derive(Resource, Default)]
+pub struct TimedEffects(Vec<(Effect, Timer)>);
+
+impl TimedEffects {
+    /// Starts `effect`, or starts it over if it is already running.
+    pub fn start(&mut self, effect: Effect, duration: Duration) {
+        self.0.retain(|(running, _)| *running != effect);
+        self.0.push((effect, Timer::new(duration, TimerMode::Once)));
+    }
+
+    pub fn active(&self, effect: Effect) -> bool {
+        self.0.iter().any(|(running, _)| *running == effect)
+    }
+
+    /// Each effect running, with how long it has left.
+    pub fn remaining(&self) -> impl Iterator<Item = (Effect, Duration)> + '_ {
+        self.0
+            .iter()
+            .map(|(effect, timer)| (*effect, timer.remaining()))
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Runs every effect down by `delta`, and returns those that wore off.
+    pub fn tick(&mut self, delta: Duration) -> Vec<Effect> {
+        let mut worn_off = Vec::new();
+        self.0.retain_mut(|(effect, timer)| {
+            let done = timer.tick(delta).finished();
+            if done {
+                worn_off.push(*effect);
+            }
+            !done
+        });
+        worn_off
+    }
+}
+
+/// Run condition for systems that only run while `effect` does.
+pub fn effect_active(effect: Effect) -> impl Fn(Res<TimedEffects>) -> bool {
+    move |effects| effects.active(effect)
+}
+
+/// Places pickups in casual runs and on boards with obstacles, and carries
+/// out their effects.
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ToastPlugin>() {
+            app.add_plugins(ToastPlugin);
+        }
+        app.init_resource::<TimedEffects>()
+            .insert_resource(PickupSpawner(Timer::new(
+                PICKUP_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .add_systems(
+                FixedUpdate,
+                (
+                    (wear_off, spawn_pickup).in_set(GameSet::Spawning),
+                    (
+                        collect_pickups,
+                        apply_power_ups.run_if(on_event::<PowerUpCollected>),
+                        pull_food.run_if(effect_active(Effect::Magnet)),
+                    )
+                        .chain()
+                        .after(snake_growth)
+                        .before(game_over)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    clear_power_ups
+                        .after(game_over)
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (sync_speed, sync_freeze)
+                    .after(wear_off)
+                    .after(clear_power_ups)
+                    .in_set(GameSet::Spawning),
+            )
+            .add_systems(Update, fade_explosions)
+            .add_event::<PowerUpCollected>();
+    }
+}
+
+#[derive(Resource)]
+struct PickupSpawner(Timer);
+
+#[derive(Event)]
+struct PowerUpCollected(PowerUp);
+
+fn wear_off(time: Res<Time>, mut effects: ResMut<TimedEffects>) {
+    for effect in effects.tick(time.delta()) {
+        info!(?effect, "effect wore off");
+    }
+}
+
+/// Which cells of the arena something is on.
+pub(crate) struct Occupancy {
+    arena: Arena,
+    taken: Vec<bool>,
+}
+
+impl Occupancy {
+    pub(crate) fn new<'a>(arena: Arena, positions: impl IntoIterator<Item = &'a Position>) -> Self {
+        let mut occupancy = Self {
+            arena,
+            taken: vec![false; arena.width as usize * arena.height as usize],
+        };
+        for &position in positions {
+            occupancy.set(position, true);
+        }
+        occupancy
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        self.arena
+            .contains(position)
+            .then(|| position.y as usize * self.arena.width as usize + position.x as usize)
+    }
+
+    pub(crate) fn set(&mut self, position: Position, taken: bool) {
+        if let Some(index) = self.index(position) {
+            self.taken[index] = taken;
+        }
+    }
+
+    /// Whether `position` is in the arena with nothing on it.
+    pub(crate) fn free(&self, position: Position) -> bool {
+        self.index(position).is_some_and(|index| !self.taken[index])
+    }
+
+    pub(crate) fn free_cells(&self) -> Vec<Position> {
+        let (width, height) = (self.arena.width as i32, self.arena.height as i32);
+        (0..width)
+            .flat_map(|x| (0..height).map(move |y| Position { x, y }))
+            .filter(|&cell| self.free(cell))
+            .collect()
+    }
+}
+
+fn spawn_pickup(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mut spawner: ResMut<PickupSpawner>,
+    mode: Res<GameMode>,
+    pickups: Query<(), With<Pickup>>,
+    obstacles: Query<(), With<Obstacle>>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    if !spawner.0.tick(clock.delta()).just_finished() || !pickups.is_empty() {
+        return;
+    }
+    let kinds: Vec<PowerUp> = PowerUp::ALL
+        .into_iter()
+        .filter(|&power_up| match power_up {
+            PowerUp::Bomb => !obstacles.is_empty(),
+            _ => mode.is_casual(),
+        })
+        .collect();
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if kinds.is_empty() || free.is_empty() {
+        return;
+    }
+    let mut rng = rand::rng();
+    let position = free[rng.random_range(0..free.len())];
+    let power_up = kinds[rng.random_range(0..kinds.len())];
+    debug!(?position, ?power_up, "pickup placed");
+    spawn_power_up(&mut commands, power_up, position);
+}
+
+/// Puts a `power_up` pickup down at `position`.
+pub fn spawn_power_up(commands: &mut Commands, power_up: PowerUp, position: Position) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                color: power_up.color(),
+                ..Default::default()
+            },
+            Pickup(power_up),
+            position,
+            Size::square(0.6),
+        ))
+        .id()
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut collected: EventWriter<PowerUpCollected>,
+    heads: Query<&Position, With<SnakeHead>>,
+    pickups: Query<(Entity, &Pickup, &Position)>,
+) {
+    for head in &heads {
+        for (entity, pickup, position) in &pickups {
+            if position == head {
+                info!(power_up = ?pickup.0, "power-up collected");
+                commands.entity(entity).despawn();
+                collected.send(PowerUpCollected(pickup.0));
+                recorder.tainted = true;
+            }
+        }
+    }
+}
+
+/// Gives the snake the effect of each power-up it just ate.
+fn apply_power_ups(
+    mut commands: Commands,
+    mut reader: EventReader<PowerUpCollected>,
+    mut effects: ResMut<TimedEffects>,
+    mut snakes: Query<&mut Segments>,
+    mut pool: ResMut<SegmentPool>,
+    positions: Query<&Position, With<SnakeSegment>>,
+    obstacles: Query<(Entity, &Position), With<Obstacle>>,
+    arena: Res<Arena>,
+    accessibility: Res<Accessibility>,
+    mut toasts: EventWriter<Toast>,
+) {
+    for &PowerUpCollected(power_up) in reader.read() {
+        let Some(mut segments) = snakes.iter_mut().next() else {
+            continue;
+        };
+        let message = match power_up {
+            PowerUp::Magnet => {
+                effects.start(Effect::Magnet, MAGNET_FOR);
+                "Magnet!"
+            }
+            PowerUp::TimeFreeze => {
+                effects.start(Effect::TimeFreeze, TIME_FREEZE_FOR);
+                "Time freeze!"
+            }
+            PowerUp::Bomb => {
+                let Some(&center) = segments
+                    .0
+                    .first()
+                    .and_then(|&head| positions.get(head).ok())
+                else {
+                    continue;
+                };
+                let in_reach = |cell: &Position| {
+                    (cell.x - center.x).abs().max((cell.y - center.y).abs()) <= BOMB_RADIUS
+                };
+                for (obstacle, _) in obstacles.iter().filter(|(_, cell)| in_reach(cell)) {
+                    commands.entity(obstacle).despawn();
+                }
+                if !accessibility.reduced_motion {
+                    let blast = (-BOMB_RADIUS..=BOMB_RADIUS).flat_map(|x| {
+                        (-BOMB_RADIUS..=BOMB_RADIUS).map(move |y| Position {
+                            x: center.x + x,
+                            y: center.y + y,
+                        })
+                    });
+                    for cell in blast.filter(|&cell| arena.contains(cell)) {
+                        commands.spawn((
+                            Sprite {
+                                color: EXPLOSION_COLOR,
+                                ..Default::default()
+                            },
+                            Explosion(Timer::new(EXPLOSION_DURATION, TimerMode::Once)),
+                            cell,
+                            Size::square(1.0),
+                        ));
+                    }
+                }
+                "Boom!"
+            }
+            PowerUp::Mystery => {
+                let mystery = Mystery::ALL[rand::rng().random_range(0..Mystery::ALL.len())];
+                info!(?mystery, "mystery pickup");
+                match mystery {
+                    Mystery::Grow => {
+                        let tail = segments
+                            .0
+                            .last()
+                            .and_then(|&tail| positions.get(tail).ok())
+                            .copied();
+                        if let Some(tail) = tail {
+                            for _ in 0..GROW_BY {
+                                let segment = spawn_segment(&mut commands, &mut pool, tail);
+                                segments.0.push(segment);
+                            }
+                        }
+                    }
+                    Mystery::Shrink => {
+                        let length = segments.0.len();
+                        let keep = length.saturating_sub(SHRINK_BY).max(MIN_LENGTH).min(length);
+                        for segment in segments.0.split_off(keep) {
+                            release_segment(&mut commands, &mut pool, segment);
+                        }
+                    }
+                    Mystery::SpeedUp => effects.start(Effect::SpeedUp, SPEED_UP_FOR),
+                    Mystery::ReverseControls => {
+                        effects.start(Effect::ReverseControls, REVERSE_CONTROLS_FOR)
+                    }
+                }
+                mystery.message()
+            }
+        };
+        toasts.send(Toast(message.to_string()));
+    }
+}
+
+/// Scales the movement timer down while [`Effect::SpeedUp`] runs. The
+/// interval itself is left alone, so settings save the usual speed and a
+/// `speed` command during the effect still holds once it stops.
+fn sync_speed(effects: Res<TimedEffects>, mut movement_timer: ResMut<TickTimer<MovementTick>>) {
+    let scale = if effects.active(Effect::SpeedUp) {
+        SPEED_UP_FACTOR
+    } else {
+        1.0
+    };
+    movement_timer.set_scale(scale);
+}
+
+/// Moves each piece of food in range one cell closer to the head, along
+/// whichever axis it is farther off on, or the other if that cell is taken.
+fn pull_food(
+    arena: Res<Arena>,
+    heads: Query<&Position, (With<SnakeHead>, Without<Food>)>,
+    taken: Query<
+        &Position,
+        (
+            Or<(With<SnakeSegment>, With<Obstacle>, With<Pickup>)>,
+            Without<Food>,
+        ),
+    >,
+    mut food: Query<&mut Position, With<Food>>,
+) {
+    let Ok(&head) = heads.get_single() else {
+        return;
+    };
+    let mut occupancy = Occupancy::new(*arena, &taken);
+    for position in &food {
+        occupancy.set(*position, true);
+    }
+    for mut position in &mut food {
+        let (dx, dy) = (head.x - position.x, head.y - position.y);
+        if dx.unsigned_abs() + dy.unsigned_abs() > MAGNET_RANGE {
+            continue;
+        }
+        let along_x = Position {
+            x: position.x + dx.signum(),
+            ..*position
+        };
+        let along_y = Position {
+            y: position.y + dy.signum(),
+            ..*position
+        };
+        let steps = if dx.abs() >= dy.abs() {
+            [along_x, along_y]
+        } else {
+            [along_y, along_x]
+        };
+        let Some(&next) = steps
+            .iter()
+            .find(|&&step| step != *position && occupancy.free(step))
+        else {
+            continue;
+        };
+        occupancy.set(*position, false);
+        occupancy.set(next, true);
+        *position = next;
+    }
+}
+
+fn clear_power_ups(
+    mut commands: Commands,
+    mut effects: ResMut<TimedEffects>,
+    pickups: Query<Entity, With<Pickup>>,
+) {
+    *effects = TimedEffects::default();
+    for pickup in &pickups {
+        commands.entity(pickup).despawn();
+    }
+}
+
+fn sync_freeze(effects: Res<TimedEffects>, mut clock: ResMut<WorldClock>) {
+    clock.frozen = effects.active(Effect::TimeFreeze);
+}
+
+/// A cell of a bomb's blast, fading away.
+#[derive(Component)]
+struct Explosion(Timer);
+
+fn fade_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut explosions: Query<(Entity, &mut Explosion, &mut Sprite)>,
+) {
+    for (entity, mut explosion, mut sprite) in &mut explosions {
+        if explosion.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color = EXPLOSION_COLOR.with_alpha(explosion.0.fraction_remaining());
+        }
+    }
+}
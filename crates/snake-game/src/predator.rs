@@ -0,0 +1,167 @@
+//! The predator, an optional enemy switched on with `predator` in
+//! `assets/config.ron` or the saved settings.
+//!
+//! It turns up on a free cell at least [`SPAWN_DISTANCE`] steps from the head,
+//! so never right next to it, and every other movement tick takes one step
+//! along the shortest way to the head, found by a breadth-first search around
+//! the walls. Catching the snake costs a life, or the run when there are none
+//! to spare; the predator then goes and turns up somewhere else. The snake can
+//! run into it just the same. A run with a predator in it is not kept as a
+//! replay.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, Direction, Position};
+
+use crate::{
+    lives::{Invulnerable, LifeLost, Lives},
+    power_ups::Occupancy,
+    replay::ReplayRecorder,
+    GameOverCause, GameOverEvent, Obstacle, Size, SnakeHead, SnakeSegment,
+};
+
+/// Fewest steps from the head a predator turns up at.
+pub const SPAWN_DISTANCE: u32 = 4;
+const PREDATOR_COLOR: Color = Color::linear_rgb(0.85, 0.1, 0.1);
+
+/// Whether the predator is switched on.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Predators(pub bool);
+
+/// The predator on the board.
+#[derive(Component)]
+pub struct Predator;
+
+/// Run condition for the predator's systems.
+pub(crate) fn predators_on(predators: Res<Predators>) -> bool {
+    predators.0
+}
+
+fn distance(from: Position, to: Position) -> u32 {
+    from.x.abs_diff(to.x) + from.y.abs_diff(to.y)
+}
+
+/// Puts a predator on the board when there is none, or takes it off when it
+/// has been switched off.
+pub(crate) fn place_predator(
+    mut commands: Commands,
+    predators: Res<Predators>,
+    arena: Res<Arena>,
+    mut recorder: ResMut<ReplayRecorder>,
+    existing: Query<Entity, With<Predator>>,
+    heads: Query<&Position, With<SnakeHead>>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Obstacle>)>>,
+) {
+    if !predators.0 {
+        for predator in &existing {
+            commands.entity(predator).despawn();
+        }
+        return;
+    }
+    let Some(&head) = heads.iter().next() else {
+        return;
+    };
+    if !existing.is_empty() {
+        return;
+    }
+    let free: Vec<Position> = Occupancy::new(*arena, &taken)
+        .free_cells()
+        .into_iter()
+        .filter(|&cell| distance(cell, head) >= SPAWN_DISTANCE)
+        .collect();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "predator placed");
+    recorder.tainted = true;
+    spawn_predator(&mut commands, position);
+}
+
+/// Puts the predator down at `position`.
+pub fn spawn_predator(commands: &mut Commands, position: Position) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                color: PREDATOR_COLOR,
+                ..Default::default()
+            },
+            Predator,
+            position,
+            Size::square(0.8),
+        ))
+        .id()
+}
+
+/// First step on a shortest way from `from` to `to` that keeps off `walls`,
+/// or `None` if there is no way.
+fn first_step(arena: Arena, walls: &Occupancy, from: Position, to: Position) -> Option<Position> {
+    let width = arena.width as usize;
+    let index = |position: Position| position.y as usize * width + position.x as usize;
+    // Where each cell was first reached from.
+    let mut came_from = vec![None; width * arena.height as usize];
+    let mut queue = VecDeque::from([from]);
+    while let Some(cell) = queue.pop_front() {
+        if cell == to {
+            let mut step = cell;
+            while let Some(previous) = came_from[index(step)] {
+                if previous == from {
+                    return Some(step);
+                }
+                step = previous;
+            }
+            return None;
+        }
+        for next in Direction::ALL.map(|direction| cell.step(direction)) {
+            if next != from && walls.free(next) && came_from[index(next)].is_none() {
+                came_from[index(next)] = Some(cell);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Moves the predator towards the head every other tick, and catches the snake
+/// when they meet.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn hunt(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    lives: Res<Lives>,
+    invulnerable: Option<Res<Invulnerable>>,
+    heads: Query<&Position, (With<SnakeHead>, Without<Predator>)>,
+    obstacles: Query<&Position, (With<Obstacle>, Without<Predator>)>,
+    mut predators: Query<(Entity, &mut Position), With<Predator>>,
+    mut game_over_writer: EventWriter<GameOverEvent>,
+    mut life_lost_writer: EventWriter<LifeLost>,
+    mut resting: Local<bool>,
+) {
+    let Some(&head) = heads.iter().next() else {
+        return;
+    };
+    *resting = !*resting;
+    let walls = Occupancy::new(*arena, &obstacles);
+    for (entity, mut position) in &mut predators {
+        if !*resting && *position != head {
+            if let Some(step) = first_step(*arena, &walls, *position, head) {
+                *position = step;
+            }
+        }
+        if *position != head || invulnerable.is_some() {
+            continue;
+        }
+        info!(?head, "caught by the predator");
+        commands.entity(entity).despawn();
+        if lives.spare > 0 {
+            life_lost_writer.send(LifeLost(GameOverCause::Caught));
+        } else {
+            game_over_writer.send(GameOverEvent {
+                cause: GameOverCause::Caught,
+                cell: Some(head),
+            });
+        }
+    }
+}
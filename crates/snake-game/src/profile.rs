@@ -0,0 +1,347 @@
+//! Named player profiles, so everyone sharing a computer keeps their own
+//! settings, personal bests, quicksave and leaderboard name.
+//!
+//! Each profile is a directory under `snake/profiles/` in the platform config
+//! directory, and everything saved for a player goes in [`Profile::path`].
+//! `--profile <name>` picks one, creating it if it is new. Without it the
+//! only profile is used, or a new `player` one; with several the game starts
+//! paused on a list to pick from with the arrow keys and Enter, where "New
+//! profile" names another with the [name entry](crate::name_entry).
+//!
+//! `--export-profile <file>` saves every file of the chosen profile into one
+//! [`Bundle`] file to carry to another machine, and `--import-profile <file>`
+//! brings one in before the profile's settings load. An import merges the
+//! bundle into the profile's own files as a sync does ([`Bundle::merge`]), or
+//! with `--replace` makes the profile an exact copy of the bundle. Either
+//! needs the profile settled without the picker.
+//!
+//! Without [`ProfilePlugin`], as on the web, Android and in tests, the
+//! [`Default`] profile keeps its files in the working directory.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use snake_core::persist;
+
+use crate::{
+    bundle::{Bundle, BundleError, ImportMode},
+    name_entry::{edit_name, spawn_name_entry, NameEntered, NameEntryPlugin},
+};
+
+const DEFAULT_PROFILE: &str = "player";
+pub const MAX_NAME_LENGTH: usize = 16;
+
+/// Whose game it is, and where their files go.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            dir: PathBuf::new(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn new(root: &Path, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            dir: root.join(name),
+        }
+    }
+
+    /// Where this profile keeps `file`.
+    pub fn path(&self, file: &str) -> PathBuf {
+        self.dir.join(file)
+    }
+}
+
+/// Whether `name` can be used as a profile, and so as a directory name:
+/// letters, digits, spaces, `-` and `_`, at most [`MAX_NAME_LENGTH`] of them,
+/// not starting or ending with a space.
+pub fn valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LENGTH).contains(&name.chars().count())
+        && name == name.trim()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+}
+
+/// `snake/profiles` in the platform config directory, if there is one.
+pub fn profiles_dir() -> Option<PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return dirs::config_dir().map(|dir| dir.join("snake").join("profiles"));
+    #[cfg(target_arch = "wasm32")]
+    None
+}
+
+/// Names of the profiles in `root`, sorted.
+pub fn list_profiles(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| valid_name(name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Chooses the profile from `--profile` or the ones already saved, and shows
+/// the picker when that is up to the player.
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let Some(root) = profiles_dir() else {
+            return;
+        };
+        let requested = std::env::args()
+            .skip_while(|arg| arg != "--profile")
+            .nth(1)
+            .filter(|name| {
+                let valid = valid_name(name);
+                if !valid {
+                    warn!("ignoring profile name {name:?}: use up to {MAX_NAME_LENGTH} letters, digits, spaces, - or _");
+                }
+                valid
+            });
+        let profiles = list_profiles(&root);
+        let name = match (requested, profiles.as_slice()) {
+            (Some(name), _) => name,
+            (None, []) => DEFAULT_PROFILE.to_string(),
+            (None, [only]) => only.clone(),
+            (None, [first, ..]) => {
+                let first = first.clone();
+                if !app.is_plugin_added::<NameEntryPlugin>() {
+                    app.add_plugins(NameEntryPlugin);
+                }
+                app.insert_resource(ProfilePicker {
+                    root: root.clone(),
+                    profiles,
+                    selected: 0,
+                    naming: None,
+                })
+                .add_systems(Startup, open_picker)
+                .add_systems(
+                    Update,
+                    // After the name entry has had the keys of the frame it
+                    // opens in, so the Enter that opens it does not close it.
+                    pick_profile
+                        .after(edit_name)
+                        .run_if(resource_exists::<ProfilePicker>),
+                );
+                first
+            }
+        };
+        let profile = Profile::new(&root, &name);
+        if let Err(err) = fs::create_dir_all(&profile.dir) {
+            warn!("could not create profile {name:?}: {err}");
+        }
+        if !app.world().contains_resource::<ProfilePicker>() {
+            info!("playing as {name}");
+            transfer_from_args(&profile);
+        } else if std::env::args().any(|arg| arg == "--export-profile" || arg == "--import-profile")
+        {
+            warn!("choose the profile to export or import with --profile");
+        }
+        app.insert_resource(profile);
+    }
+}
+
+/// Handles `--import-profile` and `--export-profile`, in that order.
+fn transfer_from_args(profile: &Profile) {
+    let arg = |flag: &str| std::env::args().skip_while(|arg| arg != flag).nth(1);
+    if let Some(file) = arg("--import-profile") {
+        let mode = if std::env::args().any(|arg| arg == "--replace") {
+            ImportMode::Replace
+        } else {
+            ImportMode::Merge
+        };
+        match import(profile, Path::new(&file), mode) {
+            Ok(names) => info!(
+                "imported {} files from {file} into {}",
+                names.len(),
+                profile.name
+            ),
+            Err(err) => warn!("could not import {file}: {err}"),
+        }
+    }
+    if let Some(file) = arg("--export-profile") {
+        match export(profile, Path::new(&file)) {
+            Ok(()) => info!("exported {} to {file}", profile.name),
+            Err(err) => warn!("could not export to {file}: {err}"),
+        }
+    }
+}
+
+/// Saves all of `profile`'s files into the bundle file `path`.
+pub fn export(profile: &Profile, path: &Path) -> Result<(), BundleError> {
+    let bundle = Bundle::collect(&profile.dir, &profile.name)?;
+    Ok(persist::write_atomic(path, &bundle.encode()?)?)
+}
+
+/// Brings the files in the bundle file `path` into `profile`, and returns the
+/// names of those written.
+pub fn import(
+    profile: &Profile,
+    path: &Path,
+    mode: ImportMode,
+) -> Result<Vec<String>, BundleError> {
+    let bundle = Bundle::decode(&fs::read(path)?)?;
+    bundle.import(&profile.dir, mode)
+}
+
+/// Run condition that holds while the player is still choosing a profile.
+pub(crate) fn picking_profile(picker: Option<Res<ProfilePicker>>) -> bool {
+    picker.is_some()
+}
+
+#[derive(Resource)]
+pub(crate) struct ProfilePicker {
+    root: PathBuf,
+    profiles: Vec<String>,
+    /// An index past the last profile is "New profile".
+    selected: usize,
+    /// The name entry for a new profile, while it is open.
+    naming: Option<Entity>,
+}
+
+impl ProfilePicker {
+    fn text(&self) -> String {
+        let names: Vec<String> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let marker = if index == self.selected { '>' } else { ' ' };
+                format!("{marker} {name}")
+            })
+            .chain([format!(
+                "{} New profile",
+                if self.selected == self.profiles.len() {
+                    '>'
+                } else {
+                    ' '
+                }
+            )])
+            .collect();
+        format!(
+            "Who is playing?\n{}\n\nUp/Down to choose, Enter to play",
+            names.join("\n")
+        )
+    }
+}
+
+#[derive(Component)]
+struct PickerScreen;
+
+/// Shows the list and holds the game until a profile is picked.
+fn open_picker(
+    mut commands: Commands,
+    picker: Res<ProfilePicker>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    time.pause();
+    commands.spawn((
+        PickerScreen,
+        Text(picker.text()),
+        TextFont {
+            font_size: 20.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pick_profile(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut picker: ResMut<ProfilePicker>,
+    mut profile: ResMut<Profile>,
+    mut time: ResMut<Time<Virtual>>,
+    mut entered: EventReader<NameEntered>,
+    mut screens: Query<(Entity, &mut Text, &mut Visibility), With<PickerScreen>>,
+) {
+    if let Some(naming) = picker.naming {
+        let Some(NameEntered { name, .. }) = entered.read().find(|event| event.entry == naming)
+        else {
+            return;
+        };
+        picker.naming = None;
+        for (_, _, mut visibility) in &mut screens {
+            *visibility = Visibility::Inherited;
+        }
+        match name.clone().filter(|name| valid_name(name)) {
+            Some(name) if !picker.profiles.contains(&name) => {
+                let new = Profile::new(&picker.root, &name);
+                if let Err(err) = fs::create_dir_all(&new.dir) {
+                    warn!("could not create profile {name:?}: {err}");
+                }
+                picker.profiles.push(name);
+                picker.profiles.sort();
+                picker.selected = picker
+                    .profiles
+                    .iter()
+                    .position(|p| *p == new.name)
+                    .unwrap_or(0);
+            }
+            Some(name) => {
+                picker.selected = picker.profiles.iter().position(|p| *p == name).unwrap_or(0);
+            }
+            None => {}
+        }
+        for (_, mut text, _) in &mut screens {
+            text.0 = picker.text();
+        }
+        return;
+    }
+    let count = picker.profiles.len() + 1;
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        picker.selected = (picker.selected + 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        picker.selected = (picker.selected + count - 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) && picker.selected == picker.profiles.len() {
+        picker.naming = Some(spawn_name_entry(
+            &mut commands,
+            &mut time,
+            "Name the new profile",
+            MAX_NAME_LENGTH,
+        ));
+        for (_, _, mut visibility) in &mut screens {
+            *visibility = Visibility::Hidden;
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Enter) {
+        let name = &picker.profiles[picker.selected];
+        info!("playing as {name}");
+        profile.set_if_neq(Profile::new(&picker.root, name));
+        for (screen, _, _) in &screens {
+            commands.entity(screen).despawn_recursive();
+        }
+        commands.remove_resource::<ProfilePicker>();
+        time.unpause();
+    } else if picker.is_changed() {
+        for (_, mut text, _) in &mut screens {
+            text.0 = picker.text();
+        }
+    }
+}
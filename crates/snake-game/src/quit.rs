@@ -0,0 +1,102 @@
+//! Quitting with Esc or by closing the window, which asks first while the run
+//! has a score to lose.
+//!
+//! The dialog pauses the game. Y or Enter quits, N or Esc goes back to the
+//! run, and closing the window again quits without asking twice. Esc is left
+//! to the console or a name entry while one is open.
+
+use bevy::{input::InputSystem, prelude::*, window::WindowCloseRequested};
+
+use crate::{console::Console, name_entry::NameEntry, Score};
+
+/// Present while the dialog is up.
+#[derive(Resource)]
+pub struct QuitDialog {
+    /// Whether the game was paused already, and so stays paused on going back.
+    was_paused: bool,
+}
+
+#[derive(Component)]
+struct QuitDialogNode;
+
+/// Asks before quitting a run in progress. The window has to be created with
+/// `close_when_requested` off for this to see close requests first.
+pub struct QuitPlugin;
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        // Before the console sees the key, so closing it does not quit too.
+        app.add_event::<WindowCloseRequested>()
+            .add_systems(PreUpdate, quit.after(InputSystem));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn quit(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+    score: Res<Score>,
+    dialog: Option<Res<QuitDialog>>,
+    mut time: ResMut<Time<Virtual>>,
+    consoles: Query<&Console>,
+    names: Query<(), With<NameEntry>>,
+    nodes: Query<Entity, With<QuitDialogNode>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let close_requested = close_requests.read().count() > 0;
+    if let Some(dialog) = dialog {
+        if close_requested || keyboard_input.any_just_pressed([KeyCode::KeyY, KeyCode::Enter]) {
+            info!("quitting");
+            exit.send(AppExit::Success);
+        } else if keyboard_input.any_just_pressed([KeyCode::KeyN, KeyCode::Escape]) {
+            if !dialog.was_paused {
+                time.unpause();
+            }
+            commands.remove_resource::<QuitDialog>();
+            for node in &nodes {
+                commands.entity(node).despawn_recursive();
+            }
+        }
+        return;
+    }
+    let escaped = keyboard_input.just_pressed(KeyCode::Escape)
+        && !consoles.iter().any(|console| console.open)
+        && names.is_empty();
+    if !close_requested && !escaped {
+        return;
+    }
+    if score.0 == 0 {
+        info!("quitting");
+        exit.send(AppExit::Success);
+        return;
+    }
+    commands.insert_resource(QuitDialog {
+        was_paused: time.is_paused(),
+    });
+    time.pause();
+    commands
+        .spawn((
+            QuitDialogNode,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_child((
+            Text::new(format!(
+                "Are you sure?\nQuitting loses this run, score {}.\n\nY: quit  N: keep playing",
+                score.0
+            )),
+            TextFont {
+                font_size: 20.0,
+                ..Default::default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+        ));
+}
@@ -0,0 +1,202 @@
+//! Remote control over a WebSocket, for bots, tools and stream overlays.
+//!
+//! Only compiled with the `remote-control` feature, and only listening when
+//! the player passes `--remote-control <port>`. The socket is bound to
+//! localhost. Every movement tick each client is sent the board as a JSON
+//! [`BoardState`], and clients steer by sending `{"steer": "Up"}` (or
+//! `Down`, `Left`, `Right`). Steering follows the keyboard rules, so a
+//! reversal is ignored.
+
+use std::{
+    io,
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use snake_core::{Arena, Direction, Position};
+use tungstenite::{Message, WebSocket};
+
+use crate::{
+    snake_movement_input, timer_finished, Food, GameSet, MovementTick, Run, Score, Segments,
+    SnakeHead,
+};
+
+/// How long a connection waits for a command before sending queued states.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Opens the WebSocket if the player asked for it on the command line.
+pub struct RemoteControlPlugin;
+
+impl Plugin for RemoteControlPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(port) = std::env::args()
+            .skip_while(|arg| arg != "--remote-control")
+            .nth(1)
+        else {
+            return;
+        };
+        let remote = match port
+            .parse::<u16>()
+            .map_err(io::Error::other)
+            .and_then(RemoteControl::listen)
+        {
+            Ok(remote) => remote,
+            Err(err) => {
+                error!("cannot open remote control on port {port}: {err}");
+                return;
+            }
+        };
+        info!("remote control listening on ws://127.0.0.1:{port}");
+        app.insert_resource(remote).add_systems(
+            FixedUpdate,
+            (
+                apply_commands
+                    .after(snake_movement_input)
+                    .in_set(GameSet::Input),
+                stream_board
+                    .after(GameSet::Spawning)
+                    .run_if(timer_finished::<MovementTick>),
+            ),
+        );
+    }
+}
+
+/// The board as streamed to clients after each movement tick.
+#[derive(Serialize)]
+pub struct BoardState {
+    pub tick: u32,
+    pub score: u32,
+    pub arena: Arena,
+    pub direction: Direction,
+    /// Segment positions, head first.
+    pub snake: Vec<Position>,
+    pub food: Vec<Position>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RemoteCommand {
+    Steer(Direction),
+}
+
+#[derive(Resource)]
+struct RemoteControl {
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+    commands: Mutex<Receiver<Direction>>,
+}
+
+impl RemoteControl {
+    fn listen(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let (command_sender, commands) = mpsc::channel();
+        let accepted = Arc::clone(&clients);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (sender, outgoing) = mpsc::channel();
+                let commands = command_sender.clone();
+                let clients = Arc::clone(&accepted);
+                thread::spawn(move || match tungstenite::accept(stream) {
+                    Ok(socket) => {
+                        clients.lock().unwrap().push(sender);
+                        serve_client(socket, outgoing, commands);
+                    }
+                    Err(err) => warn!("remote control handshake failed: {err}"),
+                });
+            }
+        });
+        Ok(Self {
+            clients,
+            commands: Mutex::new(commands),
+        })
+    }
+}
+
+/// Relays states to one client and its commands back, until either side
+/// goes away.
+fn serve_client(
+    mut socket: WebSocket<TcpStream>,
+    outgoing: Receiver<String>,
+    commands: Sender<Direction>,
+) {
+    if socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        for state in outgoing.try_iter() {
+            if socket.send(Message::text(state)).is_err() {
+                return;
+            }
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(RemoteCommand::Steer(direction)) => {
+                    if commands.send(direction).is_err() {
+                        return;
+                    }
+                }
+                Err(err) => warn!("ignoring remote command {text:?}: {err}"),
+            },
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err))
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+fn apply_commands(remote: Res<RemoteControl>, mut heads: Query<&mut SnakeHead>) {
+    let commands = remote.commands.lock().unwrap();
+    for direction in commands.try_iter() {
+        for mut head in &mut heads {
+            if direction != head.direction.opposite() {
+                head.direction = direction;
+            }
+        }
+    }
+}
+
+fn stream_board(
+    remote: Res<RemoteControl>,
+    run: Res<Run>,
+    score: Res<Score>,
+    arena: Res<Arena>,
+    heads: Query<(&SnakeHead, &Segments)>,
+    positions: Query<&Position>,
+    food: Query<&Position, With<Food>>,
+) {
+    let mut clients = remote.clients.lock().unwrap();
+    if clients.is_empty() {
+        return;
+    }
+    let Some((head, segments)) = heads.iter().next() else {
+        return;
+    };
+    let state = BoardState {
+        tick: run.tick,
+        score: score.0,
+        arena: *arena,
+        direction: head.direction,
+        snake: positions.iter_many(&segments.0).copied().collect(),
+        food: food.iter().copied().collect(),
+    };
+    let Ok(json) = serde_json::to_string(&state) else {
+        return;
+    };
+    clients.retain(|client| client.send(json.clone()).is_ok());
+}
@@ -0,0 +1,158 @@
+//! Bevy side of replays: recording the live run, exporting it and feeding a
+//! loaded replay back in as input. The file format lives in
+//! [`snake_core::replay`].
+
+use std::path::Path;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use snake_core::{
+    replay::{Replay, ReplayConfig, ReplayInput},
+    Arena, GameMode,
+};
+
+use crate::{FoodSpawnTick, MovementTick, Run, Score, SnakeHead, TickTimer};
+
+const EXPORT_DIR: &str = "replays";
+
+/// The live settings a replay has to match to play back faithfully.
+#[derive(SystemParam)]
+pub struct CurrentConfig<'w> {
+    arena: Res<'w, Arena>,
+    movement_timer: Res<'w, TickTimer<MovementTick>>,
+    food_spawn_timer: Res<'w, TickTimer<FoodSpawnTick>>,
+}
+
+impl CurrentConfig<'_> {
+    pub fn get(&self) -> ReplayConfig {
+        ReplayConfig {
+            arena_width: self.arena.width,
+            arena_height: self.arena.height,
+            movement_interval_ms: self.movement_timer.interval().as_millis() as u32,
+            food_spawn_interval_ms: self.food_spawn_timer.interval().as_millis() as u32,
+        }
+    }
+}
+
+/// Inputs of the run in progress. A run stops being replayable once its state
+/// is restored from a snapshot (quickload or rewind) or changed by a console
+/// cheat.
+#[derive(Default, Resource)]
+pub struct ReplayRecorder {
+    inputs: Vec<ReplayInput>,
+    pub tainted: bool,
+    /// Set by console cheats and snapshot restores, which also keep the run
+    /// out of high scores, stats, dailies and Steam. Other taints, such as
+    /// power-ups, only stop the replay.
+    pub cheated: bool,
+}
+
+/// The most recently finished replayable run, ready to be exported.
+#[derive(Default, Resource)]
+pub struct LastReplay(pub Option<Replay>);
+
+/// A replay being played back in place of keyboard input. Removed when the
+/// replayed run ends.
+#[derive(Resource)]
+pub struct ReplayPlayback(pub Replay);
+
+/// Starts playing back the replay given with `--replay <path>`, if any.
+pub fn load_from_args(mut commands: Commands, config: CurrentConfig, mut mode: ResMut<GameMode>) {
+    let Some(path) = std::env::args().skip_while(|arg| arg != "--replay").nth(1) else {
+        return;
+    };
+    match Replay::import(Path::new(&path), &config.get()) {
+        Ok(replay) => {
+            info!(
+                "playing back {path} (recorded score {})",
+                replay.final_score
+            );
+            *mode = replay.mode;
+            commands.insert_resource(ReplayPlayback(replay));
+        }
+        Err(err) => error!("cannot play back {path}: {err}"),
+    }
+}
+
+/// Whether the run in progress was cheated, so it is not recorded.
+pub fn run_cheated(recorder: Res<ReplayRecorder>) -> bool {
+    recorder.cheated
+}
+
+pub fn reset_recorder(mut recorder: ResMut<ReplayRecorder>) {
+    *recorder = ReplayRecorder::default();
+}
+
+pub fn record_input(run: Res<Run>, heads: Query<&SnakeHead>, mut recorder: ResMut<ReplayRecorder>) {
+    let Some(head) = heads.iter().next() else {
+        return;
+    };
+    if recorder.inputs.last().map(|input| input.direction) != Some(head.direction) {
+        recorder.inputs.push(ReplayInput {
+            tick: run.tick,
+            direction: head.direction,
+        });
+    }
+}
+
+pub fn playback_input(
+    run: Res<Run>,
+    playback: Res<ReplayPlayback>,
+    mut heads: Query<&mut SnakeHead>,
+) {
+    let Some(direction) = playback.0.direction_at(run.tick) else {
+        return;
+    };
+    for mut head in heads.iter_mut() {
+        head.direction = direction;
+    }
+}
+
+pub fn finish_recording(
+    run: Res<Run>,
+    config: CurrentConfig,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    recorder: Res<ReplayRecorder>,
+    mut last_replay: ResMut<LastReplay>,
+) {
+    if recorder.tainted {
+        last_replay.0 = None;
+        return;
+    }
+    last_replay.0 = Some(Replay {
+        seed: run.seed,
+        config: config.get(),
+        mode: *mode,
+        inputs: recorder.inputs.clone(),
+        final_score: score.0,
+        duration_ticks: run.tick,
+    });
+}
+
+pub fn finish_playback(mut commands: Commands, score: Res<Score>, playback: Res<ReplayPlayback>) {
+    info!(
+        "replay finished with score {} (recorded {})",
+        score.0, playback.0.final_score
+    );
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+pub fn export_last_replay(keyboard_input: Res<ButtonInput<KeyCode>>, last_replay: Res<LastReplay>) {
+    if !keyboard_input.just_pressed(KeyCode::F6) {
+        return;
+    }
+    let Some(replay) = &last_replay.0 else {
+        warn!("no finished run to export");
+        return;
+    };
+    export(replay);
+}
+
+/// Writes `replay` to the export directory, named after its seed.
+pub(crate) fn export(replay: &Replay) {
+    let path = Path::new(EXPORT_DIR).join(format!("{:016x}.snkr", replay.seed));
+    match replay.export(&path) {
+        Ok(()) => info!("exported replay to {}", path.display()),
+        Err(err) => error!("replay export failed: {err}"),
+    }
+}
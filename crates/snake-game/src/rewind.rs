@@ -0,0 +1,36 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use snake_core::{GameMode, MOVEMENT_INTERVAL};
+
+use crate::snapshot::{GameSnapshot, SnapshotSource, SnapshotTarget};
+
+const REWIND_WINDOW: Duration = Duration::from_secs(10);
+const REWIND_CAPACITY: usize = (REWIND_WINDOW.as_millis() / MOVEMENT_INTERVAL.as_millis()) as usize;
+
+/// Snapshots taken at the start of each movement tick, oldest first.
+#[derive(Default, Resource)]
+pub struct RewindHistory(VecDeque<GameSnapshot>);
+
+pub fn rewinding(keyboard_input: Res<ButtonInput<KeyCode>>, mode: Res<GameMode>) -> bool {
+    mode.is_casual() && keyboard_input.pressed(KeyCode::Backspace)
+}
+
+pub fn record_history(source: SnapshotSource, mut history: ResMut<RewindHistory>) {
+    match source.capture() {
+        Ok(snapshot) => {
+            if history.0.len() == REWIND_CAPACITY {
+                history.0.pop_front();
+            }
+            history.0.push_back(snapshot);
+        }
+        Err(err) => warn!("not recording rewind history: {err}"),
+    }
+}
+
+pub fn rewind(mut history: ResMut<RewindHistory>, mut target: SnapshotTarget) {
+    if let Some(snapshot) = history.0.pop_back() {
+        target.restore(&snapshot);
+    }
+}
@@ -0,0 +1,95 @@
+//! Gamepad rumble: a short buzz when the snake eats and a strong one when it
+//! dies, on every connected gamepad. `rumble_intensity` in
+//! `assets/config.ron` scales both, and 0 turns rumble off.
+
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{game_over, snake_eating, GameOverCause, GameOverEvent, GameSet, GrowthEvent};
+
+const EAT_RUMBLE: Duration = Duration::from_millis(80);
+const DEATH_RUMBLE: Duration = Duration::from_millis(400);
+
+pub struct RumblePlugin;
+
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rumble>().add_systems(
+            FixedUpdate,
+            (
+                rumble_on_eating
+                    .run_if(on_event::<GrowthEvent>)
+                    .after(snake_eating),
+                rumble_on_death
+                    .run_if(on_event::<GameOverEvent>)
+                    .before(game_over),
+            )
+                .in_set(GameSet::Logic),
+        );
+    }
+}
+
+/// How hard gamepads rumble, from 0 (off) to 1 (full strength).
+#[derive(Resource)]
+pub struct Rumble {
+    pub intensity: f32,
+}
+
+impl Default for Rumble {
+    fn default() -> Self {
+        Self { intensity: 1.0 }
+    }
+}
+
+fn send(
+    requests: &mut EventWriter<GamepadRumbleRequest>,
+    gamepads: &Query<Entity, With<Gamepad>>,
+    intensity: GamepadRumbleIntensity,
+    duration: Duration,
+) {
+    for gamepad in gamepads.iter() {
+        requests.send(GamepadRumbleRequest::Add {
+            duration,
+            intensity,
+            gamepad,
+        });
+    }
+}
+
+fn rumble_on_eating(
+    mut growth: EventReader<GrowthEvent>,
+    rumble: Res<Rumble>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+    gamepads: Query<Entity, With<Gamepad>>,
+) {
+    growth.clear();
+    if rumble.intensity > 0.0 {
+        let intensity = GamepadRumbleIntensity::weak_motor(0.5 * rumble.intensity);
+        send(&mut requests, &gamepads, intensity, EAT_RUMBLE);
+    }
+}
+
+/// Only collisions rumble; finishing a speedrun or leaving attract mode does
+/// not.
+fn rumble_on_death(
+    mut game_overs: EventReader<GameOverEvent>,
+    rumble: Res<Rumble>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+    gamepads: Query<Entity, With<Gamepad>>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = game_overs.read().last() else {
+        return;
+    };
+    if !matches!(cause, GameOverCause::Collision(_)) || rumble.intensity <= 0.0 {
+        return;
+    }
+    let intensity = GamepadRumbleIntensity {
+        strong_motor: rumble.intensity,
+        weak_motor: rumble.intensity,
+    };
+    send(&mut requests, &gamepads, intensity, DEATH_RUMBLE);
+}
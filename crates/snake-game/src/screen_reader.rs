@@ -0,0 +1,149 @@
+//! Spoken announcements for low-vision players, enabled with
+//! `--screen-reader`.
+//!
+//! Key events ("food up-left, 3 cells", "score 12", "game over") go into a
+//! caption at the bottom of the window that is also exposed to the platform
+//! accessibility API as a live region, so screen readers such as NVDA,
+//! VoiceOver or Orca read each one out as it appears.
+
+use accesskit::{Live, Node as AccessKitNode, Role};
+use bevy::{a11y::AccessibilityNode, prelude::*};
+use snake_core::Position;
+
+use crate::{game_over, Food, GameOverCause, GameOverEvent, GameSet, Score, SnakeHead};
+
+pub struct ScreenReaderPlugin;
+
+impl Plugin for ScreenReaderPlugin {
+    fn build(&self, app: &mut App) {
+        if !std::env::args().any(|arg| arg == "--screen-reader") {
+            return;
+        }
+        app.init_resource::<Announcements>()
+            .add_systems(Startup, spawn_caption)
+            .add_systems(
+                FixedUpdate,
+                announce_game_over
+                    .run_if(on_event::<GameOverEvent>)
+                    .before(game_over)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(
+                Update,
+                (announce_score, announce_food, show_announcements).chain(),
+            );
+    }
+}
+
+/// What to say next, spoken together once per frame.
+#[derive(Resource, Default)]
+struct Announcements(Vec<String>);
+
+#[derive(Component)]
+struct Caption;
+
+fn spawn_caption(mut commands: Commands) {
+    let mut node = AccessKitNode::new(Role::Label);
+    node.set_live(Live::Polite);
+    commands.spawn((
+        Caption,
+        Text::default(),
+        TextFont {
+            font_size: 24.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        },
+        AccessibilityNode(node),
+    ));
+}
+
+fn announce_game_over(
+    mut reader: EventReader<GameOverEvent>,
+    score: Res<Score>,
+    mut announcements: ResMut<Announcements>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = reader.read().last() else {
+        return;
+    };
+    let message = match cause {
+        GameOverCause::Collision(_) => "game over",
+        GameOverCause::Finished => "finished",
+        GameOverCause::Interrupted => "new game",
+        GameOverCause::Caught => "caught",
+        GameOverCause::Won => "board filled, you win",
+    };
+    announcements
+        .0
+        .push(format!("{message}, score {}", score.0));
+}
+
+/// The reset to zero after a game over is covered by that announcement.
+fn announce_score(score: Res<Score>, mut announcements: ResMut<Announcements>) {
+    if score.is_changed() && !score.is_added() && score.0 > 0 {
+        announcements.0.push(format!("score {}", score.0));
+    }
+}
+
+/// Where the nearest food is from the head, whenever that changes.
+fn announce_food(
+    heads: Query<&Position, With<SnakeHead>>,
+    food: Query<&Position, With<Food>>,
+    mut announced: Local<Option<Position>>,
+    mut announcements: ResMut<Announcements>,
+) {
+    let Ok(&head) = heads.get_single() else {
+        return;
+    };
+    let steps = |position: &Position| head.x.abs_diff(position.x) + head.y.abs_diff(position.y);
+    let nearest = food.iter().copied().min_by_key(steps);
+    if nearest == *announced {
+        return;
+    }
+    *announced = nearest;
+    if let Some(food) = nearest {
+        announcements.0.push(format!(
+            "food {}, {} cells",
+            direction_words(food.x - head.x, food.y - head.y),
+            steps(&food)
+        ));
+    }
+}
+
+fn direction_words(dx: i32, dy: i32) -> String {
+    let vertical = match dy.signum() {
+        1 => Some("up"),
+        -1 => Some("down"),
+        _ => None,
+    };
+    let horizontal = match dx.signum() {
+        1 => Some("right"),
+        -1 => Some("left"),
+        _ => None,
+    };
+    match (vertical, horizontal) {
+        (Some(vertical), Some(horizontal)) => format!("{vertical}-{horizontal}"),
+        (Some(only), None) | (None, Some(only)) => only.to_string(),
+        (None, None) => "here".to_string(),
+    }
+}
+
+fn show_announcements(
+    mut announcements: ResMut<Announcements>,
+    mut captions: Query<(&mut Text, &mut AccessibilityNode), With<Caption>>,
+) {
+    if announcements.0.is_empty() {
+        return;
+    }
+    let message = announcements.0.join(", ");
+    announcements.0.clear();
+    info!("{message}");
+    for (mut text, mut node) in &mut captions {
+        text.0.clone_from(&message);
+        node.0.set_label(message.as_str());
+    }
+}
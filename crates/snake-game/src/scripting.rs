@@ -0,0 +1,222 @@
+//! Mods with custom rules: Lua scripts loaded with `--script <file.lua>`
+//! (the `scripting` feature, see [`crate::lua`]) and WebAssembly modules from
+//! the `mods/` folder (the `wasm-mods` feature, see [`crate::wasm_mods`]).
+//!
+//! Both kinds implement the same hooks, called by the game:
+//! - `on_tick(tick)` after every movement tick
+//! - `on_eat(x, y, score)` when the snake eats the food at `x, y`
+//! - `on_spawn(x, y)` when food appears
+//! - `on_death(cause, score)` when a run ends, with `cause` one of
+//!   [`DEATH_CAUSES`]
+//!
+//! and get the same API back: spawn food or obstacles (which last until the
+//! run ends), set the time between moves, and read the score, head and arena
+//! size. Mods run sandboxed with capped memory and a fixed budget per call,
+//! so a runaway loop fails that call instead of hanging the game. Errors are
+//! logged and the game carries on. Like cheats, a mod changing the board
+//! taints the run, so it is not kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use snake_core::{Arena, Collision, Position};
+
+use crate::{
+    replay::ReplayRecorder, snake_movement, spawn_food, spawn_obstacle, timer_finished, Food,
+    GameOverCause, GameOverEvent, GameSet, GrowthEvent, MovementTick, Run, Score, SnakeHead,
+    TickTimer,
+};
+
+/// Causes passed to `on_death`; WebAssembly mods get the index instead.
+pub const DEATH_CAUSES: [&str; 6] = ["wall", "body", "finished", "interrupted", "caught", "won"];
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Mods>()
+            .init_resource::<PendingCommands>()
+            .add_systems(
+                FixedUpdate,
+                report_death
+                    .run_if(have_mods)
+                    .run_if(on_event::<GameOverEvent>)
+                    .after(snake_movement)
+                    .before(crate::game_over)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(
+                FixedUpdate,
+                (run_hooks, apply_commands)
+                    .chain()
+                    .run_if(have_mods)
+                    .in_set(GameSet::Spawning),
+            );
+
+        #[cfg(feature = "scripting")]
+        app.add_systems(Startup, crate::lua::load_from_args);
+
+        #[cfg(feature = "wasm-mods")]
+        app.add_systems(Startup, crate::wasm_mods::load_mods_folder);
+    }
+}
+
+/// A call from the game into a mod.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Hook {
+    Tick(u32),
+    Eat {
+        food: Position,
+        score: u32,
+    },
+    Spawn(Position),
+    /// `cause` is one of [`DEATH_CAUSES`].
+    Death {
+        cause: &'static str,
+        score: u32,
+    },
+}
+
+impl Hook {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::Tick(_) => "on_tick",
+            Hook::Eat { .. } => "on_eat",
+            Hook::Spawn(_) => "on_spawn",
+            Hook::Death { .. } => "on_death",
+        }
+    }
+}
+
+/// A change to the game asked for by a mod.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScriptCommand {
+    SpawnFood(Position),
+    SpawnObstacle(Position),
+    SetSpeed(Duration),
+}
+
+/// What a mod can read about the game during a call.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct View {
+    pub score: u32,
+    pub head: Option<Position>,
+    pub arena: Arena,
+}
+
+/// A loaded script or module.
+pub trait Mod: Send + Sync + 'static {
+    /// Runs `hook` if the mod defines it, returning the changes it asked for.
+    fn call(&mut self, hook: Hook, view: View) -> Vec<ScriptCommand>;
+}
+
+/// Every loaded mod, called in load order.
+#[derive(Resource, Default)]
+pub struct Mods(pub Vec<Box<dyn Mod>>);
+
+/// Changes asked for by mods, applied together after the hooks ran.
+#[derive(Resource, Default)]
+struct PendingCommands(Vec<ScriptCommand>);
+
+fn have_mods(mods: Res<Mods>) -> bool {
+    !mods.0.is_empty()
+}
+
+fn call_all(mods: &mut Mods, hook: Hook, view: View) -> Vec<ScriptCommand> {
+    mods.0
+        .iter_mut()
+        .flat_map(|loaded| loaded.call(hook, view))
+        .collect()
+}
+
+fn current_view(score: &Score, arena: &Arena, heads: &Query<&Position, With<SnakeHead>>) -> View {
+    View {
+        score: score.0,
+        head: heads.get_single().ok().copied(),
+        arena: *arena,
+    }
+}
+
+/// Runs before [`crate::game_over`] clears the board, so mods still see the
+/// final score.
+fn report_death(
+    mut mods: ResMut<Mods>,
+    mut pending: ResMut<PendingCommands>,
+    mut game_overs: EventReader<GameOverEvent>,
+    score: Res<Score>,
+    arena: Res<Arena>,
+    heads: Query<&Position, With<SnakeHead>>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = game_overs.read().last() else {
+        return;
+    };
+    let cause = match cause {
+        GameOverCause::Collision(Collision::Wall) => DEATH_CAUSES[0],
+        GameOverCause::Collision(Collision::Body) => DEATH_CAUSES[1],
+        GameOverCause::Finished => DEATH_CAUSES[2],
+        GameOverCause::Interrupted => DEATH_CAUSES[3],
+        GameOverCause::Caught => DEATH_CAUSES[4],
+        GameOverCause::Won => DEATH_CAUSES[5],
+    };
+    let view = current_view(&score, &arena, &heads);
+    let hook = Hook::Death {
+        cause,
+        score: score.0,
+    };
+    pending.0.extend(call_all(&mut mods, hook, view));
+}
+
+fn run_hooks(
+    mut mods: ResMut<Mods>,
+    mut pending: ResMut<PendingCommands>,
+    run: Res<Run>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+    mut growth: EventReader<GrowthEvent>,
+    score: Res<Score>,
+    arena: Res<Arena>,
+    heads: Query<&Position, With<SnakeHead>>,
+    new_food: Query<&Position, Added<Food>>,
+) {
+    let view = current_view(&score, &arena, &heads);
+    let mut hooks = Vec::new();
+    if timer_finished(movement_timer) {
+        hooks.push(Hook::Tick(run.tick));
+    }
+    if growth.read().count() > 0 {
+        if let Some(food) = view.head {
+            hooks.push(Hook::Eat {
+                food,
+                score: score.0,
+            });
+        }
+    }
+    hooks.extend(new_food.iter().map(|&food| Hook::Spawn(food)));
+    for hook in hooks {
+        let asked = call_all(&mut mods, hook, view);
+        pending.0.extend(asked);
+    }
+}
+
+fn apply_commands(
+    mut commands: Commands,
+    mut pending: ResMut<PendingCommands>,
+    arena: Res<Arena>,
+    mut movement_timer: ResMut<TickTimer<MovementTick>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    for command in pending.0.drain(..) {
+        recorder.tainted = true;
+        match command {
+            ScriptCommand::SpawnFood(position) if arena.contains(position) => {
+                spawn_food(&mut commands, position);
+            }
+            ScriptCommand::SpawnObstacle(position) if arena.contains(position) => {
+                spawn_obstacle(&mut commands, position);
+            }
+            ScriptCommand::SpawnFood(position) | ScriptCommand::SpawnObstacle(position) => {
+                warn!("mod command ignored, {position:?} is outside the arena");
+            }
+            ScriptCommand::SetSpeed(interval) => movement_timer.set_interval(interval),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Player settings, saved whenever one changes and put back at startup.
+//!
+//! Volume, theme, accessibility modes, UI and text size, vsync and frame rate
+//! cap, controls, speed, arena size, assists and tail cutting are kept
+//! together in `settings.json` in the player's [`Profile`], and loaded again
+//! when the profile changes. Until the player first changes something the
+//! game follows `assets/config.ron`; from then on the saved settings win, and
+//! are put back on top of every config edit too. The saved speed is the
+//! movement interval in effect, so the console's `speed` command and mods
+//! that change the speed change it as well.
+//!
+//! Files carry a schema version. A setting added later is simply missing from
+//! older files and takes its default; one that is renamed or changes meaning
+//! gets a step in [`MIGRATIONS`] that rewrites older files on load.
+
+use std::{io, path::Path, time::Duration};
+
+use bevy::{audio::Volume, ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use snake_core::{persist, Arena, Position, MOVEMENT_INTERVAL};
+
+use crate::{
+    accessibility::{Accessibility, HIGH_CONTRAST},
+    assist::{CollisionWarning, SafePathHints},
+    config::{apply_config, ConfigApplied},
+    frame_rate::{FpsCap, Vsync},
+    mobile::TouchDpad,
+    predator::Predators,
+    profile::Profile,
+    rumble::Rumble,
+    tail_cutting::TailCutting,
+    typography::TextSize,
+    ui_scale::{MAX_UI_SCALE, MIN_UI_SCALE},
+    Controls, Food, MovementTick, Theme, TickTimer,
+};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Schema version written to new files.
+pub const SETTINGS_VERSION: u32 = 1;
+/// Upgrades a file from version `n + 1` to `n + 2`, one step per schema
+/// change, oldest first.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[];
+
+/// Loads the profile's settings and saves them from then on.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .add_event::<ConfigApplied>()
+            .add_systems(
+                Update,
+                (
+                    load_settings.run_if(resource_changed::<Profile>),
+                    apply_settings.run_if(
+                        resource_exists_and_changed::<Settings>.or(on_event::<ConfigApplied>),
+                    ),
+                    save_settings,
+                )
+                    .chain()
+                    .after(apply_config),
+            );
+    }
+}
+
+/// The saved settings. Only present once there is something saved.
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Master volume, from 0 (muted) to 1.
+    pub volume: f32,
+    /// Colors used while high contrast is off.
+    pub(crate) theme: Theme,
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+    /// See [`crate::ui_scale`].
+    pub ui_scale: f32,
+    /// See [`crate::typography`].
+    pub text_size: TextSize,
+    /// See [`crate::frame_rate`].
+    pub vsync: bool,
+    pub fps_cap: FpsCap,
+    pub controls: Controls,
+    pub movement_interval_ms: u64,
+    pub arena: Arena,
+    pub rumble_intensity: f32,
+    /// See [`crate::mobile`].
+    pub touch_dpad: bool,
+    pub collision_warning: bool,
+    pub warning_blip: bool,
+    pub safe_path_hints: bool,
+    pub tail_cutting: bool,
+    pub predator: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            volume: 1.0,
+            theme: Theme::default(),
+            high_contrast: false,
+            reduced_motion: false,
+            ui_scale: 1.0,
+            text_size: TextSize::default(),
+            vsync: Vsync::default().0,
+            fps_cap: FpsCap::default(),
+            controls: Controls::default(),
+            movement_interval_ms: MOVEMENT_INTERVAL.as_millis() as u64,
+            arena: Arena::default(),
+            rumble_intensity: Rumble::default().intensity,
+            touch_dpad: TouchDpad::default().enabled,
+            collision_warning: CollisionWarning::default().enabled,
+            warning_blip: CollisionWarning::default().blip,
+            safe_path_hints: SafePathHints::default().enabled,
+            tail_cutting: false,
+            predator: false,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("could not access settings file: {0}")]
+    Io(#[from] io::Error),
+    #[error("settings file is malformed: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("settings file has no version")]
+    Unversioned,
+}
+
+impl Settings {
+    pub fn write(&self, path: &Path) -> Result<(), SettingsError> {
+        persist::write_atomic(path, &serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Loads settings saved by this or an older version of the game,
+    /// migrating them to the current schema. Falls back to the previous file
+    /// if this one is malformed.
+    pub fn read(path: &Path) -> Result<Self, SettingsError> {
+        persist::read_with_backup(path, |bytes| {
+            let mut fields: Map<String, Value> = serde_json::from_slice(bytes)?;
+            let version = fields
+                .get("version")
+                .and_then(Value::as_u64)
+                .ok_or(SettingsError::Unversioned)?;
+            if version > SETTINGS_VERSION as u64 {
+                warn!("settings were saved by a newer version, keeping what this one knows");
+            }
+            for migrate in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+                migrate(&mut fields);
+            }
+            fields.insert("version".into(), SETTINGS_VERSION.into());
+            Ok(serde_json::from_value(Value::Object(fields))?)
+        })
+    }
+}
+
+/// The game state each setting is read from and applied to. Everything but
+/// the board, theme, controls and speed is optional so the settings also
+/// work without audio, UI, a window, rumble, touch controls or assists.
+#[derive(SystemParam)]
+struct Live<'w> {
+    volume: Option<ResMut<'w, GlobalVolume>>,
+    theme: ResMut<'w, Theme>,
+    accessibility: ResMut<'w, Accessibility>,
+    ui_scale: Option<ResMut<'w, UiScale>>,
+    text_size: Option<ResMut<'w, TextSize>>,
+    vsync: Option<ResMut<'w, Vsync>>,
+    fps_cap: Option<ResMut<'w, FpsCap>>,
+    controls: ResMut<'w, Controls>,
+    movement_timer: ResMut<'w, TickTimer<MovementTick>>,
+    arena: ResMut<'w, Arena>,
+    rumble: Option<ResMut<'w, Rumble>>,
+    dpad: Option<ResMut<'w, TouchDpad>>,
+    warning: Option<ResMut<'w, CollisionWarning>>,
+    hints: Option<ResMut<'w, SafePathHints>>,
+    tail_cutting: ResMut<'w, TailCutting>,
+    predators: ResMut<'w, Predators>,
+}
+
+impl Live<'_> {
+    /// Current settings, with those the game cannot show taken from `saved`.
+    fn capture(&self, saved: &Settings) -> Settings {
+        let accessibility = *self.accessibility;
+        Settings {
+            version: SETTINGS_VERSION,
+            volume: self
+                .volume
+                .as_ref()
+                .map_or(saved.volume, |global| global.volume.get()),
+            theme: if accessibility.high_contrast {
+                saved.theme.clone()
+            } else {
+                self.theme.clone()
+            },
+            high_contrast: accessibility.high_contrast,
+            reduced_motion: accessibility.reduced_motion,
+            ui_scale: self
+                .ui_scale
+                .as_ref()
+                .map_or(saved.ui_scale, |scale| scale.0),
+            text_size: self
+                .text_size
+                .as_deref()
+                .map_or(saved.text_size, |size| *size),
+            vsync: self.vsync.as_deref().map_or(saved.vsync, |vsync| vsync.0),
+            fps_cap: self.fps_cap.as_deref().map_or(saved.fps_cap, |cap| *cap),
+            controls: *self.controls,
+            movement_interval_ms: self.movement_timer.interval().as_millis() as u64,
+            arena: *self.arena,
+            rumble_intensity: self
+                .rumble
+                .as_ref()
+                .map_or(saved.rumble_intensity, |rumble| rumble.intensity),
+            touch_dpad: self
+                .dpad
+                .as_ref()
+                .map_or(saved.touch_dpad, |dpad| dpad.enabled),
+            collision_warning: self
+                .warning
+                .as_ref()
+                .map_or(saved.collision_warning, |warning| warning.enabled),
+            warning_blip: self
+                .warning
+                .as_ref()
+                .map_or(saved.warning_blip, |warning| warning.blip),
+            safe_path_hints: self
+                .hints
+                .as_ref()
+                .map_or(saved.safe_path_hints, |hints| hints.enabled),
+            tail_cutting: self.tail_cutting.0,
+            predator: self.predators.0,
+        }
+    }
+
+    /// Returns whether the arena changed.
+    fn apply(&mut self, settings: &Settings) -> bool {
+        if let Some(global) = self.volume.as_mut() {
+            global.volume = Volume::new(settings.volume.clamp(0.0, 1.0));
+        }
+        self.accessibility.set_if_neq(Accessibility {
+            high_contrast: settings.high_contrast,
+            reduced_motion: settings.reduced_motion,
+        });
+        self.theme.set_if_neq(if settings.high_contrast {
+            HIGH_CONTRAST
+        } else {
+            settings.theme.clone()
+        });
+        if let Some(scale) = self.ui_scale.as_mut() {
+            let wanted = settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+            if scale.0 != wanted {
+                scale.0 = wanted;
+            }
+        }
+        if let Some(size) = self.text_size.as_mut() {
+            size.set_if_neq(settings.text_size);
+        }
+        if let Some(vsync) = self.vsync.as_mut() {
+            vsync.set_if_neq(Vsync(settings.vsync));
+        }
+        if let Some(cap) = self.fps_cap.as_mut() {
+            cap.set_if_neq(settings.fps_cap);
+        }
+        self.controls.set_if_neq(settings.controls);
+        self.movement_timer
+            .set_interval(Duration::from_millis(settings.movement_interval_ms.max(1)));
+        if let Some(rumble) = self.rumble.as_mut() {
+            rumble.intensity = settings.rumble_intensity.clamp(0.0, 1.0);
+        }
+        if let Some(dpad) = self.dpad.as_mut() {
+            dpad.set_if_neq(TouchDpad {
+                enabled: settings.touch_dpad,
+            });
+        }
+        if let Some(warning) = self.warning.as_mut() {
+            warning.set_if_neq(CollisionWarning {
+                enabled: settings.collision_warning,
+                blip: settings.warning_blip,
+            });
+        }
+        if let Some(hints) = self.hints.as_mut() {
+            hints.enabled = settings.safe_path_hints;
+        }
+        self.tail_cutting
+            .set_if_neq(TailCutting(settings.tail_cutting));
+        self.predators.set_if_neq(Predators(settings.predator));
+        settings.arena.width > 0
+            && settings.arena.height > 0
+            && self.arena.set_if_neq(settings.arena)
+    }
+}
+
+fn load_settings(mut commands: Commands, profile: Res<Profile>) {
+    match Settings::read(&profile.path(SETTINGS_FILE)) {
+        Ok(settings) => commands.insert_resource(settings),
+        Err(err) => {
+            if !matches!(&err, SettingsError::Io(err) if err.kind() == io::ErrorKind::NotFound) {
+                warn!("ignoring saved settings: {err}");
+            }
+            commands.remove_resource::<Settings>();
+        }
+    }
+}
+
+/// Puts the saved settings back when they are loaded and after every config
+/// change. Food left outside a shrunken arena is removed.
+fn apply_settings(
+    mut commands: Commands,
+    settings: Option<Res<Settings>>,
+    mut live: Live,
+    food: Query<(Entity, &Position), With<Food>>,
+) {
+    let Some(settings) = settings else {
+        return;
+    };
+    if live.apply(&settings) {
+        for (entity, position) in food.iter() {
+            if !live.arena.contains(*position) {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Saves the settings whenever the player changes one. Changes made by
+/// applying the config, switching profiles or the saved settings themselves
+/// are not the player's, so they only move the baseline.
+fn save_settings(
+    mut commands: Commands,
+    live: Live,
+    settings: Option<ResMut<Settings>>,
+    profile: Res<Profile>,
+    mut config_applied: EventReader<ConfigApplied>,
+    mut last: Local<Option<Settings>>,
+) {
+    let now = live.capture(settings.as_deref().unwrap_or(&Settings::default()));
+    let reconfigured = config_applied.read().count() > 0 || profile.is_changed();
+    let changed = last.as_ref().is_some_and(|last| *last != now);
+    *last = Some(now.clone());
+    if !changed || reconfigured {
+        return;
+    }
+    let path = profile.path(SETTINGS_FILE);
+    match now.write(&path) {
+        Ok(()) => debug!("saved settings to {}", path.display()),
+        Err(err) => warn!("could not save settings to {}: {err}", path.display()),
+    }
+    match settings {
+        Some(mut settings) => *settings = now,
+        None => commands.insert_resource(now),
+    }
+}
@@ -0,0 +1,161 @@
+//! The snake's body drawn as one mesh, so a snake of a thousand segments costs
+//! about as much to draw as one of ten.
+//!
+//! Body segments stay entities with their own [`Sprite`], so everything that
+//! tints, hides or outlines them keeps working, but they are moved to
+//! [`BATCHED_LAYER`], which no camera draws. Instead a single mesh with a
+//! quad per visible segment, in each segment's color and cut from its texture
+//! atlas if it has one, is drawn in their place, and rebuilt only when a
+//! segment moves, changes color or blinks. The head keeps its own sprite.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        view::RenderLayers,
+    },
+};
+
+use crate::{GameSet, Segments, SnakeHead, SnakeSegment};
+
+/// Render layer the body segments' own sprites are moved to.
+pub const BATCHED_LAYER: usize = 1;
+/// Depth of the body: under the head and food, over segment outlines.
+const BODY_Z: f32 = -0.1;
+
+/// Draws the body as one mesh. Needs meshes and materials, so it is left out
+/// of [`crate::SnakeGamePlugin`].
+pub struct SnakeMeshPlugin;
+
+impl Plugin for SnakeMeshPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, batch_segments).add_systems(
+            PostUpdate,
+            build_body
+                .after(GameSet::Presentation)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The mesh the body is drawn with.
+#[derive(Component)]
+pub struct SnakeBody;
+
+/// A segment drawn as part of the [`SnakeBody`] rather than by its sprite.
+#[derive(Component)]
+pub struct Batched;
+
+fn batch_segments(
+    mut commands: Commands,
+    segments: Query<Entity, (Added<SnakeSegment>, Without<SnakeHead>, Without<Batched>)>,
+) {
+    for segment in &segments {
+        commands
+            .entity(segment)
+            .insert((Batched, RenderLayers::layer(BATCHED_LAYER)));
+    }
+}
+
+/// Rebuilds the body from every snake's segments whenever any of them changed,
+/// making the mesh the first time it is needed.
+fn build_body(
+    mut commands: Commands,
+    snakes: Query<Ref<Segments>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    layouts: Option<Res<Assets<TextureAtlasLayout>>>,
+    bodies: Query<(&Mesh2d, &MeshMaterial2d<ColorMaterial>), With<SnakeBody>>,
+    segments: Query<(&Transform, &Sprite, &Visibility), With<Batched>>,
+    changed: Query<
+        (),
+        (
+            With<Batched>,
+            Or<(Changed<Transform>, Changed<Sprite>, Changed<Visibility>)>,
+        ),
+    >,
+) {
+    let body = bodies.get_single().ok();
+    let reordered = snakes.iter().any(|order| order.is_changed());
+    if body.is_some() && !reordered && changed.is_empty() {
+        return;
+    }
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    let mut texture = None;
+    for (transform, sprite, visibility) in snakes
+        .iter()
+        .flat_map(|order| order.0.clone())
+        .filter_map(|segment| segments.get(segment).ok())
+    {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        let center = transform.translation.truncate();
+        let half = transform.scale.truncate() / 2.0;
+        let cell = sprite.texture_atlas.as_ref().and_then(|cell| {
+            let layout = layouts.as_ref()?.get(&cell.layout)?;
+            let rect = layout.textures.get(cell.index)?.as_rect();
+            Some(Rect::from_corners(
+                rect.min / layout.size.as_vec2(),
+                rect.max / layout.size.as_vec2(),
+            ))
+        });
+        if cell.is_some() {
+            texture.get_or_insert_with(|| sprite.image.clone());
+        }
+        let cell = cell.unwrap_or(Rect::new(0.0, 0.0, 1.0, 1.0));
+        let first = positions.len() as u32;
+        // Image rows run downwards, so the bottom corners take the cell's
+        // bottom edge.
+        for (corner, uv) in [
+            (
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(cell.min.x, cell.max.y),
+            ),
+            (Vec2::new(half.x, -half.y), cell.max),
+            (Vec2::new(half.x, half.y), Vec2::new(cell.max.x, cell.min.y)),
+            (Vec2::new(-half.x, half.y), cell.min),
+        ] {
+            positions.push((center + corner).extend(0.0).to_array());
+            colors.push(sprite.color.to_linear().to_f32_array());
+            uvs.push(uv.to_array());
+        }
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+    let mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices));
+    if let Some((_, material)) = body {
+        if materials
+            .get(&material.0)
+            .is_some_and(|material| material.texture != texture)
+        {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.texture = texture.clone();
+            }
+        }
+    }
+    match body.and_then(|(body, _)| meshes.get_mut(&body.0)) {
+        Some(existing) => *existing = mesh,
+        None => {
+            commands.spawn((
+                SnakeBody,
+                Mesh2d(meshes.add(mesh)),
+                MeshMaterial2d(materials.add(ColorMaterial {
+                    texture,
+                    ..ColorMaterial::from(Color::WHITE)
+                })),
+                Transform::from_xyz(0.0, 0.0, BODY_Z),
+            ));
+        }
+    }
+}
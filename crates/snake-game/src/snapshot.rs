@@ -0,0 +1,486 @@
+use std::{io, path::Path, time::Duration};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use snake_core::{persist, Arena, Direction, Position};
+
+use crate::{
+    armor::{spawn_armor, Armor, ArmorPickup},
+    critters::{spawn_critter_at, Critter},
+    day_night::{spawn_bonus, spawn_golden, Expires},
+    eggs::{spawn_egg, Egg, HATCH_AFTER},
+    gates::{spawn_gate, spawn_key, Gate, Key},
+    lives::Lives,
+    plants::{spawn_grown_plant, Plant, Stage},
+    power_ups::{spawn_power_up, Effect, Pickup, PowerUp, TimedEffects},
+    predator::{spawn_predator, Predator},
+    profile::Profile,
+    release_segment,
+    replay::ReplayRecorder,
+    spawn_food, spawn_head, spawn_obstacle, spawn_segment,
+    venom::{spawn_spit, spawn_venom_sac, Spit, Venom, VenomSac},
+    weather::{bury, Drifted},
+    Food, GameRng, LastTailPosition, MovementTick, Obstacle, Score, SegmentPool, Segments,
+    SnakeHead, SnakeSegment, TickTimer, Worth,
+};
+
+const QUICKSAVE_PATH: &str = "quicksave.json";
+
+/// Everything needed to put the run back as it was: the snake, everything on
+/// the board, the effects running, armor, venom and speed, and the RNG so
+/// food keeps spawning in the same places after a quickload. The timers for
+/// when the next pickup, plant or sac turns up carry on as they were.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub arena_width: u32,
+    pub arena_height: u32,
+    pub direction: Direction,
+    /// Segment positions, head first.
+    pub segments: Vec<Position>,
+    pub last_tail_position: Option<Position>,
+    /// Plain food in plain sight.
+    pub food: Vec<Position>,
+    /// Every other piece of food.
+    #[serde(default)]
+    pub special_food: Vec<SavedFood>,
+    /// Missing from quicksaves made before the rest of the board was kept,
+    /// which leave it as it is.
+    #[serde(default)]
+    pub board: Option<BoardSnapshot>,
+    pub score: u32,
+    pub rng: ChaCha8Rng,
+    /// Missing from quicksaves made before the speed was kept, which leave
+    /// it as it is.
+    #[serde(default)]
+    pub movement_interval: Option<Duration>,
+}
+
+/// A piece of food other than plain food in plain sight.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedFood {
+    pub position: Position,
+    pub kind: FoodKind,
+    /// Whether it lies buried under a snow drift.
+    pub drifted: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum FoodKind {
+    Plain,
+    Golden,
+    /// Bonus food, `elapsed` into its time on the board.
+    Bonus {
+        elapsed: Duration,
+    },
+    /// An egg, `elapsed` into its time to hatch.
+    Egg {
+        elapsed: Duration,
+    },
+    VenomSac,
+}
+
+/// What else is on the board besides the snake and its food.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct BoardSnapshot {
+    /// Walls, gates left out.
+    pub obstacles: Vec<Position>,
+    /// Gate cells, with the lock each belongs to.
+    pub gates: Vec<(usize, Position)>,
+    /// Keys, with the lock each opens.
+    pub keys: Vec<(usize, Position)>,
+    pub critters: Vec<Position>,
+    pub spare_lives: u32,
+    /// Power-ups waiting to be eaten. This and the rest are missing from
+    /// quicksaves made before they were kept, which have none of them.
+    #[serde(default)]
+    pub pickups: Vec<(PowerUp, Position)>,
+    #[serde(default)]
+    pub armor_pickups: Vec<Position>,
+    /// Effects running, with how long each has left.
+    #[serde(default)]
+    pub effects: Vec<(Effect, Duration)>,
+    /// Hit points of armor the snake carries.
+    #[serde(default)]
+    pub armor: u32,
+    /// Spits the snake holds.
+    #[serde(default)]
+    pub venom: u32,
+    /// Globs of venom in flight, with the way each flies.
+    #[serde(default)]
+    pub spit: Vec<(Position, Direction)>,
+    #[serde(default)]
+    pub predator: Option<Position>,
+    /// Plants, grown ones included; they are left out of `obstacles`.
+    #[serde(default)]
+    pub plants: Vec<SavedPlant>,
+}
+
+/// A plant and how far it has grown.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedPlant {
+    pub position: Position,
+    pub stage: Stage,
+    /// Time grown of [`crate::plants::GROW_FOR`].
+    pub grown: Duration,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("could not access snapshot file: {0}")]
+    Io(#[from] io::Error),
+    #[error("snapshot file is malformed: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("snapshot was taken on a {0}x{1} arena")]
+    ArenaMismatch(u32, u32),
+    #[error("snapshot has no snake")]
+    Empty,
+    #[error("there is no snake to save")]
+    NoSnake,
+}
+
+impl GameSnapshot {
+    pub fn write(&self, path: &Path) -> Result<(), SnapshotError> {
+        persist::write_atomic(path, &serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Loads a snapshot, checking that it was taken on an arena of the given
+    /// size. Falls back to the previous quicksave if this one is malformed.
+    pub fn read(path: &Path, arena: Arena) -> Result<Self, SnapshotError> {
+        let snapshot: Self = persist::read_with_backup(path, |bytes| {
+            serde_json::from_slice(bytes).map_err(SnapshotError::from)
+        })?;
+        if snapshot.arena_width != arena.width || snapshot.arena_height != arena.height {
+            return Err(SnapshotError::ArenaMismatch(
+                snapshot.arena_width,
+                snapshot.arena_height,
+            ));
+        }
+        if snapshot.segments.is_empty() {
+            return Err(SnapshotError::Empty);
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Read access to everything a [`GameSnapshot`] is built from.
+#[derive(SystemParam)]
+pub struct SnapshotSource<'w, 's> {
+    arena: Res<'w, Arena>,
+    heads: Query<
+        'w,
+        's,
+        (
+            &'static SnakeHead,
+            &'static Segments,
+            &'static LastTailPosition,
+        ),
+    >,
+    positions: Query<'w, 's, &'static Position>,
+    food: Query<
+        'w,
+        's,
+        (
+            &'static Position,
+            Option<&'static Worth>,
+            Option<&'static Expires>,
+            Option<&'static Egg>,
+            Has<VenomSac>,
+            Has<Drifted>,
+        ),
+        With<Food>,
+    >,
+    obstacles:
+        Query<'w, 's, (&'static Position, Option<&'static Gate>), (With<Obstacle>, Without<Plant>)>,
+    keys: Query<'w, 's, (&'static Position, &'static Key)>,
+    critters: Query<'w, 's, &'static Position, With<Critter>>,
+    pickups: Query<'w, 's, (&'static Position, &'static Pickup)>,
+    armor_pickups: Query<'w, 's, &'static Position, With<ArmorPickup>>,
+    spit: Query<'w, 's, (&'static Position, &'static Spit)>,
+    predators: Query<'w, 's, &'static Position, With<Predator>>,
+    plants: Query<'w, 's, (&'static Position, &'static Plant)>,
+    effects: Option<Res<'w, TimedEffects>>,
+    armor: Option<Res<'w, Armor>>,
+    venom: Option<Res<'w, Venom>>,
+    lives: Res<'w, Lives>,
+    movement_timer: Res<'w, TickTimer<MovementTick>>,
+    score: Res<'w, Score>,
+    rng: Res<'w, GameRng>,
+}
+
+impl SnapshotSource<'_, '_> {
+    pub fn capture(&self) -> Result<GameSnapshot, SnapshotError> {
+        let (head, segments, last_tail_position) =
+            self.heads.iter().next().ok_or(SnapshotError::NoSnake)?;
+        let mut plain = Vec::new();
+        let mut special_food = Vec::new();
+        for (&position, worth, expires, egg, venom_sac, drifted) in &self.food {
+            let kind = if let Some(egg) = egg {
+                FoodKind::Egg {
+                    elapsed: egg.0.elapsed(),
+                }
+            } else if let Some(expires) = expires {
+                FoodKind::Bonus {
+                    elapsed: expires.0.elapsed(),
+                }
+            } else if venom_sac {
+                FoodKind::VenomSac
+            } else if worth.is_some() {
+                FoodKind::Golden
+            } else if !drifted {
+                plain.push(position);
+                continue;
+            } else {
+                FoodKind::Plain
+            };
+            special_food.push(SavedFood {
+                position,
+                kind,
+                drifted,
+            });
+        }
+        let mut board = BoardSnapshot {
+            keys: self
+                .keys
+                .iter()
+                .map(|(&position, key)| (key.0, position))
+                .collect(),
+            critters: self.critters.iter().copied().collect(),
+            spare_lives: self.lives.spare,
+            pickups: self
+                .pickups
+                .iter()
+                .map(|(&position, pickup)| (pickup.0, position))
+                .collect(),
+            armor_pickups: self.armor_pickups.iter().copied().collect(),
+            effects: self
+                .effects
+                .as_ref()
+                .map_or(Vec::new(), |effects| effects.remaining().collect()),
+            armor: self.armor.as_ref().map_or(0, |armor| armor.hit_points),
+            venom: self.venom.as_ref().map_or(0, |venom| venom.charges),
+            spit: self
+                .spit
+                .iter()
+                .map(|(&position, spit)| (position, spit.0))
+                .collect(),
+            predator: self.predators.iter().next().copied(),
+            plants: self
+                .plants
+                .iter()
+                .map(|(&position, plant)| SavedPlant {
+                    position,
+                    stage: plant.stage,
+                    grown: plant.growth.elapsed(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (&position, gate) in &self.obstacles {
+            match gate {
+                Some(gate) => board.gates.push((gate.0, position)),
+                None => board.obstacles.push(position),
+            }
+        }
+        Ok(GameSnapshot {
+            arena_width: self.arena.width,
+            arena_height: self.arena.height,
+            direction: head.direction,
+            segments: self.positions.iter_many(&segments.0).copied().collect(),
+            last_tail_position: last_tail_position.0,
+            food: plain,
+            special_food,
+            board: Some(board),
+            score: self.score.0,
+            rng: self.rng.0.clone(),
+            movement_interval: Some(self.movement_timer.interval()),
+        })
+    }
+}
+
+/// Write access to everything a [`GameSnapshot`] restores.
+#[derive(SystemParam)]
+pub struct SnapshotTarget<'w, 's> {
+    commands: Commands<'w, 's>,
+    pool: ResMut<'w, SegmentPool>,
+    score: ResMut<'w, Score>,
+    rng: ResMut<'w, GameRng>,
+    food: Query<'w, 's, (Entity, Option<&'static Drifted>), With<Food>>,
+    heads: Query<'w, 's, Entity, With<SnakeHead>>,
+    body: Query<'w, 's, Entity, (With<SnakeSegment>, Without<SnakeHead>)>,
+    board: Query<
+        'w,
+        's,
+        Entity,
+        Or<(
+            With<Obstacle>,
+            With<Key>,
+            With<Critter>,
+            With<Plant>,
+            With<Pickup>,
+            With<ArmorPickup>,
+            With<Spit>,
+            With<Predator>,
+        )>,
+    >,
+    effects: Option<ResMut<'w, TimedEffects>>,
+    armor: Option<ResMut<'w, Armor>>,
+    venom: Option<ResMut<'w, Venom>>,
+    lives: ResMut<'w, Lives>,
+    movement_timer: ResMut<'w, TickTimer<MovementTick>>,
+    recorder: ResMut<'w, ReplayRecorder>,
+}
+
+impl SnapshotTarget<'_, '_> {
+    /// Replaces the snake, food, score, speed and RNG with the ones in
+    /// `snapshot`, and the rest of the board too if it has it. Body segments
+    /// go back to the pool rather than being despawned.
+    pub fn restore(&mut self, snapshot: &GameSnapshot) {
+        for (food, drifted) in &self.food {
+            self.commands.entity(food).despawn();
+            if let Some(drifted) = drifted {
+                self.commands.entity(drifted.0).despawn();
+            }
+        }
+        for head in &self.heads {
+            self.commands.entity(head).despawn();
+        }
+        for segment in self.body.iter() {
+            release_segment(&mut self.commands, &mut self.pool, segment);
+        }
+        let (&head, body) = snapshot
+            .segments
+            .split_first()
+            .expect("snapshots are validated to contain a head");
+        let head = spawn_head(&mut self.commands, head, snapshot.direction);
+        let segments = std::iter::once(head)
+            .chain(
+                body.iter()
+                    .map(|&position| spawn_segment(&mut self.commands, &mut self.pool, position)),
+            )
+            .collect();
+        self.commands.entity(head).insert((
+            Segments(segments),
+            LastTailPosition(snapshot.last_tail_position),
+        ));
+        for &position in &snapshot.food {
+            spawn_food(&mut self.commands, position);
+        }
+        for food in &snapshot.special_food {
+            self.spawn_saved_food(food);
+        }
+        if let Some(board) = &snapshot.board {
+            self.restore_board(board);
+        }
+        if let Some(interval) = snapshot.movement_interval {
+            self.movement_timer.set_interval(interval);
+        }
+        self.score.0 = snapshot.score;
+        self.rng.0 = snapshot.rng.clone();
+        self.recorder.tainted = true;
+        self.recorder.cheated = true;
+    }
+
+    fn spawn_saved_food(&mut self, food: &SavedFood) {
+        let commands = &mut self.commands;
+        let entity = match food.kind {
+            FoodKind::Plain => spawn_food(commands, food.position),
+            FoodKind::Golden => spawn_golden(commands, food.position),
+            FoodKind::Bonus { elapsed } => spawn_bonus(commands, food.position, elapsed),
+            FoodKind::Egg { elapsed } => {
+                let egg = spawn_egg(commands, food.position);
+                let mut timer = Timer::new(HATCH_AFTER, TimerMode::Once);
+                timer.set_elapsed(elapsed);
+                commands.entity(egg).insert(Egg(timer));
+                egg
+            }
+            FoodKind::VenomSac => spawn_venom_sac(commands, food.position),
+        };
+        if food.drifted {
+            bury(commands, entity, food.position);
+        }
+    }
+
+    fn restore_board(&mut self, board: &BoardSnapshot) {
+        for entity in &self.board {
+            self.commands.entity(entity).despawn();
+        }
+        for &position in &board.obstacles {
+            spawn_obstacle(&mut self.commands, position);
+        }
+        for &(lock, position) in &board.gates {
+            spawn_gate(&mut self.commands, lock, position);
+        }
+        for &(lock, position) in &board.keys {
+            spawn_key(&mut self.commands, lock, position);
+        }
+        for &position in &board.critters {
+            spawn_critter_at(&mut self.commands, position);
+        }
+        for &(power_up, position) in &board.pickups {
+            spawn_power_up(&mut self.commands, power_up, position);
+        }
+        for &position in &board.armor_pickups {
+            spawn_armor(&mut self.commands, position);
+        }
+        for &(position, direction) in &board.spit {
+            spawn_spit(&mut self.commands, position, direction);
+        }
+        if let Some(position) = board.predator {
+            spawn_predator(&mut self.commands, position);
+        }
+        for plant in &board.plants {
+            spawn_grown_plant(&mut self.commands, plant.position, plant.stage, plant.grown);
+        }
+        if let Some(effects) = self.effects.as_mut() {
+            effects.clear();
+            for &(effect, remaining) in &board.effects {
+                effects.start(effect, remaining);
+            }
+        }
+        if let Some(armor) = self.armor.as_mut() {
+            armor.hit_points = board.armor;
+        }
+        if let Some(venom) = self.venom.as_mut() {
+            venom.charges = board.venom;
+        }
+        self.lives.spare = board.spare_lives;
+    }
+}
+
+pub fn quicksave(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<Profile>,
+    source: SnapshotSource,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F5) {
+        return;
+    }
+    let path = profile.path(QUICKSAVE_PATH);
+    let result = source.capture().and_then(|snapshot| snapshot.write(&path));
+    match result {
+        Ok(()) => info!("quicksaved to {}", path.display()),
+        Err(err) => error!("quicksave failed: {err}"),
+    }
+}
+
+pub fn quickload(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    arena: Res<Arena>,
+    profile: Res<Profile>,
+    mut target: SnapshotTarget,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F9) {
+        return;
+    }
+    let path = profile.path(QUICKSAVE_PATH);
+    match GameSnapshot::read(&path, *arena) {
+        Ok(snapshot) => {
+            target.restore(&snapshot);
+            info!("quickloaded from {}", path.display());
+        }
+        Err(err) => error!("quickload failed: {err}"),
+    }
+}
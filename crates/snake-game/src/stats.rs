@@ -0,0 +1,392 @@
+//! Per-run statistics and the stats screen.
+//!
+//! Every run a person plays is recorded when it ends, as one JSON line in
+//! `runs.jsonl` in their [`Profile`]: when it ended, mode, score, duration,
+//! food eaten by kind, longest length, average speed, close calls, left turns,
+//! and what ended it and where. A close call is a move that turned away from a
+//! crash: keeping straight on would have hit something. F2 shows totals and
+//! bests over the whole history.
+
+use std::{collections::BTreeMap, fmt::Write as _, io, path::Path, time::Duration};
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
+use snake_core::{
+    collision, persist, Arena, Collision, Direction, GameMode, Position, START_DIRECTION,
+};
+
+use crate::{
+    bot::bot_playing, daily::practicing, game_over, ghost::speedrun_goal, profile::Profile,
+    replay::run_cheated, timer_finished, victory::free_playing, GameOverCause, GameOverEvent,
+    GameSet, GrowthEvent, MovementTick, Score, Segments, SnakeHead, TickTimer,
+};
+
+pub const HISTORY_FILE: &str = "runs.jsonl";
+/// The only kind of food so far.
+const PLAIN_FOOD: &str = "plain";
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .init_resource::<RunStats>()
+            .add_event::<RunRecorded>()
+            .add_systems(Startup, spawn_screen)
+            .add_systems(
+                FixedUpdate,
+                (
+                    track_run,
+                    (
+                        record_run
+                            .run_if(not(bot_playing))
+                            .run_if(not(run_cheated))
+                            .run_if(not(practicing))
+                            .run_if(not(free_playing)),
+                        |mut stats: ResMut<RunStats>| *stats = RunStats::default(),
+                    )
+                        .chain()
+                        .run_if(on_event::<GameOverEvent>),
+                )
+                    .chain()
+                    // Sees the run end however it ended, finishing a speedrun too.
+                    .after(speedrun_goal)
+                    .before(game_over)
+                    .run_if(timer_finished::<MovementTick>)
+                    .in_set(GameSet::Logic),
+            )
+            .add_systems(Update, toggle_screen);
+    }
+}
+
+/// The run in progress, so far.
+#[derive(Resource)]
+struct RunStats {
+    ticks: u32,
+    duration: Duration,
+    foods: BTreeMap<String, u32>,
+    max_length: usize,
+    close_calls: u32,
+    /// Whether keeping straight on from here would crash.
+    in_danger: bool,
+    /// The way the head went on the last tick, or starts out going.
+    direction: Direction,
+    left_turns: u32,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            ticks: 0,
+            duration: Duration::ZERO,
+            foods: BTreeMap::new(),
+            max_length: 0,
+            close_calls: 0,
+            in_danger: false,
+            direction: START_DIRECTION,
+            left_turns: 0,
+        }
+    }
+}
+
+/// One finished run, as kept in the history.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch.
+    pub ended_at: u64,
+    pub mode: GameMode,
+    pub score: u32,
+    pub duration_ms: u64,
+    pub foods: BTreeMap<String, u32>,
+    pub max_length: usize,
+    /// Cells moved per second.
+    pub average_speed: f32,
+    pub close_calls: u32,
+    /// Missing from runs recorded before turns were counted.
+    #[serde(default)]
+    pub left_turns: u32,
+    /// `wall`, `body`, `finished`, `interrupted` or `caught`.
+    pub cause: String,
+    /// The cell the snake crashed into, if it crashed.
+    pub cell: Option<Position>,
+}
+
+/// Sent with each run as it is added to the history.
+#[derive(Event, Clone, Debug)]
+pub struct RunRecorded(pub RunRecord);
+
+/// Reads every run recorded in `path`, skipping lines that do not parse.
+pub fn read_history(path: &Path) -> io::Result<Vec<RunRecord>> {
+    persist::read_with_backup(path, |bytes| {
+        let text = std::str::from_utf8(bytes).map_err(io::Error::other)?;
+        Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    })
+}
+
+/// Combines two copies of a history file, with each run once and the oldest
+/// first.
+pub fn merge_history(ours: &[u8], theirs: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Ended {
+        ended_at: u64,
+    }
+    let (ours, theirs) = (
+        String::from_utf8_lossy(ours),
+        String::from_utf8_lossy(theirs),
+    );
+    let mut lines: Vec<(u64, &str)> = ours
+        .lines()
+        .chain(theirs.lines())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let ended_at = serde_json::from_str(line).map_or(0, |Ended { ended_at }| ended_at);
+            (ended_at, line)
+        })
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    let mut bytes = Vec::new();
+    for (_, line) in lines {
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+    }
+    bytes
+}
+
+fn append_to_history(path: &Path, record: &RunRecord) -> io::Result<()> {
+    let mut bytes =
+        match persist::read_with_backup(path, |bytes| Ok::<_, io::Error>(bytes.to_vec())) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+    serde_json::to_writer(&mut bytes, record)?;
+    bytes.push(b'\n');
+    persist::write_atomic(path, &bytes)
+}
+
+/// Totals and bests over a run history.
+#[derive(Default, PartialEq, Debug)]
+pub struct Aggregates {
+    pub runs: usize,
+    pub total_time: Duration,
+    pub foods: BTreeMap<String, u32>,
+    pub best_score: u32,
+    pub average_score: f32,
+    pub longest: usize,
+    pub average_speed: f32,
+    pub close_calls: u32,
+    pub causes: BTreeMap<String, usize>,
+    /// The cell crashed into most often, and how often.
+    pub deadliest_cell: Option<(Position, usize)>,
+}
+
+impl Aggregates {
+    pub fn new(history: &[RunRecord]) -> Self {
+        let mut aggregates = Self {
+            runs: history.len(),
+            ..Default::default()
+        };
+        if history.is_empty() {
+            return aggregates;
+        }
+        let mut cells: BTreeMap<(i32, i32), usize> = BTreeMap::new();
+        let mut total_score = 0;
+        let mut total_speed = 0.0;
+        for run in history {
+            aggregates.total_time += Duration::from_millis(run.duration_ms);
+            for (kind, count) in &run.foods {
+                *aggregates.foods.entry(kind.clone()).or_default() += count;
+            }
+            aggregates.best_score = aggregates.best_score.max(run.score);
+            aggregates.longest = aggregates.longest.max(run.max_length);
+            aggregates.close_calls += run.close_calls;
+            *aggregates.causes.entry(run.cause.clone()).or_default() += 1;
+            if let Some(cell) = run.cell {
+                *cells.entry((cell.x, cell.y)).or_default() += 1;
+            }
+            total_score += run.score;
+            total_speed += run.average_speed;
+        }
+        aggregates.average_score = total_score as f32 / history.len() as f32;
+        aggregates.average_speed = total_speed / history.len() as f32;
+        aggregates.deadliest_cell = cells
+            .into_iter()
+            .max_by_key(|&(_, count)| count)
+            .map(|((x, y), count)| (Position { x, y }, count));
+        aggregates
+    }
+}
+
+fn track_run(
+    mut stats: ResMut<RunStats>,
+    mut growth: EventReader<GrowthEvent>,
+    game_overs: EventReader<GameOverEvent>,
+    arena: Res<Arena>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+    heads: Query<(&SnakeHead, &Segments)>,
+    positions: Query<&Position>,
+) {
+    stats.ticks += 1;
+    stats.duration += movement_timer.current_interval();
+    let eaten = growth.read().count() as u32;
+    if eaten > 0 {
+        *stats.foods.entry(PLAIN_FOOD.to_string()).or_default() += eaten;
+    }
+    let Ok((head, segments)) = heads.get_single() else {
+        return;
+    };
+    stats.max_length = stats.max_length.max(segments.0.len());
+    if turns_left(stats.direction, head.direction) {
+        stats.left_turns += 1;
+    }
+    stats.direction = head.direction;
+    if !game_overs.is_empty() {
+        return;
+    }
+    if stats.in_danger {
+        stats.close_calls += 1;
+    }
+    let body: Vec<Position> = positions.iter_many(&segments.0).copied().collect();
+    stats.in_danger = body
+        .first()
+        .is_some_and(|position| collision(*arena, &body, position.step(head.direction)).is_some());
+}
+
+/// Whether heading `to` after `from` is a turn to the left.
+fn turns_left(from: Direction, to: Direction) -> bool {
+    let clockwise = Direction::ALL;
+    let from = clockwise.iter().position(|&direction| direction == from);
+    from.is_some_and(|from| clockwise[(from + clockwise.len() - 1) % clockwise.len()] == to)
+}
+
+fn record_run(
+    stats: Res<RunStats>,
+    mut reader: EventReader<GameOverEvent>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    profile: Res<Profile>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut recorded: EventWriter<RunRecorded>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = reader.read().last() else {
+        return;
+    };
+    let seconds = stats.duration.as_secs_f32();
+    let record = RunRecord {
+        ended_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        mode: *mode,
+        score: score.0,
+        duration_ms: stats.duration.as_millis() as u64,
+        foods: stats.foods.clone(),
+        max_length: stats.max_length,
+        average_speed: if seconds > 0.0 {
+            stats.ticks as f32 / seconds
+        } else {
+            0.0
+        },
+        close_calls: stats.close_calls,
+        left_turns: stats.left_turns,
+        cause: match cause {
+            GameOverCause::Collision(Collision::Wall) => "wall",
+            GameOverCause::Collision(Collision::Body) => "body",
+            GameOverCause::Finished => "finished",
+            GameOverCause::Interrupted => "interrupted",
+            GameOverCause::Caught => "caught",
+            GameOverCause::Won => "won",
+        }
+        .to_string(),
+        // A crashing head has already moved into what it hit.
+        cell: match cause {
+            GameOverCause::Collision(_) | GameOverCause::Caught => heads.iter().next().copied(),
+            _ => None,
+        },
+    };
+    let path = profile.path(HISTORY_FILE);
+    if let Err(err) = append_to_history(&path, &record) {
+        warn!("could not record run in {}: {err}", path.display());
+    }
+    recorded.send(RunRecorded(record));
+}
+
+#[derive(Component)]
+struct StatsScreen;
+
+fn spawn_screen(mut commands: Commands) {
+    commands.spawn((
+        StatsScreen,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_screen(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<Profile>,
+    mut screens: Query<(&mut Visibility, &mut Text), With<StatsScreen>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F2) {
+        return;
+    }
+    let Ok((mut visibility, mut text)) = screens.get_single_mut() else {
+        return;
+    };
+    visibility.toggle_visible_hidden();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let history = read_history(&profile.path(HISTORY_FILE)).unwrap_or_default();
+    text.0 = describe(&profile.name, &Aggregates::new(&history));
+}
+
+fn describe(name: &str, stats: &Aggregates) -> String {
+    let mut text = format!("Stats for {name}\n");
+    if stats.runs == 0 {
+        text.push_str("No runs yet");
+        return text;
+    }
+    let minutes = stats.total_time.as_secs() / 60;
+    let _ = writeln!(text, "Runs: {} ({minutes} min)", stats.runs);
+    let _ = writeln!(
+        text,
+        "Best score: {}, average {:.1}",
+        stats.best_score, stats.average_score
+    );
+    let _ = writeln!(text, "Longest snake: {}", stats.longest);
+    let _ = writeln!(text, "Average speed: {:.1} cells/s", stats.average_speed);
+    let _ = writeln!(text, "Close calls: {}", stats.close_calls);
+    for (kind, count) in &stats.foods {
+        let _ = writeln!(text, "Food eaten ({kind}): {count}");
+    }
+    let causes: Vec<String> = stats
+        .causes
+        .iter()
+        .map(|(cause, count)| format!("{cause} {count}"))
+        .collect();
+    let _ = write!(text, "Endings: {}", causes.join(", "));
+    if let Some((cell, count)) = stats.deadliest_cell {
+        let _ = write!(
+            text,
+            "\nDeadliest cell: ({}, {}), {count} times",
+            cell.x, cell.y
+        );
+    }
+    text
+}
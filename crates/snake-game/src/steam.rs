@@ -0,0 +1,161 @@
+//! Steam achievements, stats and rich presence.
+//!
+//! Only compiled with the `steam` feature, and does nothing unless the Steam
+//! client is running and the game was launched through it (or a
+//! `steam_appid.txt` sits next to the executable). Achievements follow the
+//! in-game ones in [`achievements`](crate::achievements): each is unlocked on
+//! Steam as it is in the game, and any unlocked before are caught up when a
+//! profile is chosen. The API names set up for the app in Steamworks have to
+//! be the in-game ids in upper case, and the stat names the ones below.
+
+use bevy::prelude::*;
+use snake_core::GameMode;
+use steamworks::{Client, UserStats};
+
+use crate::{
+    achievements::{Achievement, AchievementUnlocked, Progress, ACHIEVEMENTS, ACHIEVEMENTS_FILE},
+    bot::bot_playing,
+    daily::practicing,
+    game_over,
+    ghost::speedrun_goal,
+    profile::Profile,
+    replay::run_cheated,
+    GameOverEvent, GameSet, GrowthEvent, Score,
+};
+
+const STAT_GAMES_PLAYED: &str = "games_played";
+const STAT_FOOD_EATEN: &str = "food_eaten";
+const STAT_BEST_SCORE: &str = "best_score";
+
+/// Steamworks API name of an in-game achievement, like `FIRST_BITE` for
+/// `first_bite`.
+fn api_name(achievement: &Achievement) -> String {
+    achievement.id.to_ascii_uppercase()
+}
+
+/// Connects to Steam if it is available.
+pub struct SteamPlugin;
+
+impl Plugin for SteamPlugin {
+    fn build(&self, app: &mut App) {
+        let client = match Client::init() {
+            Ok(client) => client,
+            Err(err) => {
+                info!("Steam not available: {err}");
+                return;
+            }
+        };
+        info!("connected to Steam");
+        app.insert_resource(Steam(client))
+            .init_resource::<Profile>()
+            .init_resource::<FoodEaten>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(PreUpdate, run_callbacks)
+            .add_systems(
+                Update,
+                (
+                    update_presence,
+                    catch_up_achievements.run_if(resource_changed::<Profile>),
+                    unlock_achievements.run_if(on_event::<AchievementUnlocked>),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    count_food,
+                    (
+                        record_run
+                            .run_if(not(bot_playing))
+                            .run_if(not(run_cheated))
+                            .run_if(not(practicing)),
+                        |mut eaten: ResMut<FoodEaten>| eaten.0 = 0,
+                    )
+                        .chain()
+                        .run_if(on_event::<GameOverEvent>),
+                )
+                    .chain()
+                    .after(speedrun_goal)
+                    .before(game_over)
+                    .in_set(GameSet::Logic),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct Steam(Client);
+
+/// Food eaten so far in the run in progress. Worth and bonuses make the score
+/// run ahead of it, so it is counted on its own.
+#[derive(Resource, Default)]
+struct FoodEaten(u32);
+
+fn run_callbacks(steam: Res<Steam>) {
+    steam.0.run_callbacks();
+}
+
+fn update_presence(steam: Res<Steam>, mode: Res<GameMode>, score: Res<Score>) {
+    if !mode.is_changed() && !score.is_changed() {
+        return;
+    }
+    let status = format!("{mode:?}, score {}", score.0);
+    steam.0.friends().set_rich_presence("status", Some(&status));
+}
+
+fn count_food(mut eaten: ResMut<FoodEaten>, mut growth: EventReader<GrowthEvent>) {
+    eaten.0 += growth.read().count() as u32;
+}
+
+fn record_run(steam: Res<Steam>, score: Res<Score>, eaten: Res<FoodEaten>) {
+    let stats = steam.0.user_stats();
+    let score = i32::try_from(score.0).unwrap_or(i32::MAX);
+    let add = |name: &str, amount: i32| {
+        let current = stats.get_stat_i32(name).unwrap_or(0);
+        if stats.set_stat_i32(name, current.max(0) + amount).is_err() {
+            warn!("could not set Steam stat {name}");
+        }
+    };
+    add(STAT_GAMES_PLAYED, 1);
+    add(STAT_FOOD_EATEN, i32::try_from(eaten.0).unwrap_or(i32::MAX));
+    if stats.get_stat_i32(STAT_BEST_SCORE).unwrap_or(0) < score
+        && stats.set_stat_i32(STAT_BEST_SCORE, score).is_err()
+    {
+        warn!("could not set Steam stat {STAT_BEST_SCORE}");
+    }
+    store(&stats);
+}
+
+/// Unlocks `achievement` on Steam unless it already is.
+fn unlock(stats: &UserStats, achievement: &Achievement) {
+    let name = api_name(achievement);
+    let on_steam = stats.achievement(&name);
+    if on_steam.get() == Ok(false) && on_steam.set().is_err() {
+        warn!("could not unlock Steam achievement {name}");
+    }
+}
+
+fn store(stats: &UserStats) {
+    if stats.store_stats().is_err() {
+        warn!("could not store Steam stats");
+    }
+}
+
+fn unlock_achievements(steam: Res<Steam>, mut unlocked: EventReader<AchievementUnlocked>) {
+    let stats = steam.0.user_stats();
+    for AchievementUnlocked(achievement) in unlocked.read() {
+        unlock(&stats, achievement);
+    }
+    store(&stats);
+}
+
+/// Unlocks on Steam whatever the profile unlocked while Steam was not
+/// running.
+fn catch_up_achievements(steam: Res<Steam>, profile: Res<Profile>) {
+    let progress = Progress::read(&profile.path(ACHIEVEMENTS_FILE)).unwrap_or_default();
+    let stats = steam.0.user_stats();
+    for achievement in ACHIEVEMENTS {
+        if progress.unlocked.contains_key(achievement.id) {
+            unlock(&stats, achievement);
+        }
+    }
+    store(&stats);
+}
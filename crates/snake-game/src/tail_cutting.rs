@@ -0,0 +1,54 @@
+//! Tail cutting, an optional rule for casual runs switched on with
+//! `tail_cutting` in `assets/config.ron` or the saved settings.
+//!
+//! Running into its own body then cuts the snake where it bit instead of
+//! ending the run: the segments from there to the tail are dropped, and the
+//! score loses a point for each of them. Runs in other modes still end. The
+//! core simulation has no such rule, so a run cut short this way is not kept
+//! as a replay.
+
+use bevy::prelude::*;
+use snake_core::GameMode;
+
+use crate::{release_segment, replay::ReplayRecorder, Score, SegmentPool, Segments};
+
+/// Whether tail cutting is switched on. It only applies to casual runs.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TailCutting(pub bool);
+
+impl TailCutting {
+    pub fn applies(self, mode: GameMode) -> bool {
+        self.0 && mode.is_casual()
+    }
+}
+
+/// Sent when a head bites its body, with the index of the segment it bit.
+#[derive(Event)]
+pub(crate) struct TailCut {
+    pub head: Entity,
+    pub at: usize,
+}
+
+/// Drops the segments from the one bitten to the tail.
+pub(crate) fn cut_tail(
+    mut commands: Commands,
+    mut reader: EventReader<TailCut>,
+    mut snakes: Query<&mut Segments>,
+    mut pool: ResMut<SegmentPool>,
+    mut score: ResMut<Score>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    for &TailCut { head, at } in reader.read() {
+        let Ok(mut segments) = snakes.get_mut(head) else {
+            continue;
+        };
+        let at = at.clamp(1, segments.0.len());
+        let cut = segments.0.split_off(at);
+        score.0 = score.0.saturating_sub(cut.len() as u32);
+        info!(lost = cut.len(), length = segments.0.len(), "tail cut");
+        recorder.tainted = true;
+        for segment in cut {
+            release_segment(&mut commands, &mut pool, segment);
+        }
+    }
+}
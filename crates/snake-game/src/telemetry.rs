@@ -0,0 +1,121 @@
+//! Opt-in anonymous run summaries for tuning speeds and food spawn rates.
+//!
+//! Only compiled with the `telemetry` feature, and even then nothing is
+//! collected unless the player passes `--telemetry-endpoint <url>`. A summary
+//! holds the mode, score, duration and what ended the run; no seed, inputs,
+//! paths or machine details. Summaries are posted as a JSON array once
+//! [`BATCH_SIZE`] have piled up, and whatever is left when the game exits.
+
+use std::time::Duration;
+
+use bevy::{app::AppExit, prelude::*, tasks::IoTaskPool};
+use serde::Serialize;
+use snake_core::{Collision, GameMode};
+
+use crate::{
+    bot::bot_playing, game_over, GameOverCause, GameOverEvent, GameSet, MovementTick, Run, Score,
+    TickTimer,
+};
+
+pub const BATCH_SIZE: usize = 10;
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Adds run summary collection if the player opted in on the command line.
+pub struct TelemetryPlugin;
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(endpoint) = std::env::args()
+            .skip_while(|arg| arg != "--telemetry-endpoint")
+            .nth(1)
+        else {
+            return;
+        };
+        info!("telemetry enabled, sending run summaries to {endpoint}");
+        app.insert_resource(Telemetry {
+            endpoint,
+            pending: Vec::new(),
+        })
+        .add_systems(
+            FixedUpdate,
+            record_run
+                .run_if(on_event::<GameOverEvent>)
+                .run_if(not(bot_playing))
+                .before(game_over)
+                .in_set(GameSet::Logic),
+        )
+        .add_systems(Last, flush_on_exit.run_if(on_event::<AppExit>));
+    }
+}
+
+#[derive(Resource)]
+struct Telemetry {
+    endpoint: String,
+    pending: Vec<RunSummary>,
+}
+
+#[derive(Serialize)]
+struct RunSummary {
+    mode: &'static str,
+    score: u32,
+    duration_ms: u64,
+    cause: &'static str,
+}
+
+fn record_run(
+    mut telemetry: ResMut<Telemetry>,
+    mut reader: EventReader<GameOverEvent>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    run: Res<Run>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+) {
+    let Some(GameOverEvent { cause, .. }) = reader.read().last() else {
+        return;
+    };
+    telemetry.pending.push(RunSummary {
+        mode: match *mode {
+            GameMode::Classic => "classic",
+            GameMode::Casual => "casual",
+            GameMode::Speedrun => "speedrun",
+        },
+        score: score.0,
+        duration_ms: (movement_timer.interval() * run.tick).as_millis() as u64,
+        cause: match cause {
+            GameOverCause::Collision(Collision::Wall) => "wall",
+            GameOverCause::Collision(Collision::Body) => "body",
+            GameOverCause::Finished => "finished",
+            GameOverCause::Interrupted => "interrupted",
+            GameOverCause::Caught => "caught",
+            GameOverCause::Won => "won",
+        },
+    });
+    if telemetry.pending.len() >= BATCH_SIZE {
+        let batch = std::mem::take(&mut telemetry.pending);
+        let endpoint = telemetry.endpoint.clone();
+        IoTaskPool::get()
+            .spawn(async move { send(&endpoint, &batch) })
+            .detach();
+    }
+}
+
+fn flush_on_exit(mut telemetry: ResMut<Telemetry>) {
+    if !telemetry.pending.is_empty() {
+        let batch = std::mem::take(&mut telemetry.pending);
+        send(&telemetry.endpoint, &batch);
+    }
+}
+
+/// Posts a batch, dropping it on failure: losing a few summaries is better
+/// than retrying against an endpoint that is down.
+fn send(endpoint: &str, batch: &[RunSummary]) {
+    let result = ureq::AgentBuilder::new()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .post(endpoint)
+        .send_json(batch);
+    match result {
+        Ok(_) => debug!("sent {} run summaries", batch.len()),
+        Err(err) => warn!("dropping {} run summaries: {err}", batch.len()),
+    }
+}
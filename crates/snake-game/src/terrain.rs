@@ -0,0 +1,155 @@
+//! Terrain: tiles under the board that change how the snake moves over them.
+//!
+//! - Speed strips hurry the snake on: the tick after it moves onto one comes
+//!   straight away.
+//! - Mud holds it back: while its head is in mud it only moves every other
+//!   tick.
+//! - Ice makes it slide: turns are ignored while its head is on ice, so it
+//!   carries on straight until it is off.
+//!
+//! Levels paint terrain with [`Terrain::new`]; boards without any have no
+//! [`Terrain`] at all. It is drawn beneath everything else on the board.
+
+use bevy::prelude::*;
+use snake_core::{Arena, Position};
+
+use crate::{MovementTick, Size, SnakeHead, TickTimer};
+
+const SPEED_COLOR: Color = Color::linear_rgb(0.35, 0.3, 0.05);
+const MUD_COLOR: Color = Color::linear_rgb(0.2, 0.12, 0.05);
+const ICE_COLOR: Color = Color::linear_rgb(0.3, 0.45, 0.55);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Tile {
+    #[default]
+    Plain,
+    Speed,
+    Mud,
+    Ice,
+}
+
+/// The tile on each cell of the arena.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct Terrain {
+    arena: Arena,
+    tiles: Vec<Tile>,
+}
+
+impl Terrain {
+    /// Plain ground with `painted` tiles on top. Cells outside `arena` are
+    /// left out.
+    pub fn new(arena: Arena, painted: impl IntoIterator<Item = (Position, Tile)>) -> Self {
+        let mut terrain = Self {
+            arena,
+            tiles: vec![Tile::Plain; arena.width as usize * arena.height as usize],
+        };
+        for (position, tile) in painted {
+            if let Some(index) = terrain.index(position) {
+                terrain.tiles[index] = tile;
+            }
+        }
+        terrain
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        self.arena
+            .contains(position)
+            .then(|| position.y as usize * self.arena.width as usize + position.x as usize)
+    }
+
+    pub fn at(&self, position: Position) -> Tile {
+        self.index(position)
+            .map_or(Tile::Plain, |index| self.tiles[index])
+    }
+
+    fn painted(&self) -> impl Iterator<Item = (Position, Tile)> + '_ {
+        let width = self.arena.width as usize;
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, &tile)| tile != Tile::Plain)
+            .map(move |(index, &tile)| {
+                let position = Position {
+                    x: (index % width) as i32,
+                    y: (index / width) as i32,
+                };
+                (position, tile)
+            })
+    }
+}
+
+/// A head sitting out this movement tick in mud.
+#[derive(Component)]
+pub(crate) struct Held;
+
+/// Holds each head in mud back every other movement tick. Runs on movement
+/// ticks only, so it counts those rather than fixed steps.
+pub(crate) fn wade(
+    mut commands: Commands,
+    terrain: Option<Res<Terrain>>,
+    heads: Query<(Entity, &Position, Has<Held>), With<SnakeHead>>,
+) {
+    for (head, &position, held) in &heads {
+        let in_mud = terrain
+            .as_ref()
+            .is_some_and(|terrain| terrain.at(position) == Tile::Mud);
+        if in_mud && !held {
+            commands.entity(head).insert(Held);
+        } else if held {
+            commands.entity(head).remove::<Held>();
+        }
+    }
+}
+
+/// Brings the next tick forward when the head has just moved onto a speed
+/// strip.
+pub(crate) fn speed_strips(
+    terrain: Res<Terrain>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut movement_timer: ResMut<TickTimer<MovementTick>>,
+) {
+    if heads.iter().any(|&head| terrain.at(head) == Tile::Speed) {
+        movement_timer.hurry();
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct TerrainTile;
+
+/// Redraws the terrain whenever it changes.
+pub(crate) fn paint_terrain(
+    mut commands: Commands,
+    terrain: Option<Res<Terrain>>,
+    tiles: Query<Entity, With<TerrainTile>>,
+) {
+    for tile in &tiles {
+        commands.entity(tile).despawn();
+    }
+    let Some(terrain) = terrain else {
+        return;
+    };
+    for (position, tile) in terrain.painted() {
+        let color = match tile {
+            Tile::Plain => continue,
+            Tile::Speed => SPEED_COLOR,
+            Tile::Mud => MUD_COLOR,
+            Tile::Ice => ICE_COLOR,
+        };
+        commands.spawn((
+            TerrainTile,
+            Sprite {
+                color,
+                ..Default::default()
+            },
+            position,
+            Size::square(1.0),
+        ));
+    }
+}
+
+/// Draws terrain beneath whatever is on it.
+pub(crate) fn sink_terrain(mut tiles: Query<&mut Transform, Added<TerrainTile>>) {
+    for mut transform in &mut tiles {
+        transform.translation.z = -1.0;
+    }
+}
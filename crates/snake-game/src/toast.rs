@@ -0,0 +1,110 @@
+//! Toasts: short messages that slide in at the top of the screen and go away
+//! on their own, for anything the player should notice in passing.
+//!
+//! Send a [`Toast`] to show one. Toasts are shown one at a time, in the order
+//! sent, each for [`TOAST_FOR`]: sliding down over [`SLIDE_FOR`], staying,
+//! and sliding back up. With reduced motion they appear and go in place.
+//! Plugins that send toasts add [`ToastPlugin`] themselves.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::accessibility::Accessibility;
+
+pub const TOAST_FOR: Duration = Duration::from_secs(2);
+pub const SLIDE_FOR: Duration = Duration::from_millis(200);
+/// Most toasts waiting their turn; the oldest are dropped past it.
+const MAX_QUEUED: usize = 5;
+const TOP: f32 = 8.0;
+/// Where a toast slides in from, above the top of the screen.
+const HIDDEN_TOP: f32 = -40.0;
+
+/// A message to show as a toast.
+#[derive(Event, Clone, Debug)]
+pub struct Toast(pub String);
+
+/// Shows [`Toast`]s one after another.
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Toast>()
+            .init_resource::<ToastQueue>()
+            .add_systems(Update, (queue_toasts, show_toasts).chain());
+    }
+}
+
+/// Toasts waiting for the one on screen to go.
+#[derive(Resource, Default)]
+pub struct ToastQueue(pub VecDeque<String>);
+
+/// The toast on screen, with how long it has been there.
+#[derive(Component)]
+pub struct ShownToast(Timer);
+
+fn queue_toasts(mut reader: EventReader<Toast>, mut queue: ResMut<ToastQueue>) {
+    for Toast(message) in reader.read() {
+        queue.0.push_back(message.clone());
+        if queue.0.len() > MAX_QUEUED {
+            queue.0.pop_front();
+        }
+    }
+}
+
+fn show_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+    mut queue: ResMut<ToastQueue>,
+    mut shown: Query<(Entity, &mut ShownToast, &mut Node)>,
+) {
+    if let Ok((entity, mut toast, mut node)) = shown.get_single_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            node.top = Val::Px(top(&toast.0, accessibility.reduced_motion));
+        }
+        return;
+    }
+    let Some(message) = queue.0.pop_front() else {
+        return;
+    };
+    let timer = Timer::new(TOAST_FOR, TimerMode::Once);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(top(&timer, accessibility.reduced_motion)),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            ShownToast(timer),
+        ))
+        .with_child((
+            Text::new(message),
+            TextFont {
+                font_size: 20.0,
+                ..Default::default()
+            },
+            Node {
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                ..Default::default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        ));
+}
+
+/// How far down the toast is `timer` into showing it: sliding in at the
+/// start and out at the end.
+fn top(timer: &Timer, reduced_motion: bool) -> f32 {
+    if reduced_motion {
+        return TOP;
+    }
+    let slide = SLIDE_FOR.as_secs_f32();
+    let shown = (timer.elapsed_secs() / slide)
+        .min(timer.remaining_secs() / slide)
+        .clamp(0.0, 1.0);
+    HIDDEN_TOP + (TOP - HIDDEN_TOP) * shown
+}
@@ -0,0 +1,120 @@
+//! Tunnels: pairs of openings in the arena's edge cells, like the ones in
+//! Pac-Man's maze.
+//!
+//! The board does not wrap: a snake that runs off the edge hits the wall.
+//! Except through an opening, where it comes back in at the other end of the
+//! tunnel, heading away from that edge. Levels dig tunnels with
+//! [`Tunnels::new`]; boards without any have no [`Tunnels`] at all. Both ends
+//! of a tunnel are marked in the same color, beneath everything else on the
+//! board.
+
+use bevy::prelude::*;
+use snake_core::{Arena, Direction, Position};
+
+use crate::Size;
+
+const TUNNEL_COLORS: [Color; 3] = [
+    Color::linear_rgb(0.1, 0.55, 0.5),
+    Color::linear_rgb(0.55, 0.3, 0.6),
+    Color::linear_rgb(0.6, 0.45, 0.15),
+];
+
+/// Two openings in the edge of the arena, each leading in at the other.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Tunnel(pub Position, pub Position);
+
+/// The tunnels dug into the arena's edge.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct Tunnels {
+    arena: Arena,
+    tunnels: Vec<Tunnel>,
+}
+
+impl Tunnels {
+    /// `dug` tunnels in `arena`. Tunnels with an end that is not an edge cell
+    /// of `arena`, or both ends on the same cell, are left out.
+    pub fn new(arena: Arena, dug: impl IntoIterator<Item = Tunnel>) -> Self {
+        let tunnels = dug
+            .into_iter()
+            .filter(|&Tunnel(a, b)| a != b && on_edge(arena, a) && on_edge(arena, b))
+            .collect();
+        Self { arena, tunnels }
+    }
+
+    /// Where a head stepping `direction` off the board from `from` comes back
+    /// in and which way it then heads, if `from` is an opening. It keeps its
+    /// heading through a tunnel between opposite edges.
+    pub fn exit(&self, from: Position, direction: Direction) -> Option<(Position, Direction)> {
+        if self.arena.contains(from.step(direction)) {
+            return None;
+        }
+        let other = self.tunnels.iter().find_map(|&Tunnel(a, b)| {
+            if from == a {
+                Some(b)
+            } else if from == b {
+                Some(a)
+            } else {
+                None
+            }
+        })?;
+        let heading = if outward(self.arena, other).any(|out| out == direction.opposite()) {
+            direction
+        } else {
+            outward(self.arena, other).next()?.opposite()
+        };
+        Some((other, heading))
+    }
+
+    pub fn tunnels(&self) -> &[Tunnel] {
+        &self.tunnels
+    }
+}
+
+/// The directions leading off the board from `position`.
+fn outward(arena: Arena, position: Position) -> impl Iterator<Item = Direction> {
+    Direction::ALL
+        .into_iter()
+        .filter(move |&direction| !arena.contains(position.step(direction)))
+}
+
+fn on_edge(arena: Arena, position: Position) -> bool {
+    arena.contains(position) && outward(arena, position).next().is_some()
+}
+
+#[derive(Component)]
+pub(crate) struct TunnelMarker;
+
+/// Redraws the tunnel openings whenever the tunnels change.
+pub(crate) fn mark_tunnels(
+    mut commands: Commands,
+    tunnels: Option<Res<Tunnels>>,
+    markers: Query<Entity, With<TunnelMarker>>,
+) {
+    for marker in &markers {
+        commands.entity(marker).despawn();
+    }
+    let Some(tunnels) = tunnels else {
+        return;
+    };
+    for (index, &Tunnel(a, b)) in tunnels.tunnels.iter().enumerate() {
+        let color = TUNNEL_COLORS[index % TUNNEL_COLORS.len()];
+        for position in [a, b] {
+            commands.spawn((
+                TunnelMarker,
+                Sprite {
+                    color,
+                    ..Default::default()
+                },
+                position,
+                Size::square(1.0),
+            ));
+        }
+    }
+}
+
+/// Draws the openings beneath whatever passes through them.
+pub(crate) fn sink_markers(mut markers: Query<&mut Transform, Added<TunnelMarker>>) {
+    for mut transform in &mut markers {
+        transform.translation.z = -1.0;
+    }
+}
@@ -0,0 +1,149 @@
+//! The typeface and size of all text in the HUD and menus.
+//!
+//! The typeface comes with the [`Theme`]: `font` in the theme section of
+//! `assets/config.ron` names a font file under `assets/`, by default the
+//! bundled [`PIXEL_FONT`], and themes without one use Bevy's built-in font.
+//! Text keeps the built-in font until the theme's has loaded, and for good if
+//! it fails to. [`TextSize`] scales every text from the size it was spawned
+//! with; Ctrl+T steps through the sizes, and the choice is saved with the
+//! other [`crate::settings`].
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "embedded-assets")]
+use crate::embedded::EmbeddedAssetsPlugin;
+use crate::{
+    config::apply_config, console::console_closed, embedded::asset_path, loading::LoadingAssets,
+    Theme,
+};
+
+/// The pixel font the default theme uses, under `assets/`.
+pub const PIXEL_FONT: &str = "fonts/pixel.ttf";
+
+/// How large text is drawn, relative to the size each was made with.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum TextSize {
+    Small,
+    #[default]
+    Normal,
+    Large,
+    Huge,
+}
+
+impl TextSize {
+    pub fn scale(self) -> f32 {
+        match self {
+            TextSize::Small => 0.8,
+            TextSize::Normal => 1.0,
+            TextSize::Large => 1.25,
+            TextSize::Huge => 1.5,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            TextSize::Small => TextSize::Normal,
+            TextSize::Normal => TextSize::Large,
+            TextSize::Large => TextSize::Huge,
+            TextSize::Huge => TextSize::Small,
+        }
+    }
+}
+
+/// Sets the font and size of text as it is spawned, and of all text when
+/// either changes. Needs the asset server, so it is left out of
+/// [`crate::SnakeGamePlugin`].
+pub struct TypographyPlugin;
+
+impl Plugin for TypographyPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "embedded-assets")]
+        if !app.is_plugin_added::<EmbeddedAssetsPlugin>() {
+            app.add_plugins(EmbeddedAssetsPlugin);
+        }
+        app.init_resource::<TextSize>()
+            .init_resource::<ThemeFont>()
+            .add_systems(
+                Update,
+                (
+                    size_input.run_if(console_closed),
+                    load_font.run_if(resource_changed::<Theme>),
+                    set_typography,
+                )
+                    .chain()
+                    .after(apply_config),
+            );
+    }
+}
+
+/// The theme's font, while it has one.
+#[derive(Resource, Default)]
+struct ThemeFont(Option<Handle<Font>>);
+
+/// Size a text was spawned with, before [`TextSize`] scaled it.
+#[derive(Component)]
+struct BaseFontSize(f32);
+
+fn size_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut size: ResMut<TextSize>) {
+    if keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && keyboard_input.just_pressed(KeyCode::KeyT)
+    {
+        *size = size.next();
+        info!(size = ?*size, "text size");
+    }
+}
+
+fn load_font(
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    mut font: ResMut<ThemeFont>,
+    loading: Option<ResMut<LoadingAssets>>,
+) {
+    let loaded = theme
+        .font
+        .as_deref()
+        .map(|path| asset_server.load(asset_path(path)));
+    if loaded != font.0 {
+        if let (Some(handle), Some(mut loading)) = (&loaded, loading) {
+            loading.track(handle.clone());
+        }
+        font.0 = loaded;
+    }
+}
+
+fn set_typography(
+    mut commands: Commands,
+    size: Res<TextSize>,
+    font: Res<ThemeFont>,
+    fonts: Res<Assets<Font>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut texts: Query<(Entity, &mut TextFont, Option<&BaseFontSize>)>,
+) {
+    let refresh = size.is_changed() || font.is_changed() || font_events.read().count() > 0;
+    let handle = font
+        .0
+        .as_ref()
+        .filter(|handle| fonts.contains(*handle))
+        .cloned()
+        .unwrap_or_default();
+    for (entity, mut text_font, base) in &mut texts {
+        if !refresh && !text_font.is_added() {
+            continue;
+        }
+        let base = match base {
+            Some(base) => base.0,
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(BaseFontSize(text_font.font_size));
+                text_font.font_size
+            }
+        };
+        let font_size = base * size.scale();
+        if text_font.font != handle || text_font.font_size != font_size {
+            text_font.font = handle.clone();
+            text_font.font_size = font_size;
+        }
+    }
+}
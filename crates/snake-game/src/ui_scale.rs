@@ -0,0 +1,99 @@
+//! Scaling the HUD and menus, so text stays readable from 4K monitors down to
+//! small laptop screens.
+//!
+//! Text and menus are laid out in logical pixels, so they already follow the
+//! scale factor the OS sets for the display. On top of that, Ctrl with `=` or
+//! `-` slides [`UiScale`] between [`MIN_UI_SCALE`] and [`MAX_UI_SCALE`] in
+//! steps of [`UI_SCALE_STEP`], and Ctrl+0 puts it back to 1, with a slider
+//! shown for a moment as it changes. The board is drawn by the camera rather
+//! than the UI, so the grid keeps its size. The scale is saved with the other
+//! [`crate::settings`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 2.0;
+pub const UI_SCALE_STEP: f32 = 0.1;
+const SLIDER_FOR: Duration = Duration::from_secs(2);
+/// Notches in the slider, one per step.
+const NOTCHES: usize = ((MAX_UI_SCALE - MIN_UI_SCALE) / UI_SCALE_STEP) as usize;
+
+/// Adds the UI scale keys and the slider shown while they are used.
+pub struct UiScalePlugin;
+
+impl Plugin for UiScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiScale>()
+            .add_systems(Startup, spawn_slider)
+            .add_systems(Update, (scale_input, show_slider).chain());
+    }
+}
+
+#[derive(Component)]
+struct Slider(Timer);
+
+fn spawn_slider(mut commands: Commands) {
+    let mut timer = Timer::new(SLIDER_FOR, TimerMode::Once);
+    timer.tick(SLIDER_FOR);
+    commands.spawn((
+        Slider(timer),
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+fn scale_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut scale: ResMut<UiScale>) {
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let wanted = if keyboard_input.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        scale.0 + UI_SCALE_STEP
+    } else if keyboard_input.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        scale.0 - UI_SCALE_STEP
+    } else if keyboard_input.any_just_pressed([KeyCode::Digit0, KeyCode::Numpad0]) {
+        1.0
+    } else {
+        return;
+    };
+    // Whole steps, so repeated presses land on the same values.
+    let wanted =
+        ((wanted / UI_SCALE_STEP).round() * UI_SCALE_STEP).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    if scale.0 != wanted {
+        scale.0 = wanted;
+    }
+}
+
+fn show_slider(
+    time: Res<Time>,
+    scale: Res<UiScale>,
+    mut sliders: Query<(&mut Slider, &mut Text, &mut Visibility)>,
+) {
+    for (mut slider, mut text, mut visibility) in &mut sliders {
+        if scale.is_changed() && !scale.is_added() {
+            let filled = (((scale.0 - MIN_UI_SCALE) / UI_SCALE_STEP).round() as usize).min(NOTCHES);
+            text.0 = format!(
+                "UI scale [{}{}] {:.0}%",
+                "#".repeat(filled),
+                "-".repeat(NOTCHES - filled),
+                scale.0 * 100.0
+            );
+            slider.0.reset();
+            *visibility = Visibility::Inherited;
+        } else if slider.0.tick(time.delta()).just_finished() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
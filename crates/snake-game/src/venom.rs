@@ -0,0 +1,210 @@
+//! Venom: special food that turns up now and then in casual runs and lets the
+//! snake spit.
+//!
+//! Eating a venom sac counts as food and charges one spit, up to
+//! [`MAX_VENOM`]. Pressing F spends one: a glob of venom flies from the head
+//! one cell a movement tick in the direction the snake is heading, and
+//! destroys the first obstacle, critter or predator it hits, or is lost off
+//! the edge of the board. Sacs turn up every [`VENOM_INTERVAL`], placed with
+//! randomness of their own, and a run that spits is no longer kept as a
+//! replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, Direction, GameMode, Position};
+
+use crate::{
+    console::console_closed, critters::Critter, game_over, name_entry::entering_name,
+    power_ups::Occupancy, predator::Predator, replay::ReplayRecorder, snake_eating, snake_growth,
+    snake_movement, spawn_food, timer_finished, world_clock::WorldClock, Food, GameOverEvent,
+    GameSet, MovementTick, Obstacle, Size, SnakeHead, SnakeSegment, ThemeColor,
+};
+
+pub const VENOM_INTERVAL: Duration = Duration::from_secs(12);
+/// Most spits the snake can hold.
+pub const MAX_VENOM: u32 = 3;
+const SAC_COLOR: Color = Color::linear_rgb(0.6, 0.2, 0.8);
+const SPIT_COLOR: Color = Color::linear_rgb(0.55, 1.0, 0.2);
+
+/// Spits the snake holds, and whether it spits on the coming tick.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Venom {
+    pub charges: u32,
+    /// Set by the spit key, and cleared on the next movement tick whether or
+    /// not there was a charge to spend.
+    pub spitting: bool,
+}
+
+/// Food that charges a spit when eaten.
+#[derive(Component)]
+pub struct VenomSac;
+
+/// A glob of venom in flight.
+#[derive(Component)]
+pub struct Spit(pub Direction);
+
+/// Puts a venom sac down at `position`.
+pub fn spawn_venom_sac(commands: &mut Commands, position: Position) -> Entity {
+    let sac = spawn_food(commands, position);
+    commands.entity(sac).remove::<ThemeColor>().insert((
+        VenomSac,
+        Sprite::from_color(SAC_COLOR, Vec2::ONE),
+        Size::square(0.6),
+    ));
+    sac
+}
+
+/// Places venom sacs in casual runs and flies the spit.
+pub struct VenomPlugin;
+
+impl Plugin for VenomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Venom>()
+            .insert_resource(SacTimer(Timer::new(VENOM_INTERVAL, TimerMode::Repeating)))
+            .add_systems(
+                Update,
+                spit_input.run_if(not(entering_name)).run_if(console_closed),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    place_sac.in_set(GameSet::Spawning),
+                    charge
+                        .after(snake_movement)
+                        .before(snake_eating)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    (spit, fly)
+                        .chain()
+                        .after(snake_growth)
+                        .before(game_over)
+                        .run_if(not(on_event::<GameOverEvent>))
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    clear_venom
+                        .after(game_over)
+                        .run_if(on_event::<GameOverEvent>)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                ),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct SacTimer(Timer);
+
+fn spit_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut venom: ResMut<Venom>) {
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        venom.spitting = true;
+    }
+}
+
+fn place_sac(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    arena: Res<Arena>,
+    mode: Res<GameMode>,
+    mut timer: ResMut<SacTimer>,
+    sacs: Query<(), With<VenomSac>>,
+    taken: Query<&Position, Or<(With<SnakeSegment>, With<Food>, With<Obstacle>)>>,
+) {
+    if !timer.0.tick(clock.delta()).just_finished() || !mode.is_casual() || !sacs.is_empty() {
+        return;
+    }
+    let free = Occupancy::new(*arena, &taken).free_cells();
+    if free.is_empty() {
+        return;
+    }
+    let position = free[rand::rng().random_range(0..free.len())];
+    debug!(?position, "venom sac placed");
+    spawn_venom_sac(&mut commands, position);
+}
+
+/// Charges a spit for each sac the head has reached, before it is eaten.
+fn charge(
+    mut venom: ResMut<Venom>,
+    heads: Query<&Position, With<SnakeHead>>,
+    sacs: Query<&Position, With<VenomSac>>,
+) {
+    for head in &heads {
+        if sacs.iter().any(|sac| sac == head) {
+            venom.charges = (venom.charges + 1).min(MAX_VENOM);
+            debug!(charges = venom.charges, "venom charged");
+        }
+    }
+}
+
+/// Spends a charge on a glob of venom at the head, if the snake is spitting.
+fn spit(
+    mut commands: Commands,
+    mut venom: ResMut<Venom>,
+    mut recorder: ResMut<ReplayRecorder>,
+    heads: Query<(&SnakeHead, &Position)>,
+) {
+    if !std::mem::take(&mut venom.spitting) || venom.charges == 0 {
+        return;
+    }
+    let Some((head, &position)) = heads.iter().next() else {
+        return;
+    };
+    venom.charges -= 1;
+    info!(?position, charges = venom.charges, "spat venom");
+    recorder.tainted = true;
+    spawn_spit(&mut commands, position, head.direction);
+}
+
+/// Puts a glob of venom flying `direction` at `position`.
+pub fn spawn_spit(commands: &mut Commands, position: Position, direction: Direction) -> Entity {
+    commands
+        .spawn((
+            Spit(direction),
+            Sprite::from_color(SPIT_COLOR, Vec2::ONE),
+            position,
+            Size::square(0.4),
+        ))
+        .id()
+}
+
+/// Moves each glob one cell on, and destroys whatever it hits along with it.
+fn fly(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    mut spit: Query<(Entity, &Spit, &mut Position)>,
+    targets: Query<
+        (Entity, &Position),
+        (
+            Or<(With<Obstacle>, With<Critter>, With<Predator>)>,
+            Without<Spit>,
+        ),
+    >,
+) {
+    for (entity, spit, mut position) in &mut spit {
+        *position = position.step(spit.0);
+        if !arena.contains(*position) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some((target, _)) = targets.iter().find(|(_, target)| **target == *position) {
+            info!(position = ?*position, "venom hit");
+            commands.entity(target).despawn();
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn clear_venom(
+    mut commands: Commands,
+    mut venom: ResMut<Venom>,
+    mut timer: ResMut<SacTimer>,
+    spit: Query<Entity, With<Spit>>,
+) {
+    *venom = Venom::default();
+    timer.0.reset();
+    for entity in &spit {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,83 @@
+//! Determinism check enabled with `--verify`.
+//!
+//! Steps a [`Simulation`] alongside the live game, fed the same seed and the
+//! direction the snake actually moved in, and compares board hashes after
+//! every movement tick. Works for both live play and `--replay` playback. A
+//! mismatch means the ECS systems and the core rules have drifted apart, which
+//! would break replays and ghosts, so the game exits with an error.
+
+use bevy::prelude::*;
+use snake_core::{
+    sim::{state_hash, Simulation},
+    Arena, Position,
+};
+
+use crate::{replay::ReplayRecorder, Food, Run, Score, Segments, SnakeHead};
+
+/// Present while runs are being verified.
+#[derive(Default, Resource)]
+pub struct VerifyDeterminism;
+
+/// The simulation shadowing the current run. Dropped for the rest of the run
+/// once it stops being comparable.
+#[derive(Resource)]
+pub struct Verification(Simulation);
+
+pub fn enable_from_args(mut commands: Commands) {
+    if std::env::args().any(|arg| arg == "--verify") {
+        commands.insert_resource(VerifyDeterminism);
+    }
+}
+
+/// Starts a fresh simulation for the run that is beginning.
+pub fn reset_verification(mut commands: Commands, run: Res<Run>, arena: Res<Arena>) {
+    commands.insert_resource(Verification(Simulation::new(run.seed, *arena)));
+}
+
+pub fn verify_tick(
+    mut commands: Commands,
+    mut verification: ResMut<Verification>,
+    run: Res<Run>,
+    score: Res<Score>,
+    recorder: Res<ReplayRecorder>,
+    heads: Query<(&SnakeHead, &Segments)>,
+    positions: Query<&Position>,
+    food: Query<&Position, With<Food>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if recorder.tainted {
+        info!("run was altered, no longer verifying it");
+        commands.remove_resource::<Verification>();
+        return;
+    }
+    let Some((head, segments)) = heads.iter().next() else {
+        return;
+    };
+    let sim = &mut verification.0;
+    sim.step(head.direction);
+    if !sim.alive {
+        return;
+    }
+    let body: Vec<Position> = positions.iter_many(&segments.0).copied().collect();
+    let food: Vec<Position> = food.iter().copied().collect();
+    let live = state_hash(&body, &food, score.0);
+    let expected = sim.state_hash();
+    if live != expected {
+        error!(
+            tick = run.tick,
+            "determinism check failed: live board {live:016x} != simulated {expected:016x}\n\
+             live: body {body:?}, food {food:?}, score {}\n\
+             simulated: body {:?}, food {:?}, score {}",
+            score.0,
+            sim.body,
+            sim.food,
+            sim.score,
+        );
+        exit.send(AppExit::error());
+        commands.remove_resource::<Verification>();
+    }
+}
+
+pub fn spawn_verification_food(mut verification: ResMut<Verification>) {
+    verification.0.spawn_food();
+}
@@ -0,0 +1,300 @@
+//! Winning: a snake that fills every cell not taken by an obstacle has played
+//! a perfect game, and the run ends there. Clearing a campaign level is a win
+//! too.
+//!
+//! A won run counts towards the "Perfect game" achievement. [`VictoryPlugin`]
+//! pauses the game on a results screen with the score, length, time and stars,
+//! with confetti falling over it unless motion is reduced, until the player
+//! picks what to do next: replay the board, go on to the next level, or keep
+//! playing the cleared level in [`FreePlay`], where nothing ends the run but a
+//! crash and the run is not recorded. A perfect game is also exported as a
+//! replay straight away, as F6 would, unless something the core simulation
+//! does not know of happened in it. Runs the bot plays go straight on.
+
+use std::fmt::Write as _;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::{Arena, Position};
+
+use crate::{
+    accessibility::Accessibility,
+    bot::bot_playing,
+    campaign::{clear_level, Campaign, LEVELS, MAX_STARS},
+    console::console_closed,
+    game_over, gates,
+    power_ups::Occupancy,
+    replay::{finish_recording, LastReplay},
+    timer_finished, GameOverCause, GameOverEvent, GameSet, MovementTick, Obstacle, Run, Score,
+    Segments, SnakeSegment, TickTimer,
+};
+
+const CONFETTI: usize = 80;
+const CONFETTI_COLORS: [Color; 5] = [
+    Color::linear_rgb(1.0, 0.3, 0.3),
+    Color::linear_rgb(1.0, 0.85, 0.2),
+    Color::linear_rgb(0.3, 0.9, 0.4),
+    Color::linear_rgb(0.3, 0.6, 1.0),
+    Color::linear_rgb(0.9, 0.4, 1.0),
+];
+/// Falling speed of confetti, in window heights per second.
+const CONFETTI_SPEED: f32 = 0.25;
+
+/// Ends the run as won once the snake covers every free cell.
+pub(crate) fn board_filled(
+    arena: Res<Arena>,
+    segments: Query<&Position, With<SnakeSegment>>,
+    obstacles: Query<&Position, With<Obstacle>>,
+    mut game_over_writer: EventWriter<GameOverEvent>,
+) {
+    let mut board = Occupancy::new(*arena, &obstacles);
+    for &position in &segments {
+        board.set(position, true);
+    }
+    if board.free_cells().is_empty() {
+        info!(length = segments.iter().count(), "board filled");
+        game_over_writer.send(GameOverEvent {
+            cause: GameOverCause::Won,
+            cell: None,
+        });
+    }
+}
+
+/// Exports the replay of a won run.
+fn save_replay(mut reader: EventReader<GameOverEvent>, last_replay: Res<LastReplay>) {
+    let Some(&GameOverEvent {
+        cause: GameOverCause::Won,
+        ..
+    }) = reader.read().last()
+    else {
+        return;
+    };
+    if let Some(replay) = &last_replay.0 {
+        crate::replay::export(replay);
+    }
+}
+
+/// The results screen.
+#[derive(Component)]
+pub struct VictoryScreen;
+
+/// How the won run went, while the results screen waits for a choice.
+#[derive(Resource, Clone, Debug)]
+pub struct Results {
+    pub score: u32,
+    pub length: usize,
+    pub seconds: f32,
+    pub stars: u8,
+    /// The campaign level cleared, if it was one.
+    pub level: Option<usize>,
+    /// Whether the game was paused already, and so stays paused after.
+    was_paused: bool,
+}
+
+impl Results {
+    /// Whether there is a level after the one cleared.
+    pub fn has_next_level(&self) -> bool {
+        self.level.is_some_and(|level| level + 1 < LEVELS.len())
+    }
+
+    fn text(&self) -> String {
+        let mut text = match self.level {
+            Some(level) => format!("Level cleared: {}", LEVELS[level].name),
+            None => "Perfect game!".to_string(),
+        };
+        let _ = write!(
+            text,
+            "\n\nScore {}\nLength {}\nTime {:.1}s\nStars {}/{MAX_STARS}\n\nR: replay",
+            self.score, self.length, self.seconds, self.stars
+        );
+        if self.has_next_level() {
+            text.push_str("  N: next level");
+        }
+        if self.level.is_some() {
+            text.push_str("  F: free play");
+        }
+        text
+    }
+}
+
+/// Present while the cleared level is played on casually: reaching the goal
+/// or filling the board does not end the run, and the run is not recorded.
+#[derive(Resource)]
+pub struct FreePlay;
+
+/// Run condition for what a free play run skips.
+pub fn free_playing(free_play: Option<Res<FreePlay>>) -> bool {
+    free_play.is_some()
+}
+
+/// A piece of confetti, at a share of the window's width and height.
+#[derive(Component)]
+struct Confetti(Vec2);
+
+/// Shows the victory screen and keeps the replay when a run is won.
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                // Before the game over resets the score it shows, and before
+                // the campaign moves on from the level cleared.
+                show_victory
+                    .after(board_filled)
+                    .before(clear_level)
+                    .before(game_over)
+                    .run_if(not(bot_playing)),
+                save_replay.after(finish_recording),
+                end_free_play.after(game_over).run_if(free_playing),
+            )
+                .run_if(on_event::<GameOverEvent>)
+                .run_if(timer_finished::<MovementTick>)
+                .in_set(GameSet::Logic),
+        )
+        .add_systems(Update, (fall, choose.run_if(console_closed)));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_victory(
+    mut commands: Commands,
+    mut reader: EventReader<GameOverEvent>,
+    score: Res<Score>,
+    run: Res<Run>,
+    movement_timer: Res<TickTimer<MovementTick>>,
+    campaign: Option<Res<Campaign>>,
+    accessibility: Res<Accessibility>,
+    mut time: ResMut<Time<Virtual>>,
+    snakes: Query<&Segments>,
+) {
+    let Some(&GameOverEvent { cause, .. }) = reader.read().last() else {
+        return;
+    };
+    let level = campaign.map(|campaign| campaign.progress.level.min(LEVELS.len() - 1));
+    let stars = match (cause, level) {
+        (GameOverCause::Won, _) => MAX_STARS,
+        (GameOverCause::Finished, Some(level)) => LEVELS[level].stars(run.tick),
+        _ => return,
+    };
+    let results = Results {
+        score: score.0,
+        length: snakes.iter().next().map_or(0, |segments| segments.0.len()),
+        seconds: run.tick as f32 * movement_timer.interval().as_secs_f32(),
+        stars,
+        level,
+        was_paused: time.is_paused(),
+    };
+    info!(?results, "won");
+    time.pause();
+    let text = results.text();
+    commands.insert_resource(results);
+    commands
+        .spawn((
+            VictoryScreen,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.5)),
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size: 28.0,
+                    ..Default::default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            if accessibility.reduced_motion {
+                return;
+            }
+            let mut rng = rand::rng();
+            for _ in 0..CONFETTI {
+                let at = Vec2::new(rng.random(), -rng.random::<f32>());
+                screen.spawn((
+                    Confetti(at),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(at.x * 100.0),
+                        top: Val::Percent(at.y * 100.0),
+                        width: Val::Px(6.0),
+                        height: Val::Px(10.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(CONFETTI_COLORS[rng.random_range(0..CONFETTI_COLORS.len())]),
+                ));
+            }
+        });
+}
+
+/// Falls in real time, as the game is paused under it.
+fn fall(time: Res<Time<Real>>, mut confetti: Query<(&mut Confetti, &mut Node)>) {
+    let seconds = time.elapsed_secs();
+    for (mut piece, mut node) in &mut confetti {
+        piece.0.y += CONFETTI_SPEED * time.delta_secs();
+        let sway = (seconds * 3.0 + piece.0.x * 20.0).sin() * 0.02;
+        node.left = Val::Percent((piece.0.x + sway) * 100.0);
+        node.top = Val::Percent(piece.0.y * 100.0);
+    }
+}
+
+/// Acts on the player's choice and takes the results screen down. The run
+/// that won has already ended, and the next one starts on the campaign's
+/// next level, so going on needs nothing more.
+#[allow(clippy::too_many_arguments)]
+fn choose(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    results: Option<Res<Results>>,
+    campaign: Option<ResMut<Campaign>>,
+    arena: Res<Arena>,
+    mut time: ResMut<Time<Virtual>>,
+    screens: Query<Entity, With<VictoryScreen>>,
+    walls: Query<Entity, Or<(With<Obstacle>, With<gates::Key>)>>,
+) {
+    let Some(results) = results else {
+        return;
+    };
+    let next = results.has_next_level()
+        && keyboard_input.any_just_pressed([KeyCode::KeyN, KeyCode::Enter]);
+    let replay = keyboard_input.just_pressed(KeyCode::KeyR)
+        || (!results.has_next_level() && keyboard_input.just_pressed(KeyCode::Enter));
+    let free_play = results.level.is_some() && keyboard_input.just_pressed(KeyCode::KeyF);
+    if !next && !replay && !free_play {
+        return;
+    }
+    if let (Some(level), Some(mut campaign)) = (results.level, campaign) {
+        if replay || free_play {
+            for wall in &walls {
+                commands.entity(wall).despawn();
+            }
+            LEVELS[level].build(&mut commands, *arena);
+        }
+        if replay {
+            campaign.progress.level = level;
+        }
+        if free_play {
+            info!("free play on {}", LEVELS[level].name);
+            commands.insert_resource(FreePlay);
+        }
+    }
+    if !results.was_paused {
+        time.unpause();
+    }
+    commands.remove_resource::<Results>();
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+/// A crash ends free play; the campaign goes on from the level after.
+fn end_free_play(mut commands: Commands) {
+    commands.remove_resource::<FreePlay>();
+}
@@ -0,0 +1,179 @@
+//! WebAssembly mods for [`crate::scripting`], loaded from every `.wasm` file
+//! in `mods/` at startup.
+//!
+//! A module exports any of the hooks as functions of `i32`s returning
+//! nothing: `on_tick(tick)`, `on_eat(x, y, score)`, `on_spawn(x, y)` and
+//! `on_death(cause, score)`, with `cause` an index into
+//! [`DEATH_CAUSES`](crate::scripting::DEATH_CAUSES). It may import these from
+//! the `snake` module:
+//! - `spawn_food(x, y)`, `spawn_obstacle(x, y)` and `set_speed(ms)`
+//! - `score()`, `head_x()`, `head_y()` (both -1 with no snake),
+//!   `arena_width()` and `arena_height()`, all returning `i32`
+//!
+//! Nothing else is offered to import, so modules cannot reach files, the
+//! clock or the network. Memory is capped and each call runs on a fixed
+//! amount of fuel.
+
+use std::{path::Path, time::Duration};
+
+use bevy::prelude::*;
+use snake_core::Position;
+use wasmi::{
+    Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+    WasmParams,
+};
+
+use crate::scripting::{Hook, Mod, Mods, ScriptCommand, View, DEATH_CAUSES};
+
+const MODS_FOLDER: &str = "mods";
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+/// Roughly the number of instructions a call may run.
+const FUEL_PER_CALL: u64 = 1_000_000;
+
+struct State {
+    view: View,
+    commands: Vec<ScriptCommand>,
+    limits: StoreLimits,
+}
+
+pub struct WasmMod {
+    store: Store<State>,
+    instance: Instance,
+}
+
+impl WasmMod {
+    /// Instantiates a module from its binary (or, for tests, text) form.
+    pub fn new(wasm: impl AsRef<[u8]>) -> Result<Self, wasmi::Error> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm)?;
+        let mut store = Store::new(
+            &engine,
+            State {
+                view: View::default(),
+                commands: Vec::new(),
+                limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL)?;
+
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap(
+                "snake",
+                "spawn_food",
+                |mut caller: Caller<'_, State>, x: i32, y: i32| {
+                    let food = Position { x, y };
+                    caller
+                        .data_mut()
+                        .commands
+                        .push(ScriptCommand::SpawnFood(food));
+                },
+            )?
+            .func_wrap(
+                "snake",
+                "spawn_obstacle",
+                |mut caller: Caller<'_, State>, x: i32, y: i32| {
+                    let obstacle = Position { x, y };
+                    caller
+                        .data_mut()
+                        .commands
+                        .push(ScriptCommand::SpawnObstacle(obstacle));
+                },
+            )?
+            .func_wrap(
+                "snake",
+                "set_speed",
+                |mut caller: Caller<'_, State>, ms: i32| {
+                    let interval = Duration::from_millis(ms.max(1) as u64);
+                    caller
+                        .data_mut()
+                        .commands
+                        .push(ScriptCommand::SetSpeed(interval));
+                },
+            )?
+            .func_wrap("snake", "score", |caller: Caller<'_, State>| {
+                caller.data().view.score as i32
+            })?
+            .func_wrap("snake", "head_x", |caller: Caller<'_, State>| {
+                caller.data().view.head.map_or(-1, |head| head.x)
+            })?
+            .func_wrap("snake", "head_y", |caller: Caller<'_, State>| {
+                caller.data().view.head.map_or(-1, |head| head.y)
+            })?
+            .func_wrap("snake", "arena_width", |caller: Caller<'_, State>| {
+                caller.data().view.arena.width as i32
+            })?
+            .func_wrap("snake", "arena_height", |caller: Caller<'_, State>| {
+                caller.data().view.arena.height as i32
+            })?;
+        let instance = linker.instantiate_and_start(&mut store, &module)?;
+        Ok(Self { store, instance })
+    }
+
+    fn call_export<Params: WasmParams>(&mut self, name: &str, params: Params) {
+        if self.instance.get_func(&self.store, name).is_none() {
+            return;
+        }
+        let result = self
+            .instance
+            .get_typed_func::<Params, ()>(&self.store, name)
+            .and_then(|function| {
+                self.store.set_fuel(FUEL_PER_CALL)?;
+                function.call(&mut self.store, params)
+            });
+        if let Err(err) = result {
+            warn!("mod {name} failed: {err}");
+        }
+    }
+}
+
+impl Mod for WasmMod {
+    fn call(&mut self, hook: Hook, view: View) -> Vec<ScriptCommand> {
+        self.store.data_mut().view = view;
+        let name = hook.name();
+        match hook {
+            Hook::Tick(tick) => self.call_export(name, tick as i32),
+            Hook::Eat { food, score } => self.call_export(name, (food.x, food.y, score as i32)),
+            Hook::Spawn(food) => self.call_export(name, (food.x, food.y)),
+            Hook::Death { cause, score } => {
+                let cause = DEATH_CAUSES
+                    .iter()
+                    .position(|&known| known == cause)
+                    .unwrap_or_default();
+                self.call_export(name, (cause as i32, score as i32));
+            }
+        }
+        std::mem::take(&mut self.store.data_mut().commands)
+    }
+}
+
+pub(crate) fn load_mods_folder(mut mods: ResMut<Mods>) {
+    let Ok(entries) = std::fs::read_dir(MODS_FOLDER) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "wasm")
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        match load(&path) {
+            Ok(loaded) => {
+                info!("loaded mod {}", path.display());
+                mods.0.push(Box::new(loaded));
+            }
+            Err(err) => warn!("mod {} failed to load: {err}", path.display()),
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<WasmMod, Box<dyn std::error::Error>> {
+    Ok(WasmMod::new(std::fs::read(path)?)?)
+}
@@ -0,0 +1,238 @@
+//! Weather, set for each campaign level: rain or snow falling over the board.
+//!
+//! Both dim the board a little behind the falling drops or flakes, which are
+//! left out with reduced motion. Snow also drifts over a piece of food every
+//! [`DRIFT_INTERVAL`], hiding it: the snake has to pass over the drift once to
+//! clear it before the food underneath can be eaten. Drifts settle with the
+//! [`WorldClock`] and with randomness of their own, so a run that gets one is
+//! no longer kept as a replay.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use snake_core::Position;
+
+use crate::{
+    accessibility::Accessibility, replay::ReplayRecorder, snake_eating, timer_finished,
+    world_clock::WorldClock, Food, GameSet, MovementTick, Size, SnakeHead,
+};
+
+pub const DRIFT_INTERVAL: Duration = Duration::from_secs(8);
+const PARTICLES: usize = 40;
+const DRIFT_COLOR: Color = Color::linear_rgb(0.9, 0.92, 0.95);
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+}
+
+impl Weather {
+    /// Color laid over the whole board.
+    fn dimming(self) -> Color {
+        match self {
+            Self::Clear => Color::NONE,
+            Self::Rain => Color::linear_rgba(0.0, 0.02, 0.06, 0.25),
+            Self::Snow => Color::linear_rgba(0.8, 0.85, 0.9, 0.12),
+        }
+    }
+
+    /// Size in pixels, color and falling speed in window heights per second
+    /// of each drop or flake.
+    fn particle(self) -> (Vec2, Color, f32) {
+        match self {
+            Self::Clear => (Vec2::ZERO, Color::NONE, 0.0),
+            Self::Rain => (
+                Vec2::new(1.5, 12.0),
+                Color::linear_rgba(0.5, 0.6, 0.8, 0.6),
+                1.2,
+            ),
+            Self::Snow => (
+                Vec2::splat(4.0),
+                Color::linear_rgba(1.0, 1.0, 1.0, 0.8),
+                0.15,
+            ),
+        }
+    }
+}
+
+/// Food hidden under a drift, which the snake cannot eat until it has passed
+/// over it.
+#[derive(Component)]
+pub struct Drifted(pub(crate) Entity);
+
+#[derive(Component)]
+struct DriftCover;
+
+#[derive(Component)]
+struct WeatherOverlay;
+
+/// A drop or flake, at a share of the window's width and height.
+#[derive(Component)]
+struct Particle(Vec2);
+
+/// Shows the weather, and piles snow over food.
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .insert_resource(DriftTimer(Timer::new(DRIFT_INTERVAL, TimerMode::Repeating)))
+            .add_systems(
+                Update,
+                (
+                    show_weather
+                        .run_if(resource_changed::<Weather>.or(resource_changed::<Accessibility>)),
+                    fall,
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    clear_drifts
+                        .after(snake_eating)
+                        .run_if(timer_finished::<MovementTick>)
+                        .in_set(GameSet::Logic),
+                    (drift_snow, melt_orphaned_drifts)
+                        .chain()
+                        .in_set(GameSet::Spawning),
+                ),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct DriftTimer(Timer);
+
+fn show_weather(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    accessibility: Res<Accessibility>,
+    shown: Query<Entity, Or<(With<WeatherOverlay>, With<Particle>)>>,
+) {
+    for entity in &shown {
+        commands.entity(entity).despawn_recursive();
+    }
+    if *weather == Weather::Clear {
+        return;
+    }
+    commands.spawn((
+        WeatherOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..Default::default()
+        },
+        BackgroundColor(weather.dimming()),
+    ));
+    if accessibility.reduced_motion {
+        return;
+    }
+    let (size, color, _) = weather.particle();
+    let mut rng = rand::rng();
+    for _ in 0..PARTICLES {
+        let at = Vec2::new(rng.random(), rng.random());
+        commands.spawn((
+            Particle(at),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(at.x * 100.0),
+                top: Val::Percent(at.y * 100.0),
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                ..Default::default()
+            },
+            BackgroundColor(color),
+        ));
+    }
+}
+
+fn fall(time: Res<Time>, weather: Res<Weather>, mut particles: Query<(&mut Particle, &mut Node)>) {
+    let (_, _, speed) = weather.particle();
+    let seconds = time.elapsed_secs();
+    for (mut particle, mut node) in &mut particles {
+        particle.0.y = (particle.0.y + speed * time.delta_secs()).fract();
+        let sway = if *weather == Weather::Snow {
+            (seconds + particle.0.x * 10.0).sin() * 0.01
+        } else {
+            0.0
+        };
+        node.left = Val::Percent((particle.0.x + sway) * 100.0);
+        node.top = Val::Percent(particle.0.y * 100.0);
+    }
+}
+
+/// Hides `food`, lying at `position`, under a drift.
+pub fn bury(commands: &mut Commands, food: Entity, position: Position) {
+    let cover = commands
+        .spawn((
+            DriftCover,
+            Sprite::from_color(DRIFT_COLOR, Vec2::ONE),
+            position,
+            Size::square(0.9),
+        ))
+        .id();
+    commands
+        .entity(food)
+        .insert((Drifted(cover), Visibility::Hidden));
+}
+
+/// Buries a piece of food in snow now and then.
+fn drift_snow(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    clock: Res<WorldClock>,
+    mut timer: ResMut<DriftTimer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    food: Query<(Entity, &Position), (With<Food>, Without<Drifted>)>,
+) {
+    if !timer.0.tick(clock.delta()).just_finished() || *weather != Weather::Snow {
+        return;
+    }
+    let count = food.iter().count();
+    if count == 0 {
+        return;
+    }
+    let pick = rand::rng().random_range(0..count);
+    let Some((entity, &position)) = food.iter().nth(pick) else {
+        return;
+    };
+    debug!(?position, "food drifted over");
+    recorder.tainted = true;
+    bury(&mut commands, entity, position);
+}
+
+/// Clears the drift off any food the head passes over.
+fn clear_drifts(
+    mut commands: Commands,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut drifted: Query<(Entity, &Drifted, &Position, &mut Visibility)>,
+) {
+    for head in &heads {
+        for (entity, drift, position, mut visibility) in &mut drifted {
+            if position == head {
+                commands.entity(drift.0).despawn();
+                commands.entity(entity).remove::<Drifted>();
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+/// Takes away drifts whose food is gone, eaten by a critter or cleared at the
+/// end of a run.
+fn melt_orphaned_drifts(
+    mut commands: Commands,
+    covers: Query<Entity, With<DriftCover>>,
+    drifted: Query<&Drifted>,
+) {
+    for cover in &covers {
+        if !drifted.iter().any(|drift| drift.0 == cover) {
+            commands.entity(cover).despawn();
+        }
+    }
+}
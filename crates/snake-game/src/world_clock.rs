@@ -0,0 +1,43 @@
+//! Time for the world around the snake.
+//!
+//! Food spawning, pickups and anything else on the board that changes by
+//! itself go by the [`WorldClock`] rather than by [`Time`], so the world can
+//! be stopped while the snake keeps moving, as the time-freeze power-up does.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::frame_step::FrameStep;
+
+#[derive(Resource, Default, Debug)]
+pub struct WorldClock {
+    /// Whether the world stands still.
+    pub frozen: bool,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl WorldClock {
+    /// Time the world moved on by in this fixed update.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Time the world has moved on by since the game started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Moves the clock on, unless the world is frozen or frame-stepping is
+/// paused.
+pub(crate) fn advance(time: Res<Time>, frame_step: Res<FrameStep>, mut clock: ResMut<WorldClock>) {
+    let delta = if clock.frozen || frame_step.paused {
+        Duration::ZERO
+    } else {
+        time.delta()
+    };
+    clock.delta = delta;
+    clock.elapsed += delta;
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use snake_core::{GameMode, Position};
+use snake_game::{
+    achievements::{AchievementsPlugin, Progress, ACHIEVEMENTS_FILE},
+    harness::TestGame,
+    profile::Profile,
+    stats::{RunRecord, StatsPlugin},
+};
+
+fn run(score: u32, ended_at: u64) -> RunRecord {
+    RunRecord {
+        ended_at,
+        mode: GameMode::Classic,
+        score,
+        duration_ms: 60_000,
+        foods: BTreeMap::from([("plain".to_string(), score)]),
+        max_length: score as usize + 2,
+        average_speed: 6.0,
+        close_calls: 0,
+        left_turns: 0,
+        cause: "wall".to_string(),
+        cell: None,
+    }
+}
+
+#[test]
+fn totals_add_up_and_bests_keep_the_highest() {
+    let mut progress = Progress::default();
+    let unlocked = progress.record(&run(20, 100));
+    let ids: Vec<&str> = unlocked.iter().map(|achievement| achievement.id).collect();
+    assert_eq!(ids, ["first_bite", "length_10"]);
+    progress.record(&run(3, 200));
+    assert_eq!(progress.progress["food_100"], 23);
+    assert_eq!(progress.progress["score_25"], 20);
+    assert_eq!(progress.progress["hour_played"], 120);
+
+    for ended_at in 300..306 {
+        progress.record(&run(20, ended_at));
+    }
+    assert_eq!(progress.progress["food_100"], 100, "capped at the target");
+    assert_eq!(progress.unlocked["food_100"], 303);
+    assert_eq!(progress.unlocked["first_bite"], 100, "first unlock is kept");
+}
+
+#[test]
+fn filling_the_board_is_a_perfect_game() {
+    let mut progress = Progress::default();
+    progress.record(&run(20, 100));
+    assert!(!progress.unlocked.contains_key("perfect_game"));
+    let won = RunRecord {
+        cause: "won".to_string(),
+        ..run(20, 200)
+    };
+    progress.record(&won);
+    assert_eq!(progress.unlocked["perfect_game"], 200);
+}
+
+#[test]
+fn runs_count_towards_the_profiles_achievements() {
+    let root = std::env::temp_dir().join(format!("snake-achievements-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins((StatsPlugin, AchievementsPlugin));
+
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(10);
+    assert_eq!(game.game_overs(), 1);
+
+    let progress = Progress::read(&profile.path(ACHIEVEMENTS_FILE)).unwrap();
+    assert!(progress.unlocked.contains_key("first_bite"));
+    assert_eq!(progress.progress["runs_50"], 1);
+    assert!(!progress.unlocked.contains_key("score_25"));
+}
+
+#[test]
+fn winning_without_a_left_turn() {
+    let mut progress = Progress::default();
+    let won = |left_turns, ended_at| RunRecord {
+        cause: "finished".to_string(),
+        left_turns,
+        ..run(30, ended_at)
+    };
+    progress.record(&won(1, 100));
+    assert!(!progress.unlocked.contains_key("no_left_turns"));
+    progress.record(&RunRecord {
+        cause: "wall".to_string(),
+        ..won(0, 200)
+    });
+    assert!(
+        !progress.unlocked.contains_key("no_left_turns"),
+        "crashing is not winning"
+    );
+    progress.record(&won(0, 300));
+    assert_eq!(progress.unlocked["no_left_turns"], 300);
+    assert_eq!(progress.unlocked["length_25"], 100);
+    assert!(!progress.unlocked.contains_key("length_50"));
+}
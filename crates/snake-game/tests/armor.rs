@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use snake_core::{controller::Autopilot, Direction, GameMode, Position};
+use snake_game::{
+    armor::{spawn_armor, Armor, ArmorPickup, ArmorPlugin},
+    harness::TestGame,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(ArmorPlugin);
+    game
+}
+
+fn hit_points(game: &mut TestGame) -> u32 {
+    game.app_mut().world().resource::<Armor>().hit_points
+}
+
+fn pickups(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    world
+        .query_filtered::<(), With<ArmorPickup>>()
+        .iter(world)
+        .count()
+}
+
+fn place_armor(game: &mut TestGame, position: Position) {
+    let world = game.app_mut().world_mut();
+    spawn_armor(&mut world.commands(), position);
+    world.flush();
+}
+
+#[test]
+fn armor_takes_a_crash_instead_of_the_run() {
+    let mut game = game();
+    place_armor(&mut game, Position { x: 3, y: 4 });
+    game.place_obstacle(Position { x: 3, y: 6 });
+    game.advance(1);
+    assert_eq!(hit_points(&mut game), 1);
+
+    game.advance(2);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(hit_points(&mut game), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 5 }, "held still");
+    game.steer(Direction::Right);
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 4, y: 5 });
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn crashing_without_armor_left_ends_the_run() {
+    let mut game = game();
+    game.place_obstacle(Position { x: 3, y: 5 });
+    game.advance(2);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn armor_only_turns_up_in_harder_modes() {
+    let mut game = game();
+    // Keeps the snake alive, as every run that ends holds the armor back.
+    game.pilot(Box::new(Autopilot));
+    game.app_mut().insert_resource(GameMode::Casual);
+    game.advance(300);
+    assert_eq!(pickups(&mut game), 0);
+
+    game.app_mut().insert_resource(GameMode::Classic);
+    game.advance(300);
+    assert_eq!(game.game_overs(), 0);
+    assert!(pickups(&mut game) + hit_points(&mut game) as usize > 0);
+}
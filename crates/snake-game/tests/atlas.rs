@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use snake_core::Position;
+use snake_game::{
+    atlas::{Art, AtlasPlugin, SpriteAtlas},
+    harness::TestGame,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((AssetPlugin::default(), AtlasPlugin))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>();
+    game
+}
+
+/// The atlas cell `entity` is drawn with.
+fn cell(game: &mut TestGame, entity: Entity) -> Option<usize> {
+    let world = game.app_mut().world();
+    let sprite = world.get::<Sprite>(entity).unwrap();
+    let atlas = world.resource::<SpriteAtlas>();
+    assert_eq!(sprite.image, atlas.image, "everything shares one image");
+    sprite.texture_atlas.as_ref().map(|cell| cell.index)
+}
+
+fn segments(game: &mut TestGame) -> Vec<(Position, usize)> {
+    let world = game.app_mut().world_mut();
+    let mut segments: Vec<(Position, usize)> = world
+        .query::<(&Position, &Sprite)>()
+        .iter(world)
+        .filter_map(|(position, sprite)| Some((*position, sprite.texture_atlas.as_ref()?.index)))
+        .filter(|(_, index)| {
+            [Art::Head, Art::Body, Art::Tail]
+                .map(Art::index)
+                .contains(index)
+        })
+        .collect();
+    segments.sort_by_key(|(position, _)| position.y);
+    segments
+}
+
+#[test]
+fn themed_sprites_are_cut_from_one_atlas() {
+    let mut game = game();
+    let food = game.place_food(Position { x: 8, y: 8 });
+    game.app_mut().update();
+    assert_eq!(cell(&mut game, food), Some(Art::Food.index()));
+
+    let world = game.app_mut().world();
+    let atlas = world.resource::<SpriteAtlas>();
+    let layout = world
+        .resource::<Assets<TextureAtlasLayout>>()
+        .get(&atlas.layout)
+        .unwrap();
+    assert_eq!(layout.textures.len(), Art::ALL.len());
+    assert!(world.resource::<Assets<Image>>().contains(&atlas.image));
+}
+
+#[test]
+fn the_last_segment_is_drawn_as_the_tail() {
+    let mut game = game();
+    game.place_food(Position { x: 3, y: 5 });
+    game.advance(1);
+    game.app_mut().update();
+    assert_eq!(
+        segments(&mut game),
+        [
+            (Position { x: 3, y: 3 }, Art::Tail.index()),
+            (Position { x: 3, y: 4 }, Art::Head.index()),
+        ]
+    );
+
+    game.advance(1);
+    game.app_mut().update();
+    assert_eq!(
+        segments(&mut game),
+        [
+            (Position { x: 3, y: 3 }, Art::Tail.index()),
+            (Position { x: 3, y: 4 }, Art::Body.index()),
+            (Position { x: 3, y: 5 }, Art::Head.index()),
+        ]
+    );
+}
@@ -0,0 +1,203 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use snake_game::{
+    achievements::Progress,
+    bundle::{Bundle, BundleError, BundledFile, ImportMode, BUNDLE_VERSION},
+    high_scores::HighScores,
+    profile::{export, import, Profile},
+};
+
+fn profile_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("snake-bundle-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn write(dir: &Path, name: &str, contents: &str, modified: u64) {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, contents).unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(modified))
+        .unwrap();
+}
+
+#[test]
+fn merging_keeps_the_newest_copy_of_each_file() {
+    let laptop = profile_dir("laptop");
+    write(&laptop, "settings.json", "laptop settings", 200);
+    write(&laptop, "replays/speedrun-pb.snkr", "old best", 100);
+    write(&laptop, "settings.json.bak", "backup", 300);
+    let desktop = profile_dir("desktop");
+    write(&desktop, "settings.json", "desktop settings", 150);
+    write(&desktop, "replays/speedrun-pb.snkr", "new best", 250);
+    write(&desktop, "quicksave.json", "save", 50);
+
+    let mut local = Bundle::collect(&laptop, "ada").unwrap();
+    assert!(!local.files.contains_key("settings.json.bak"));
+    let remote =
+        Bundle::decode(&Bundle::collect(&desktop, "ada").unwrap().encode().unwrap()).unwrap();
+    let pulled = local.merge(&remote);
+    assert_eq!(pulled, ["quicksave.json", "replays/speedrun-pb.snkr"]);
+    assert_eq!(local.files["settings.json"].contents, b"laptop settings");
+
+    local.unpack(&laptop, &pulled).unwrap();
+    assert_eq!(
+        fs::read_to_string(laptop.join("replays/speedrun-pb.snkr")).unwrap(),
+        "new best"
+    );
+    assert_eq!(Bundle::collect(&laptop, "ada").unwrap().files, local.files);
+}
+
+#[test]
+fn merging_keeps_the_runs_played_on_both_machines() {
+    let laptop = profile_dir("laptop-runs");
+    write(
+        &laptop,
+        "high-scores.json",
+        r#"{"classic-10x10": [{"score": 30, "ended_at": 100}, {"score": 10, "ended_at": 90}]}"#,
+        100,
+    );
+    write(
+        &laptop,
+        "runs.jsonl",
+        "{\"ended_at\":90}\n{\"ended_at\":100}\n",
+        100,
+    );
+    write(
+        &laptop,
+        "achievements.json",
+        r#"{"progress": {"score_25": 25, "food_1000": 300}, "unlocked": {"score_25": 100}}"#,
+        100,
+    );
+    let desktop = profile_dir("desktop-runs");
+    write(
+        &desktop,
+        "high-scores.json",
+        r#"{"classic-10x10": [{"score": 20, "ended_at": 200}, {"score": 10, "ended_at": 90}]}"#,
+        200,
+    );
+    write(
+        &desktop,
+        "runs.jsonl",
+        "{\"ended_at\":90}\n{\"ended_at\":200}\n",
+        200,
+    );
+    write(
+        &desktop,
+        "achievements.json",
+        r#"{"progress": {"score_25": 20, "food_1000": 1000}, "unlocked": {"food_1000": 200}}"#,
+        200,
+    );
+
+    let mut local = Bundle::collect(&laptop, "ada").unwrap();
+    let remote = Bundle::collect(&desktop, "ada").unwrap();
+    let pulled = local.merge(&remote);
+    assert_eq!(
+        pulled,
+        ["achievements.json", "high-scores.json", "runs.jsonl"]
+    );
+    local.unpack(&laptop, &pulled).unwrap();
+
+    let scores = HighScores::read(&laptop.join("high-scores.json")).unwrap();
+    let scores: Vec<(u32, u64)> = scores.0["classic-10x10"]
+        .iter()
+        .map(|high| (high.score, high.ended_at))
+        .collect();
+    assert_eq!(scores, [(30, 100), (20, 200), (10, 90)]);
+    assert_eq!(
+        fs::read_to_string(laptop.join("runs.jsonl")).unwrap(),
+        "{\"ended_at\":90}\n{\"ended_at\":100}\n{\"ended_at\":200}\n"
+    );
+    let progress = Progress::read(&laptop.join("achievements.json")).unwrap();
+    assert_eq!(progress.progress["score_25"], 25);
+    assert_eq!(progress.progress["food_1000"], 1000);
+    assert_eq!(progress.unlocked.len(), 2);
+
+    // The desktop ends up with the same files, so the next sync is a no-op.
+    let mut remote = remote;
+    remote.merge(&local);
+    assert_eq!(remote.files, local.files);
+    assert!(local.merge(&remote).is_empty());
+}
+
+#[test]
+fn unpacking_stays_inside_the_profile() {
+    let dir = profile_dir("escape");
+    let mut bundle = Bundle::default();
+    bundle.files.insert(
+        "../outside".to_string(),
+        BundledFile {
+            modified: 0,
+            contents: b"gotcha".to_vec(),
+        },
+    );
+    let names: Vec<String> = bundle.files.keys().cloned().collect();
+    assert!(matches!(
+        bundle.unpack(&dir, &names),
+        Err(BundleError::UnsafePath(_))
+    ));
+}
+
+#[test]
+fn exported_profiles_import_by_merging_or_replacing() {
+    let root = profile_dir("transfer");
+    let home = Profile::new(&root, "ada");
+    write(&home.dir, "settings.json", "home settings", 300);
+    write(&home.dir, "campaign/slot-1.json", "home slot", 100);
+    let file = root.join("ada.snakeprofile");
+    export(&home, &file).unwrap();
+
+    let work = Profile::new(&root, "ada at work");
+    write(&work.dir, "campaign/slot-1.json", "work slot", 200);
+    write(&work.dir, "quicksave.json", "save", 50);
+    let mut imported = import(&work, &file, ImportMode::Merge).unwrap();
+    assert_eq!(imported, ["settings.json"]);
+    assert_eq!(
+        fs::read_to_string(work.path("campaign/slot-1.json")).unwrap(),
+        "work slot"
+    );
+
+    imported = import(&work, &file, ImportMode::Replace).unwrap();
+    assert_eq!(imported, ["campaign/slot-1.json", "settings.json"]);
+    assert_eq!(
+        fs::read_to_string(work.path("campaign/slot-1.json")).unwrap(),
+        "home slot"
+    );
+    assert!(!work.path("quicksave.json").exists());
+
+    // A bundle with a path that escapes the profile is refused before any of
+    // the profile's own files go.
+    let mut unsafe_bundle = Bundle::collect(&home.dir, "ada").unwrap();
+    unsafe_bundle.files.insert(
+        "../outside".to_string(),
+        BundledFile {
+            modified: 0,
+            contents: b"gotcha".to_vec(),
+        },
+    );
+    write(&work.dir, "quicksave.json", "save", 50);
+    assert!(matches!(
+        unsafe_bundle.import(&work.dir, ImportMode::Replace),
+        Err(BundleError::UnsafePath(_))
+    ));
+    assert!(work.path("quicksave.json").exists());
+    assert_eq!(
+        Bundle::collect(&work.dir, "ada").unwrap().files.len(),
+        3,
+        "nothing is left staged"
+    );
+
+    fs::write(&file, format!(r#"{{"version": {}}}"#, BUNDLE_VERSION + 1)).unwrap();
+    assert!(matches!(
+        import(&work, &file, ImportMode::Merge),
+        Err(BundleError::TooNew(_))
+    ));
+}
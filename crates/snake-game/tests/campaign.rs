@@ -0,0 +1,76 @@
+use snake_core::{Arena, Position, START_POSITION};
+use snake_game::{
+    campaign::{CampaignSlot, LEVELS, MAX_STARS},
+    profile::Profile,
+    tunnels::{Tunnel, Tunnels},
+};
+
+#[test]
+fn clearing_levels_earns_stars_and_unlocks_the_next() {
+    let mut slot = CampaignSlot::default();
+    assert_eq!(slot.completion(), 0);
+    let fast = LEVELS[0].star_ticks[1];
+    assert_eq!(slot.clear(fast), MAX_STARS);
+    assert_eq!((slot.level, slot.unlocked), (1, 2));
+
+    assert_eq!(slot.clear(u32::MAX), 1);
+    assert_eq!(slot.stars, [3, 1]);
+    let total = LEVELS.len() as u32 * u32::from(MAX_STARS);
+    assert_eq!(slot.completion(), 4 * 100 / total);
+
+    for _ in 2..LEVELS.len() + 2 {
+        slot.clear(0);
+    }
+    assert_eq!(slot.level, LEVELS.len() - 1, "stays on the last level");
+    assert_eq!(slot.unlocked, LEVELS.len());
+    assert!(
+        slot.completion() < 100,
+        "the second level is not perfect yet"
+    );
+}
+
+#[test]
+fn slots_are_saved_independently() {
+    let root = std::env::temp_dir().join(format!("snake-campaign-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut slot = CampaignSlot::default();
+    slot.clear(0);
+    slot.write(&profile.path(&CampaignSlot::file(1))).unwrap();
+
+    let read = |index| CampaignSlot::read(&profile.path(&CampaignSlot::file(index))).unwrap();
+    assert_eq!(read(0), None);
+    assert_eq!(read(1), Some(slot));
+    assert_eq!(read(2), None);
+}
+
+#[test]
+fn levels_leave_room_to_start() {
+    for arena in [
+        Arena::default(),
+        Arena {
+            width: 20,
+            height: 15,
+        },
+    ] {
+        for level in LEVELS {
+            let obstacles = level.obstacles(arena);
+            assert!(obstacles.iter().all(|&cell| arena.contains(cell)));
+            for y in START_POSITION.y - 1..arena.height as i32 {
+                let cell = Position {
+                    x: START_POSITION.x,
+                    y,
+                };
+                assert!(!obstacles.contains(&cell), "{} blocks {cell:?}", level.name);
+                for lock in level.locks(arena) {
+                    assert!(!lock.gate.contains(&cell), "{} gates {cell:?}", level.name);
+                }
+            }
+            for Tunnel(a, b) in Tunnels::new(arena, (level.tunnels)(arena)).tunnels() {
+                for end in [a, b] {
+                    assert!(!obstacles.contains(end), "{} walls off {end:?}", level.name);
+                }
+            }
+        }
+    }
+}
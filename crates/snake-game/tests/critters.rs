@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use snake_core::{controller::Autopilot, GameMode, Position};
+use snake_game::{
+    critters::{Critter, CrittersPlugin},
+    harness::TestGame,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(CrittersPlugin);
+    game
+}
+
+fn critters(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    world
+        .query_filtered::<(), With<Critter>>()
+        .iter(world)
+        .count()
+}
+
+#[test]
+fn critters_steal_food_before_the_snake_gets_there() {
+    let mut game = game();
+    game.place_food(Position { x: 8, y: 0 });
+    game.app_mut()
+        .world_mut()
+        .spawn((Critter, Position { x: 8, y: 4 }));
+    game.advance(12);
+    let world = game.app_mut().world_mut();
+    let food_left = world
+        .query_filtered::<&Position, Without<Critter>>()
+        .iter(world)
+        .any(|&position| position == Position { x: 8, y: 0 });
+    assert!(!food_left, "the critter ate it");
+    assert_eq!(game.score(), 0);
+}
+
+#[test]
+fn critters_are_harmless_to_touch() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .spawn((Critter, Position { x: 3, y: 5 }));
+    game.advance(4);
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn critters_only_turn_up_in_casual_runs() {
+    let mut game = game();
+    // Keeps the snake alive, as every run that ends holds the critters back.
+    game.pilot(Box::new(Autopilot));
+    game.advance(120);
+    assert_eq!(critters(&mut game), 0);
+
+    game.app_mut().insert_resource(GameMode::Casual);
+    game.advance(120);
+    assert_eq!(game.game_overs(), 0);
+    assert!(critters(&mut game) > 0);
+}
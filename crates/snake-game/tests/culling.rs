@@ -0,0 +1,61 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use snake_core::{Arena, Position};
+use snake_game::{
+    culling::{Culled, CullingPlugin},
+    harness::TestGame,
+};
+
+const HUGE: Arena = Arena {
+    width: 500,
+    height: 500,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(CullingPlugin);
+    let world = game.app_mut().world_mut();
+    world.spawn((Window::default(), PrimaryWindow));
+    world.insert_resource(HUGE);
+    game
+}
+
+/// Whether the food is drawn, or only kept as data.
+fn drawn(game: &mut TestGame, food: Entity) -> bool {
+    let world = game.app_mut().world();
+    let drawn = world.get::<Sprite>(food).is_some();
+    assert_ne!(drawn, world.get::<Culled>(food).is_some());
+    drawn
+}
+
+#[test]
+fn only_what_is_in_view_is_drawn() {
+    let mut game = game();
+    let near = game.place_food(Position { x: 10, y: 10 });
+    let far = game.place_food(Position { x: 400, y: 400 });
+    game.app_mut().update();
+    game.app_mut().update();
+    assert!(drawn(&mut game, near));
+    assert!(!drawn(&mut game, far));
+
+    // Moving into view brings the sprite back.
+    *game.app_mut().world_mut().get_mut::<Position>(far).unwrap() = Position { x: 20, y: 20 };
+    game.app_mut().update();
+    game.app_mut().update();
+    assert!(drawn(&mut game, far));
+}
+
+#[test]
+fn everything_is_drawn_once_the_arena_fits_the_window() {
+    let mut game = game();
+    let far = game.place_food(Position { x: 400, y: 400 });
+    game.app_mut().update();
+    game.app_mut().update();
+    assert!(!drawn(&mut game, far));
+
+    let world = game.app_mut().world_mut();
+    let mut windows = world.query::<&mut Window>();
+    windows.single_mut(world).resolution.set(4000.0, 4000.0);
+    game.app_mut().update();
+    game.app_mut().update();
+    assert!(drawn(&mut game, far));
+}
@@ -0,0 +1,74 @@
+use snake_core::Position;
+use snake_game::{
+    daily::{parse_date, replay_path, Daily, DailyHistory, DailyPlugin, DAILIES_FILE},
+    harness::TestGame,
+    high_scores::{HighScoresPlugin, HIGH_SCORES_FILE},
+    profile::Profile,
+};
+
+#[test]
+fn dates_name_days_since_the_epoch() {
+    assert_eq!(parse_date("1970-01-01"), Some(0));
+    assert_eq!(parse_date("2024-02-29"), Some(19_782));
+    let day = parse_date("2026-10-15").unwrap();
+    assert_eq!(
+        Daily {
+            day,
+            practice: false
+        }
+        .date(),
+        "2026-10-15"
+    );
+    assert_eq!(parse_date("2025-04-31"), None);
+    assert_eq!(parse_date("2025-13-01"), None);
+    assert_eq!(parse_date("yesterday"), None);
+}
+
+fn playing(name: &str, daily: Daily) -> (TestGame, Profile) {
+    let root = std::env::temp_dir().join(format!("snake-daily-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .insert_resource(daily)
+        .add_plugins((DailyPlugin, HighScoresPlugin));
+    (game, profile)
+}
+
+#[test]
+fn each_try_at_the_day_is_logged_with_its_best() {
+    let daily = Daily {
+        day: 20_000,
+        practice: false,
+    };
+    let (mut game, profile) = playing("today", daily);
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(10);
+    game.advance(10);
+    assert_eq!(game.game_overs(), 2);
+
+    let history = DailyHistory::read(&profile.path(DAILIES_FILE)).unwrap();
+    let entry = &history.0[&daily.date()];
+    assert_eq!(entry.seed, daily.seed());
+    assert_eq!(entry.attempts, 2);
+    assert_eq!(entry.best_score, 1);
+    assert!(profile.path(&replay_path(&daily.date())).exists());
+    assert!(profile.path(HIGH_SCORES_FILE).exists());
+}
+
+#[test]
+fn practice_runs_are_not_scored() {
+    let (mut game, profile) = playing(
+        "practice",
+        Daily {
+            day: 20_000,
+            practice: true,
+        },
+    );
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(10);
+    assert_eq!(game.game_overs(), 1);
+    assert!(!profile.path(DAILIES_FILE).exists());
+    assert!(!profile.path(HIGH_SCORES_FILE).exists());
+}
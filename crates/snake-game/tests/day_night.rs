@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use snake_core::{GameMode, Position};
+use snake_game::{
+    day_night::{spawn_golden, DayNight, DayNightPlugin, Phase, DAY_LENGTH, GOLDEN_WORTH},
+    harness::TestGame,
+    world_clock::WorldClock,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(DayNightPlugin);
+    game.app_mut().insert_resource(GameMode::Casual);
+    game
+}
+
+fn day_night(game: &mut TestGame) -> DayNight {
+    *game.app_mut().world().resource::<DayNight>()
+}
+
+#[test]
+fn days_go_round_through_the_night() {
+    let at = |share: f32| {
+        DayNight {
+            elapsed: DAY_LENGTH.mul_f32(share),
+        }
+        .phase()
+    };
+    assert_eq!(at(0.0), Phase::Day);
+    assert_eq!(at(0.45), Phase::Dusk);
+    assert_eq!(at(0.6), Phase::Night);
+    assert_eq!(at(0.9), Phase::Dawn);
+    assert_eq!(at(1.1), Phase::Day);
+}
+
+#[test]
+fn the_day_stops_with_the_world() {
+    let mut game = game();
+    game.advance(2);
+    let before = day_night(&mut game).elapsed;
+    assert!(before > Duration::ZERO);
+
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<WorldClock>()
+        .frozen = true;
+    game.advance(2);
+    assert_eq!(day_night(&mut game).elapsed, before);
+}
+
+#[test]
+fn golden_food_is_worth_more() {
+    let mut game = game();
+    let world = game.app_mut().world_mut();
+    spawn_golden(&mut world.commands(), Position { x: 3, y: 5 });
+    world.flush();
+    game.advance(2);
+    assert_eq!(game.score(), GOLDEN_WORTH);
+}
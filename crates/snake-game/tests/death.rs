@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use snake_core::{Direction, Position};
+use snake_game::{
+    death::{FatalCell, LastDeath, FATAL_CELL_FOR},
+    harness::TestGame,
+    TICK_HZ,
+};
+
+fn last_death(game: &mut TestGame) -> LastDeath {
+    game.app_mut().world().resource::<LastDeath>().clone()
+}
+
+fn fatal_cells(game: &mut TestGame) -> Vec<Position> {
+    let world = game.app_mut().world_mut();
+    world
+        .query_filtered::<&Position, With<FatalCell>>()
+        .iter(world)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn hitting_the_wall_names_the_cell_and_lights_it_up() {
+    let mut game = TestGame::new();
+    game.advance(7);
+    assert_eq!(game.game_overs(), 1);
+    let cell = Position { x: 3, y: 9 };
+    assert_eq!(
+        last_death(&mut game),
+        LastDeath {
+            reason: "Hit the wall at (3,9)".to_string(),
+            cell: Some(cell),
+        }
+    );
+    game.app_mut().update();
+    assert_eq!(fatal_cells(&mut game), [cell]);
+
+    let step = Time::<Fixed>::from_hz(TICK_HZ).timestep();
+    for _ in 0..=(FATAL_CELL_FOR.as_secs_f64() / step.as_secs_f64()).ceil() as usize {
+        game.app_mut().update();
+    }
+    assert!(fatal_cells(&mut game).is_empty(), "the highlight fades");
+}
+
+#[test]
+fn biting_the_tail_gives_the_length() {
+    let mut game = TestGame::new();
+    for y in 4..=6 {
+        game.place_food(Position { x: 3, y });
+    }
+    game.advance(3);
+    for direction in [Direction::Right, Direction::Down, Direction::Left] {
+        game.steer(direction);
+        game.advance(1);
+    }
+    assert_eq!(game.game_overs(), 1);
+    assert_eq!(
+        last_death(&mut game),
+        LastDeath {
+            reason: "Bit your own tail at length 5".to_string(),
+            cell: Some(Position { x: 3, y: 5 }),
+        }
+    );
+}
@@ -0,0 +1,44 @@
+use snake_core::Position;
+use snake_game::{
+    eggs::{spawn_egg, Egg, EggsPlugin, HATCHLINGS, HATCH_AFTER, HATCH_RADIUS},
+    harness::TestGame,
+};
+
+fn game_with_egg(position: Position) -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(EggsPlugin);
+    let world = game.app_mut().world_mut();
+    spawn_egg(&mut world.commands(), position);
+    world.flush();
+    game
+}
+
+#[test]
+fn eggs_left_alone_hatch_into_food_nearby() {
+    let egg = Position { x: 7, y: 2 };
+    let mut game = game_with_egg(egg);
+    let world = game.app_mut().world_mut();
+    let mut eggs = world.query::<&mut Egg>();
+    let almost = HATCH_AFTER - std::time::Duration::from_millis(1);
+    eggs.single_mut(world).0.set_elapsed(almost);
+    game.advance(1);
+
+    let food = game.food();
+    assert_eq!(food.len(), HATCHLINGS);
+    for cell in food {
+        assert!(cell.x.abs_diff(egg.x) as i32 <= HATCH_RADIUS);
+        assert!(cell.y.abs_diff(egg.y) as i32 <= HATCH_RADIUS);
+    }
+    let world = game.app_mut().world_mut();
+    assert!(world.query::<&Egg>().iter(world).next().is_none());
+}
+
+#[test]
+fn eating_an_egg_is_worth_a_point() {
+    let mut game = game_with_egg(Position { x: 3, y: 5 });
+    let length = game.length();
+    game.advance(2);
+    assert_eq!(game.score(), 1);
+    assert_eq!(game.length(), length + 1);
+    assert!(game.food().is_empty());
+}
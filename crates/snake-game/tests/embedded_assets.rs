@@ -0,0 +1,48 @@
+#![cfg(feature = "embedded-assets")]
+
+use bevy::{asset::LoadState, prelude::*, text::FontLoader};
+use snake_game::{
+    embedded::asset_path,
+    harness::TestGame,
+    typography::{TypographyPlugin, PIXEL_FONT},
+};
+
+/// App updates to wait for an asset to load before giving up.
+const MAX_UPDATES: usize = 1_000;
+
+#[test]
+fn themed_fonts_load_without_an_assets_directory() {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((
+            AssetPlugin {
+                file_path: "no-assets-here".to_string(),
+                ..Default::default()
+            },
+            TypographyPlugin,
+        ))
+        .init_asset::<Font>()
+        .init_asset_loader::<FontLoader>();
+    assert!(!std::path::Path::new("no-assets-here").exists());
+    let font: Handle<Font> = game
+        .app_mut()
+        .world()
+        .resource::<AssetServer>()
+        .load(asset_path(PIXEL_FONT));
+    for _ in 0..MAX_UPDATES {
+        let state = game
+            .app_mut()
+            .world()
+            .resource::<AssetServer>()
+            .load_state(&font);
+        match state {
+            LoadState::Loaded => return,
+            LoadState::Failed(err) => panic!("the font did not load: {err}"),
+            _ => {
+                game.app_mut().update();
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+    }
+    panic!("still loading after {MAX_UPDATES} updates");
+}
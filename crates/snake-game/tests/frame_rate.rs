@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+    winit::{UpdateMode, WinitSettings},
+};
+use snake_game::{
+    frame_rate::{FpsCap, FrameRatePlugin, Vsync, LOW_POWER_FRAME},
+    harness::TestGame,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(FrameRatePlugin);
+    game.app_mut()
+        .world_mut()
+        .spawn((Window::default(), PrimaryWindow));
+    game
+}
+
+fn key(game: &mut TestGame, key_code: KeyCode, state: ButtonState) {
+    game.app_mut().world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    game.app_mut().update();
+}
+
+fn ctrl(game: &mut TestGame, key_code: KeyCode) {
+    key(game, KeyCode::ControlLeft, ButtonState::Pressed);
+    key(game, key_code, ButtonState::Pressed);
+    key(game, key_code, ButtonState::Released);
+    key(game, KeyCode::ControlLeft, ButtonState::Released);
+}
+
+fn present_mode(game: &mut TestGame) -> PresentMode {
+    let world = game.app_mut().world_mut();
+    world.query::<&Window>().single(world).present_mode
+}
+
+#[test]
+fn ctrl_v_turns_vsync_off_and_on() {
+    let mut game = game();
+    game.app_mut().update();
+    assert_eq!(present_mode(&mut game), PresentMode::AutoVsync);
+
+    ctrl(&mut game, KeyCode::KeyV);
+    assert_eq!(*game.app_mut().world().resource::<Vsync>(), Vsync(false));
+    assert_eq!(present_mode(&mut game), PresentMode::AutoNoVsync);
+    ctrl(&mut game, KeyCode::KeyV);
+    assert_eq!(present_mode(&mut game), PresentMode::AutoVsync);
+}
+
+#[test]
+fn ctrl_l_steps_through_the_caps() {
+    let mut game = game();
+    let mut caps = Vec::new();
+    for _ in 0..4 {
+        ctrl(&mut game, KeyCode::KeyL);
+        caps.push(*game.app_mut().world().resource::<FpsCap>());
+    }
+    assert_eq!(
+        caps,
+        [
+            FpsCap::Fps30,
+            FpsCap::Fps60,
+            FpsCap::Fps120,
+            FpsCap::Unlimited
+        ]
+    );
+}
+
+#[test]
+fn a_cap_holds_frames_back() {
+    let mut game = game();
+    game.app_mut().insert_resource(FpsCap::Fps30);
+    game.app_mut().update();
+    let start = Instant::now();
+    // The first frame only marks where the cap counts from.
+    for _ in 0..4 {
+        game.app_mut().update();
+    }
+    assert!(start.elapsed() >= Duration::from_secs(3) / 30);
+}
+
+fn focused_mode(game: &mut TestGame) -> UpdateMode {
+    game.app_mut()
+        .world()
+        .resource::<WinitSettings>()
+        .focused_mode
+}
+
+#[test]
+fn pausing_drops_to_low_power_until_play_resumes() {
+    let mut game = game();
+    game.app_mut().update();
+    assert_eq!(focused_mode(&mut game), UpdateMode::Continuous);
+
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .pause();
+    game.app_mut().update();
+    assert_eq!(
+        focused_mode(&mut game),
+        UpdateMode::reactive(LOW_POWER_FRAME)
+    );
+
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .unpause();
+    game.app_mut().update();
+    assert_eq!(focused_mode(&mut game), UpdateMode::Continuous);
+
+    key(&mut game, KeyCode::F10, ButtonState::Pressed);
+    assert_eq!(
+        focused_mode(&mut game),
+        UpdateMode::reactive(LOW_POWER_FRAME),
+        "frame-stepping counts as paused"
+    );
+}
@@ -0,0 +1,350 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use snake_core::{controller::Autopilot, Arena, Direction, GameMode, Position};
+use snake_game::{
+    gates::Lock,
+    harness::TestGame,
+    lives::Lives,
+    tail_cutting::TailCutting,
+    terrain::{Terrain, Tile},
+    TickAlpha,
+};
+
+#[test]
+fn snake_keeps_moving_in_its_direction() {
+    let mut game = TestGame::new();
+    game.advance(3);
+    assert_eq!(game.head(), Position { x: 3, y: 6 });
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn steering_turns_the_snake() {
+    let mut game = TestGame::new();
+    game.steer(Direction::Right);
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 5, y: 3 });
+    assert_eq!(game.direction(), Direction::Right);
+}
+
+#[test]
+fn reversing_is_ignored() {
+    let mut game = TestGame::new();
+    game.steer(Direction::Down);
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+    assert_eq!(game.direction(), Direction::Up);
+}
+
+#[test]
+fn eating_food_scores_and_grows() {
+    let mut game = TestGame::new();
+    game.place_food(Position { x: 3, y: 5 });
+    game.advance(1);
+    assert_eq!((game.score(), game.length()), (0, 2));
+    game.advance(1);
+    assert_eq!((game.score(), game.length()), (1, 3));
+}
+
+#[test]
+fn each_snake_moves_and_grows_on_its_own() {
+    let mut game = TestGame::new();
+    let other = game.add_snake(Position { x: 7, y: 3 }, Direction::Up);
+    game.place_food(Position { x: 7, y: 5 });
+    game.advance(2);
+
+    let snakes = game.snakes();
+    assert_eq!(snakes.len(), 2);
+    let first = snakes.into_iter().find(|&head| head != other).unwrap();
+    assert_eq!(game.head_of(first), Position { x: 3, y: 5 });
+    assert_eq!(game.head_of(other), Position { x: 7, y: 5 });
+    assert_eq!(game.length_of(first), 2);
+    assert_eq!(game.length_of(other), 3, "only the snake that ate grows");
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn the_window_title_shows_the_mode_and_score() {
+    let mut game = TestGame::new();
+    let window = game
+        .app_mut()
+        .world_mut()
+        .spawn((Window::default(), PrimaryWindow))
+        .id();
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(1);
+    game.app_mut().update();
+    let title = |game: &mut TestGame| {
+        game.app_mut()
+            .world()
+            .get::<Window>(window)
+            .unwrap()
+            .title
+            .clone()
+    };
+    assert_eq!(title(&mut game), "Snake — Classic — 1");
+
+    game.app_mut().insert_resource(GameMode::Casual);
+    game.app_mut().update();
+    assert_eq!(title(&mut game), "Snake — Casual — 1");
+}
+
+#[test]
+fn resizing_the_window_lays_the_board_out_again() {
+    let mut game = TestGame::new();
+    let window = game
+        .app_mut()
+        .world_mut()
+        .spawn((Window::default(), PrimaryWindow))
+        .id();
+    game.place_obstacle(Position { x: 0, y: 0 });
+    game.app_mut().update();
+    let corner = |game: &mut TestGame| {
+        let world = game.app_mut().world_mut();
+        let mut transforms = world.query_filtered::<&Transform, Without<Camera2d>>();
+        transforms
+            .iter(world)
+            .min_by(|a, b| a.translation.x.total_cmp(&b.translation.x))
+            .map(|transform| (transform.translation.truncate(), transform.scale.x))
+    };
+    // 1280x720 fits the 10x10 arena at 72 pixels a cell.
+    assert_eq!(corner(&mut game), Some((Vec2::splat(-324.0), 72.0)));
+
+    game.app_mut()
+        .world_mut()
+        .get_mut::<Window>(window)
+        .unwrap()
+        .resolution
+        .set(500.0, 500.0);
+    game.app_mut().update();
+    assert_eq!(corner(&mut game), Some((Vec2::splat(-225.0), 50.0)));
+}
+
+#[test]
+fn tick_alpha_runs_from_one_movement_tick_to_the_next() {
+    let mut game = TestGame::new();
+    game.advance(1);
+    let alpha = |game: &mut TestGame| game.app_mut().world().resource::<TickAlpha>().0;
+    let after_tick = alpha(&mut game);
+    assert!(after_tick < 0.2, "{after_tick}");
+    for _ in 0..4 {
+        game.app_mut().update();
+    }
+    let between = alpha(&mut game);
+    assert!(between > after_tick && between < 1.0, "{between}");
+    game.advance(1);
+    assert!(alpha(&mut game) < between);
+}
+
+#[test]
+fn movement_ticks_come_every_same_number_of_steps() {
+    let mut game = TestGame::new();
+    let mut head = game.head();
+    let (mut gaps, mut updates) = (Vec::new(), 0);
+    while gaps.len() < 4 {
+        game.app_mut().update();
+        updates += 1;
+        if game.head() != head {
+            head = game.head();
+            gaps.push(updates);
+            updates = 0;
+        }
+    }
+    // 150 ms at 100 Hz, after however much of a tick start-up took.
+    assert_eq!(gaps[1..], [15, 15, 15]);
+}
+
+#[test]
+fn hitting_the_wall_ends_the_run() {
+    let mut game = TestGame::new();
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(6);
+    assert_eq!(game.head(), Position { x: 3, y: 9 });
+    assert_eq!(game.game_overs(), 0);
+
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+    assert_eq!(game.head(), Position { x: 3, y: 3 });
+    assert_eq!((game.score(), game.length()), (0, 2));
+}
+
+#[test]
+fn obstacles_end_the_run_and_are_cleared() {
+    let mut game = TestGame::new();
+    game.place_obstacle(Position { x: 3, y: 5 });
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+
+    // The next run goes straight through the cell.
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 3, y: 5 });
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn a_spare_life_brings_the_snake_back_unable_to_crash() {
+    let mut game = TestGame::new();
+    game.app_mut().world_mut().resource_mut::<Lives>().spare = 1;
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(7);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 3 });
+    assert_eq!((game.score(), game.length()), (1, 2));
+    assert_eq!(game.app_mut().world().resource::<Lives>().spare, 0);
+
+    // Coming back next to an obstacle holds the snake still instead.
+    game.place_obstacle(Position { x: 3, y: 4 });
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 3, y: 3 });
+    game.steer(Direction::Right);
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 4, y: 3 });
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn biting_the_tail_ends_the_run() {
+    let mut game = TestGame::new();
+    for y in 4..=6 {
+        game.place_food(Position { x: 3, y });
+    }
+    game.advance(3);
+    assert_eq!(game.length(), 5);
+
+    for direction in [Direction::Right, Direction::Down] {
+        game.steer(direction);
+        game.advance(1);
+    }
+    assert_eq!(game.game_overs(), 0);
+    game.steer(Direction::Left);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn tail_cutting_cuts_the_snake_where_it_bites() {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(GameMode::Casual)
+        .insert_resource(TailCutting(true));
+    for y in 4..=6 {
+        game.place_food(Position { x: 3, y });
+    }
+    game.advance(3);
+    for direction in [Direction::Right, Direction::Down, Direction::Left] {
+        game.steer(direction);
+        game.advance(1);
+    }
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 5 });
+    assert_eq!((game.score(), game.length()), (1, 3));
+}
+
+#[test]
+fn mud_lets_the_snake_move_every_other_tick() {
+    let mut game = TestGame::new();
+    let mud = (4..=5).map(|y| (Position { x: 3, y }, Tile::Mud));
+    game.app_mut()
+        .insert_resource(Terrain::new(Arena::default(), mud));
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 3, y: 5 });
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 3, y: 6 });
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 3, y: 7 }, "out of the mud");
+}
+
+#[test]
+fn mud_holds_the_snake_back_on_every_other_movement_tick() {
+    let mut game = TestGame::new();
+    let mud = (4..=9).map(|y| (Position { x: 3, y }, Tile::Mud));
+    game.app_mut()
+        .insert_resource(Terrain::new(Arena::default(), mud));
+    game.advance(1);
+    let mut moved = Vec::new();
+    for _ in 0..10 {
+        let before = game.head();
+        game.advance(1);
+        moved.push(game.head() != before);
+    }
+    assert_eq!(moved, [false, true].repeat(5));
+    assert_eq!(game.head(), Position { x: 3, y: 9 });
+}
+
+#[test]
+fn the_snake_cannot_turn_on_ice() {
+    let mut game = TestGame::new();
+    let ice = (4..=5).map(|y| (Position { x: 3, y }, Tile::Ice));
+    game.app_mut()
+        .insert_resource(Terrain::new(Arena::default(), ice));
+    game.advance(1);
+    game.steer(Direction::Right);
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 3, y: 6 });
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 4, y: 6 });
+}
+
+#[test]
+fn keys_open_their_gates() {
+    let mut game = TestGame::new();
+    let lock = Lock {
+        key: Position { x: 3, y: 5 },
+        gate: vec![Position { x: 3, y: 7 }],
+    };
+    game.place_lock(0, &lock);
+    game.place_obstacle(Position { x: 3, y: 9 });
+    game.advance(5);
+    assert_eq!(game.head(), Position { x: 3, y: 8 });
+    assert_eq!(game.game_overs(), 0, "the gate opened");
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1, "other walls stay");
+}
+
+#[test]
+fn gates_without_their_key_are_walls() {
+    let mut game = TestGame::new();
+    let lock = Lock {
+        key: Position { x: 0, y: 0 },
+        gate: vec![Position { x: 3, y: 5 }],
+    };
+    game.place_lock(0, &lock);
+    game.advance(2);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn bots_steer_instead_of_the_keyboard() {
+    let mut game = TestGame::new();
+    game.pilot(Box::new(Autopilot));
+    game.place_food(Position { x: 5, y: 3 });
+    game.steer(Direction::Left);
+    game.advance(2);
+    assert_eq!(game.head(), Position { x: 5, y: 3 });
+    assert_eq!(game.score(), 1);
+}
+
+#[test]
+fn live_game_matches_core_simulation() {
+    let mut game = TestGame::new();
+    game.enable_food_spawning();
+    game.verify_determinism();
+    // Loop around the arena so the run lasts long enough to eat and grow.
+    let laps = [
+        (Direction::Up, 5),
+        (Direction::Right, 5),
+        (Direction::Down, 7),
+        (Direction::Left, 6),
+        (Direction::Up, 2),
+    ];
+    for _ in 0..8 {
+        for (direction, ticks) in laps {
+            game.steer(direction);
+            game.advance(ticks);
+        }
+    }
+    assert_eq!(game.exited(), None);
+}
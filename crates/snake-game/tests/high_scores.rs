@@ -0,0 +1,108 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_core::{Arena, GameMode, Position};
+use snake_game::{
+    harness::TestGame,
+    high_scores::{HighScore, HighScores, HighScoresPlugin, Table, HIGH_SCORES_FILE, TABLE_SIZE},
+    profile::Profile,
+};
+
+const CLASSIC: Table = Table {
+    mode: GameMode::Classic,
+    arena: Arena {
+        width: 10,
+        height: 10,
+    },
+};
+
+fn press(game: &mut TestGame, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        game.app_mut().world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        game.app_mut().update();
+    }
+}
+
+fn high(score: u32) -> HighScore {
+    HighScore { score, ended_at: 0 }
+}
+
+#[test]
+fn each_mode_and_arena_size_has_its_own_table() {
+    let wrap = Table {
+        mode: GameMode::Casual,
+        arena: Arena {
+            width: 20,
+            height: 20,
+        },
+    };
+    let mut high_scores = HighScores::default();
+    assert_eq!(high_scores.insert(CLASSIC, high(5)), Some(0));
+    assert_eq!(high_scores.insert(CLASSIC, high(9)), Some(0));
+    assert_eq!(
+        high_scores.insert(CLASSIC, high(5)),
+        Some(2),
+        "ties go below"
+    );
+    assert_eq!(high_scores.insert(wrap, high(1)), Some(0));
+    assert_eq!(CLASSIC.key(), "classic-10x10");
+    assert_eq!(high_scores.table(wrap), [high(1)]);
+
+    for score in 10..20 {
+        high_scores.insert(CLASSIC, high(score));
+    }
+    assert_eq!(high_scores.table(CLASSIC).len(), TABLE_SIZE);
+    assert_eq!(high_scores.insert(CLASSIC, high(3)), None);
+    assert_eq!(high_scores.table(CLASSIC)[0], high(19));
+}
+
+#[test]
+fn finished_runs_enter_the_profiles_table() {
+    let root = std::env::temp_dir().join(format!("snake-high-scores-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins(HighScoresPlugin);
+
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(10);
+    assert_eq!(game.game_overs(), 1);
+
+    let high_scores = HighScores::read(&profile.path(HIGH_SCORES_FILE)).unwrap();
+    let [entry] = high_scores.table(CLASSIC) else {
+        panic!("expected one score, got {high_scores:?}");
+    };
+    assert_eq!(entry.score, 1);
+}
+
+#[test]
+fn quickloaded_runs_stay_out_of_the_table() {
+    let root = std::env::temp_dir().join(format!("snake-high-scores-cheat-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins(HighScoresPlugin);
+
+    press(&mut game, KeyCode::F5);
+    press(&mut game, KeyCode::F9);
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(10);
+    assert_eq!(game.game_overs(), 1);
+
+    let high_scores = HighScores::read(&profile.path(HIGH_SCORES_FILE)).unwrap();
+    assert!(high_scores.table(CLASSIC).is_empty());
+}
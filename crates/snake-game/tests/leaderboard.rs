@@ -0,0 +1,27 @@
+#![cfg(feature = "leaderboard")]
+
+use snake_core::GameMode;
+use snake_game::leaderboard::{Pending, Submission};
+
+fn submission(score: u32) -> Submission {
+    Submission {
+        name: "ada".to_string(),
+        mode: GameMode::Classic,
+        table: "classic-20x20".to_string(),
+        score,
+        duration_ticks: score * 10,
+        seed: u64::from(score),
+        replay_hash: format!("{score:016x}"),
+    }
+}
+
+#[test]
+fn runs_queued_while_sending_survive_the_reply() {
+    let mut pending = Pending(vec![submission(3), submission(5)]);
+    let in_flight = pending.0.clone();
+    pending.0.push(submission(8));
+
+    // The server took the first run and refused the second.
+    pending.confirm(&in_flight[..1]);
+    assert_eq!(pending, Pending(vec![submission(5), submission(8)]));
+}
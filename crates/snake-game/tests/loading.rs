@@ -0,0 +1,124 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bevy::{prelude::*, tasks::futures_lite::future::yield_now};
+use snake_game::{
+    harness::TestGame,
+    loading::{LoadingAssets, LoadingPlugin, LoadingScreen},
+};
+
+/// App updates to wait for a released asset before giving up.
+const MAX_UPDATES: usize = 1_000;
+
+#[derive(Asset, TypePath)]
+struct Placeholder;
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((AssetPlugin::default(), LoadingPlugin))
+        .init_asset::<Placeholder>();
+    game
+}
+
+/// Tracks an asset that loads, or fails to if `fails`, once the returned
+/// flag is set.
+fn pending(game: &mut TestGame, fails: bool) -> Arc<AtomicBool> {
+    let release = Arc::new(AtomicBool::new(false));
+    let released = release.clone();
+    let world = game.app_mut().world_mut();
+    let handle = world.resource::<AssetServer>().add_async(async move {
+        while !released.load(Ordering::Acquire) {
+            yield_now().await;
+        }
+        if fails {
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        } else {
+            Ok(Placeholder)
+        }
+    });
+    world.resource_mut::<LoadingAssets>().track(handle);
+    release
+}
+
+fn loading(game: &mut TestGame) -> bool {
+    game.app_mut().world().contains_resource::<LoadingScreen>()
+}
+
+fn paused(game: &mut TestGame) -> bool {
+    game.app_mut()
+        .world()
+        .resource::<Time<Virtual>>()
+        .is_paused()
+}
+
+fn loaded(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world();
+    world
+        .resource::<LoadingAssets>()
+        .progress(world.resource::<AssetServer>())
+        .0
+}
+
+/// Updates until `done` holds.
+fn update_until(game: &mut TestGame, done: impl Fn(&mut TestGame) -> bool) {
+    for _ in 0..MAX_UPDATES {
+        if done(game) {
+            return;
+        }
+        game.app_mut().update();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    panic!("still waiting after {MAX_UPDATES} updates");
+}
+
+#[test]
+fn the_game_waits_behind_the_loading_screen_until_assets_are_in() {
+    let mut game = game();
+    let first = pending(&mut game, false);
+    let second = pending(&mut game, false);
+    game.app_mut().update();
+    assert!(loading(&mut game));
+    assert!(paused(&mut game));
+
+    first.store(true, Ordering::Release);
+    update_until(&mut game, |game| loaded(game) == 1);
+    game.app_mut().update();
+    assert!(loading(&mut game), "half done is still loading");
+
+    second.store(true, Ordering::Release);
+    update_until(&mut game, |game| !loading(game));
+    assert!(!paused(&mut game));
+    assert!(game
+        .app_mut()
+        .world()
+        .resource::<LoadingAssets>()
+        .finished());
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn assets_that_fail_to_load_do_not_hold_the_game_up() {
+    let mut game = game();
+    let release = pending(&mut game, true);
+    game.app_mut().update();
+    assert!(loading(&mut game));
+    release.store(true, Ordering::Release);
+    update_until(&mut game, |game| !loading(game));
+    assert!(!paused(&mut game));
+}
+
+#[test]
+fn nothing_to_load_shows_no_screen() {
+    let mut game = game();
+    game.app_mut().update();
+    assert!(!loading(&mut game));
+    assert!(game
+        .app_mut()
+        .world()
+        .resource::<LoadingAssets>()
+        .finished());
+}
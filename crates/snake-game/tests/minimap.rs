@@ -0,0 +1,86 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use snake_core::{Arena, Position};
+use snake_game::{
+    harness::TestGame,
+    minimap::{Minimap, MinimapPlugin},
+};
+
+fn game(arena: Arena) -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((AssetPlugin::default(), MinimapPlugin))
+        .init_asset::<Image>();
+    let world = game.app_mut().world_mut();
+    world.spawn((Window::default(), PrimaryWindow));
+    world.insert_resource(arena);
+    game
+}
+
+const HUGE: Arena = Arena {
+    width: 200,
+    height: 200,
+};
+
+fn minimap(game: &mut TestGame) -> Image {
+    let world = game.app_mut().world();
+    let handle = &world.resource::<Minimap>().0;
+    world
+        .resource::<Assets<Image>>()
+        .get(handle)
+        .unwrap()
+        .clone()
+}
+
+fn pixel(image: &Image, position: Position) -> &[u8] {
+    let row = image.height() - 1 - position.y as u32;
+    let at = ((row * image.width() + position.x as u32) * 4) as usize;
+    &image.data[at..at + 4]
+}
+
+fn minimap_shown(game: &mut TestGame) -> bool {
+    let world = game.app_mut().world_mut();
+    let mut nodes = world.query_filtered::<&Visibility, With<ImageNode>>();
+    nodes
+        .iter(world)
+        .any(|visibility| *visibility != Visibility::Hidden)
+}
+
+fn camera(game: &mut TestGame) -> Vec2 {
+    let world = game.app_mut().world_mut();
+    let mut cameras = world.query_filtered::<&Transform, With<Camera2d>>();
+    cameras.single(world).translation.truncate()
+}
+
+#[test]
+fn huge_arena_is_mapped_cell_by_cell() {
+    let mut game = game(HUGE);
+    game.place_obstacle(Position { x: 150, y: 120 });
+    game.advance(2);
+    assert!(minimap_shown(&mut game));
+    let image = minimap(&mut game);
+    assert_eq!((image.width(), image.height()), (200, 200));
+    let head = pixel(&image, game.head());
+    let obstacle = pixel(&image, Position { x: 150, y: 120 });
+    let empty = pixel(&image, Position { x: 100, y: 100 });
+    assert_ne!(head, empty);
+    assert_ne!(obstacle, empty);
+    assert_ne!(head, obstacle);
+}
+
+#[test]
+fn camera_follows_the_head_within_the_arena() {
+    let mut game = game(HUGE);
+    game.advance(1);
+    game.app_mut().update();
+    // The head is near the bottom left corner, so the view stops at it.
+    assert_eq!(camera(&mut game), Vec2::new(-160.0, -440.0));
+}
+
+#[test]
+fn arena_that_fits_has_no_minimap_and_a_still_camera() {
+    let mut game = game(Arena::default());
+    game.advance(2);
+    game.app_mut().update();
+    assert!(!minimap_shown(&mut game));
+    assert_eq!(camera(&mut game), Vec2::ZERO);
+}
@@ -0,0 +1,137 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_game::{
+    harness::TestGame,
+    name_entry::{is_clean, spawn_name_entry, NameEntered, NameEntryPlugin, DEFAULT_NAMES},
+};
+
+fn open(game: &mut TestGame) -> Entity {
+    game.app_mut().add_plugins(NameEntryPlugin);
+    game.app_mut()
+        .world_mut()
+        .run_system_once(|mut commands: Commands, mut time: ResMut<Time<Virtual>>| {
+            spawn_name_entry(&mut commands, &mut time, "Name", 6)
+        })
+        .unwrap()
+}
+
+/// Presses and lets go of `key` over one update.
+fn tap(game: &mut TestGame, key: KeyCode, logical_key: Key) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        game.app_mut().world_mut().send_event(KeyboardInput {
+            key_code: key,
+            logical_key: logical_key.clone(),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        game.app_mut().update();
+    }
+}
+
+fn type_text(game: &mut TestGame, text: &str) {
+    for c in text.chars() {
+        tap(game, KeyCode::KeyA, Key::Character(c.to_string().into()));
+    }
+}
+
+/// The names the entry closed with so far.
+fn entered(game: &mut TestGame, entry: Entity) -> Vec<Option<String>> {
+    let events = game.app_mut().world().resource::<Events<NameEntered>>();
+    events
+        .get_cursor()
+        .read(events)
+        .filter(|event| event.entry == entry)
+        .map(|event| event.name.clone())
+        .collect()
+}
+
+fn paused(game: &mut TestGame) -> bool {
+    game.app_mut()
+        .world()
+        .resource::<Time<Virtual>>()
+        .is_paused()
+}
+
+#[test]
+fn typed_name_is_entered_and_the_game_waits_for_it() {
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    game.app_mut().update();
+    assert!(paused(&mut game));
+    type_text(&mut game, "zed");
+    tap(&mut game, KeyCode::Enter, Key::Enter);
+    assert_eq!(entered(&mut game, entry), [Some("ZED".to_string())]);
+    assert!(!paused(&mut game));
+}
+
+#[test]
+fn wheels_spin_and_the_cursor_moves() {
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    type_text(&mut game, "ab");
+    tap(&mut game, KeyCode::ArrowLeft, Key::ArrowLeft);
+    tap(&mut game, KeyCode::ArrowDown, Key::ArrowDown);
+    tap(&mut game, KeyCode::ArrowLeft, Key::ArrowLeft);
+    tap(&mut game, KeyCode::ArrowUp, Key::ArrowUp);
+    tap(&mut game, KeyCode::ArrowUp, Key::ArrowUp);
+    tap(&mut game, KeyCode::Enter, Key::Enter);
+    // Up from A goes round the end of the wheel, past the blank.
+    assert_eq!(entered(&mut game, entry), [Some("_C".to_string())]);
+}
+
+#[test]
+fn name_stops_at_its_length() {
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    type_text(&mut game, "abcdefgh");
+    tap(&mut game, KeyCode::Enter, Key::Enter);
+    assert_eq!(entered(&mut game, entry), [Some("ABCDEH".to_string())]);
+}
+
+#[test]
+fn untouched_or_unclean_name_falls_back_to_a_default() {
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    tap(&mut game, KeyCode::Enter, Key::Enter);
+    let name = entered(&mut game, entry).remove(0).unwrap();
+    assert!(DEFAULT_NAMES
+        .iter()
+        .any(|default| default.starts_with(&name)));
+
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    type_text(&mut game, "shx");
+    tap(&mut game, KeyCode::Backspace, Key::Backspace);
+    type_text(&mut game, "it");
+    tap(&mut game, KeyCode::Enter, Key::Enter);
+    let name = entered(&mut game, entry).remove(0).unwrap();
+    assert_ne!(name, "SHIT");
+    assert!(DEFAULT_NAMES
+        .iter()
+        .any(|default| default.starts_with(&name)));
+}
+
+#[test]
+fn escape_cancels() {
+    let mut game = TestGame::new();
+    let entry = open(&mut game);
+    type_text(&mut game, "zed");
+    tap(&mut game, KeyCode::Escape, Key::Escape);
+    assert_eq!(entered(&mut game, entry), [None]);
+    assert!(!paused(&mut game));
+}
+
+#[test]
+fn blocked_words_are_caught_through_case_and_spacing() {
+    assert!(!is_clean("s-h-i-t"));
+    assert!(!is_clean("Fuckface"));
+    assert!(is_clean("Cobra"));
+    assert!(is_clean("player 2"));
+}
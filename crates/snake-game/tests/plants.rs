@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use snake_core::Position;
+use snake_game::{
+    harness::TestGame,
+    plants::{spawn_plant, Plant, PlantsPlugin, Stage, GROW_FOR},
+};
+
+fn game_with_plant(position: Position) -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(PlantsPlugin);
+    let world = game.app_mut().world_mut();
+    spawn_plant(&mut world.commands(), position);
+    world.flush();
+    game
+}
+
+fn stages(game: &mut TestGame) -> Vec<Stage> {
+    let world = game.app_mut().world_mut();
+    world
+        .query::<&Plant>()
+        .iter(world)
+        .map(|plant| plant.stage)
+        .collect()
+}
+
+fn grow_by(game: &mut TestGame, elapsed: Duration) {
+    let world = game.app_mut().world_mut();
+    for mut plant in world.query::<&mut Plant>().iter_mut(world) {
+        plant.growth.set_elapsed(elapsed);
+    }
+}
+
+#[test]
+fn sprouts_are_harmless_and_can_be_eaten() {
+    let mut game = game_with_plant(Position { x: 3, y: 5 });
+    grow_by(&mut game, GROW_FOR / 2);
+    game.advance(1);
+    assert_eq!(stages(&mut game), [Stage::Budding]);
+    game.advance(2);
+    assert_eq!(game.game_overs(), 0);
+    assert!(stages(&mut game).is_empty(), "eaten");
+}
+
+#[test]
+fn grown_plants_are_walls() {
+    let mut game = game_with_plant(Position { x: 3, y: 6 });
+    grow_by(&mut game, GROW_FOR);
+    game.advance(1);
+    assert_eq!(stages(&mut game), [Stage::Grown]);
+    game.advance(2);
+    assert_eq!(game.game_overs(), 1);
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use snake_core::{Arena, Direction, Position};
+use snake_game::{
+    harness::TestGame,
+    power_ups::{Effect, Pickup, PowerUp, PowerUpsPlugin, TimedEffects, MAGNET_FOR},
+    profile::Profile,
+    settings::{Settings, SettingsPlugin, SETTINGS_FILE},
+    world_clock::WorldClock,
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(PowerUpsPlugin);
+    game
+}
+
+#[test]
+fn eating_a_pickup_starts_its_effect_for_a_while() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .spawn((Pickup(PowerUp::Magnet), Position { x: 3, y: 4 }));
+    game.advance(1);
+    let effects = game.app_mut().world().resource::<TimedEffects>();
+    assert!(effects.active(Effect::Magnet));
+
+    let mut effects = game.app_mut().world_mut().resource_mut::<TimedEffects>();
+    assert_eq!(effects.tick(MAGNET_FOR), [Effect::Magnet]);
+    assert!(!effects.active(Effect::Magnet));
+}
+
+#[test]
+fn the_magnet_pulls_food_towards_the_head() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<TimedEffects>()
+        .start(Effect::Magnet, Duration::from_secs(60));
+    game.place_food(Position { x: 3, y: 6 });
+    game.advance(2);
+    assert_eq!(game.score(), 1, "eaten a tick early");
+
+    // Food just behind the tail stays put rather than moving onto the body.
+    game.place_food(Position { x: 3, y: 3 });
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(game.score(), 1);
+}
+
+#[test]
+fn reversed_controls_steer_the_other_way() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<TimedEffects>()
+        .start(Effect::ReverseControls, Duration::from_secs(60));
+    game.steer(Direction::Left);
+    game.advance(1);
+    assert_eq!(game.direction(), Direction::Right);
+    assert_eq!(game.head(), Position { x: 4, y: 3 });
+}
+
+#[test]
+fn time_freeze_stops_the_world_but_not_the_snake() {
+    let mut game = game();
+    game.advance(1);
+    let before = game.app_mut().world().resource::<WorldClock>().elapsed();
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<TimedEffects>()
+        .start(Effect::TimeFreeze, Duration::from_secs(60));
+    game.advance(3);
+    assert_eq!(game.head(), Position { x: 3, y: 7 });
+    let after = game.app_mut().world().resource::<WorldClock>().elapsed();
+    assert!(after - before < Duration::from_millis(100), "{after:?}");
+}
+
+#[test]
+fn bombs_clear_obstacles_within_reach() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .spawn((Pickup(PowerUp::Bomb), Position { x: 3, y: 4 }));
+    game.place_obstacle(Position { x: 3, y: 6 });
+    game.place_obstacle(Position { x: 3, y: 8 });
+    game.advance(3);
+    assert_eq!(game.head(), Position { x: 3, y: 6 });
+    assert_eq!(game.game_overs(), 0);
+
+    game.advance(2);
+    assert_eq!(game.game_overs(), 1, "out of reach");
+}
+
+#[test]
+fn speeding_up_leaves_the_saved_speed_alone() {
+    let root = std::env::temp_dir().join(format!("snake-speed-up-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = game();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins(SettingsPlugin);
+    game.app_mut().update();
+    let resize = |game: &mut TestGame, width| {
+        game.app_mut().insert_resource(Arena { width, height: 10 });
+        game.app_mut().update();
+        Settings::read(&profile.path(SETTINGS_FILE)).unwrap()
+    };
+    let usual = resize(&mut game, 12).movement_interval_ms;
+    game.app_mut().update();
+
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<TimedEffects>()
+        .start(Effect::SpeedUp, Duration::from_secs(60));
+    game.advance(1);
+    assert_eq!(resize(&mut game, 14).movement_interval_ms, usual);
+}
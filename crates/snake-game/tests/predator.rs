@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use snake_core::{Direction, GameMode, Position};
+use snake_game::{
+    harness::TestGame,
+    lives::Lives,
+    predator::{Predator, Predators, SPAWN_DISTANCE},
+};
+
+fn predator(game: &mut TestGame) -> Option<Position> {
+    let world = game.app_mut().world_mut();
+    world
+        .query_filtered::<&Position, With<Predator>>()
+        .iter(world)
+        .next()
+        .copied()
+}
+
+#[test]
+fn the_predator_turns_up_away_from_the_head() {
+    for _ in 0..20 {
+        let mut game = TestGame::new();
+        game.app_mut().insert_resource(Predators(true));
+        let predator = loop {
+            game.app_mut().update();
+            if let Some(predator) = predator(&mut game) {
+                break predator;
+            }
+        };
+        let head = game.head();
+        let steps = predator.x.abs_diff(head.x) + predator.y.abs_diff(head.y);
+        assert!(
+            steps >= SPAWN_DISTANCE,
+            "{predator:?} too close to {head:?}"
+        );
+    }
+}
+
+#[test]
+fn the_predator_goes_around_walls_to_catch_the_snake() {
+    let mut game = TestGame::new();
+    let walls: Vec<Position> = (2..=4).map(|x| Position { x, y: 6 }).collect();
+    for &wall in &walls {
+        game.place_obstacle(wall);
+    }
+    game.app_mut()
+        .world_mut()
+        .spawn((Predator, Position { x: 3, y: 8 }));
+    game.app_mut().insert_resource(Predators(true));
+    // Circles a 2x2 square below the wall until caught.
+    let circle = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+    for direction in circle.into_iter().cycle().take(40) {
+        game.steer(direction);
+        game.advance(1);
+        if game.game_overs() > 0 {
+            break;
+        }
+        let predator = predator(&mut game).expect("a predator");
+        assert!(!walls.contains(&predator), "walked into a wall");
+    }
+    assert_eq!(game.game_overs(), 1, "caught");
+}
+
+#[test]
+fn being_caught_costs_a_spare_life() {
+    let mut game = TestGame::new();
+    game.app_mut().insert_resource(GameMode::Casual);
+    game.app_mut().insert_resource(Lives { spare: 1 });
+    game.app_mut()
+        .world_mut()
+        .spawn((Predator, Position { x: 3, y: 6 }));
+    game.app_mut().insert_resource(Predators(true));
+    game.advance(2);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(game.app_mut().world().resource::<Lives>().spare, 0);
+}
@@ -0,0 +1,96 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ButtonState},
+    prelude::*,
+    window::WindowCloseRequested,
+};
+use snake_core::Position;
+use snake_game::{
+    harness::TestGame,
+    quit::{QuitDialog, QuitPlugin},
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(QuitPlugin);
+    game
+}
+
+/// Presses and lets go of `key` over one update.
+fn tap(game: &mut TestGame, key: KeyCode, character: &str) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        game.app_mut().world_mut().send_event(KeyboardInput {
+            key_code: key,
+            logical_key: bevy::input::keyboard::Key::Character(character.into()),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        game.app_mut().update();
+    }
+}
+
+fn dialog_open(game: &mut TestGame) -> bool {
+    game.app_mut().world().contains_resource::<QuitDialog>()
+}
+
+fn scored() -> TestGame {
+    let mut game = game();
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(1);
+    assert_eq!(game.score(), 1);
+    game
+}
+
+#[test]
+fn nothing_to_lose_quits_straight_away() {
+    let mut game = game();
+    tap(&mut game, KeyCode::Escape, "");
+    assert!(exited(&mut game));
+}
+
+#[test]
+fn quitting_a_run_asks_first() {
+    let mut game = scored();
+    tap(&mut game, KeyCode::Escape, "");
+    assert!(dialog_open(&mut game));
+    assert!(game
+        .app_mut()
+        .world()
+        .resource::<Time<Virtual>>()
+        .is_paused());
+    assert!(!exited(&mut game));
+
+    tap(&mut game, KeyCode::KeyN, "n");
+    assert!(!dialog_open(&mut game));
+    assert!(!game
+        .app_mut()
+        .world()
+        .resource::<Time<Virtual>>()
+        .is_paused());
+
+    tap(&mut game, KeyCode::Escape, "");
+    tap(&mut game, KeyCode::KeyY, "y");
+    assert!(exited(&mut game));
+}
+
+#[test]
+fn closing_the_window_twice_quits() {
+    let mut game = scored();
+    let close = WindowCloseRequested {
+        window: Entity::PLACEHOLDER,
+    };
+    game.app_mut().world_mut().send_event(close.clone());
+    game.app_mut().update();
+    assert!(dialog_open(&mut game));
+    assert!(!exited(&mut game));
+    game.app_mut().world_mut().send_event(close);
+    game.app_mut().update();
+    assert!(exited(&mut game));
+}
+
+/// Whether an exit was sent in the last two updates, which is as long as
+/// events last.
+fn exited(game: &mut TestGame) -> bool {
+    let events = game.app_mut().world().resource::<Events<AppExit>>();
+    !events.is_empty()
+}
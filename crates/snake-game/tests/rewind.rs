@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use snake_core::{GameMode, Position};
+use snake_game::{
+    day_night::{spawn_golden, GOLDEN_WORTH},
+    harness::TestGame,
+    power_ups::{spawn_power_up, Effect, Pickup, PowerUp, PowerUpsPlugin, TimedEffects},
+};
+
+fn hold_backspace(game: &mut TestGame, held: bool) {
+    let mut keyboard_input = game
+        .app_mut()
+        .world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>();
+    if held {
+        keyboard_input.press(KeyCode::Backspace);
+    } else {
+        keyboard_input.release(KeyCode::Backspace);
+    }
+}
+
+#[test]
+fn rewinding_puts_eaten_special_food_back_as_it_was() {
+    let mut game = TestGame::new();
+    game.app_mut().insert_resource(GameMode::Casual);
+    let golden = Position { x: 3, y: 5 };
+    let world = game.app_mut().world_mut();
+    spawn_golden(&mut world.commands(), golden);
+    world.flush();
+    game.advance(2);
+    assert_eq!(game.score(), GOLDEN_WORTH);
+    assert!(game.food().is_empty());
+
+    hold_backspace(&mut game, true);
+    game.advance(1);
+    hold_backspace(&mut game, false);
+    assert_eq!(game.score(), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+    assert_eq!(game.food(), [golden]);
+
+    game.advance(1);
+    assert_eq!(game.score(), GOLDEN_WORTH, "golden food is still golden");
+}
+
+#[test]
+fn rewinding_takes_back_an_eaten_power_up() {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(GameMode::Casual)
+        .add_plugins(PowerUpsPlugin);
+    let world = game.app_mut().world_mut();
+    spawn_power_up(
+        &mut world.commands(),
+        PowerUp::Magnet,
+        Position { x: 3, y: 5 },
+    );
+    world.flush();
+    game.advance(2);
+    let effects = game.app_mut().world().resource::<TimedEffects>();
+    assert!(effects.active(Effect::Magnet));
+
+    hold_backspace(&mut game, true);
+    game.advance(1);
+    hold_backspace(&mut game, false);
+    let effects = game.app_mut().world().resource::<TimedEffects>();
+    assert!(!effects.active(Effect::Magnet));
+    let world = game.app_mut().world_mut();
+    let pickups: Vec<Position> = world
+        .query_filtered::<&Position, With<Pickup>>()
+        .iter(world)
+        .copied()
+        .collect();
+    assert_eq!(pickups, [Position { x: 3, y: 5 }]);
+}
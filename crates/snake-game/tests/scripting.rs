@@ -0,0 +1,124 @@
+#![cfg(any(feature = "scripting", feature = "wasm-mods"))]
+
+use snake_core::Position;
+use snake_game::{
+    harness::TestGame,
+    scripting::{Mod, Mods, ScriptingPlugin},
+};
+
+fn modded(loaded: impl Mod) -> TestGame {
+    let mut game = TestGame::new();
+    let app = game.app_mut();
+    app.add_plugins(ScriptingPlugin);
+    app.world_mut()
+        .resource_mut::<Mods>()
+        .0
+        .push(Box::new(loaded));
+    game
+}
+
+/// Expects a mod that puts food in front of the head on the first tick and
+/// an obstacle two cells past whatever it eats.
+fn check_board_changes(mut game: TestGame) {
+    game.advance(2);
+    assert_eq!(game.score(), 1);
+    assert_eq!(game.head(), Position { x: 3, y: 5 });
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[cfg(feature = "scripting")]
+mod lua {
+    use snake_game::lua::LuaScript;
+
+    use super::*;
+
+    #[test]
+    fn hooks_can_change_the_board() {
+        let script = LuaScript::new(
+            r#"
+            function on_tick(tick)
+                if tick == 1 then
+                    local x, y = snake.head()
+                    snake.spawn_food(x, y + 1)
+                end
+            end
+            function on_eat(x, y, score)
+                snake.spawn_obstacle(x, y + 2)
+            end
+            "#,
+        )
+        .unwrap();
+        check_board_changes(modded(script));
+    }
+
+    #[test]
+    fn runaway_scripts_are_cut_off() {
+        let script = LuaScript::new("function on_tick() while true do end end").unwrap();
+        let mut game = modded(script);
+        game.advance(3);
+        assert_eq!(game.head(), Position { x: 3, y: 6 });
+    }
+
+    #[test]
+    fn scripts_cannot_reach_the_system() {
+        for source in [
+            "os.exit()",
+            "io.open('x')",
+            "require('x')",
+            "debug.getinfo(1)",
+        ] {
+            assert!(LuaScript::new(source).is_err(), "{source}");
+        }
+    }
+}
+
+#[cfg(feature = "wasm-mods")]
+mod wasm {
+    use snake_game::wasm_mods::WasmMod;
+
+    use super::*;
+
+    #[test]
+    fn hooks_can_change_the_board() {
+        let module = WasmMod::new(
+            r#"
+            (module
+                (import "snake" "spawn_food" (func $spawn_food (param i32 i32)))
+                (import "snake" "spawn_obstacle" (func $spawn_obstacle (param i32 i32)))
+                (import "snake" "head_x" (func $head_x (result i32)))
+                (import "snake" "head_y" (func $head_y (result i32)))
+                (func (export "on_tick") (param $tick i32)
+                    (if (i32.eq (local.get $tick) (i32.const 1))
+                        (then (call $spawn_food
+                            (call $head_x)
+                            (i32.add (call $head_y) (i32.const 1))))))
+                (func (export "on_eat") (param $x i32) (param $y i32) (param $score i32)
+                    (call $spawn_obstacle (local.get $x) (i32.add (local.get $y) (i32.const 2)))))
+            "#,
+        )
+        .unwrap();
+        check_board_changes(modded(module));
+    }
+
+    #[test]
+    fn runaway_modules_run_out_of_fuel() {
+        let module = WasmMod::new(
+            r#"(module (func (export "on_tick") (param i32) (loop $forever (br $forever))))"#,
+        )
+        .unwrap();
+        let mut game = modded(module);
+        game.advance(3);
+        assert_eq!(game.head(), Position { x: 3, y: 6 });
+    }
+
+    #[test]
+    fn modules_only_get_the_snake_api() {
+        let module = WasmMod::new(
+            r#"(module (import "wasi_snapshot_preview1" "proc_exit" (func (param i32))))"#,
+        );
+        assert!(module.is_err());
+    }
+}
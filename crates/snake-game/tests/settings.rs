@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use snake_core::{Arena, Direction};
+use snake_game::{
+    harness::TestGame,
+    profile::{list_profiles, valid_name, Profile},
+    settings::{Settings, SettingsPlugin, SETTINGS_FILE, SETTINGS_VERSION},
+    Controls,
+};
+
+fn profiles_root(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("snake-profiles-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn playing_as(profile: &Profile) -> TestGame {
+    let mut game = TestGame::new();
+    let app = game.app_mut();
+    app.insert_resource(profile.clone())
+        .add_plugins(SettingsPlugin);
+    app.update();
+    game
+}
+
+const WASD: Controls = Controls {
+    up: KeyCode::KeyW,
+    down: KeyCode::KeyS,
+    left: KeyCode::KeyA,
+    right: KeyCode::KeyD,
+};
+
+#[test]
+fn changed_settings_are_restored_next_session() {
+    let profile = Profile::new(&profiles_root("restore"), "alice");
+    let path = profile.path(SETTINGS_FILE);
+    let mut game = playing_as(&profile);
+    assert!(!path.exists(), "nothing changed yet");
+
+    let world = game.app_mut().world_mut();
+    world.insert_resource(WASD);
+    world.insert_resource(Arena {
+        width: 12,
+        height: 8,
+    });
+    game.app_mut().update();
+    let saved = Settings::read(&path).unwrap();
+    assert_eq!(saved.controls, WASD);
+    assert_eq!(saved.arena.width, 12);
+
+    let mut game = playing_as(&profile);
+    assert_eq!(*game.app_mut().world().resource::<Arena>(), saved.arena);
+    game.app_mut()
+        .world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyD);
+    game.advance(1);
+    assert_eq!(game.direction(), Direction::Right);
+}
+
+#[test]
+fn older_files_take_defaults_for_new_settings() {
+    let path = Profile::new(&profiles_root("migrate"), "bob").path(SETTINGS_FILE);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{"version": 1, "volume": 0.25}"#).unwrap();
+    let settings = Settings::read(&path).unwrap();
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert_eq!(settings.volume, 0.25);
+    assert_eq!(settings.controls, Controls::default());
+
+    std::fs::write(&path, r#"{"volume": 0.25}"#).unwrap();
+    assert!(
+        Settings::read(&path).is_err(),
+        "unversioned files are refused"
+    );
+}
+
+#[test]
+fn each_profile_keeps_its_own_settings() {
+    let root = profiles_root("switch");
+    let alice = Profile::new(&root, "alice");
+    let bob = Profile::new(&root, "bob");
+    let mut settings = Settings::default();
+    settings.controls = WASD;
+    settings.write(&alice.path(SETTINGS_FILE)).unwrap();
+    let mut settings = Settings::default();
+    settings.arena = Arena {
+        width: 15,
+        height: 15,
+    };
+    settings.write(&bob.path(SETTINGS_FILE)).unwrap();
+    assert_eq!(list_profiles(&root), ["alice", "bob"]);
+
+    let mut game = playing_as(&alice);
+    assert_eq!(*game.app_mut().world().resource::<Controls>(), WASD);
+    game.app_mut().insert_resource(bob.clone());
+    game.app_mut().update();
+    let world = game.app_mut().world();
+    assert_eq!(*world.resource::<Controls>(), Controls::default());
+    assert_eq!(world.resource::<Arena>().width, 15);
+
+    game.app_mut().world_mut().resource_mut::<Arena>().width = 9;
+    game.app_mut().update();
+    assert_eq!(
+        Settings::read(&bob.path(SETTINGS_FILE))
+            .unwrap()
+            .arena
+            .width,
+        9
+    );
+    assert_eq!(
+        Settings::read(&alice.path(SETTINGS_FILE)).unwrap().arena,
+        Arena::default()
+    );
+}
+
+#[test]
+fn profile_names_must_be_usable_as_directories() {
+    assert!(valid_name("Ada Lovelace"));
+    assert!(valid_name("kid_2"));
+    assert!(!valid_name(""));
+    assert!(!valid_name(" padded"));
+    assert!(!valid_name("../escape"));
+    assert!(!valid_name("a name far too long for the list"));
+}
@@ -0,0 +1,80 @@
+use bevy::{prelude::*, render::view::RenderLayers, window::PrimaryWindow};
+use snake_core::Position;
+use snake_game::{
+    harness::TestGame,
+    snake_mesh::{Batched, SnakeBody, SnakeMeshPlugin, BATCHED_LAYER},
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((AssetPlugin::default(), SnakeMeshPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>();
+    game.app_mut()
+        .world_mut()
+        .spawn((Window::default(), PrimaryWindow));
+    game
+}
+
+/// Quads in the body mesh.
+fn quads(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    let handle = world
+        .query_filtered::<&Mesh2d, With<SnakeBody>>()
+        .single(world)
+        .0
+        .clone();
+    world
+        .resource::<Assets<Mesh>>()
+        .get(&handle)
+        .unwrap()
+        .count_vertices()
+        / 4
+}
+
+#[test]
+fn the_body_is_one_mesh_with_a_quad_per_segment() {
+    let mut game = game();
+    for y in 4..=6 {
+        game.place_food(Position { x: 3, y });
+    }
+    game.advance(4);
+    game.app_mut().update();
+    assert_eq!(game.length(), 5);
+    assert_eq!(quads(&mut game), 4, "the head keeps its own sprite");
+
+    let world = game.app_mut().world_mut();
+    let layers: Vec<RenderLayers> = world
+        .query_filtered::<&RenderLayers, With<Batched>>()
+        .iter(world)
+        .cloned()
+        .collect();
+    assert_eq!(layers.len(), 4);
+    assert!(layers
+        .iter()
+        .all(|layers| *layers == RenderLayers::layer(BATCHED_LAYER)));
+}
+
+#[test]
+fn the_body_is_rebuilt_only_when_the_snake_changes() {
+    let mut game = game();
+    game.advance(1);
+    game.app_mut().update();
+    let world = game.app_mut().world_mut();
+    let mut events = world.resource_mut::<Events<AssetEvent<Mesh>>>();
+    events.clear();
+
+    game.app_mut().update();
+    let modified = |game: &mut TestGame| {
+        let world = game.app_mut().world_mut();
+        let mut events = world.resource_mut::<Events<AssetEvent<Mesh>>>();
+        events
+            .drain()
+            .filter(|event| matches!(event, AssetEvent::Modified { .. }))
+            .count()
+    };
+    assert_eq!(modified(&mut game), 0);
+    game.advance(1);
+    assert!(modified(&mut game) > 0);
+}
@@ -0,0 +1,147 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+};
+use std::time::Duration;
+
+use snake_core::Position;
+use snake_game::{
+    day_night::{spawn_golden, GOLDEN_WORTH},
+    eggs::{spawn_egg, Egg},
+    gates::{self, Lock},
+    harness::TestGame,
+    lives::Lives,
+    plants::{spawn_grown_plant, spawn_plant, Plant, PlantsPlugin, Stage, GROW_FOR},
+    power_ups::{spawn_power_up, Effect, Pickup, PowerUp, PowerUpsPlugin, TimedEffects},
+    profile::Profile,
+    venom::{spawn_venom_sac, VenomSac},
+};
+
+fn press(game: &mut TestGame, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        game.app_mut().world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        game.app_mut().update();
+    }
+}
+
+fn count<F: bevy::ecs::query::QueryFilter>(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    world.query_filtered::<(), F>().iter(world).count()
+}
+
+#[test]
+fn quickload_brings_back_special_food_and_the_rest_of_the_board() {
+    let root = std::env::temp_dir().join(format!("snake-snapshot-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut game = TestGame::new();
+    game.app_mut().insert_resource(Profile::new(&root, "ada"));
+    let world = game.app_mut().world_mut();
+    spawn_golden(&mut world.commands(), Position { x: 3, y: 6 });
+    spawn_egg(&mut world.commands(), Position { x: 6, y: 6 });
+    spawn_venom_sac(&mut world.commands(), Position { x: 7, y: 7 });
+    world.flush();
+    world.resource_mut::<Lives>().spare = 2;
+    game.place_obstacle(Position { x: 0, y: 9 });
+    game.place_lock(
+        0,
+        &Lock {
+            key: Position { x: 1, y: 1 },
+            gate: vec![Position { x: 8, y: 8 }],
+        },
+    );
+    press(&mut game, KeyCode::F5);
+
+    game.advance(3);
+    let eaten = game.score();
+    assert_eq!(eaten, GOLDEN_WORTH);
+    game.app_mut().world_mut().resource_mut::<Lives>().spare = 0;
+    let board = game.app_mut().world_mut();
+    let walls: Vec<Entity> = board
+        .query_filtered::<Entity, Or<(With<gates::Key>, With<gates::Gate>)>>()
+        .iter(board)
+        .collect();
+    for wall in walls {
+        board.despawn(wall);
+    }
+
+    press(&mut game, KeyCode::F9);
+    assert_eq!(game.score(), 0);
+    assert_eq!(game.app_mut().world().resource::<Lives>().spare, 2);
+    assert_eq!(count::<With<Egg>>(&mut game), 1);
+    assert_eq!(count::<With<VenomSac>>(&mut game), 1);
+    assert_eq!(count::<With<gates::Key>>(&mut game), 1);
+    assert_eq!(count::<With<gates::Gate>>(&mut game), 1);
+    assert_eq!(game.food().len(), 3);
+
+    game.advance(3);
+    assert_eq!(game.score(), eaten, "golden food is still golden");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn quickload_brings_back_pickups_plants_and_effects() {
+    let root = std::env::temp_dir().join(format!("snake-snapshot-extras-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(Profile::new(&root, "ada"))
+        .add_plugins((PowerUpsPlugin, PlantsPlugin));
+    let world = game.app_mut().world_mut();
+    spawn_power_up(
+        &mut world.commands(),
+        PowerUp::Magnet,
+        Position { x: 6, y: 6 },
+    );
+    spawn_plant(&mut world.commands(), Position { x: 1, y: 8 });
+    spawn_grown_plant(
+        &mut world.commands(),
+        Position { x: 8, y: 8 },
+        Stage::Grown,
+        GROW_FOR,
+    );
+    world.flush();
+    world
+        .resource_mut::<TimedEffects>()
+        .start(Effect::SpeedUp, Duration::from_secs(60));
+    press(&mut game, KeyCode::F5);
+
+    let world = game.app_mut().world_mut();
+    let board: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Pickup>, With<Plant>)>>()
+        .iter(world)
+        .collect();
+    for entity in board {
+        world.despawn(entity);
+    }
+    world.resource_mut::<TimedEffects>().clear();
+
+    press(&mut game, KeyCode::F9);
+    assert_eq!(count::<With<Pickup>>(&mut game), 1);
+    let world = game.app_mut().world_mut();
+    let mut plants: Vec<(Position, Stage)> = world
+        .query::<(&Position, &Plant)>()
+        .iter(world)
+        .map(|(&position, plant)| (position, plant.stage))
+        .collect();
+    plants.sort_by_key(|(position, _)| position.x);
+    assert_eq!(
+        plants,
+        [
+            (Position { x: 1, y: 8 }, Stage::Sprout),
+            (Position { x: 8, y: 8 }, Stage::Grown),
+        ],
+        "the grown plant is still a plant"
+    );
+    let effects = game.app_mut().world().resource::<TimedEffects>();
+    assert!(effects.active(Effect::SpeedUp));
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use snake_core::{Direction, GameMode, Position};
+use snake_game::{
+    harness::TestGame,
+    profile::Profile,
+    stats::{read_history, Aggregates, RunRecord, StatsPlugin, HISTORY_FILE},
+};
+
+#[test]
+fn finished_runs_are_recorded_in_the_profile() {
+    let root = std::env::temp_dir().join(format!("snake-stats-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins(StatsPlugin);
+
+    game.place_food(Position { x: 3, y: 4 });
+    game.advance(6);
+    // Straight on would hit the top wall.
+    game.steer(Direction::Right);
+    game.advance(7);
+    assert_eq!(game.game_overs(), 1);
+
+    let history = read_history(&profile.path(HISTORY_FILE)).unwrap();
+    let [run] = history.as_slice() else {
+        panic!("expected one run, got {history:?}");
+    };
+    assert_eq!(run.mode, GameMode::Classic);
+    assert_eq!(run.score, 1);
+    assert_eq!(run.foods, BTreeMap::from([("plain".to_string(), 1)]));
+    assert_eq!(run.max_length, 3);
+    assert_eq!(run.close_calls, 1);
+    assert_eq!(run.left_turns, 0);
+    assert_eq!(run.cause, "wall");
+    assert_eq!(run.cell, Some(Position { x: 10, y: 9 }));
+    assert!(run.duration_ms > 0 && run.average_speed > 0.0);
+}
+
+#[test]
+fn left_turns_are_counted() {
+    let root = std::env::temp_dir().join(format!("snake-stats-turns-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let mut game = TestGame::new();
+    game.app_mut()
+        .insert_resource(profile.clone())
+        .add_plugins(StatsPlugin);
+
+    for direction in [Direction::Left, Direction::Down] {
+        game.steer(direction);
+        game.advance(1);
+    }
+    game.advance(3);
+    assert_eq!(game.game_overs(), 1);
+
+    let history = read_history(&profile.path(HISTORY_FILE)).unwrap();
+    assert_eq!(history[0].left_turns, 2);
+
+    // Runs recorded before turns were counted still read.
+    let path = root.join("old.jsonl");
+    let mut line = serde_json::to_value(&history[0]).unwrap();
+    line.as_object_mut().unwrap().remove("left_turns");
+    std::fs::write(&path, format!("{line}\n")).unwrap();
+    assert_eq!(read_history(&path).unwrap()[0].left_turns, 0);
+}
+
+#[test]
+fn aggregates_sum_and_pick_bests() {
+    let run = |score, cause: &str, cell| RunRecord {
+        ended_at: 0,
+        mode: GameMode::Classic,
+        score,
+        duration_ms: 30_000,
+        foods: BTreeMap::from([("plain".to_string(), score)]),
+        max_length: score as usize + 2,
+        average_speed: 6.0,
+        close_calls: 2,
+        left_turns: 0,
+        cause: cause.to_string(),
+        cell,
+    };
+    let corner = Some(Position { x: 0, y: 0 });
+    let stats = Aggregates::new(&[
+        run(4, "wall", corner),
+        run(10, "body", Some(Position { x: 5, y: 5 })),
+        run(1, "wall", corner),
+    ]);
+    assert_eq!(stats.runs, 3);
+    assert_eq!(stats.total_time.as_secs(), 90);
+    assert_eq!(stats.foods["plain"], 15);
+    assert_eq!((stats.best_score, stats.longest), (10, 12));
+    assert_eq!(stats.average_score, 5.0);
+    assert_eq!(stats.close_calls, 6);
+    assert_eq!(stats.causes["wall"], 2);
+    assert_eq!(stats.deadliest_cell, Some((Position { x: 0, y: 0 }, 2)));
+    assert_eq!(Aggregates::new(&[]).runs, 0);
+}
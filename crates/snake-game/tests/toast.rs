@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use snake_core::Position;
+use snake_game::{
+    harness::TestGame,
+    power_ups::{Pickup, PowerUp, PowerUpsPlugin},
+    toast::{ShownToast, Toast, ToastPlugin, TOAST_FOR},
+    TICK_HZ,
+};
+
+/// The message on screen, if any.
+fn shown(game: &mut TestGame) -> Option<String> {
+    let world = game.app_mut().world_mut();
+    let toast = world
+        .query_filtered::<&Children, With<ShownToast>>()
+        .iter(world)
+        .next()?[0];
+    Some(world.get::<Text>(toast)?.0.clone())
+}
+
+/// Runs updates for as long as a toast is shown.
+fn wait_out(game: &mut TestGame) {
+    let step = Time::<Fixed>::from_hz(TICK_HZ).timestep();
+    for _ in 0..=(TOAST_FOR.as_secs_f64() / step.as_secs_f64()).ceil() as usize {
+        game.app_mut().update();
+    }
+}
+
+#[test]
+fn toasts_show_one_at_a_time_in_order_and_go() {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(ToastPlugin);
+    let world = game.app_mut().world_mut();
+    world.send_event(Toast("first".into()));
+    world.send_event(Toast("second".into()));
+    game.app_mut().update();
+    game.app_mut().update();
+    assert_eq!(shown(&mut game).as_deref(), Some("first"));
+    wait_out(&mut game);
+    game.app_mut().update();
+    assert_eq!(shown(&mut game).as_deref(), Some("second"));
+    wait_out(&mut game);
+    game.app_mut().update();
+    assert_eq!(shown(&mut game), None);
+}
+
+#[test]
+fn pickups_say_what_they_did() {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(PowerUpsPlugin);
+    game.app_mut()
+        .world_mut()
+        .spawn((Pickup(PowerUp::TimeFreeze), Position { x: 3, y: 4 }));
+    game.advance(1);
+    game.app_mut().update();
+    assert_eq!(shown(&mut game).as_deref(), Some("Time freeze!"));
+}
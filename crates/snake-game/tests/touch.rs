@@ -0,0 +1,87 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        touch::{TouchInput, TouchPhase},
+        ButtonState,
+    },
+    prelude::*,
+    window::AppLifecycle,
+};
+use snake_core::Direction;
+use snake_game::{
+    harness::TestGame,
+    mobile::{Dpad, DpadButton, MobilePlugin, TouchDpad},
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_event::<AppLifecycle>()
+        .add_plugins(MobilePlugin);
+    game
+}
+
+fn touch(game: &mut TestGame) {
+    game.app_mut().world_mut().send_event(TouchInput {
+        phase: TouchPhase::Started,
+        position: Vec2::new(40.0, 400.0),
+        window: Entity::PLACEHOLDER,
+        force: None,
+        id: 0,
+    });
+    game.app_mut().update();
+}
+
+fn dpad_shown(game: &mut TestGame) -> bool {
+    let world = game.app_mut().world_mut();
+    let mut dpads = world.query_filtered::<&Visibility, With<Dpad>>();
+    dpads
+        .iter(world)
+        .any(|visibility| *visibility != Visibility::Hidden)
+}
+
+#[test]
+fn dpad_shows_on_touch_and_hides_on_keys() {
+    let mut game = game();
+    game.app_mut().update();
+    assert!(!dpad_shown(&mut game));
+    touch(&mut game);
+    assert!(dpad_shown(&mut game));
+
+    game.app_mut().world_mut().send_event(KeyboardInput {
+        key_code: KeyCode::Space,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state: ButtonState::Pressed,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    game.app_mut().update();
+    assert!(!dpad_shown(&mut game));
+}
+
+#[test]
+fn dpad_stays_hidden_when_turned_off() {
+    let mut game = game();
+    game.app_mut()
+        .world_mut()
+        .insert_resource(TouchDpad { enabled: false });
+    touch(&mut game);
+    assert!(!dpad_shown(&mut game));
+}
+
+#[test]
+fn dpad_buttons_steer() {
+    let mut game = game();
+    touch(&mut game);
+    game.app_mut().update();
+    let world = game.app_mut().world_mut();
+    let right = world
+        .query::<(Entity, &DpadButton)>()
+        .iter(world)
+        .find(|(_, button)| button.0 == Direction::Right)
+        .unwrap()
+        .0;
+    world.entity_mut(right).insert(Interaction::Pressed);
+    game.advance(1);
+    assert_eq!(game.direction(), Direction::Right);
+}
@@ -0,0 +1,62 @@
+use snake_core::{Arena, Direction, Position};
+use snake_game::{
+    harness::TestGame,
+    tunnels::{Tunnel, Tunnels},
+};
+
+/// A game with the start column's top cell tunnelled through to `other`.
+fn game(other: Position) -> TestGame {
+    let mut game = TestGame::new();
+    let tunnel = Tunnel(Position { x: 3, y: 9 }, other);
+    game.app_mut()
+        .insert_resource(Tunnels::new(Arena::default(), [tunnel]));
+    game
+}
+
+#[test]
+fn leaving_through_an_opening_comes_back_in_at_the_other_end() {
+    let mut game = game(Position { x: 3, y: 0 });
+    game.advance(6);
+    assert_eq!(game.head(), Position { x: 3, y: 9 });
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 0 });
+    assert_eq!(game.direction(), Direction::Up);
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 3, y: 1 });
+}
+
+#[test]
+fn the_snake_heads_away_from_the_edge_it_comes_out_of() {
+    let mut game = game(Position { x: 0, y: 5 });
+    game.advance(7);
+    assert_eq!(game.head(), Position { x: 0, y: 5 });
+    assert_eq!(game.direction(), Direction::Right);
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 1, y: 5 });
+    assert_eq!(game.game_overs(), 0);
+}
+
+#[test]
+fn the_edge_away_from_the_openings_is_still_a_wall() {
+    let mut game = game(Position { x: 3, y: 0 });
+    game.steer(Direction::Left);
+    game.advance(4);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn tunnels_only_open_onto_the_edge() {
+    let arena = Arena::default();
+    let edge = Position { x: 0, y: 4 };
+    let tunnels = Tunnels::new(
+        arena,
+        [
+            Tunnel(edge, Position { x: 4, y: 4 }),
+            Tunnel(edge, Position { x: 10, y: 4 }),
+            Tunnel(edge, edge),
+            Tunnel(edge, Position { x: 9, y: 9 }),
+        ],
+    );
+    assert_eq!(tunnels.tunnels(), [Tunnel(edge, Position { x: 9, y: 9 })]);
+}
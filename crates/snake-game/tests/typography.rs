@@ -0,0 +1,78 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_game::{
+    harness::TestGame,
+    typography::{TextSize, TypographyPlugin},
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut()
+        .add_plugins((AssetPlugin::default(), TypographyPlugin))
+        .init_asset::<Font>();
+    game
+}
+
+fn key(game: &mut TestGame, key_code: KeyCode, state: ButtonState) {
+    game.app_mut().world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    game.app_mut().update();
+}
+
+fn spawn_text(game: &mut TestGame, font_size: f32) -> Entity {
+    let text = game
+        .app_mut()
+        .world_mut()
+        .spawn((
+            Text::new("score"),
+            TextFont {
+                font_size,
+                ..Default::default()
+            },
+        ))
+        .id();
+    game.app_mut().update();
+    text
+}
+
+fn font_size(game: &mut TestGame, text: Entity) -> f32 {
+    game.app_mut()
+        .world()
+        .get::<TextFont>(text)
+        .unwrap()
+        .font_size
+}
+
+#[test]
+fn text_size_scales_text_old_and_new() {
+    let mut game = game();
+    let old = spawn_text(&mut game, 20.0);
+    assert_eq!(font_size(&mut game, old), 20.0);
+
+    key(&mut game, KeyCode::ControlLeft, ButtonState::Pressed);
+    key(&mut game, KeyCode::KeyT, ButtonState::Pressed);
+    key(&mut game, KeyCode::KeyT, ButtonState::Released);
+    key(&mut game, KeyCode::ControlLeft, ButtonState::Released);
+    assert_eq!(
+        *game.app_mut().world().resource::<TextSize>(),
+        TextSize::Large
+    );
+    assert_eq!(font_size(&mut game, old), 25.0);
+    let new = spawn_text(&mut game, 16.0);
+    assert_eq!(font_size(&mut game, new), 20.0);
+
+    // Sizes scale from the original, not from the last scaled size.
+    game.app_mut().world_mut().insert_resource(TextSize::Small);
+    game.app_mut().update();
+    assert_eq!(font_size(&mut game, old), 16.0);
+}
@@ -0,0 +1,77 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_game::{
+    harness::TestGame,
+    profile::Profile,
+    settings::{Settings, SettingsPlugin, SETTINGS_FILE},
+    ui_scale::{UiScalePlugin, MAX_UI_SCALE},
+};
+
+fn key(game: &mut TestGame, key_code: KeyCode, state: ButtonState) {
+    game.app_mut().world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(bevy::input::keyboard::NativeKey::Unidentified),
+        state,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    game.app_mut().update();
+}
+
+/// Presses `key_code` `times` over with Ctrl held.
+fn ctrl(game: &mut TestGame, key_code: KeyCode, times: usize) {
+    key(game, KeyCode::ControlLeft, ButtonState::Pressed);
+    for _ in 0..times {
+        key(game, key_code, ButtonState::Pressed);
+        key(game, key_code, ButtonState::Released);
+    }
+    key(game, KeyCode::ControlLeft, ButtonState::Released);
+}
+
+fn scale(game: &mut TestGame) -> f32 {
+    game.app_mut().world().resource::<UiScale>().0
+}
+
+#[test]
+fn ctrl_keys_slide_the_scale_within_bounds() {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(UiScalePlugin);
+    ctrl(&mut game, KeyCode::Equal, 2);
+    assert!((scale(&mut game) - 1.2).abs() < 1e-4);
+    ctrl(&mut game, KeyCode::Equal, 20);
+    assert_eq!(scale(&mut game), MAX_UI_SCALE);
+    ctrl(&mut game, KeyCode::Digit0, 1);
+    assert_eq!(scale(&mut game), 1.0);
+
+    // Without Ctrl the keys are left to the game.
+    key(&mut game, KeyCode::Minus, ButtonState::Pressed);
+    assert_eq!(scale(&mut game), 1.0);
+}
+
+#[test]
+fn scale_is_saved_and_restored() {
+    let root = std::env::temp_dir().join(format!("snake-ui-scale-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let profile = Profile::new(&root, "ada");
+    let playing = || {
+        let mut game = TestGame::new();
+        game.app_mut()
+            .insert_resource(profile.clone())
+            .add_plugins((SettingsPlugin, UiScalePlugin));
+        game.app_mut().update();
+        game
+    };
+    let mut game = playing();
+    ctrl(&mut game, KeyCode::Minus, 3);
+    let saved = Settings::read(&profile.path(SETTINGS_FILE)).unwrap();
+    assert!((saved.ui_scale - 0.7).abs() < 1e-4);
+
+    let mut game = playing();
+    assert!((scale(&mut game) - 0.7).abs() < 1e-4);
+    let _ = std::fs::remove_dir_all(&root);
+}
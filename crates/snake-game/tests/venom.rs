@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use snake_core::Position;
+use snake_game::{
+    critters::Critter,
+    harness::TestGame,
+    venom::{spawn_venom_sac, Spit, Venom, VenomPlugin},
+};
+
+fn game() -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(VenomPlugin);
+    game
+}
+
+fn venom(game: &mut TestGame) -> Venom {
+    *game.app_mut().world().resource::<Venom>()
+}
+
+fn spit(game: &mut TestGame) {
+    game.app_mut().world_mut().resource_mut::<Venom>().spitting = true;
+}
+
+fn count<T: Component>(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    world.query_filtered::<(), With<T>>().iter(world).count()
+}
+
+#[test]
+fn eating_a_venom_sac_charges_a_spit() {
+    let mut game = game();
+    let world = game.app_mut().world_mut();
+    spawn_venom_sac(&mut world.commands(), Position { x: 3, y: 4 });
+    world.flush();
+    game.advance(1);
+    assert_eq!(venom(&mut game).charges, 1);
+    assert_eq!(game.score(), 1, "it is food too");
+}
+
+#[test]
+fn spit_destroys_the_first_obstacle_it_hits() {
+    let mut game = game();
+    game.app_mut().world_mut().resource_mut::<Venom>().charges = 1;
+    game.place_obstacle(Position { x: 3, y: 7 });
+    game.place_obstacle(Position { x: 3, y: 8 });
+    spit(&mut game);
+    game.advance(1);
+    assert_eq!(venom(&mut game).charges, 0);
+    assert_eq!(count::<Spit>(&mut game), 1);
+    game.advance(2);
+    assert_eq!(count::<Spit>(&mut game), 0);
+
+    // The snake goes through where the first wall was, but not the second.
+    game.advance(1);
+    assert_eq!(game.head(), Position { x: 3, y: 7 });
+    assert_eq!(game.game_overs(), 0);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn spit_kills_critters() {
+    let mut game = game();
+    game.app_mut().world_mut().resource_mut::<Venom>().charges = 1;
+    game.app_mut()
+        .world_mut()
+        .spawn((Critter, Position { x: 3, y: 6 }));
+    spit(&mut game);
+    game.advance(3);
+    assert_eq!(count::<Critter>(&mut game), 0);
+}
+
+#[test]
+fn spitting_needs_a_charge() {
+    let mut game = game();
+    spit(&mut game);
+    game.advance(1);
+    assert_eq!(count::<Spit>(&mut game), 0);
+    assert!(!venom(&mut game).spitting);
+}
+
+#[test]
+fn spit_is_lost_off_the_edge() {
+    let mut game = game();
+    game.app_mut().world_mut().resource_mut::<Venom>().charges = 1;
+    spit(&mut game);
+    game.advance(30);
+    assert_eq!(count::<Spit>(&mut game), 0);
+}
@@ -0,0 +1,146 @@
+use std::sync::Mutex;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+};
+use snake_core::{Arena, Position};
+use snake_game::{
+    campaign::{Campaign, CampaignSlot, MAX_STARS},
+    harness::TestGame,
+    terrain::Terrain,
+    victory::{FreePlay, Results, VictoryPlugin, VictoryScreen},
+};
+
+/// Held by tests whose wins export into the replays directory.
+static REPLAYS: Mutex<()> = Mutex::new(());
+
+/// Walls off every cell but a corridor of `length` cells up from the start,
+/// with food on the next two.
+fn corridor(length: i32) -> TestGame {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(VictoryPlugin);
+    let arena = *game.app_mut().world().resource::<Arena>();
+    for x in 0..arena.width as i32 {
+        for y in 0..arena.height as i32 {
+            if x != 3 || !(3..3 + length).contains(&y) {
+                game.place_obstacle(Position { x, y });
+            }
+        }
+    }
+    game.place_food(Position { x: 3, y: 4 });
+    game.place_food(Position { x: 3, y: 5 });
+    game
+}
+
+fn victory_screens(game: &mut TestGame) -> usize {
+    let world = game.app_mut().world_mut();
+    world
+        .query_filtered::<(), With<VictoryScreen>>()
+        .iter(world)
+        .count()
+}
+
+fn press(game: &mut TestGame, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        game.app_mut().world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        game.app_mut().update();
+    }
+}
+
+fn paused(game: &mut TestGame) -> bool {
+    game.app_mut()
+        .world()
+        .resource::<Time<Virtual>>()
+        .is_paused()
+}
+
+fn results(game: &mut TestGame) -> Option<Results> {
+    game.app_mut().world().get_resource::<Results>().cloned()
+}
+
+#[test]
+fn filling_the_board_wins_the_run_keeps_the_replay_and_waits_for_a_choice() {
+    let _replays = REPLAYS.lock().unwrap_or_else(|err| err.into_inner());
+    let mut game = corridor(3);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 0);
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+    assert_eq!(victory_screens(&mut game), 1);
+
+    let exported = std::fs::read_dir("replays")
+        .map(|entries| entries.count())
+        .unwrap_or(0);
+    let _ = std::fs::remove_dir_all("replays");
+    assert_eq!(exported, 1);
+
+    let won = results(&mut game).unwrap();
+    assert_eq!((won.score, won.length), (2, 4));
+    assert_eq!(won.stars, MAX_STARS);
+    assert_eq!(won.level, None);
+    assert!(paused(&mut game));
+
+    for _ in 0..3 {
+        game.app_mut().update();
+    }
+    assert_eq!(victory_screens(&mut game), 1);
+    press(&mut game, KeyCode::KeyN);
+    assert_eq!(victory_screens(&mut game), 1, "no next level to go to");
+
+    press(&mut game, KeyCode::Enter);
+    assert_eq!(victory_screens(&mut game), 0);
+    assert!(results(&mut game).is_none());
+    assert!(!paused(&mut game));
+    game.advance(1);
+    assert_eq!(game.game_overs(), 1);
+}
+
+#[test]
+fn free_play_keeps_the_cleared_level_until_a_crash() {
+    let _replays = REPLAYS.lock().unwrap_or_else(|err| err.into_inner());
+    let mut game = corridor(3);
+    game.app_mut().insert_resource(Campaign {
+        slot: 0,
+        progress: CampaignSlot {
+            level: 1,
+            ..Default::default()
+        },
+    });
+    game.advance(2);
+    let _ = std::fs::remove_dir_all("replays");
+    let cleared = results(&mut game).unwrap();
+    assert_eq!(cleared.level, Some(1));
+    assert!(cleared.has_next_level());
+
+    press(&mut game, KeyCode::KeyF);
+    assert!(game.app_mut().world().contains_resource::<FreePlay>());
+    assert!(!paused(&mut game));
+    assert!(
+        game.app_mut().world().contains_resource::<Terrain>(),
+        "the level is built again"
+    );
+
+    while game.game_overs() == 1 {
+        game.advance(1);
+    }
+    assert_eq!(victory_screens(&mut game), 0);
+    assert!(!game.app_mut().world().contains_resource::<FreePlay>());
+}
+
+#[test]
+fn a_board_with_room_left_goes_on() {
+    let mut game = corridor(4);
+    game.advance(2);
+    assert_eq!(game.game_overs(), 0);
+    assert_eq!(victory_screens(&mut game), 0);
+}
@@ -0,0 +1,32 @@
+use snake_core::{Direction, Position};
+use snake_game::{
+    harness::TestGame,
+    weather::{bury, Weather, WeatherPlugin},
+};
+
+#[test]
+fn drifted_food_is_uncovered_before_it_can_be_eaten() {
+    let mut game = TestGame::new();
+    game.app_mut().add_plugins(WeatherPlugin);
+    game.app_mut().insert_resource(Weather::Snow);
+    let food = game.place_food(Position { x: 3, y: 5 });
+    let world = game.app_mut().world_mut();
+    bury(&mut world.commands(), food, Position { x: 3, y: 5 });
+    world.flush();
+
+    game.advance(2);
+    assert_eq!(game.score(), 0, "passed over the drift");
+    assert_eq!(game.food(), [Position { x: 3, y: 5 }]);
+
+    for direction in [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ] {
+        game.steer(direction);
+        game.advance(1);
+    }
+    assert_eq!(game.score(), 1);
+    assert_eq!(game.game_overs(), 0);
+}
@@ -0,0 +1,153 @@
+//! Gym-style environment over the core rules, for training agents on exactly
+//! the game players play.
+//!
+//! [`SnakeEnv::reset`] starts a seeded run and [`SnakeEnv::step`] plays one
+//! movement tick. Observations are a `CHANNELS × height × width` grid of 0s
+//! and 1s marking the head, the rest of the body and food; the reward is how
+//! much the score went up. Food spawns on the same schedule as in the game.
+//!
+//! With the `python` feature the crate also builds a Python module exposing
+//! the same environment with Gymnasium's `reset`/`step` signatures.
+
+use snake_core::{
+    sim::Simulation, Arena, Direction, Position, FOOD_SPAWN_INTERVAL, MOVEMENT_INTERVAL,
+    START_DIRECTION,
+};
+
+#[cfg(feature = "python")]
+mod python;
+
+/// Planes of an [`Observation`], in order.
+pub const CHANNELS: usize = 3;
+pub const HEAD_CHANNEL: usize = 0;
+pub const BODY_CHANNEL: usize = 1;
+pub const FOOD_CHANNEL: usize = 2;
+
+/// Steps before a run is cut off, so an agent that circles forever still
+/// ends its episode.
+pub const DEFAULT_MAX_TICKS: u32 = 2_000;
+
+const FOOD_EVERY: u32 = (FOOD_SPAWN_INTERVAL.as_millis() / MOVEMENT_INTERVAL.as_millis()) as u32;
+
+/// Board as a flattened `[channel][y][x]` tensor, row 0 at the bottom.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Observation {
+    pub arena: Arena,
+    pub cells: Vec<u8>,
+}
+
+impl Observation {
+    pub fn shape(&self) -> [usize; 3] {
+        [
+            CHANNELS,
+            self.arena.height as usize,
+            self.arena.width as usize,
+        ]
+    }
+
+    pub fn get(&self, channel: usize, position: Position) -> u8 {
+        self.cells[self.index(channel, position)]
+    }
+
+    fn index(&self, channel: usize, position: Position) -> usize {
+        let [_, height, width] = self.shape();
+        (channel * height + position.y as usize) * width + position.x as usize
+    }
+}
+
+/// Result of one [`SnakeEnv::step`].
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub observation: Observation,
+    /// Score gained this tick.
+    pub reward: f32,
+    /// The snake died.
+    pub terminated: bool,
+    /// The run hit [`SnakeEnv::max_ticks`] alive.
+    pub truncated: bool,
+}
+
+pub struct SnakeEnv {
+    arena: Arena,
+    sim: Simulation,
+    direction: Direction,
+    tick: u32,
+    pub max_ticks: u32,
+}
+
+impl SnakeEnv {
+    pub fn new(arena: Arena) -> Self {
+        Self {
+            arena,
+            sim: Simulation::new(0, arena),
+            direction: START_DIRECTION,
+            tick: 0,
+            max_ticks: DEFAULT_MAX_TICKS,
+        }
+    }
+
+    /// Starts a fresh run; the same seed and actions replay it exactly.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.sim = Simulation::new(seed, self.arena);
+        self.direction = START_DIRECTION;
+        self.tick = 0;
+        self.observation()
+    }
+
+    /// Turns towards `action`, then moves. Reversing onto the neck is ignored,
+    /// as it is for players. Stepping a finished run does nothing.
+    pub fn step(&mut self, action: Direction) -> Step {
+        let score = self.sim.score;
+        if self.sim.alive && self.tick < self.max_ticks {
+            if action != self.direction.opposite() {
+                self.direction = action;
+            }
+            self.sim.step(self.direction);
+            self.tick += 1;
+            if self.tick.is_multiple_of(FOOD_EVERY) {
+                self.sim.spawn_food();
+            }
+        }
+        Step {
+            observation: self.observation(),
+            reward: (self.sim.score - score) as f32,
+            terminated: !self.sim.alive,
+            truncated: self.sim.alive && self.tick >= self.max_ticks,
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        let mut observation = Observation {
+            arena: self.arena,
+            cells: vec![0; CHANNELS * (self.arena.width * self.arena.height) as usize],
+        };
+        let mut mark = |channel, position: Position| {
+            if self.arena.contains(position) {
+                let index = observation.index(channel, position);
+                observation.cells[index] = 1;
+            }
+        };
+        for (segment, &position) in self.sim.body.iter().enumerate() {
+            mark(
+                if segment == 0 {
+                    HEAD_CHANNEL
+                } else {
+                    BODY_CHANNEL
+                },
+                position,
+            );
+        }
+        for &position in &self.sim.food {
+            mark(FOOD_CHANNEL, position);
+        }
+        observation
+    }
+
+    pub fn score(&self) -> u32 {
+        self.sim.score
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+}
@@ -0,0 +1,99 @@
+//! `snake_gym.SnakeEnv` for Python, following Gymnasium's API:
+//!
+//! ```python
+//! env = snake_gym.SnakeEnv(width=10, height=10)
+//! observation, info = env.reset(seed=7)
+//! observation, reward, terminated, truncated, info = env.step(1)
+//! ```
+//!
+//! Actions are 0 up, 1 right, 2 down and 3 left. Observations are nested
+//! lists shaped `[channel][y][x]`, ready for `numpy.asarray`.
+
+use std::collections::HashMap;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use snake_core::{Arena, Direction};
+
+use crate::{Observation, SnakeEnv};
+
+const ACTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+];
+
+type Grid = Vec<Vec<Vec<u8>>>;
+type Info = HashMap<&'static str, u32>;
+
+#[pyclass(name = "SnakeEnv")]
+struct PySnakeEnv(SnakeEnv);
+
+#[pymethods]
+impl PySnakeEnv {
+    #[new]
+    #[pyo3(signature = (width = 10, height = 10, max_ticks = crate::DEFAULT_MAX_TICKS))]
+    fn new(width: u32, height: u32, max_ticks: u32) -> PyResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(PyValueError::new_err("arena must be at least 1x1"));
+        }
+        let mut env = SnakeEnv::new(Arena { width, height });
+        env.max_ticks = max_ticks;
+        Ok(Self(env))
+    }
+
+    /// Number of discrete actions.
+    #[classattr]
+    fn action_count() -> usize {
+        ACTIONS.len()
+    }
+
+    #[pyo3(signature = (seed = None))]
+    fn reset(&mut self, seed: Option<u64>) -> (Grid, Info) {
+        let observation = self.0.reset(seed.unwrap_or_else(clock_seed));
+        (grid(&observation), self.info())
+    }
+
+    fn step(&mut self, action: usize) -> PyResult<(Grid, f32, bool, bool, Info)> {
+        let direction = *ACTIONS.get(action).ok_or_else(|| {
+            PyValueError::new_err(format!("action must be below {}", ACTIONS.len()))
+        })?;
+        let step = self.0.step(direction);
+        Ok((
+            grid(&step.observation),
+            step.reward,
+            step.terminated,
+            step.truncated,
+            self.info(),
+        ))
+    }
+}
+
+impl PySnakeEnv {
+    fn info(&self) -> Info {
+        HashMap::from([("score", self.0.score()), ("tick", self.0.tick())])
+    }
+}
+
+fn clock_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+fn grid(observation: &Observation) -> Grid {
+    let [channels, height, width] = observation.shape();
+    (0..channels)
+        .map(|channel| {
+            observation.cells[channel * height * width..(channel + 1) * height * width]
+                .chunks(width)
+                .map(<[u8]>::to_vec)
+                .collect()
+        })
+        .collect()
+}
+
+#[pymodule]
+fn snake_gym(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PySnakeEnv>()
+}
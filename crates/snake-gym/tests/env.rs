@@ -0,0 +1,86 @@
+use snake_core::{
+    controller::Autopilot, controller::BoardView, controller::SnakeController, Arena, Direction,
+    Position,
+};
+use snake_gym::{SnakeEnv, BODY_CHANNEL, FOOD_CHANNEL, HEAD_CHANNEL};
+
+#[test]
+fn observation_marks_the_snake() {
+    let mut env = SnakeEnv::new(Arena::default());
+    let observation = env.reset(1);
+    assert_eq!(observation.shape(), [3, 10, 10]);
+    assert_eq!(observation.get(HEAD_CHANNEL, Position { x: 3, y: 3 }), 1);
+    assert_eq!(
+        observation
+            .cells
+            .iter()
+            .map(|&cell| u32::from(cell))
+            .sum::<u32>(),
+        2
+    );
+
+    let step = env.step(Direction::Up);
+    assert_eq!(
+        step.observation.get(HEAD_CHANNEL, Position { x: 3, y: 4 }),
+        1
+    );
+    assert_eq!(
+        step.observation.get(BODY_CHANNEL, Position { x: 3, y: 3 }),
+        1
+    );
+    assert_eq!((step.reward, step.terminated), (0.0, false));
+}
+
+#[test]
+fn running_into_the_wall_terminates() {
+    let mut env = SnakeEnv::new(Arena::default());
+    env.reset(1);
+    let steps: Vec<_> = (0..7).map(|_| env.step(Direction::Up)).collect();
+    assert!(steps[..6].iter().all(|step| !step.terminated));
+    assert!(steps[6].terminated);
+}
+
+#[test]
+fn eating_is_rewarded_and_seeds_replay() {
+    let play = |seed| {
+        let mut env = SnakeEnv::new(Arena::default());
+        let mut observation = env.reset(seed);
+        let mut pilot = Autopilot;
+        let mut direction = Direction::Up;
+        let mut rewards = 0.0;
+        for _ in 0..200 {
+            let body = cells(&observation, HEAD_CHANNEL)
+                .into_iter()
+                .chain(cells(&observation, BODY_CHANNEL))
+                .collect::<Vec<_>>();
+            let food = cells(&observation, FOOD_CHANNEL);
+            let board = BoardView {
+                arena: observation.arena,
+                body: &body,
+                others: &[],
+                food: &food,
+                direction,
+            };
+            direction = pilot.steer(&board).unwrap_or(direction);
+            let step = env.step(direction);
+            rewards += step.reward;
+            observation = step.observation;
+            if step.terminated {
+                break;
+            }
+        }
+        (rewards, env.score())
+    };
+    let (rewards, score) = play(5);
+    assert!(rewards > 0.0);
+    assert_eq!(rewards, score as f32);
+    assert_eq!(play(5), (rewards, score));
+}
+
+fn cells(observation: &snake_gym::Observation, channel: usize) -> Vec<Position> {
+    let arena = observation.arena;
+    (0..arena.height as i32)
+        .flat_map(|y| (0..arena.width as i32).map(move |x| Position { x, y }))
+        .filter(|&position| observation.get(channel, position) == 1)
+        .collect()
+}
@@ -0,0 +1,97 @@
+use std::{
+    io,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::protocol::{
+    read_message, write_message, ClientMessage, ProtocolError, ServerMessage, PROTOCOL_VERSION,
+};
+
+/// A connection to a server. Incoming messages are read on a background
+/// thread and collected with [`Client::poll`].
+pub struct Client {
+    stream: TcpStream,
+    incoming: Mutex<Receiver<ServerMessage>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("disconnected from server")]
+pub struct Disconnected;
+
+impl Client {
+    /// Connects and joins `room` as `name`.
+    pub fn connect(
+        address: impl ToSocketAddrs,
+        name: &str,
+        room: &str,
+    ) -> Result<Self, ProtocolError> {
+        Self::open(address, name, room, false)
+    }
+
+    /// Connects and watches `room` without taking a seat.
+    pub fn spectate(
+        address: impl ToSocketAddrs,
+        name: &str,
+        room: &str,
+    ) -> Result<Self, ProtocolError> {
+        Self::open(address, name, room, true)
+    }
+
+    fn open(
+        address: impl ToSocketAddrs,
+        name: &str,
+        room: &str,
+        spectate: bool,
+    ) -> Result<Self, ProtocolError> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        let reader = stream.try_clone()?;
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(message) = read_message(&reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        let client = Self {
+            stream,
+            incoming: Mutex::new(incoming),
+        };
+        client.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            name: name.to_string(),
+            room: room.to_string(),
+            spectate,
+        })?;
+        Ok(client)
+    }
+
+    pub fn send(&self, message: &ClientMessage) -> Result<(), ProtocolError> {
+        write_message(&self.stream, message)
+    }
+
+    /// Messages received since the last call. Fails once the connection has
+    /// dropped and every message before that has been returned.
+    pub fn poll(&self) -> Result<Vec<ServerMessage>, Disconnected> {
+        let incoming = self.incoming.lock().unwrap_or_else(|err| err.into_inner());
+        let mut messages = Vec::new();
+        loop {
+            match incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => return Ok(messages),
+                Err(TryRecvError::Disconnected) if messages.is_empty() => return Err(Disconnected),
+                Err(TryRecvError::Disconnected) => return Ok(messages),
+            }
+        }
+    }
+
+    pub fn disconnect(&self) -> io::Result<()> {
+        self.stream.shutdown(std::net::Shutdown::Both)
+    }
+}
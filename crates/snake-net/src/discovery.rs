@@ -0,0 +1,108 @@
+//! Finding servers on the local network without typing addresses.
+//!
+//! A host broadcasts an [`Announcement`] over UDP every
+//! [`ANNOUNCE_INTERVAL`]; a [`Browser`] listens for them and keeps a list of
+//! the games it heard from recently.
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const DISCOVERY_PORT: u16 = 7879;
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+/// A game drops off the list after this long without an announcement.
+pub const EXPIRY: Duration = Duration::from_secs(3);
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Announcement {
+    pub name: String,
+    /// TCP port the server accepts players on.
+    pub port: u16,
+}
+
+/// Where announcements go by default: every host on the local network.
+pub fn broadcast_address() -> SocketAddr {
+    (Ipv4Addr::BROADCAST, DISCOVERY_PORT).into()
+}
+
+/// Announces `announcement` to `target` on a background thread for as long
+/// as the process runs.
+pub fn announce(announcement: Announcement, target: impl ToSocketAddrs) -> io::Result<()> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket.set_broadcast(true)?;
+    let target: Vec<SocketAddr> = target.to_socket_addrs()?.collect();
+    let bytes = serde_json::to_vec(&announcement).map_err(io::Error::other)?;
+    thread::spawn(move || loop {
+        for &address in &target {
+            // A missing network is not fatal; it may come back.
+            let _ = socket.send_to(&bytes, address);
+        }
+        thread::sleep(ANNOUNCE_INTERVAL);
+    });
+    Ok(())
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LanGame {
+    pub name: String,
+    /// Address to hand to [`crate::client::Client::connect`].
+    pub address: SocketAddr,
+    last_seen: Instant,
+}
+
+pub struct Browser {
+    socket: UdpSocket,
+    games: Vec<LanGame>,
+}
+
+impl Browser {
+    /// Listens for announcements on `port`, usually [`DISCOVERY_PORT`].
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            games: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Reads pending announcements and returns the games heard from within
+    /// [`EXPIRY`], in the order they were first seen.
+    pub fn poll(&mut self) -> io::Result<&[LanGame]> {
+        let mut buffer = [0; 512];
+        loop {
+            let (len, from) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            };
+            let Ok(announcement) = serde_json::from_slice::<Announcement>(&buffer[..len]) else {
+                continue;
+            };
+            let address = SocketAddr::new(from.ip(), announcement.port);
+            let now = Instant::now();
+            match self.games.iter_mut().find(|game| game.address == address) {
+                Some(game) => {
+                    game.name = announcement.name;
+                    game.last_seen = now;
+                }
+                None => self.games.push(LanGame {
+                    name: announcement.name,
+                    address,
+                    last_seen: now,
+                }),
+            }
+        }
+        self.games.retain(|game| game.last_seen.elapsed() < EXPIRY);
+        Ok(&self.games)
+    }
+}
@@ -0,0 +1,12 @@
+//! Networked versus play: the wire protocol, an authoritative server that
+//! runs a [`snake_core::versus::Match`], and the client connection the game
+//! uses to join it, plus LAN discovery of running servers.
+//!
+//! Everything here is engine-free and blocking; each connection gets its own
+//! reader thread and hands messages over through channels.
+
+pub mod client;
+pub mod discovery;
+pub mod protocol;
+pub mod rollback;
+pub mod server;
@@ -0,0 +1,305 @@
+//! Messages exchanged between clients and the server, and how boards are
+//! kept in sync.
+//!
+//! Each message is sent as a little-endian `u32` byte length followed by the
+//! message as JSON. A client opens with a hello carrying its
+//! [`PROTOCOL_VERSION`], and the server rejects any other version before it
+//! joins a room. After a match starts with the full board, every tick only
+//! carries a [`BoardDiff`] that [`Board::apply`] replays on the client: the
+//! direction each snake moved, whether it grew or shed, its score if that
+//! changed and the food and shed segments that came and went. That is a few
+//! bytes per snake whatever the arena or snake size.
+
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use snake_core::{
+    controller::Difficulty,
+    versus::{Match, Snake, VersusMode},
+    Arena, Direction, Position,
+};
+
+/// Messages larger than this are treated as a broken stream.
+const MAX_MESSAGE_LEN: u32 = 1 << 20;
+
+/// Bumped whenever a message changes shape.
+pub const PROTOCOL_VERSION: u16 = 3;
+
+/// Room name asking the server to seat the player in any open public room.
+pub const QUICK_MATCH: &str = "*";
+/// Number of snake colors players can pick from.
+pub const COLORS: u8 = 4;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Joins the room called `room`, opening it if needed. Spectators take
+    /// no seat and their steering is ignored. Clients from before versioning
+    /// send no version, which reads as 0.
+    Hello {
+        #[serde(default)]
+        version: u16,
+        name: String,
+        room: String,
+        #[serde(default)]
+        spectate: bool,
+    },
+    /// Changes the sender's name and color (below [`COLORS`]) in the lobby.
+    SetProfile {
+        name: String,
+        color: u8,
+    },
+    Ready(bool),
+    /// Changes the next match's settings; ignored from anyone but the owner.
+    Configure(RoomSettings),
+    /// Starts the match once every other seated player is ready; ignored
+    /// from anyone but the owner.
+    Start,
+    Steer(Direction),
+    /// Sheds the sender's last segment, see [`Match::shed`].
+    Shed,
+}
+
+/// What the room owner picked for the next match.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct RoomSettings {
+    pub mode: VersusMode,
+    pub arena: Arena,
+    /// Computer opponents that fill free seats when the match starts.
+    #[serde(default)]
+    pub bots: u8,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct LobbySeat {
+    pub name: String,
+    pub color: u8,
+    pub ready: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Accepted into the room, in seat `seat`.
+    Welcome {
+        seat: u8,
+    },
+    /// Accepted into the room as a spectator.
+    Watching,
+    Rejected {
+        reason: String,
+    },
+    /// Seated players by seat, the seat allowed to start the match, how many
+    /// are watching and the settings for the next match.
+    Lobby {
+        players: Vec<Option<LobbySeat>>,
+        owner: u8,
+        #[serde(default)]
+        spectators: u8,
+        #[serde(default)]
+        settings: RoomSettings,
+    },
+    /// A match began, or is under way when a spectator arrives. `you` is the
+    /// receiver's snake, if it plays, and `names` the players' names in snake
+    /// order, with `colors` their picked colors.
+    MatchStarted {
+        board: Board,
+        you: Option<u8>,
+        #[serde(default)]
+        names: Vec<String>,
+        #[serde(default)]
+        colors: Vec<u8>,
+    },
+    Update(BoardDiff),
+    MatchOver {
+        winner: Option<u8>,
+    },
+}
+
+/// The client's copy of a match.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Board {
+    pub arena: Arena,
+    pub tick: u32,
+    pub snakes: Vec<Snake>,
+    pub food: Vec<Position>,
+    /// Where shed segments lie.
+    #[serde(default)]
+    pub shed: Vec<Position>,
+}
+
+impl From<&Match> for Board {
+    fn from(game: &Match) -> Self {
+        Self {
+            arena: game.arena(),
+            tick: game.tick,
+            snakes: game.snakes.clone(),
+            food: game.food.clone(),
+            shed: game.shed.iter().map(|shed| shed.position).collect(),
+        }
+    }
+}
+
+/// What changed on the board during one tick.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BoardDiff {
+    pub tick: u32,
+    /// One entry per snake, in match order.
+    pub snakes: Vec<SnakeDiff>,
+    pub food_added: Vec<Position>,
+    pub food_removed: Vec<Position>,
+    #[serde(default)]
+    pub shed_added: Vec<Position>,
+    #[serde(default)]
+    pub shed_removed: Vec<Position>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SnakeDiff {
+    Unchanged,
+    /// Moved its head one cell in `direction`, keeping its tail if it grew,
+    /// after dropping its last segment if it shed. `score` is only sent when
+    /// it changed.
+    Moved {
+        direction: Direction,
+        grew: bool,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        shed: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        score: Option<u32>,
+    },
+    Died,
+}
+
+impl BoardDiff {
+    pub fn between(before: &Match, after: &Match) -> Self {
+        let snakes = before
+            .snakes
+            .iter()
+            .zip(&after.snakes)
+            .map(|(before, after)| match (before.alive, after.alive) {
+                (true, false) => SnakeDiff::Died,
+                (true, true) => SnakeDiff::Moved {
+                    direction: after.direction,
+                    grew: after.body.len() > before.body.len()
+                        || after.body.len() == before.body.len()
+                            && after.shed_ready != before.shed_ready,
+                    shed: after.shed_ready != before.shed_ready,
+                    score: (after.score != before.score).then_some(after.score),
+                },
+                _ => SnakeDiff::Unchanged,
+            })
+            .collect();
+        Self {
+            tick: after.tick,
+            snakes,
+            food_added: difference(&after.food, &before.food),
+            food_removed: difference(&before.food, &after.food),
+            shed_added: difference(&shed(after), &shed(before)),
+            shed_removed: difference(&shed(before), &shed(after)),
+        }
+    }
+}
+
+fn shed(game: &Match) -> Vec<Position> {
+    game.shed.iter().map(|shed| shed.position).collect()
+}
+
+/// Items of `a` not matched by an item of `b`, counting duplicates.
+fn difference(a: &[Position], b: &[Position]) -> Vec<Position> {
+    let mut unmatched = b.to_vec();
+    a.iter()
+        .filter(
+            |item| match unmatched.iter().position(|other| other == *item) {
+                Some(index) => {
+                    unmatched.swap_remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .copied()
+        .collect()
+}
+
+impl Board {
+    pub fn apply(&mut self, diff: &BoardDiff) {
+        self.tick = diff.tick;
+        for (snake, change) in self.snakes.iter_mut().zip(&diff.snakes) {
+            match *change {
+                SnakeDiff::Unchanged => {}
+                SnakeDiff::Moved {
+                    direction,
+                    grew,
+                    shed,
+                    score,
+                } => {
+                    let Some(&head) = snake.body.first() else {
+                        continue;
+                    };
+                    if shed {
+                        snake.body.pop();
+                    }
+                    if !grew {
+                        snake.body.pop();
+                    }
+                    snake.body.insert(0, head.step(direction));
+                    snake.direction = direction;
+                    if let Some(score) = score {
+                        snake.score = score;
+                    }
+                }
+                SnakeDiff::Died => {
+                    snake.alive = false;
+                    snake.body.clear();
+                }
+            }
+        }
+        for removed in &diff.food_removed {
+            if let Some(index) = self.food.iter().position(|food| food == removed) {
+                self.food.swap_remove(index);
+            }
+        }
+        self.food.extend_from_slice(&diff.food_added);
+        for removed in &diff.shed_removed {
+            if let Some(index) = self.shed.iter().position(|shed| shed == removed) {
+                self.shed.swap_remove(index);
+            }
+        }
+        self.shed.extend_from_slice(&diff.shed_added);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("connection failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("malformed message: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("message of {0} bytes is too large")]
+    TooLarge(u32),
+}
+
+pub fn write_message<T: Serialize>(
+    mut writer: impl Write,
+    message: &T,
+) -> Result<(), ProtocolError> {
+    let bytes = serde_json::to_vec(message)?;
+    let mut frame = Vec::with_capacity(4 + bytes.len());
+    frame.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&bytes);
+    writer.write_all(&frame)?;
+    Ok(())
+}
+
+pub fn read_message<T: DeserializeOwned>(mut reader: impl Read) -> Result<T, ProtocolError> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        return Err(ProtocolError::TooLarge(len));
+    }
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
@@ -0,0 +1,244 @@
+//! Input-delay and rollback for two-player matches without a server.
+//!
+//! Both peers run the same [`Match`]. Local turns are scheduled
+//! [`RollbackSession::input_delay`] ticks ahead and sent to the other peer,
+//! which assumes "no turn" for any tick it has not heard about yet. When a
+//! late turn arrives for a tick that was already simulated, the session
+//! restores the state saved before that tick and re-simulates up to the
+//! present, so both peers end up with identical boards while each player's
+//! own turns show up without waiting for the network.
+
+use std::{
+    collections::VecDeque,
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use serde::{Deserialize, Serialize};
+use snake_core::{versus::Match, Arena, Direction};
+
+/// How many ticks the simulation may run ahead of the last tick with known
+/// inputs from both players before it waits for the other peer.
+pub const MAX_PREDICTION: u32 = 8;
+
+/// Local inputs the peer has not acknowledged yet, resent with every packet
+/// so a lost datagram is covered by the next one.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct InputPacket {
+    /// Latest tick for which the sender knows all of the receiver's inputs.
+    pub ack: u32,
+    /// Tick of the first entry in `inputs`.
+    pub start: u32,
+    pub inputs: Vec<Option<Direction>>,
+}
+
+pub struct RollbackSession {
+    local: usize,
+    input_delay: u32,
+    game: Match,
+    /// States before each tick after `confirmed`, oldest first.
+    saved: VecDeque<Match>,
+    /// Inputs by tick, starting at tick 1.
+    local_inputs: Vec<Option<Direction>>,
+    remote_inputs: Vec<Option<Direction>>,
+    /// Latest tick the peer has acknowledged receiving our input for.
+    remote_ack: u32,
+    rollbacks: u32,
+}
+
+impl RollbackSession {
+    /// `local` is this peer's snake, 0 or 1.
+    pub fn new(seed: u64, arena: Arena, local: usize, input_delay: u32) -> Self {
+        Self {
+            local,
+            input_delay,
+            game: Match::new(seed, arena, 2),
+            saved: VecDeque::new(),
+            local_inputs: vec![None; input_delay as usize],
+            remote_inputs: Vec::new(),
+            remote_ack: 0,
+            rollbacks: 0,
+        }
+    }
+
+    /// Best guess at the current board.
+    pub fn game(&self) -> &Match {
+        &self.game
+    }
+
+    pub fn local(&self) -> usize {
+        self.local
+    }
+
+    pub fn input_delay(&self) -> u32 {
+        self.input_delay
+    }
+
+    /// Latest tick with inputs known from both players.
+    pub fn confirmed(&self) -> u32 {
+        (self.remote_inputs.len() as u32).min(self.game.tick)
+    }
+
+    /// How many times a late input forced a re-simulation.
+    pub fn rollbacks(&self) -> u32 {
+        self.rollbacks
+    }
+
+    /// Whether another tick may be predicted without hearing from the peer.
+    pub fn can_advance(&self) -> bool {
+        self.game.tick - self.confirmed() < MAX_PREDICTION
+    }
+
+    /// Schedules `turn` and simulates one tick. Returns the packet to send to
+    /// the peer.
+    pub fn advance(&mut self, turn: Option<Direction>) -> InputPacket {
+        self.local_inputs.push(turn);
+        self.saved.push_back(self.game.clone());
+        self.simulate_next();
+        self.trim();
+        self.packet()
+    }
+
+    /// Records the peer's inputs, rolling back if any of them change a tick
+    /// that was already predicted.
+    pub fn receive(&mut self, packet: &InputPacket) {
+        // Acks come off the wire and cannot be ahead of what was sent.
+        let ack = packet.ack.min(self.local_inputs.len() as u32);
+        self.remote_ack = self.remote_ack.max(ack);
+        let known = self.remote_inputs.len() as u32;
+        let new_inputs = packet
+            .inputs
+            .iter()
+            .zip(packet.start..)
+            .filter(|&(_, tick)| tick > known);
+        let mut mispredicted = None;
+        for (&input, tick) in new_inputs {
+            if tick != self.remote_inputs.len() as u32 + 1 {
+                break;
+            }
+            self.remote_inputs.push(input);
+            if input.is_some() && tick <= self.game.tick && mispredicted.is_none() {
+                mispredicted = Some(tick);
+            }
+        }
+        if let Some(tick) = mispredicted {
+            self.rollback_to(tick);
+        }
+        self.trim();
+    }
+
+    /// Re-simulates from the state saved before `tick` up to the present.
+    fn rollback_to(&mut self, tick: u32) {
+        let present = self.game.tick;
+        let oldest = present + 1 - self.saved.len() as u32;
+        let index = (tick - oldest) as usize;
+        self.game = self.saved[index].clone();
+        self.saved.truncate(index + 1);
+        while self.game.tick < present {
+            if self.game.tick + 1 > tick {
+                self.saved.push_back(self.game.clone());
+            }
+            self.simulate_next();
+        }
+        self.rollbacks += 1;
+    }
+
+    fn simulate_next(&mut self) {
+        let tick = self.game.tick as usize + 1;
+        let input = |inputs: &[Option<Direction>]| inputs.get(tick - 1).copied().flatten();
+        if let Some(direction) = input(&self.local_inputs) {
+            self.game.steer(self.local, direction);
+        }
+        if let Some(direction) = input(&self.remote_inputs) {
+            self.game.steer(1 - self.local, direction);
+        }
+        self.game.tick();
+    }
+
+    /// Drops saved states that no late input can roll back to any more.
+    fn trim(&mut self) {
+        let oldest = self.game.tick + 1 - self.saved.len() as u32;
+        let droppable = (self.confirmed() + 1).saturating_sub(oldest) as usize;
+        self.saved.drain(..droppable.min(self.saved.len()));
+    }
+
+    fn packet(&self) -> InputPacket {
+        let start = self.remote_ack + 1;
+        InputPacket {
+            ack: self.remote_inputs.len() as u32,
+            start,
+            inputs: self.local_inputs[(start - 1) as usize..].to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PeerMessage {
+    Join,
+    Start { seed: u64 },
+    Inputs(InputPacket),
+}
+
+/// UDP link to the other player. The host waits for a [`PeerMessage::Join`]
+/// and answers with the match seed; the joiner keeps asking until it hears
+/// back.
+pub struct Peer {
+    socket: UdpSocket,
+    remote: Option<SocketAddr>,
+}
+
+impl Peer {
+    pub fn host(port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            remote: None,
+        })
+    }
+
+    pub fn join(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            remote: socket.peer_addr().ok(),
+            socket,
+        })
+    }
+
+    pub fn connected(&self) -> bool {
+        self.remote.is_some()
+    }
+
+    pub fn send(&self, message: &PeerMessage) -> io::Result<()> {
+        let Some(remote) = self.remote else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(message).map_err(io::Error::other)?;
+        match self.socket.send_to(&bytes, remote) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+            result => result.map(drop),
+        }
+    }
+
+    /// Datagrams received since the last call. The host adopts the first
+    /// sender as its peer and ignores everyone else.
+    pub fn poll(&mut self) -> io::Result<Vec<PeerMessage>> {
+        let mut messages = Vec::new();
+        let mut buffer = [0; 2048];
+        loop {
+            let (len, from) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(messages),
+                Err(err) => return Err(err),
+            };
+            if *self.remote.get_or_insert(from) != from {
+                continue;
+            }
+            if let Ok(message) = serde_json::from_slice(&buffer[..len]) {
+                messages.push(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,602 @@
+//! Authoritative server for rooms of up to [`MAX_PLAYERS`] snakes each.
+//!
+//! Players join a room by connecting and saying hello with its name, which
+//! opens the room if it does not exist yet, and take the first free seat.
+//! Asking for [`QUICK_MATCH`] instead picks any public room with a free seat.
+//! Seated players pick a name and color and ready up, while the owner, the
+//! player in the lowest seat, picks the [`RoomSettings`] and starts a match
+//! once at least two are seated and the others are ready. The server then
+//! ticks the [`Match`] at a fixed rate, applies the latest direction each
+//! player steered and broadcasts what changed. Seats left free can go to
+//! computer [`Opponent`]s, so one player can start a match alone against
+//! them. When one snake is left the room goes back to the lobby. Up to
+//! [`MAX_SPECTATORS`] more can watch a room, even mid-match. A room closes
+//! when its last connection drops.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use snake_core::{
+    controller::{steer_match, Opponent},
+    versus::{Match, MAX_PLAYERS},
+    Arena, MOVEMENT_INTERVAL,
+};
+
+use crate::protocol::{
+    read_message, write_message, Board, BoardDiff, ClientMessage, LobbySeat, RoomSettings,
+    ServerMessage, COLORS, PROTOCOL_VERSION, QUICK_MATCH,
+};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_SPECTATORS: usize = 16;
+/// Bounds on each side of an arena the owner picks.
+pub const ARENA_SIDES: std::ops::RangeInclusive<u32> = 8..=64;
+const MAX_NAME_LEN: usize = 16;
+/// How long a write may block before its client is dropped. Every room is
+/// served from one thread, so a client that stops reading must not hold up
+/// the others once its socket buffer is full.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(250);
+const QUICK_ROOM_PREFIX: &str = "quick-";
+
+#[derive(Clone, Copy, Debug)]
+pub struct ServerConfig {
+    pub arena: Arena,
+    pub tick_interval: Duration,
+    /// Hellos for new rooms are rejected once this many are open.
+    pub max_rooms: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            arena: Arena::default(),
+            tick_interval: MOVEMENT_INTERVAL,
+            max_rooms: 16,
+        }
+    }
+}
+
+type ConnectionId = u64;
+
+enum Event {
+    Connected(ConnectionId, TcpStream),
+    Message(ConnectionId, ClientMessage),
+    Disconnected(ConnectionId),
+}
+
+/// Accepts connections on `listener` and runs rooms until the listener fails.
+/// Blocks the calling thread.
+pub fn serve(listener: TcpListener, config: ServerConfig) -> io::Result<()> {
+    let (events, receiver) = mpsc::channel();
+    let accept_events = events.clone();
+    let accept = thread::spawn(move || accept_connections(listener, accept_events));
+    drop(events);
+
+    let mut rooms = Rooms {
+        config,
+        unassigned: HashMap::new(),
+        rooms: HashMap::new(),
+        room_of: HashMap::new(),
+        quick_rooms: 0,
+    };
+    let mut next_tick = Instant::now() + config.tick_interval;
+    loop {
+        match receiver.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+            Ok(event) => rooms.handle(event),
+            Err(RecvTimeoutError::Timeout) => {
+                for room in rooms.rooms.values_mut() {
+                    room.tick();
+                }
+                next_tick += config.tick_interval;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    accept
+        .join()
+        .unwrap_or_else(|_| Err(io::Error::other("accept thread panicked")))
+}
+
+fn accept_connections(listener: TcpListener, events: Sender<Event>) -> io::Result<()> {
+    for (id, stream) in (0..).zip(listener.incoming()) {
+        let stream = stream?;
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let reader = stream.try_clone()?;
+        if events.send(Event::Connected(id, stream)).is_err() {
+            break;
+        }
+        let events = events.clone();
+        thread::spawn(move || {
+            while let Ok(message) = read_message(&reader) {
+                if events.send(Event::Message(id, message)).is_err() {
+                    return;
+                }
+            }
+            let _ = events.send(Event::Disconnected(id));
+        });
+    }
+    Ok(())
+}
+
+/// Routes connections to the room they said hello to.
+struct Rooms {
+    config: ServerConfig,
+    /// Connections that have not said hello yet.
+    unassigned: HashMap<ConnectionId, TcpStream>,
+    rooms: HashMap<String, Room>,
+    room_of: HashMap<ConnectionId, String>,
+    /// Quick-match rooms opened so far, for naming the next one.
+    quick_rooms: u64,
+}
+
+impl Rooms {
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Connected(connection, stream) => {
+                self.unassigned.insert(connection, stream);
+            }
+            Event::Message(
+                connection,
+                ClientMessage::Hello {
+                    version,
+                    name,
+                    room,
+                    spectate,
+                },
+            ) => {
+                let Some(stream) = self.unassigned.remove(&connection) else {
+                    return;
+                };
+                if version != PROTOCOL_VERSION {
+                    let reason = format!(
+                        "protocol version {version} is not supported, the server speaks {PROTOCOL_VERSION}"
+                    );
+                    let _ = write_message(&stream, &ServerMessage::Rejected { reason });
+                    return;
+                }
+                let room = if room == QUICK_MATCH {
+                    self.quick_match_room()
+                } else {
+                    room
+                };
+                if !self.rooms.contains_key(&room) && self.rooms.len() >= self.config.max_rooms {
+                    let _ = write_message(
+                        &stream,
+                        &ServerMessage::Rejected {
+                            reason: "the server is full".to_string(),
+                        },
+                    );
+                    return;
+                }
+                let target = self
+                    .rooms
+                    .entry(room.clone())
+                    .or_insert_with(|| Room::new(self.config));
+                target.handle(Event::Connected(connection, stream));
+                target.handle(Event::Message(
+                    connection,
+                    ClientMessage::Hello {
+                        version,
+                        name,
+                        room: room.clone(),
+                        spectate,
+                    },
+                ));
+                self.room_of.insert(connection, room);
+            }
+            Event::Message(connection, message) => {
+                if let Some(room) = self.room_of(connection) {
+                    room.handle(Event::Message(connection, message));
+                }
+            }
+            Event::Disconnected(connection) => {
+                self.unassigned.remove(&connection);
+                if let Some(room) = self.room_of(connection) {
+                    room.handle(Event::Disconnected(connection));
+                }
+                if let Some(name) = self.room_of.remove(&connection) {
+                    if self
+                        .rooms
+                        .get(&name)
+                        .is_some_and(|room| room.connections.is_empty())
+                    {
+                        self.rooms.remove(&name);
+                    }
+                }
+            }
+        }
+    }
+
+    /// An open quick-match room with a free seat, or a name for a new one.
+    fn quick_match_room(&mut self) -> String {
+        let open = self
+            .rooms
+            .iter()
+            .filter(|(name, room)| name.starts_with(QUICK_ROOM_PREFIX) && room.has_free_seat())
+            .map(|(name, _)| name)
+            .min();
+        if let Some(name) = open {
+            return name.clone();
+        }
+        self.quick_rooms += 1;
+        format!("{QUICK_ROOM_PREFIX}{}", self.quick_rooms)
+    }
+
+    fn room_of(&mut self, connection: ConnectionId) -> Option<&mut Room> {
+        let name = self.room_of.get(&connection)?;
+        self.rooms.get_mut(name)
+    }
+}
+
+struct Player {
+    connection: ConnectionId,
+    name: String,
+    color: u8,
+    ready: bool,
+}
+
+struct Playing {
+    game: Match,
+    /// Snake index of each seat that took part.
+    snakes: HashMap<usize, usize>,
+    /// Player names and colors in snake order.
+    names: Vec<String>,
+    colors: Vec<u8>,
+    /// Computer opponents by snake index.
+    bots: Vec<(usize, Opponent)>,
+    /// Snakes whose players left since the last tick, killed in the next one
+    /// so the clients hear of it.
+    departed: Vec<usize>,
+}
+
+struct Room {
+    connections: HashMap<ConnectionId, TcpStream>,
+    seats: [Option<Player>; MAX_PLAYERS],
+    spectators: Vec<ConnectionId>,
+    settings: RoomSettings,
+    playing: Option<Playing>,
+    seed: u64,
+}
+
+impl Room {
+    fn new(config: ServerConfig) -> Self {
+        Self {
+            connections: HashMap::new(),
+            seats: Default::default(),
+            spectators: Vec::new(),
+            settings: RoomSettings {
+                arena: config.arena,
+                ..Default::default()
+            },
+            playing: None,
+            seed: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_nanos() as u64),
+        }
+    }
+
+    fn seat_of(&self, connection: ConnectionId) -> Option<usize> {
+        self.seats.iter().position(|seat| {
+            seat.as_ref()
+                .is_some_and(|player| player.connection == connection)
+        })
+    }
+
+    fn owner(&self) -> Option<usize> {
+        self.seats.iter().position(Option::is_some)
+    }
+
+    fn has_free_seat(&self) -> bool {
+        self.playing.is_none() && self.seats.iter().any(Option::is_none)
+    }
+
+    fn is_owner(&self, connection: ConnectionId) -> bool {
+        self.seat_of(connection).is_some() && self.seat_of(connection) == self.owner()
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Connected(connection, stream) => {
+                self.connections.insert(connection, stream);
+            }
+            Event::Message(connection, ClientMessage::Hello { spectate: true, .. }) => {
+                self.watch(connection)
+            }
+            Event::Message(connection, ClientMessage::Hello { name, .. }) => {
+                self.join(connection, name)
+            }
+            Event::Message(connection, ClientMessage::SetProfile { name, color }) => {
+                let name = name.trim();
+                let seat = self.seat_of(connection);
+                if let (Some(seat), false, true) = (seat, name.is_empty(), color < COLORS) {
+                    if let Some(player) = &mut self.seats[seat] {
+                        player.name = name.chars().take(MAX_NAME_LEN).collect();
+                        player.color = color;
+                    }
+                    self.broadcast_lobby();
+                }
+            }
+            Event::Message(connection, ClientMessage::Ready(ready)) => {
+                let seat = self.seat_of(connection);
+                if let (Some(seat), None) = (seat, &self.playing) {
+                    if let Some(player) = &mut self.seats[seat] {
+                        player.ready = ready;
+                    }
+                    self.broadcast_lobby();
+                }
+            }
+            Event::Message(connection, ClientMessage::Configure(settings)) => {
+                let valid = ARENA_SIDES.contains(&settings.arena.width)
+                    && ARENA_SIDES.contains(&settings.arena.height)
+                    && usize::from(settings.bots) < MAX_PLAYERS;
+                if valid && self.playing.is_none() && self.is_owner(connection) {
+                    self.settings = settings;
+                    // Everyone confirms again under the new settings.
+                    for player in self.seats.iter_mut().flatten() {
+                        player.ready = false;
+                    }
+                    self.broadcast_lobby();
+                }
+            }
+            Event::Message(connection, ClientMessage::Start) => {
+                if self.is_owner(connection) {
+                    self.start();
+                }
+            }
+            Event::Message(connection, ClientMessage::Steer(direction)) => {
+                let seat = self.seat_of(connection);
+                if let (Some(playing), Some(seat)) = (&mut self.playing, seat) {
+                    if let Some(&snake) = playing.snakes.get(&seat) {
+                        playing.game.steer(snake, direction);
+                    }
+                }
+            }
+            Event::Message(connection, ClientMessage::Shed) => {
+                let seat = self.seat_of(connection);
+                if let (Some(playing), Some(seat)) = (&mut self.playing, seat) {
+                    if let Some(&snake) = playing.snakes.get(&seat) {
+                        playing.game.shed(snake);
+                    }
+                }
+            }
+            Event::Disconnected(connection) => self.leave(connection),
+        }
+    }
+
+    fn watch(&mut self, connection: ConnectionId) {
+        if self.seat_of(connection).is_some() || self.spectators.contains(&connection) {
+            return;
+        }
+        if self.spectators.len() >= MAX_SPECTATORS {
+            self.send(
+                connection,
+                &ServerMessage::Rejected {
+                    reason: "too many spectators".to_string(),
+                },
+            );
+            self.connections.remove(&connection);
+            return;
+        }
+        self.spectators.push(connection);
+        self.send(connection, &ServerMessage::Watching);
+        if let Some(playing) = &self.playing {
+            let message = ServerMessage::MatchStarted {
+                board: Board::from(&playing.game),
+                you: None,
+                names: playing.names.clone(),
+                colors: playing.colors.clone(),
+            };
+            self.send(connection, &message);
+        }
+        self.broadcast_lobby();
+    }
+
+    fn join(&mut self, connection: ConnectionId, name: String) {
+        if self.seat_of(connection).is_some() || self.spectators.contains(&connection) {
+            return;
+        }
+        let free_seat = self.seats.iter().position(Option::is_none);
+        let reason = match (free_seat, &self.playing) {
+            (_, Some(_)) => "a match is in progress",
+            (None, None) => "the room is full",
+            (Some(seat), None) => {
+                let taken: Vec<u8> = self
+                    .seats
+                    .iter()
+                    .flatten()
+                    .map(|player| player.color)
+                    .collect();
+                let color = (0..COLORS)
+                    .find(|color| !taken.contains(color))
+                    .unwrap_or_default();
+                self.seats[seat] = Some(Player {
+                    connection,
+                    name: name.chars().take(MAX_NAME_LEN).collect(),
+                    color,
+                    ready: false,
+                });
+                self.send(connection, &ServerMessage::Welcome { seat: seat as u8 });
+                self.broadcast_lobby();
+                return;
+            }
+        };
+        self.send(
+            connection,
+            &ServerMessage::Rejected {
+                reason: reason.to_string(),
+            },
+        );
+        self.connections.remove(&connection);
+    }
+
+    fn leave(&mut self, connection: ConnectionId) {
+        self.connections.remove(&connection);
+        if let Some(index) = self
+            .spectators
+            .iter()
+            .position(|&spectator| spectator == connection)
+        {
+            self.spectators.remove(index);
+            self.broadcast_lobby();
+            return;
+        }
+        let Some(seat) = self.seat_of(connection) else {
+            return;
+        };
+        self.seats[seat] = None;
+        if let Some(playing) = &mut self.playing {
+            if let Some(&snake) = playing.snakes.get(&seat) {
+                playing.departed.push(snake);
+            }
+        }
+        self.broadcast_lobby();
+    }
+
+    fn start(&mut self) {
+        let seated: Vec<usize> = (0..MAX_PLAYERS)
+            .filter(|&seat| self.seats[seat].is_some())
+            .collect();
+        let owner = self.owner();
+        let unready = seated.iter().any(|&seat| {
+            Some(seat) != owner
+                && self.seats[seat]
+                    .as_ref()
+                    .is_some_and(|player| !player.ready)
+        });
+        let bots = usize::from(self.settings.bots).min(MAX_PLAYERS - seated.len());
+        if self.playing.is_some() || seated.len() + bots < MIN_PLAYERS || unready {
+            return;
+        }
+        self.seed = self.seed.wrapping_add(1);
+        let mut game = Match::new(self.seed, self.settings.arena, seated.len() + bots);
+        game.mode = self.settings.mode;
+        let board = Board::from(&game);
+        let snakes: HashMap<usize, usize> = seated
+            .iter()
+            .enumerate()
+            .map(|(snake, &seat)| (seat, snake))
+            .collect();
+        let players: Vec<&Player> = seated
+            .iter()
+            .filter_map(|&seat| self.seats[seat].as_ref())
+            .collect();
+        let mut names: Vec<String> = players.iter().map(|player| player.name.clone()).collect();
+        let mut colors: Vec<u8> = players.iter().map(|player| player.color).collect();
+        let difficulty = self.settings.difficulty;
+        let bots: Vec<(usize, Opponent)> = (seated.len()..seated.len() + bots)
+            .map(|snake| {
+                let seed = self.seed.wrapping_add(snake as u64);
+                (snake, Opponent::new(difficulty, seed))
+            })
+            .collect();
+        for _ in &bots {
+            let color = (0..COLORS)
+                .find(|color| !colors.contains(color))
+                .unwrap_or_default();
+            names.push(format!("{difficulty:?} bot"));
+            colors.push(color);
+        }
+        let connections: Vec<ConnectionId> = self.connections.keys().copied().collect();
+        for connection in connections {
+            let seat = self.seat_of(connection);
+            let you = seat
+                .and_then(|seat| snakes.get(&seat))
+                .map(|&snake| snake as u8);
+            self.send(
+                connection,
+                &ServerMessage::MatchStarted {
+                    board: board.clone(),
+                    you,
+                    names: names.clone(),
+                    colors: colors.clone(),
+                },
+            );
+        }
+        self.playing = Some(Playing {
+            game,
+            snakes,
+            names,
+            colors,
+            bots,
+            departed: Vec::new(),
+        });
+    }
+
+    fn tick(&mut self) {
+        let Some(playing) = &mut self.playing else {
+            return;
+        };
+        for (snake, bot) in &mut playing.bots {
+            steer_match(&mut playing.game, *snake, bot);
+        }
+        let before = playing.game.clone();
+        for snake in playing.departed.drain(..) {
+            let snake = &mut playing.game.snakes[snake];
+            snake.alive = false;
+            snake.body.clear();
+        }
+        playing.game.tick();
+        let diff = BoardDiff::between(&before, &playing.game);
+        let over = playing.game.finished();
+        let winner = playing.game.winner();
+        self.broadcast(&ServerMessage::Update(diff));
+        if over {
+            self.playing = None;
+            for player in self.seats.iter_mut().flatten() {
+                player.ready = false;
+            }
+            self.broadcast(&ServerMessage::MatchOver {
+                winner: winner.map(|snake| snake as u8),
+            });
+            self.broadcast_lobby();
+        }
+    }
+
+    fn broadcast_lobby(&mut self) {
+        let players = self
+            .seats
+            .iter()
+            .map(|seat| {
+                seat.as_ref().map(|player| LobbySeat {
+                    name: player.name.clone(),
+                    color: player.color,
+                    ready: player.ready,
+                })
+            })
+            .collect();
+        let owner = self.owner().unwrap_or_default() as u8;
+        let spectators = self.spectators.len() as u8;
+        self.broadcast(&ServerMessage::Lobby {
+            players,
+            owner,
+            spectators,
+            settings: self.settings,
+        });
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        let connections: Vec<ConnectionId> = self.connections.keys().copied().collect();
+        for connection in connections {
+            self.send(connection, message);
+        }
+    }
+
+    /// Sends to one connection, dropping it if the write fails or times out.
+    /// Its reader thread then reports the disconnect.
+    fn send(&mut self, connection: ConnectionId, message: &ServerMessage) {
+        let Some(stream) = self.connections.get(&connection) else {
+            return;
+        };
+        if write_message(stream, message).is_err() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+            self.connections.remove(&connection);
+        }
+    }
+}
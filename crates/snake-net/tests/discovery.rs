@@ -0,0 +1,28 @@
+use std::{thread, time::Duration};
+
+use snake_net::discovery::{announce, Announcement, Browser};
+
+#[test]
+fn browser_lists_announced_games() {
+    let mut browser = Browser::bind(0).unwrap();
+    let port = browser.local_addr().unwrap().port();
+    announce(
+        Announcement {
+            name: "den".to_string(),
+            port: 4000,
+        },
+        ("127.0.0.1", port),
+    )
+    .unwrap();
+
+    for _ in 0..200 {
+        let games = browser.poll().unwrap();
+        if let Some(game) = games.first() {
+            assert_eq!(game.name, "den");
+            assert_eq!(game.address, "127.0.0.1:4000".parse().unwrap());
+            return;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("no announcement arrived");
+}
@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+
+use snake_core::{versus::Match, Arena, Direction};
+use snake_net::rollback::{InputPacket, RollbackSession};
+
+const TURNS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+/// Scripted turn for `player` on local tick `tick`, turning every few ticks
+/// so the snakes circle instead of hitting a wall.
+fn turn(player: usize, tick: u32) -> Option<Direction> {
+    tick.is_multiple_of(3 + player as u32)
+        .then(|| TURNS[(tick as usize / 3 + player) % 4])
+}
+
+#[test]
+fn late_inputs_roll_back_to_the_same_board() {
+    const DELAY: u32 = 1;
+    const LATENCY: usize = 4;
+    let arena = Arena {
+        width: 16,
+        height: 16,
+    };
+    let mut peers = [
+        RollbackSession::new(3, arena, 0, DELAY),
+        RollbackSession::new(3, arena, 1, DELAY),
+    ];
+    // Packets in flight towards each peer, delivered LATENCY ticks later.
+    let mut wires: [VecDeque<InputPacket>; 2] = Default::default();
+    let mut reference = Match::new(3, arena, 2);
+
+    for tick in 1..=120 {
+        for player in 0..2 {
+            let packet = peers[player].advance(turn(player, tick));
+            wires[1 - player].push_back(packet);
+            if wires[1 - player].len() > LATENCY {
+                let packet = wires[1 - player].pop_front().unwrap();
+                peers[1 - player].receive(&packet);
+            }
+        }
+        // The reference applies each turn DELAY ticks after it was made.
+        if tick > DELAY {
+            for player in 0..2 {
+                if let Some(direction) = turn(player, tick - DELAY) {
+                    reference.steer(player, direction);
+                }
+            }
+        }
+        reference.tick();
+    }
+    for player in 0..2 {
+        while let Some(packet) = wires[player].pop_front() {
+            peers[player].receive(&packet);
+        }
+    }
+
+    assert!(peers[0].rollbacks() > 0, "latency should force rollbacks");
+    for peer in &peers {
+        assert_eq!(peer.confirmed(), 120);
+        assert_eq!(peer.game().snakes, reference.snakes);
+        assert_eq!(peer.game().food, reference.food);
+    }
+}
+
+#[test]
+fn acks_beyond_the_sent_inputs_are_clamped() {
+    let mut session = RollbackSession::new(5, Arena::default(), 0, 2);
+    session.advance(None);
+    session.receive(&InputPacket {
+        ack: 1_000,
+        start: 1,
+        inputs: vec![None],
+    });
+    // Only the three inputs sent so far count as acknowledged.
+    let packet = session.advance(Some(Direction::Up));
+    assert_eq!(packet.start, 4);
+    assert_eq!(packet.inputs, [Some(Direction::Up)]);
+}
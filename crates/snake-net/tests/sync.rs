@@ -0,0 +1,426 @@
+use std::{
+    collections::BTreeMap,
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use snake_core::{
+    controller::Difficulty,
+    versus::{Match, VersusMode},
+    Arena, Direction,
+};
+use snake_net::{
+    client::Client,
+    protocol::{
+        read_message, write_message, Board, BoardDiff, ClientMessage, RoomSettings, ServerMessage,
+        PROTOCOL_VERSION, QUICK_MATCH,
+    },
+    server::{serve, ServerConfig},
+};
+
+#[test]
+fn diffs_rebuild_the_server_board() {
+    let mut game = Match::new(7, Arena::default(), 4);
+    let mut board = Board::from(&game);
+    let turns = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+    for tick in 0..200 {
+        for snake in 0..4 {
+            game.steer(snake, turns[(tick / 3 + snake) % 4]);
+        }
+        let before = game.clone();
+        game.tick();
+        board.apply(&BoardDiff::between(&before, &game));
+        assert_eq!(board, Board::from(&game), "tick {tick}");
+    }
+}
+
+#[test]
+fn diffs_rebuild_shedding() {
+    let mut game = Match::new(3, Arena::default(), 2);
+    game.food_every = 2;
+    let mut board = Board::from(&game);
+    let turns = [
+        Direction::Down,
+        Direction::Right,
+        Direction::Up,
+        Direction::Left,
+    ];
+    for tick in 0..300 {
+        for snake in 0..2 {
+            game.steer(snake, turns[(tick / 4 + snake) % 4]);
+            game.shed(snake);
+        }
+        let before = game.clone();
+        game.tick();
+        board.apply(&BoardDiff::between(&before, &game));
+        assert_eq!(board, Board::from(&game), "tick {tick}");
+    }
+}
+
+#[test]
+fn updates_stay_small_on_large_arenas() {
+    let arena = Arena {
+        width: 64,
+        height: 64,
+    };
+    let mut game = Match::new(3, arena, 4);
+    game.food_every = 1;
+    for tick in 0..30 {
+        let before = game.clone();
+        game.tick();
+        let update =
+            serde_json::to_vec(&ServerMessage::Update(BoardDiff::between(&before, &game))).unwrap();
+        assert!(update.len() < 400, "tick {tick}: {} bytes", update.len());
+    }
+}
+
+#[test]
+fn mismatched_versions_are_rejected() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, ServerConfig::default()));
+
+    let stream = TcpStream::connect(address).unwrap();
+    let hello = ClientMessage::Hello {
+        version: PROTOCOL_VERSION - 1,
+        name: "old".to_string(),
+        room: "main".to_string(),
+        spectate: false,
+    };
+    write_message(&stream, &hello).unwrap();
+    let reply: ServerMessage = read_message(&stream).unwrap();
+    assert!(matches!(reply, ServerMessage::Rejected { .. }), "{reply:?}");
+}
+
+fn wait_for(client: &Client, mut matches: impl FnMut(&ServerMessage) -> bool) -> ServerMessage {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(message) = client.poll().unwrap().into_iter().find(&mut matches) {
+            return message;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("timed out waiting for server");
+}
+
+#[test]
+fn two_players_play_a_match() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        tick_interval: Duration::from_millis(10),
+        ..Default::default()
+    };
+    thread::spawn(move || serve(listener, config));
+
+    let host = Client::connect(address, "host", "main").unwrap();
+    assert_eq!(
+        wait_for(&host, |message| matches!(
+            message,
+            ServerMessage::Welcome { .. }
+        )),
+        ServerMessage::Welcome { seat: 0 }
+    );
+    let guest = Client::connect(address, "guest", "main").unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { players, .. } if players.iter().flatten().count() == 2),
+    );
+
+    guest.send(&ClientMessage::Ready(true)).unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { players, .. } if players.iter().flatten().any(|seat| seat.ready)),
+    );
+    host.send(&ClientMessage::Start).unwrap();
+    let ServerMessage::MatchStarted { board, you, .. } = wait_for(&guest, |message| {
+        matches!(message, ServerMessage::MatchStarted { .. })
+    }) else {
+        unreachable!();
+    };
+    assert_eq!((board.snakes.len(), you), (2, Some(1)));
+
+    // Neither player steers, so both snakes run into a wall.
+    let ServerMessage::MatchOver { winner } = wait_for(&guest, |message| {
+        matches!(message, ServerMessage::MatchOver { .. })
+    }) else {
+        unreachable!();
+    };
+    assert_eq!(winner, None);
+}
+
+#[test]
+fn rooms_are_separate_and_capped() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        max_rooms: 2,
+        ..Default::default()
+    };
+    thread::spawn(move || serve(listener, config));
+
+    let welcome = |message: &ServerMessage| {
+        matches!(
+            message,
+            ServerMessage::Welcome { .. } | ServerMessage::Rejected { .. }
+        )
+    };
+    let first = Client::connect(address, "first", "a").unwrap();
+    assert_eq!(
+        wait_for(&first, welcome),
+        ServerMessage::Welcome { seat: 0 }
+    );
+    let second = Client::connect(address, "second", "b").unwrap();
+    assert_eq!(
+        wait_for(&second, welcome),
+        ServerMessage::Welcome { seat: 0 }
+    );
+    let third = Client::connect(address, "third", "c").unwrap();
+    assert!(matches!(
+        wait_for(&third, welcome),
+        ServerMessage::Rejected { .. }
+    ));
+}
+
+#[test]
+fn spectators_join_a_match_in_progress() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        tick_interval: Duration::from_millis(50),
+        ..Default::default()
+    };
+    thread::spawn(move || serve(listener, config));
+
+    let host = Client::connect(address, "host", "main").unwrap();
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Welcome { .. })
+    });
+    let guest = Client::connect(address, "guest", "main").unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { players, .. } if players.iter().flatten().count() == 2),
+    );
+    guest.send(&ClientMessage::Ready(true)).unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { players, .. } if players.iter().flatten().any(|seat| seat.ready)),
+    );
+    host.send(&ClientMessage::Start).unwrap();
+    wait_for(&guest, |message| {
+        matches!(message, ServerMessage::Update(_))
+    });
+
+    let spectator = Client::spectate(address, "watcher", "main").unwrap();
+    let ServerMessage::MatchStarted {
+        board, you, names, ..
+    } = wait_for(&spectator, |message| {
+        matches!(message, ServerMessage::MatchStarted { .. })
+    })
+    else {
+        unreachable!();
+    };
+    assert!(board.tick > 0);
+    assert_eq!(you, None);
+    assert_eq!(names, ["host", "guest"]);
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Lobby { spectators: 1, .. })
+    });
+
+    wait_for(&spectator, |message| {
+        matches!(message, ServerMessage::MatchOver { .. })
+    });
+}
+
+#[test]
+fn quick_match_shares_a_room_and_owner_picks_settings() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, ServerConfig::default()));
+
+    let host = Client::connect(address, "host", QUICK_MATCH).unwrap();
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Welcome { .. })
+    });
+    let guest = Client::connect(address, "guest", QUICK_MATCH).unwrap();
+    assert_eq!(
+        wait_for(&guest, |message| matches!(
+            message,
+            ServerMessage::Welcome { .. }
+        )),
+        ServerMessage::Welcome { seat: 1 }
+    );
+
+    let settings = RoomSettings {
+        mode: VersusMode::Race,
+        arena: Arena {
+            width: 20,
+            height: 16,
+        },
+        ..Default::default()
+    };
+    // Only the owner may change the settings.
+    guest
+        .send(&ClientMessage::Configure(RoomSettings::default()))
+        .unwrap();
+    host.send(&ClientMessage::Configure(settings)).unwrap();
+    wait_for(
+        &guest,
+        |message| matches!(message, ServerMessage::Lobby { settings: lobby_settings, .. } if *lobby_settings == settings),
+    );
+    guest
+        .send(&ClientMessage::SetProfile {
+            name: "renamed".to_string(),
+            color: 3,
+        })
+        .unwrap();
+    guest.send(&ClientMessage::Ready(true)).unwrap();
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Lobby { players, settings: lobby_settings, .. }
+            if *lobby_settings == settings
+                && players[1].as_ref().is_some_and(|seat| seat.ready && seat.name == "renamed" && seat.color == 3))
+    });
+
+    host.send(&ClientMessage::Start).unwrap();
+    let ServerMessage::MatchStarted { board, colors, .. } = wait_for(&guest, |message| {
+        matches!(message, ServerMessage::MatchStarted { .. })
+    }) else {
+        unreachable!();
+    };
+    assert_eq!(board.arena, settings.arena);
+    assert_eq!(colors, [0, 3]);
+}
+
+#[test]
+fn bots_fill_free_seats() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        tick_interval: Duration::from_millis(5),
+        ..Default::default()
+    };
+    thread::spawn(move || serve(listener, config));
+
+    let host = Client::connect(address, "host", "main").unwrap();
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Welcome { .. })
+    });
+    let settings = RoomSettings {
+        bots: 2,
+        difficulty: Difficulty::Beginner,
+        ..Default::default()
+    };
+    host.send(&ClientMessage::Configure(settings)).unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { settings: lobby_settings, .. } if *lobby_settings == settings),
+    );
+    host.send(&ClientMessage::Start).unwrap();
+    let ServerMessage::MatchStarted {
+        board, you, names, ..
+    } = wait_for(&host, |message| {
+        matches!(message, ServerMessage::MatchStarted { .. })
+    })
+    else {
+        unreachable!();
+    };
+    assert_eq!((board.snakes.len(), you), (3, Some(0)));
+    assert_eq!(names[1..], ["Beginner bot", "Beginner bot"]);
+
+    // The host never steers and crashes, but the bots play on until one
+    // of them outlasts the other or both crash.
+    let ServerMessage::MatchOver { winner } = wait_for(&host, |message| {
+        matches!(message, ServerMessage::MatchOver { .. })
+    }) else {
+        unreachable!();
+    };
+    assert_ne!(winner, Some(0));
+}
+
+/// Applies everything `client` receives to its boards by tick until `done`.
+fn follow(
+    client: &Client,
+    boards: &mut BTreeMap<u32, Board>,
+    mut done: impl FnMut(&BTreeMap<u32, Board>) -> bool,
+) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        for message in client.poll().unwrap() {
+            match message {
+                ServerMessage::MatchStarted { board, .. } => {
+                    boards.insert(board.tick, board);
+                }
+                ServerMessage::Update(diff) => {
+                    let Some((_, latest)) = boards.last_key_value() else {
+                        continue;
+                    };
+                    let mut board = latest.clone();
+                    board.apply(&diff);
+                    boards.insert(board.tick, board);
+                }
+                _ => {}
+            }
+        }
+        if done(boards) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("timed out following the match");
+}
+
+#[test]
+fn boards_stay_in_sync_when_a_player_leaves() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        arena: Arena {
+            width: 64,
+            height: 64,
+        },
+        tick_interval: Duration::from_millis(20),
+        ..Default::default()
+    };
+    thread::spawn(move || serve(listener, config));
+
+    let host = Client::connect(address, "host", "main").unwrap();
+    wait_for(&host, |message| {
+        matches!(message, ServerMessage::Welcome { .. })
+    });
+    let leaver = Client::connect(address, "leaver", "main").unwrap();
+    let third = Client::connect(address, "third", "main").unwrap();
+    leaver.send(&ClientMessage::Ready(true)).unwrap();
+    third.send(&ClientMessage::Ready(true)).unwrap();
+    wait_for(
+        &host,
+        |message| matches!(message, ServerMessage::Lobby { players, .. } if players.iter().flatten().filter(|seat| seat.ready).count() == 2),
+    );
+    host.send(&ClientMessage::Start).unwrap();
+
+    let mut boards = BTreeMap::new();
+    follow(&host, &mut boards, |boards| boards.len() > 1);
+    leaver.disconnect().unwrap();
+    follow(&host, &mut boards, |boards| {
+        boards
+            .last_key_value()
+            .is_some_and(|(_, board)| !board.snakes[1].alive)
+    });
+
+    // A spectator arriving now is sent the server's own board.
+    let spectator = Client::spectate(address, "watcher", "main").unwrap();
+    let ServerMessage::MatchStarted { board, .. } = wait_for(&spectator, |message| {
+        matches!(message, ServerMessage::MatchStarted { .. })
+    }) else {
+        unreachable!();
+    };
+    follow(&host, &mut boards, |boards| {
+        boards.contains_key(&board.tick)
+    });
+    assert_eq!(boards[&board.tick], board);
+}
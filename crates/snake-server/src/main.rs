@@ -0,0 +1,109 @@
+//! Headless host for versus rooms.
+//!
+//! Each setting can be given as a flag or an environment variable, with the
+//! flag winning:
+//!
+//! - `--port` / `SNAKE_PORT`: TCP port to listen on (default 7878)
+//! - `--arena` / `SNAKE_ARENA`: board size as `WIDTHxHEIGHT`, each side within
+//!   [`ARENA_SIDES`]
+//! - `--tick-ms` / `SNAKE_TICK_MS`: milliseconds between movement ticks, at
+//!   least 1
+//! - `--max-rooms` / `SNAKE_MAX_ROOMS`: rooms open at once
+//! - `--lan-name` / `SNAKE_LAN_NAME`: announce the server on the local
+//!   network under this name (off by default)
+
+use std::{env, net::TcpListener, process::ExitCode, str::FromStr, time::Duration};
+
+use snake_core::Arena;
+use snake_net::{
+    discovery::{announce, broadcast_address, Announcement},
+    server::{serve, ServerConfig, ARENA_SIDES},
+};
+
+const DEFAULT_PORT: u16 = 7878;
+
+fn setting(flag: &str, variable: &str) -> Option<String> {
+    env::args()
+        .skip_while(|arg| arg != flag)
+        .nth(1)
+        .or_else(|| env::var(variable).ok())
+}
+
+fn parsed<T: FromStr>(flag: &str, variable: &str) -> Result<Option<T>, String> {
+    setting(flag, variable)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("{flag} / {variable}: cannot parse {value:?}"))
+        })
+        .transpose()
+}
+
+fn parse_arena(value: &str) -> Option<Arena> {
+    let (width, height) = value.split_once('x')?;
+    let arena = Arena {
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+    };
+    (ARENA_SIDES.contains(&arena.width) && ARENA_SIDES.contains(&arena.height)).then_some(arena)
+}
+
+fn config() -> Result<(u16, ServerConfig), String> {
+    let mut config = ServerConfig::default();
+    let port = parsed("--port", "SNAKE_PORT")?.unwrap_or(DEFAULT_PORT);
+    if let Some(arena) = setting("--arena", "SNAKE_ARENA") {
+        config.arena = parse_arena(&arena).ok_or_else(|| {
+            format!(
+                "--arena / SNAKE_ARENA: expected WIDTHxHEIGHT with sides from {} to {}, got {arena:?}",
+                ARENA_SIDES.start(),
+                ARENA_SIDES.end()
+            )
+        })?;
+    }
+    if let Some(millis) = parsed("--tick-ms", "SNAKE_TICK_MS")? {
+        if millis == 0 {
+            return Err("--tick-ms / SNAKE_TICK_MS: must be at least 1".to_string());
+        }
+        config.tick_interval = Duration::from_millis(millis);
+    }
+    if let Some(max_rooms) = parsed("--max-rooms", "SNAKE_MAX_ROOMS")? {
+        config.max_rooms = max_rooms;
+    }
+    Ok((port, config))
+}
+
+fn main() -> ExitCode {
+    let (port, config) = match config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("snake-server: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("snake-server: cannot listen on port {port}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "snake-server listening on port {port}: {}x{} arena, {} ms ticks, up to {} rooms",
+        config.arena.width,
+        config.arena.height,
+        config.tick_interval.as_millis(),
+        config.max_rooms
+    );
+    if let Some(name) = setting("--lan-name", "SNAKE_LAN_NAME") {
+        if let Err(err) = announce(Announcement { name, port }, broadcast_address()) {
+            eprintln!("snake-server: cannot announce on the local network: {err}");
+        }
+    }
+    match serve(listener, config) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("snake-server: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Terminal frontend, for playing over SSH or recording demos in CI.
+//!
+//! The board is a [`Simulation`], so every rule is the one the graphical game
+//! plays by; this binary only draws it and reads the keyboard.
+//!
+//! - arrows, WASD or hjkl steer, `r` restarts, `q` or Esc quits
+//! - `--seed N` and `--arena WIDTHxHEIGHT` pick the board
+//! - `--bot NAME` lets one of the reference bots play, and `--demo` is short
+//!   for `--bot autopilot`; `--ticks N` stops after `N` movement ticks and
+//!   prints the score, so a CI job can record a run
+//! - `tournament A B` instead pits two bots against each other without
+//!   drawing anything, see [`tournament`]
+
+use std::{
+    env, io,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Flex, Layout},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    DefaultTerminal, Frame,
+};
+use snake_core::{
+    controller::{bot, BoardView, SnakeController, BOTS},
+    sim::Simulation,
+    Arena, Direction, Position, FOOD_SPAWN_INTERVAL, MOVEMENT_INTERVAL, START_DIRECTION,
+};
+
+mod tournament;
+
+/// Each cell is two columns wide so the board looks roughly square.
+const CELL: &str = "  ";
+
+struct Game {
+    sim: Simulation,
+    arena: Arena,
+    seed: u64,
+    direction: Direction,
+    /// The direction steered since the last tick, applied on the next one.
+    queued: Option<Direction>,
+    tick: u64,
+}
+
+impl Game {
+    fn new(seed: u64, arena: Arena) -> Self {
+        Self {
+            sim: Simulation::new(seed, arena),
+            arena,
+            seed,
+            direction: START_DIRECTION,
+            queued: None,
+            tick: 0,
+        }
+    }
+
+    fn restart(&mut self) {
+        *self = Self::new(self.seed, self.arena);
+    }
+
+    /// Reversing onto the neck is ignored, as in the graphical game.
+    fn steer(&mut self, direction: Direction) {
+        if direction != self.direction.opposite() {
+            self.queued = Some(direction);
+        }
+    }
+
+    fn tick(&mut self) {
+        if let Some(direction) = self.queued.take() {
+            self.direction = direction;
+        }
+        self.sim.step(self.direction);
+        self.tick += 1;
+        let food_every = (FOOD_SPAWN_INTERVAL.as_millis() / MOVEMENT_INTERVAL.as_millis()) as u64;
+        if self.tick.is_multiple_of(food_every.max(1)) {
+            self.sim.spawn_food();
+        }
+    }
+}
+
+fn direction_for(key: KeyCode) -> Option<Direction> {
+    match key {
+        KeyCode::Up | KeyCode::Char('w' | 'k') => Some(Direction::Up),
+        KeyCode::Down | KeyCode::Char('s' | 'j') => Some(Direction::Down),
+        KeyCode::Left | KeyCode::Char('a' | 'h') => Some(Direction::Left),
+        KeyCode::Right | KeyCode::Char('d' | 'l') => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+fn draw(frame: &mut Frame, game: &Game) {
+    let head = game.sim.body[0];
+    let rows: Vec<Line> = (0..game.arena.height as i32)
+        .rev()
+        .map(|y| {
+            (0..game.arena.width as i32)
+                .map(|x| {
+                    let position = Position { x, y };
+                    let color = if position == head {
+                        Color::LightGreen
+                    } else if game.sim.body.contains(&position) {
+                        Color::Green
+                    } else if game.sim.food.contains(&position) {
+                        Color::Red
+                    } else {
+                        Color::Reset
+                    };
+                    Span::styled(CELL, Style::new().bg(color))
+                })
+                .collect()
+        })
+        .collect();
+
+    let status = if game.sim.alive {
+        format!(" Score {} ", game.sim.score)
+    } else {
+        format!(" Game over, score {}. r restarts, q quits ", game.sim.score)
+    };
+    let board = Paragraph::new(rows).block(Block::bordered().title(status.bold()));
+
+    let [area] = Layout::horizontal([Constraint::Length(game.arena.width as u16 * 2 + 2)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(game.arena.height as u16 + 2)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(board, area);
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    game: &mut Game,
+    mut pilot: Option<Box<dyn SnakeController>>,
+    ticks: Option<u64>,
+) -> io::Result<()> {
+    let mut next_tick = Instant::now() + MOVEMENT_INTERVAL;
+    loop {
+        terminal.draw(|frame| draw(frame, game))?;
+        if ticks.is_some_and(|ticks| game.tick >= ticks) {
+            return Ok(());
+        }
+
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => game.restart(),
+                    code if pilot.is_none() => {
+                        if let Some(direction) = direction_for(code) {
+                            game.steer(direction);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        if let Some(pilot) = &mut pilot {
+            let board = BoardView {
+                arena: game.arena,
+                body: &game.sim.body,
+                others: &[],
+                food: &game.sim.food,
+                direction: game.direction,
+            };
+            if let Some(direction) = pilot.steer(&board) {
+                game.steer(direction);
+            }
+        }
+        game.tick();
+        next_tick += MOVEMENT_INTERVAL;
+        // Bots restart on their own so they can run unattended.
+        if pilot.is_some() && !game.sim.alive && ticks.is_none() {
+            std::thread::sleep(Duration::from_secs(1));
+            game.restart();
+            next_tick = Instant::now() + MOVEMENT_INTERVAL;
+        }
+    }
+}
+
+fn parse_arena(value: &str) -> Option<Arena> {
+    let (width, height) = value.split_once('x')?;
+    let arena = Arena {
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+    };
+    (arena.width > 0 && arena.height > 0).then_some(arena)
+}
+
+fn flag(name: &str) -> Option<String> {
+    env::args().skip_while(|arg| arg != name).nth(1)
+}
+
+fn main() -> ExitCode {
+    let seed = match flag("--seed").map(|seed| seed.parse()) {
+        None => rand::random(),
+        Some(Ok(seed)) => seed,
+        Some(Err(_)) => {
+            eprintln!("--seed: expected a number");
+            return ExitCode::FAILURE;
+        }
+    };
+    let arena = match flag("--arena").map(|arena| parse_arena(&arena)) {
+        None => Arena::default(),
+        Some(Some(arena)) => arena,
+        Some(None) => {
+            eprintln!("--arena: expected WIDTHxHEIGHT");
+            return ExitCode::FAILURE;
+        }
+    };
+    if env::args().nth(1).as_deref() == Some("tournament") {
+        return tournament::main(seed, arena);
+    }
+    let ticks = match flag("--ticks").map(|ticks| ticks.parse()) {
+        None => None,
+        Some(Ok(ticks)) => Some(ticks),
+        Some(Err(_)) => {
+            eprintln!("--ticks: expected a number");
+            return ExitCode::FAILURE;
+        }
+    };
+    let pilot = match flag("--bot").or_else(|| {
+        env::args()
+            .any(|arg| arg == "--demo")
+            .then(|| "autopilot".to_string())
+    }) {
+        None => None,
+        Some(name) => match bot(&name) {
+            Some(pilot) => Some(pilot),
+            None => {
+                eprintln!("--bot: expected one of {BOTS:?}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let mut game = Game::new(seed, arena);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut game, pilot, ticks);
+    ratatui::restore();
+    if let Err(err) = result {
+        eprintln!("snake-tui: {err}");
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "seed {seed}: score {} after {} ticks",
+        game.sim.score, game.tick
+    );
+    ExitCode::SUCCESS
+}
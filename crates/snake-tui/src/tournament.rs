@@ -0,0 +1,62 @@
+//! `snake-tui tournament A B [--games N]`: plays `N` (default 100) seeded
+//! versus matches between two of the reference bots and prints how often
+//! each won and how long it grew on average, for checking whether a bot
+//! change is an improvement. `--seed` and `--arena` pick the boards as for a
+//! normal game, and the same seed replays the same tournament.
+
+use std::{env, process::ExitCode};
+
+use snake_core::{
+    controller::{bot, BOTS},
+    tournament::{tournament, MAX_TICKS},
+    Arena,
+};
+
+use crate::flag;
+
+const DEFAULT_GAMES: u32 = 100;
+
+pub fn main(seed: u64, arena: Arena) -> ExitCode {
+    let names: Vec<String> = env::args().skip(2).take(2).collect();
+    let [a, b] = names.as_slice() else {
+        eprintln!("usage: snake-tui tournament BOT BOT [--games N]");
+        return ExitCode::FAILURE;
+    };
+    if let Some(unknown) = [a, b].into_iter().find(|name| bot(name).is_none()) {
+        eprintln!("unknown bot {unknown:?}, expected one of {BOTS:?}");
+        return ExitCode::FAILURE;
+    }
+    let games = match flag("--games").map(|games| games.parse()) {
+        None => DEFAULT_GAMES,
+        Some(Ok(games)) => games,
+        Some(Err(_)) => {
+            eprintln!("--games: expected a number");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let standings = tournament(arena, seed, games, |contestant| {
+        bot([a, b][contestant]).expect("checked above")
+    });
+    println!(
+        "{games} games on {}x{}, seed {seed}",
+        arena.width, arena.height
+    );
+    println!(
+        "{:<12} {:>6} {:>9} {:>11}",
+        "bot", "wins", "win rate", "avg length"
+    );
+    for (contestant, name) in [a, b].into_iter().enumerate() {
+        println!(
+            "{name:<12} {:>6} {:>8.1}% {:>11.1}",
+            standings.wins[contestant],
+            standings.win_rate(contestant) * 100.0,
+            standings.average_length(contestant),
+        );
+    }
+    println!(
+        "{} draws (both crashed, or both alive after {MAX_TICKS} ticks)",
+        standings.draws
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,15 @@
+#!/bin/sh
+# Builds the browser version into web/dist. Needs the wasm32-unknown-unknown
+# target and a wasm-bindgen-cli matching the wasm-bindgen version in
+# Cargo.lock. Serve web/dist over HTTP, e.g. `python3 -m http.server -d
+# web/dist`; browsers refuse to load wasm from file:// URLs.
+set -eu
+cd "$(dirname "$0")/.."
+
+cargo build --release --target wasm32-unknown-unknown -p snake-game --bin snake-game
+rm -rf web/dist
+mkdir -p web/dist
+wasm-bindgen --no-typescript --target web --out-dir web/dist \
+    target/wasm32-unknown-unknown/release/snake-game.wasm
+cp web/index.html web/dist/
+cp -r crates/snake-game/assets web/dist/assets